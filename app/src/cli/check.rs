@@ -2,16 +2,24 @@ use std::path::PathBuf;
 
 use driver::Database;
 
+use crate::utils::entry_point::entry_point;
+use crate::utils::eval_limits::EvalLimitArgs;
+
 #[derive(clap::Args)]
 pub struct Args {
+    /// The file to check; defaults to the entry module of the project in the current directory
     #[clap(value_parser, value_name = "FILE")]
-    filepath: PathBuf,
+    filepath: Option<PathBuf>,
+    #[clap(flatten)]
+    limits: EvalLimitArgs,
 }
 
 pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
-    let uri = db.resolve_path(&cmd.filepath)?;
+    let filepath = entry_point(cmd.filepath)?;
+    let mut db = Database::from_path(&filepath)?;
+    db.eval_limits = cmd.limits.limits();
+    let uri = db.resolve_path(&filepath)?;
     let _ = db.ast(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
-    println!("{} typechecked successfully!", cmd.filepath.display());
+    println!("{} typechecked successfully!", filepath.display());
     Ok(())
 }
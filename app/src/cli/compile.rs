@@ -1,35 +1,82 @@
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use driver::{Database, IR_PATH};
+use driver::{Database, BIN_PATH, C_PATH, IR_PATH, JS_PATH};
 use printer::{Print, PrintCfg};
 
+use super::run::MainNotFound;
+
 #[derive(clap::Args)]
 pub struct Args {
     #[clap(value_parser, value_name = "FILE")]
     filepath: PathBuf,
+    /// The output which is generated
+    #[clap(long, value_enum, default_value_t = Target::Ir)]
+    target: Target,
+}
+
+/// The outputs which can be selected with `pol compile --target`
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Target {
+    /// The intermediate representation, written to `target_pol/ir/`
+    Ir,
+    /// An ES module, written to `target_pol/js/`
+    Js,
+    /// An executable which prints the value of `main`, written to `target_pol/bin/`
+    ///
+    /// The C code is written to `target_pol/c/` and compiled with the system C compiler.
+    C,
 }
 
 pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
+    let mut db = Database::from_path(&cmd.filepath)?;
     let uri = db.resolve_path(&cmd.filepath)?;
-    let ir = db.ir(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
 
-    if !Path::new(IR_PATH).exists() {
-        fs::create_dir_all(IR_PATH).expect("Failed to create IR directory");
-    }
+    match cmd.target {
+        Target::Ir => {
+            let ir = db.ir(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+
+            if !Path::new(IR_PATH).exists() {
+                fs::create_dir_all(IR_PATH).expect("Failed to create IR directory");
+            }
 
-    let ir_path = target_path(&cmd.filepath);
-    let mut file = fs::File::create(&ir_path).expect("Failed to create file");
-    let cfg = PrintCfg::default();
-    ir.print_io(&cfg, &mut file).expect("Failed to print to file");
+            let ir_path = target_path(IR_PATH, &cmd.filepath, "ir");
+            let mut file = fs::File::create(&ir_path).expect("Failed to create file");
+            let cfg = PrintCfg::default();
+            ir.print_io(&cfg, &mut file).expect("Failed to print to file");
+        }
+        Target::Js => {
+            let js = db.js(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+
+            if !Path::new(JS_PATH).exists() {
+                fs::create_dir_all(JS_PATH).expect("Failed to create JS directory");
+            }
+
+            let js_path = target_path(JS_PATH, &cmd.filepath, "js");
+            fs::write(&js_path, js).expect("Failed to write file");
+        }
+        Target::C => {
+            let c = db.c(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+            let c = c.ok_or(MainNotFound {})?;
+
+            for dir in [C_PATH, BIN_PATH] {
+                if !Path::new(dir).exists() {
+                    fs::create_dir_all(dir).expect("Failed to create target directory");
+                }
+            }
+
+            let c_path = target_path(C_PATH, &cmd.filepath, "c");
+            fs::write(&c_path, c).expect("Failed to write file");
+            let bin_path = target_path(BIN_PATH, &cmd.filepath, std::env::consts::EXE_EXTENSION);
+            driver::compile_c(&c_path, &bin_path).map_err(miette::Report::new)?;
+        }
+    }
 
     Ok(())
 }
 
-fn target_path(filepath: &Path) -> PathBuf {
-    let mut path =
-        Path::new(IR_PATH).join(filepath.file_name().unwrap().to_string_lossy().as_ref());
-    path.set_extension("ir");
+fn target_path(dir: &str, filepath: &Path, extension: &str) -> PathBuf {
+    let mut path = Path::new(dir).join(filepath.file_name().unwrap().to_string_lossy().as_ref());
+    path.set_extension(extension);
     path
 }
@@ -48,7 +48,7 @@ fn compute_output_stream(cmd: &Args) -> Box<dyn WriteColor> {
 }
 
 pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
+    let mut db = Database::from_path(&cmd.filepath)?;
     let uri = db.resolve_path(&cmd.filepath)?;
     let prg = if cmd.checked { db.ast(&uri).await } else { db.ust(&uri).await }
         .map_err(|err| db.pretty_error(&uri, err))?;
@@ -66,6 +66,7 @@ pub async fn exec(cmd: Args) -> miette::Result<()> {
         print_function_sugar: !cmd.omit_function_sugar,
         print_metavar_ids: false,
         print_metavar_args: false,
+        highlight: None,
     };
 
     print_prg(&prg, &cfg, &mut stream);
@@ -1,12 +1,25 @@
-use tower_lsp_server::{LspService, Server};
+use tower_lsp_server::Server;
+
+use driver::{Database, FileSource, InMemorySource, StdSource};
+
+use crate::utils::eval_limits::EvalLimitArgs;
 
 #[derive(clap::Args)]
-pub struct Args {}
+pub struct Args {
+    #[clap(flatten)]
+    limits: EvalLimitArgs,
+}
 
-pub async fn exec(_: Args) -> miette::Result<()> {
+pub async fn exec(cmd: Args) -> miette::Result<()> {
     let stdin = async_std::io::stdin();
     let stdout = async_std::io::stdout();
-    let (service, messages) = LspService::new(lsp_server::Server::new);
+    let limits = cmd.limits.limits_with_default(Some(lsp_server::DEFAULT_MAX_STEPS));
+    let (service, messages) = lsp_server::Server::service(|client| {
+        let mut database =
+            Database::from_source(InMemorySource::new().fallback_to(StdSource::new()));
+        database.eval_limits = limits;
+        lsp_server::Server::with_database(client, database)
+    });
     Server::new(stdin, stdout, messages).serve(service).await;
     Ok(())
 }
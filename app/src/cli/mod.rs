@@ -9,6 +9,7 @@ mod gen_completions;
 mod lex;
 mod lift;
 mod lsp;
+mod repl;
 mod run;
 mod texify;
 mod xfunc;
@@ -40,6 +41,7 @@ pub fn exec() -> miette::Result<()> {
             Lex(args) => lex::exec(args).await,
             Lsp(args) => lsp::exec(args).await,
             Lift(args) => lift::exec(args).await,
+            Repl(args) => repl::exec(args).await,
             Doc(args) => doc::exec(args).await,
             Clean => clean::exec().await,
             GenerateCompletion(args) => gen_completions::exec(args).await,
@@ -83,6 +85,8 @@ enum Command {
     Lex(lex::Args),
     /// Lift local (co)matches of a type to the top-level
     Lift(lift::Args),
+    /// Start an interactive session
+    Repl(repl::Args),
     /// Generate documentation for a file
     Doc(doc::Args),
     /// Clean target_pol directory
@@ -0,0 +1,241 @@
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use driver::{Database, FileSource, FileSystemSource, InMemorySource, Manifest, StdSource};
+use printer::{ColorChoice, Print, StandardStream};
+use url::Url;
+
+/// The name of the in-memory module which contains the declarations entered in a session
+const SCRATCH_MODULE: &str = "repl.pol";
+
+const HELP: &str = "\
+Commands:
+  :load FILE       Load a module and bring its declarations into scope
+  :type EXP        Infer the type of an expression
+  :normalize EXP   Evaluate an expression to normal form
+  :print DECL      Print a declaration
+  :xfunc TYPE      Print the module which declares TYPE with TYPE de-/refunctionalized
+  :help            Show this message
+  :quit            Exit the session
+
+Any other input is added to the session as a use declaration or as top-level declarations.";
+
+#[derive(clap::Args)]
+pub struct Args {
+    /// The file to load at the start of the session
+    #[clap(value_parser, value_name = "FILE")]
+    filepath: Option<PathBuf>,
+}
+
+pub async fn exec(cmd: Args) -> miette::Result<()> {
+    let mut repl = Repl::new()?;
+    if let Some(filepath) = cmd.filepath {
+        if let Err(report) = repl.load(&filepath).await {
+            eprintln!("{report:?}");
+        }
+    }
+
+    println!("Type :help for a list of commands.");
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    while let Some(input) = read_input(&mut lines) {
+        match repl.eval(input.trim()).await {
+            Ok(Continue::Yes) => {}
+            Ok(Continue::No) => break,
+            Err(report) => eprintln!("{report:?}"),
+        }
+    }
+    Ok(())
+}
+
+/// Read the next input, which continues over several lines as long as there are unclosed brackets
+///
+/// Returns `None` at the end of the input.
+fn read_input(lines: &mut impl Iterator<Item = io::Result<String>>) -> Option<String> {
+    let mut input = String::new();
+    let mut prompt = "> ";
+    loop {
+        print!("{prompt}");
+        io::stdout().flush().expect("Failed to flush stdout");
+        let line = lines.next()?.expect("Failed to read from stdin");
+        input.push_str(&line);
+        input.push('\n');
+        if open_brackets(&input) <= 0 {
+            return Some(input);
+        }
+        prompt = "| ";
+    }
+}
+
+fn open_brackets(input: &str) -> isize {
+    input
+        .chars()
+        .map(|c| match c {
+            '(' | '{' | '[' => 1,
+            ')' | '}' | ']' => -1,
+            _ => 0,
+        })
+        .sum()
+}
+
+enum Continue {
+    Yes,
+    No,
+}
+
+/// The state of an interactive session
+struct Repl {
+    db: Database,
+    /// The in-memory module which contains the declarations entered in the session
+    scratch: Url,
+    /// The module loaded with `:load`
+    loaded: Option<Url>,
+    /// The use declarations entered in the session
+    imports: Vec<String>,
+    /// The declarations entered in the session
+    decls: Vec<String>,
+}
+
+impl Repl {
+    fn new() -> miette::Result<Self> {
+        let cwd = std::env::current_dir().expect("Could not get current directory");
+        let scratch = Url::from_directory_path(&cwd)
+            .and_then(|dir| dir.join(SCRATCH_MODULE).map_err(|_| ()))
+            .expect("Could not convert path to URI");
+
+        let mut source = InMemorySource::new();
+        source.insert(scratch.clone(), String::new());
+        let source = source.fallback_to(StdSource::new()).fallback_to(FileSystemSource::new(&cwd));
+        let mut db = Database::from_source(source);
+        db.manifest = Manifest::discover(&cwd)?;
+
+        Ok(Repl { db, scratch, loaded: None, imports: vec![], decls: vec![] })
+    }
+
+    async fn eval(&mut self, input: &str) -> miette::Result<Continue> {
+        let (command, arg) = match input.strip_prefix(':') {
+            Some(command) => command.split_once(char::is_whitespace).unwrap_or((command, "")),
+            None if input.is_empty() => return Ok(Continue::Yes),
+            None => {
+                self.add(input).await?;
+                return Ok(Continue::Yes);
+            }
+        };
+        let arg = arg.trim();
+        match command {
+            "l" | "load" => self.load(Path::new(arg)).await?,
+            "t" | "type" => self.infer(arg).await?,
+            "n" | "normalize" => self.normalize(arg).await?,
+            "p" | "print" => self.print(arg).await?,
+            "x" | "xfunc" => self.xfunc(arg).await?,
+            "h" | "help" => println!("{HELP}"),
+            "q" | "quit" => return Ok(Continue::No),
+            _ => return Err(miette::miette!("Unknown command :{command}. Type :help for help.")),
+        }
+        Ok(Continue::Yes)
+    }
+
+    /// The source of the scratch module
+    fn scratch_source(&self) -> String {
+        let loaded = self.loaded.iter().map(|uri| format!("use \"{uri}\""));
+        let imports = loaded.chain(self.imports.iter().cloned()).collect::<Vec<_>>().join("\n");
+        let decls = self.decls.join("\n\n");
+        format!("{imports}\n\n{decls}\n")
+    }
+
+    /// Write the scratch module and check it
+    ///
+    /// If the scratch module does not typecheck, the changes made by `update` are undone.
+    async fn update(&mut self, update: impl FnOnce(&mut Self)) -> miette::Result<()> {
+        let (loaded, imports, decls) =
+            (self.loaded.clone(), self.imports.clone(), self.decls.clone());
+        update(self);
+        let source = self.scratch_source();
+        self.db.write_source(&self.scratch, &source).await?;
+        if let Err(err) = self.db.ast(&self.scratch).await {
+            let report = self.db.pretty_error(&self.scratch, err);
+            (self.loaded, self.imports, self.decls) = (loaded, imports, decls);
+            let source = self.scratch_source();
+            self.db.write_source(&self.scratch, &source).await?;
+            return Err(report);
+        }
+        Ok(())
+    }
+
+    async fn add(&mut self, input: &str) -> miette::Result<()> {
+        let input = input.to_owned();
+        if input.starts_with("use ") {
+            self.update(|repl| repl.imports.push(input)).await
+        } else {
+            self.update(|repl| repl.decls.push(input)).await
+        }
+    }
+
+    async fn load(&mut self, filepath: &Path) -> miette::Result<()> {
+        let uri = self.db.resolve_path(filepath)?;
+        // Re-read the module and its imports in case they changed since they were last loaded
+        let deps = self.db.deps(&uri).await.unwrap_or_default();
+        for dep in deps.iter().chain([&uri]) {
+            self.db.invalidate(dep).await;
+        }
+        self.db.ast(&uri).await.map_err(|err| self.db.pretty_error(&uri, err))?;
+        self.update(|repl| repl.loaded = Some(uri)).await?;
+        println!("Loaded {}", filepath.display());
+        Ok(())
+    }
+
+    async fn infer(&mut self, input: &str) -> miette::Result<()> {
+        let typ = self
+            .db
+            .infer_expr(&self.scratch, input)
+            .await
+            .map_err(|err| self.db.pretty_expr_error(input, err))?;
+        println!("{input} : {typ}");
+        Ok(())
+    }
+
+    async fn normalize(&mut self, input: &str) -> miette::Result<()> {
+        let nf = self
+            .db
+            .normalize_expr(&self.scratch, input)
+            .await
+            .map_err(|err| self.db.pretty_expr_error(input, err))?;
+        println!("{nf}");
+        Ok(())
+    }
+
+    /// The scratch module followed by the modules it imports
+    async fn modules(&mut self) -> miette::Result<Vec<Url>> {
+        let deps = self.db.deps(&self.scratch).await?;
+        Ok(std::iter::once(self.scratch.clone()).chain(deps).collect())
+    }
+
+    async fn print(&mut self, name: &str) -> miette::Result<()> {
+        for uri in self.modules().await? {
+            let module = self.db.ast(&uri).await.map_err(|err| self.db.pretty_error(&uri, err))?;
+            let decl =
+                module.decls.iter().find(|decl| decl.ident().is_some_and(|id| id.id == name));
+            if let Some(decl) = decl {
+                let mut stream = StandardStream::stdout(ColorChoice::Auto);
+                decl.print_colored(&Default::default(), &mut stream)
+                    .expect("Failed to print to stdout");
+                println!();
+                return Ok(());
+            }
+        }
+        Err(miette::miette!("Declaration {name} not found"))
+    }
+
+    async fn xfunc(&mut self, type_name: &str) -> miette::Result<()> {
+        for uri in self.modules().await? {
+            let types = self.db.all_declared_type_names(&uri).await?;
+            if types.iter().any(|typ| typ.id == type_name) {
+                let xfunc = self.db.xfunc(&uri, type_name).await.map_err(miette::Report::msg)?;
+                let output = self.db.edited(&uri, xfunc.edits);
+                print!("{output}");
+                return Ok(());
+            }
+        }
+        Err(miette::miette!("Type {type_name} not found"))
+    }
+}
@@ -1,20 +1,65 @@
 use std::path::PathBuf;
 
+use elaborator::result::TypeError;
 use miette::Diagnostic;
 use thiserror::Error;
 
-use driver::Database;
-use printer::{ColorChoice, Print, StandardStream};
+use driver::{Database, Step, Steps, REDEX_SPAN};
+use printer::{ColorChoice, Print, PrintCfg, StandardStream};
+
+use crate::utils::entry_point::entry_point;
+use crate::utils::eval_limits::EvalLimitArgs;
 
 #[derive(clap::Args)]
 pub struct Args {
+    /// The file to run; defaults to the entry module of the project in the current directory
     #[clap(value_parser, value_name = "FILE")]
-    filepath: PathBuf,
+    filepath: Option<PathBuf>,
+    #[clap(flatten)]
+    limits: EvalLimitArgs,
+    /// Print each reduction step of the main expression, with the redex underlined
+    #[clap(long)]
+    steps: bool,
+    /// Only print the steps which reduce the whole expression
+    #[clap(long, requires = "steps")]
+    top_level: bool,
+    /// Stop after N reduction steps
+    #[clap(long, value_name = "N", requires = "steps")]
+    stop_after: Option<usize>,
+    /// Print the reduction steps as JSON
+    #[clap(long, requires = "steps")]
+    json: bool,
+    /// The evaluator which computes the value of `main`
+    #[clap(long, value_enum, default_value_t = Backend::Nbe, conflicts_with = "steps")]
+    backend: Backend,
+}
+
+/// The evaluators which can be selected with `pol run --backend`
+#[derive(clap::ValueEnum, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    /// The normalizer of the typechecker, which computes the normal form of `main`
+    Nbe,
+    /// The interpreter for the erased intermediate representation
+    Ir,
 }
 
 pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
-    let uri = db.resolve_path(&cmd.filepath)?;
+    let filepath = entry_point(cmd.filepath)?;
+    let mut db = Database::from_path(&filepath)?;
+    db.eval_limits = cmd.limits.limits();
+    let uri = db.resolve_path(&filepath)?;
+    if cmd.steps {
+        let steps = db.run_steps(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+        let steps = steps.ok_or(MainNotFound {})?;
+        let trace = StepOptions { top_level: cmd.top_level, stop_after: cmd.stop_after };
+        let res = if cmd.json { trace.print_json(steps) } else { trace.print(steps) };
+        return res.map_err(|err| db.pretty_error(&uri, err.into()));
+    }
+    if cmd.backend == Backend::Ir {
+        let val = db.run_ir(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+        print_nf(&val.ok_or(MainNotFound {})?);
+        return Ok(());
+    }
     let nf = db.run(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
 
     match nf {
@@ -24,12 +69,73 @@ pub async fn exec(cmd: Args) -> miette::Result<()> {
     Ok(())
 }
 
-fn print_nf(nf: &ast::Exp) {
+fn print_nf<T: Print>(nf: &T) {
     let mut stream = StandardStream::stdout(ColorChoice::Auto);
     nf.print_colored(&Default::default(), &mut stream).expect("Failed to print to stdout");
     println!();
 }
 
+/// The options of `pol run --steps`
+struct StepOptions {
+    top_level: bool,
+    stop_after: Option<usize>,
+}
+
+impl StepOptions {
+    /// The steps to print, numbered from 1
+    fn shown_steps<'a>(
+        &'a self,
+        steps: &'a mut Steps,
+    ) -> impl Iterator<Item = Result<(usize, Step), Box<TypeError>>> + 'a {
+        steps
+            .take(self.stop_after.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(i, step)| step.map(|step| (i + 1, step)))
+            .filter(|step| {
+                !self.top_level || step.as_ref().map_or(true, |(_, step)| step.top_level)
+            })
+    }
+
+    fn print(&self, mut steps: Steps) -> Result<(), Box<TypeError>> {
+        let mut stream = StandardStream::stdout(ColorChoice::Auto);
+        let cfg = PrintCfg { highlight: Some(REDEX_SPAN), ..Default::default() };
+        for step in self.shown_steps(&mut steps) {
+            let (number, step) = step?;
+            print!("{number:>3} {:<8}", step.rule.to_string());
+            step.exp.print_colored(&cfg, &mut stream).expect("Failed to print to stdout");
+            println!();
+        }
+        print_nf(steps.exp());
+        if let Some(stop_after) = self.stop_after {
+            if !steps.is_normal()? {
+                eprintln!("Stopped after step {stop_after}");
+            }
+        }
+        Ok(())
+    }
+
+    fn print_json(&self, mut steps: Steps) -> Result<(), Box<TypeError>> {
+        let mut trace = vec![];
+        for step in self.shown_steps(&mut steps) {
+            let (number, step) = step?;
+            trace.push(serde_json::json!({
+                "step": number,
+                "rule": step.rule.to_string(),
+                "top_level": step.top_level,
+                "exp": step.exp.print_to_string(None),
+                "redex": step.redex.print_to_string(None),
+            }));
+        }
+        let output = serde_json::json!({
+            "steps": trace,
+            "result": steps.exp().print_to_string(None),
+            "normal_form": steps.is_normal()?,
+        });
+        println!("{output:#}");
+        Ok(())
+    }
+}
+
 #[derive(Error, Diagnostic, Debug)]
 #[error("Main expression was not found")]
 #[diagnostic(help("Main expressions must be called \"main\" and not take any arguments."))]
@@ -74,7 +74,7 @@ fn compute_output_stream(cmd: &Args) -> Box<dyn io::Write> {
 }
 
 pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
+    let mut db = Database::from_path(&cmd.filepath)?;
     let uri = db.resolve_path(&cmd.filepath)?;
     let prg = db.ust(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
 
@@ -90,6 +90,7 @@ pub async fn exec(cmd: Args) -> miette::Result<()> {
         print_function_sugar: !cmd.omit_function_sugar,
         print_metavar_ids: false,
         print_metavar_args: false,
+        highlight: None,
     };
 
     stream.write_all(latex_start(&cmd.fontsize).as_bytes()).unwrap();
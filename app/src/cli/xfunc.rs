@@ -15,7 +15,7 @@ pub struct Args {
 }
 
 pub async fn exec(cmd: Args) -> miette::Result<()> {
-    let mut db = Database::from_path(&cmd.filepath);
+    let mut db = Database::from_path(&cmd.filepath)?;
     let uri = db.resolve_path(&cmd.filepath)?;
     let Xfunc { edits, .. } = db.xfunc(&uri, &cmd.r#type).await.map_err(miette::Report::msg)?;
 
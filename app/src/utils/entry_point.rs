@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use miette::Diagnostic;
+use thiserror::Error;
+
+use driver::Manifest;
+
+/// The file given on the command line, or else the entry module of the project in the current directory
+pub fn entry_point(filepath: Option<PathBuf>) -> miette::Result<PathBuf> {
+    if let Some(filepath) = filepath {
+        return Ok(filepath);
+    }
+    let cwd = std::env::current_dir().expect("Could not get current directory");
+    let entry = Manifest::discover(cwd)?.and_then(|manifest| manifest.entry);
+    match entry {
+        Some(entry) => Ok(entry.to_file_path().expect("Could not convert URI to path")),
+        None => Err(miette::Report::from(EntryNotFound {})),
+    }
+}
+
+#[derive(Error, Diagnostic, Debug)]
+#[error("No file was given and no entry module was found")]
+#[diagnostic(help(
+    "Either pass a file, or declare `entry` in the `[package]` section of a `polarity.toml`."
+))]
+pub struct EntryNotFound {}
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use driver::EvalLimits;
+
+/// The options which limit the evaluation performed by the normalizer
+#[derive(clap::Args)]
+pub struct EvalLimitArgs {
+    /// The maximal number of evaluation steps per declaration and for `main`; 0 means no limit.
+    /// Unlimited by default, except for `pol lsp`, which stops after 100000 steps
+    #[clap(long, value_name = "N")]
+    max_steps: Option<u64>,
+    /// The maximal time in milliseconds spent evaluating per declaration and for `main`
+    #[clap(long, value_name = "MS")]
+    timeout: Option<u64>,
+}
+
+impl EvalLimitArgs {
+    /// The limits given by the options, where evaluation is unlimited unless specified
+    pub fn limits(&self) -> EvalLimits {
+        self.limits_with_default(None)
+    }
+
+    /// The limits given by the options, where the number of steps is limited by `max_steps`
+    /// unless specified
+    pub fn limits_with_default(&self, max_steps: Option<u64>) -> EvalLimits {
+        EvalLimits {
+            max_steps: self.max_steps.or(max_steps).filter(|steps| *steps > 0),
+            timeout: self.timeout.map(Duration::from_millis),
+        }
+    }
+}
@@ -1 +1,4 @@
+#[cfg(not(target_arch = "wasm32"))]
+pub mod entry_point;
+pub mod eval_limits;
 pub mod ignore_colors;
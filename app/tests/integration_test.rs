@@ -28,6 +28,18 @@ fn check_command_2() {
     assert.success().stdout("../examples/encoding_church.pol typechecked successfully!\n");
 }
 
+/// Check that "pol check --max-steps" limits the evaluation during typechecking
+#[test]
+fn check_command_max_steps() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["check", "--max-steps", "3", "../test/suites/success/037-vect.pol"];
+    let output = cmd.env("NO_COLOR", "1").args(args).output().unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(stderr.contains("T-028"));
+    assert!(stderr.contains("Evaluation did not finish within 3 steps"));
+}
+
 /// Check that "pol run" works correctly
 #[test]
 fn run_command() {
@@ -38,3 +50,106 @@ fn run_command() {
         .success()
         .stdout("Cons(S(S(S(Z))), Z, Cons(S(S(Z)), Z, Cons(S(Z), Z, Cons(Z, Z, Nil))))\n");
 }
+
+/// Check that "pol repl" works correctly
+#[test]
+fn repl_command() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let input = "use \"std/data/nat.pol\"\nlet two: Nat { 2 }\n:type two.add(1)\n:normalize 2.add(1)\n:quit\n";
+    let assert = cmd.env("NO_COLOR", "1").arg("repl").write_stdin(input).assert();
+    assert
+        .success()
+        .stdout("Type :help for a list of commands.\n> > > two.add(1) : Nat\n> S(S(S(Z)))\n> ");
+}
+
+/// Check that errors in expressions entered in "pol repl" refer to the expression
+#[test]
+fn repl_command_error() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let input = "use \"std/data/nat.pol\"\n:type 2.add(foo)\n";
+    let output = cmd.env("NO_COLOR", "1").arg("repl").write_stdin(input).output().unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Undefined identifier foo"));
+    assert!(stderr.contains("[<expression>:1:7]"));
+}
+
+/// Check that holes in expressions entered in "pol repl" must be solved
+#[test]
+fn repl_command_unsolved_hole() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let input = "use \"std/data/list.pol\"\n:type Nil(_)\n";
+    let output = cmd.env("NO_COLOR", "1").arg("repl").write_stdin(input).output().unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("The metavariable _0 could not be solved"));
+}
+
+/// Check that "pol run --max-steps" stops the evaluation of a diverging program
+#[test]
+fn run_command_max_steps() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "--max-steps", "1000", "../test/suites/success/056-diverging-main.pol"];
+    let output = cmd.env("NO_COLOR", "1").args(args).output().unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(!output.status.success());
+    assert!(stderr.contains("T-028"));
+    assert!(stderr.contains("Evaluation did not finish within 1000 steps"));
+}
+
+/// Check that "pol run" does not limit the number of evaluation steps by default
+#[test]
+fn run_command_unlimited_steps() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "--backend", "ir", "../test/suites/success/065-many-steps.pol"];
+    let assert = cmd.env("NO_COLOR", "1").args(args).assert();
+    assert.success().stdout("GT\n");
+}
+
+/// Check that "pol run" does not evaluate arguments which are not used
+#[test]
+fn run_command_call_by_need() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "../test/suites/success/057-call-by-need.pol"];
+    let assert = cmd.env("NO_COLOR", "1").args(args).assert();
+    assert.success().stdout("S(Z)\n");
+}
+
+/// Check that "pol run" evaluates the argument of a function once, even if it is used twice
+#[test]
+fn run_command_sharing() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "--max-steps", "100", "../test/suites/success/064-sharing.pol"];
+    let assert = cmd.env("NO_COLOR", "1").args(args).assert();
+    assert.success().stdout("S(Z)\n");
+}
+
+/// Check that "pol run --backend ir" evaluates the main expression with the IR interpreter
+#[test]
+fn run_command_backend_ir() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "--backend", "ir", "../test/suites/success/037-vect.pol"];
+    let assert = cmd.env("NO_COLOR", "1").args(args).assert();
+    assert
+        .success()
+        .stdout("Cons(S(S(S(Z))), Z, Cons(S(S(Z)), Z, Cons(S(Z), Z, Cons(Z, Z, Nil))))\n");
+}
+
+/// Check that "pol run --backend ir" prints values which are too deep to be traversed recursively
+#[test]
+fn run_command_backend_ir_deep_value() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "--backend", "ir", "../test/suites/success/062-deep-value.pol"];
+    let assert = cmd.env("NO_COLOR", "1").args(args).assert();
+    let stdout = String::from_utf8(assert.success().get_output().stdout.clone()).unwrap();
+    assert_eq!(stdout.matches('S').count(), 10000);
+}
+
+/// Check that "pol run --steps" prints the reduction steps of the main expression
+#[test]
+fn run_command_steps() {
+    let mut cmd = Command::cargo_bin(BINARY).unwrap();
+    let args = vec!["run", "--steps", "--stop-after", "2", "../test/suites/success/037-vect.pol"];
+    let assert = cmd.env("NO_COLOR", "1").args(args).assert();
+    assert.success().stdout(
+        "  1 match   Unit.example1.append(S(S(Z)), S(S(Z)), Unit.example1)\n  2 match   Cons(S(Z), Z, Cons(Z, Z, Nil)).append(S(S(Z)), S(S(Z)), Unit.example1)\nCons(S(Z), Z, Cons(Z, Z, Nil)).append(S(S(Z)), S(S(Z)), Cons(S(Z), Z, Cons(Z, Z, Nil)))\n",
+    );
+}
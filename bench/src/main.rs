@@ -1,10 +1,23 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use std::path::{Path, PathBuf};
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
 use driver::{Database, FileSource, InMemorySource};
 use url::Url;
 
 const EXAMPLE_STLC: &str = include_str!("../../examples/stlc.pol");
 const EXAMPLE_STRONG_EX: &str = include_str!("../../examples/strong_existentials.pol");
 
+/// Expressions which are evaluated in the scope of `examples/stlc.pol`
+///
+/// The example imports the functions of `std/data/nat.pol`, such as `fact`, and defines `cmp`.
+const STLC_EXPRESSIONS: &[(&str, &str)] = &[
+    ("fact", "5.fact"),
+    ("fact_twice", "4.fact.add(4.fact)"),
+    ("mul_zero", "Z.mul(6.fact)"),
+    ("monus", "6.fact.monus(5.fact)"),
+    ("cmp", "5.fact.cmp(4.fact.mul(5))"),
+];
+
 fn benchmark_stlc(c: &mut Criterion) {
     c.bench_function("stlc.pol", |b| b.iter(|| run(EXAMPLE_STLC)));
 }
@@ -13,12 +26,42 @@ fn benchmark_strong_existentials(c: &mut Criterion) {
     c.bench_function("strong_existentials.pol", |b| b.iter(|| run(EXAMPLE_STRONG_EX)));
 }
 
+/// Typecheck every example, which includes the normalization during conversion checking
+fn benchmark_examples(c: &mut Criterion) {
+    let mut group = c.benchmark_group("check");
+    for path in examples() {
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &path, |b, path| {
+            b.iter(|| block_on(check_file(path)))
+        });
+    }
+    group.finish();
+}
+
+/// Evaluate the main expression of the tutorial
+fn benchmark_run(c: &mut Criterion) {
+    let path = examples_dir().join("tutorial.pol");
+    c.bench_function("run/tutorial.pol", |b| b.iter(|| block_on(run_file(&path))));
+}
+
+/// Evaluate expressions in the scope of `examples/stlc.pol`, which share the values of arguments
+fn benchmark_normalize(c: &mut Criterion) {
+    let mut group = c.benchmark_group("normalize");
+    let path = examples_dir().join("stlc.pol");
+    for (name, exp) in STLC_EXPRESSIONS {
+        group.bench_with_input(BenchmarkId::from_parameter(name), exp, |b, exp| {
+            b.iter(|| block_on(normalize(&path, exp)).unwrap())
+        });
+    }
+    group.finish();
+}
+
 fn run(example: &str) -> miette::Result<()> {
-    tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-        .unwrap()
-        .block_on(run_async(example))
+    block_on(run_async(example))
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap().block_on(future)
 }
 
 async fn run_async(example: &str) -> miette::Result<()> {
@@ -30,5 +73,48 @@ async fn run_async(example: &str) -> miette::Result<()> {
     Ok(())
 }
 
-criterion_group!(benches, benchmark_stlc, benchmark_strong_existentials);
+async fn check_file(path: &Path) -> miette::Result<()> {
+    let mut db = Database::from_path(path)?;
+    let uri = db.resolve_path(path)?;
+    db.ast(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+    Ok(())
+}
+
+async fn run_file(path: &Path) -> miette::Result<()> {
+    let mut db = Database::from_path(path)?;
+    let uri = db.resolve_path(path)?;
+    db.run(&uri).await.map_err(|err| db.pretty_error(&uri, err))?;
+    Ok(())
+}
+
+async fn normalize(path: &Path, exp: &str) -> miette::Result<()> {
+    let mut db = Database::from_path(path)?;
+    let uri = db.resolve_path(path)?;
+    db.normalize_expr(&uri, exp).await.map_err(|err| db.pretty_expr_error(exp, err))?;
+    Ok(())
+}
+
+fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples")
+}
+
+/// The paths of all examples, in alphabetical order
+fn examples() -> Vec<PathBuf> {
+    let mut paths: Vec<_> = std::fs::read_dir(examples_dir())
+        .expect("Failed to read the examples directory")
+        .map(|entry| entry.expect("Failed to read the examples directory").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "pol"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+criterion_group!(
+    benches,
+    benchmark_stlc,
+    benchmark_strong_existentials,
+    benchmark_examples,
+    benchmark_run,
+    benchmark_normalize
+);
 criterion_main!(benches);
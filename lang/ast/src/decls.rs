@@ -3,6 +3,7 @@ use miette_util::codespan::Span;
 use pretty::DocAllocator;
 use printer::print_comma_separated;
 use printer::theme::ThemeExt;
+use printer::tokens::AS;
 use printer::tokens::CODATA;
 use printer::tokens::CODEF;
 use printer::tokens::COLON;
@@ -12,10 +13,15 @@ use printer::tokens::DATA;
 use printer::tokens::DEF;
 use printer::tokens::DOT;
 use printer::tokens::HASH;
-use printer::tokens::IMPLICIT;
+use printer::tokens::HASH_BANG;
+use printer::tokens::HIDING;
 use printer::tokens::INFIX;
+use printer::tokens::INFIXL;
+use printer::tokens::INFIXR;
 use printer::tokens::LET;
+use printer::tokens::PUB;
 use printer::tokens::USE;
+use printer::tokens::{ERASED, IMPLICIT};
 use printer::util::BracesExt;
 use printer::util::IsNilExt;
 use printer::Alloc;
@@ -78,6 +84,14 @@ pub enum Attribute {
     Transparent,
     /// An opaque let-binding is not expanded during normalization.
     Opaque,
+    /// Recursive definitions with this annotation are exempt from the termination check.
+    NonTerminating,
+    /// Data and codata types with this annotation are exempt from the strict positivity check.
+    NoPositivityCheck,
+    /// Modules with this annotation use a predicative hierarchy of universes instead of `Type : Type`.
+    Predicative,
+    /// Declarations with this annotation are not visible to the modules which import them.
+    Private,
     /// The compiler does not know about the meaning of this annotation.
     Other(String),
 }
@@ -88,6 +102,10 @@ impl Print for Attribute {
             Attribute::OmitPrint => alloc.text("omit_print"),
             Attribute::Opaque => alloc.text("opaque"),
             Attribute::Transparent => alloc.text("transparent"),
+            Attribute::NonTerminating => alloc.text("non_terminating"),
+            Attribute::NoPositivityCheck => alloc.text("no_positivity_check"),
+            Attribute::Predicative => alloc.text("predicative"),
+            Attribute::Private => alloc.text("private"),
             Attribute::Other(s) => alloc.text(s),
         }
     }
@@ -168,17 +186,67 @@ impl MetaVarState {
 ///
 /// ```text
 /// use "Data/Bool.pol"
+/// use "Data/List.pol" as L
+/// use "Data/List.pol" (List, Cons, Nil)
+/// use "Data/Nat.pol" hiding (add)
+/// pub use "Data/Bool.pol"
 /// ```
 #[derive(Debug, Clone)]
 pub struct UseDecl {
     pub span: Span,
+    /// Whether the names of the module are re-exported to the importers of this module
+    pub public: bool,
     pub path: String,
+    /// If present, the names of the module are only in scope qualified by the alias
+    pub alias: Option<IdBind>,
+    /// Restricts the names of the module which are brought into scope
+    pub filter: Option<ImportFilter>,
 }
 
 impl Print for UseDecl {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let UseDecl { public, path, alias, filter, .. } = self;
+        let doc = if *public { alloc.text(PUB).append(alloc.space()) } else { alloc.nil() };
+        let doc = doc
+            .append(alloc.text(USE))
+            .append(alloc.space())
+            .append(alloc.text(path).double_quotes());
+        let doc = match alias {
+            Some(alias) => doc
+                .append(alloc.space())
+                .append(alloc.keyword(AS))
+                .append(alloc.space())
+                .append(alloc.text(&alias.id)),
+            None => doc,
+        };
+        match filter {
+            Some(filter) => doc.append(alloc.space()).append(filter.print(cfg, alloc)),
+            None => doc,
+        }
+    }
+}
+
+/// The list of names in a use declaration
+#[derive(Debug, Clone)]
+pub enum ImportFilter {
+    /// Only import the given names: `(A, B)`
+    Only(Vec<IdBind>),
+    /// Import all names except the given ones: `hiding (A, B)`
+    Hiding(Vec<IdBind>),
+}
+
+impl Print for ImportFilter {
     fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let UseDecl { path, .. } = self;
-        alloc.text(USE).append(alloc.space()).append(alloc.text(path).double_quotes())
+        let print_items = |items: &'a [IdBind]| {
+            let sep = alloc.text(COMMA).append(alloc.space());
+            alloc.intersperse(items.iter().map(|item| alloc.text(&item.id)), sep).parens()
+        };
+        match self {
+            ImportFilter::Only(items) => print_items(items),
+            ImportFilter::Hiding(items) => {
+                alloc.keyword(HIDING).append(alloc.space()).append(print_items(items))
+            }
+        }
     }
 }
 
@@ -189,6 +257,8 @@ impl Print for UseDecl {
 pub struct Module {
     /// The location of the module on disk
     pub uri: Url,
+    /// Attributes of the module, written as `#![attr1,attr2]` at the top of the module.
+    pub attr: Attributes,
     /// List of module imports at the top of a module.
     pub use_decls: Vec<UseDecl>,
     /// Declarations contained in the module other than imports.
@@ -198,20 +268,21 @@ pub struct Module {
 }
 
 impl Module {
+    /// Checks whether the `#![predicative]` attribute is present.
+    pub fn is_predicative(&self) -> bool {
+        self.attr.attrs.contains(&Attribute::Predicative)
+    }
+
     pub fn xdefs_for_type(&self, type_name: &str) -> Vec<IdBind> {
         let mut out = vec![];
 
         for decl in &self.decls {
             match decl {
-                Decl::Def(def) => {
-                    if def.self_param.typ.name.id == type_name {
-                        out.push(def.name.clone());
-                    }
+                Decl::Def(def) if def.self_param.typ.name.id == type_name => {
+                    out.push(def.name.clone());
                 }
-                Decl::Codef(codef) => {
-                    if codef.typ.name.id == type_name {
-                        out.push(codef.name.clone());
-                    }
+                Decl::Codef(codef) if codef.typ.name.id == type_name => {
+                    out.push(codef.name.clone());
                 }
                 _ => {}
             }
@@ -225,18 +296,14 @@ impl Module {
 
         for decl in &self.decls {
             match decl {
-                Decl::Data(data) => {
-                    if data.name.id == type_name {
-                        for ctor in &data.ctors {
-                            out.push(ctor.name.clone());
-                        }
+                Decl::Data(data) if data.name.id == type_name => {
+                    for ctor in &data.ctors {
+                        out.push(ctor.name.clone());
                     }
                 }
-                Decl::Codata(codata) => {
-                    if codata.name.id == type_name {
-                        for dtor in &codata.dtors {
-                            out.push(dtor.name.clone());
-                        }
+                Decl::Codata(codata) if codata.name.id == type_name => {
+                    for dtor in &codata.dtors {
+                        out.push(dtor.name.clone());
                     }
                 }
                 _ => {}
@@ -270,7 +337,18 @@ impl Module {
 
 impl Print for Module {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Module { use_decls, decls, .. } = self;
+        let Module { attr, use_decls, decls, .. } = self;
+
+        // Attributes
+        //
+        //
+
+        let attr = if attr.attrs.is_empty() {
+            alloc.nil()
+        } else {
+            let p = print_comma_separated(&attr.attrs, cfg, alloc);
+            alloc.text(HASH_BANG).append(p.brackets()).append(alloc.hardline()).append(alloc.line())
+        };
 
         // UseDecls
         //
@@ -297,9 +375,9 @@ impl Print for Module {
         //
 
         if use_decls.is_nil() {
-            alloc.intersperse(decls, sep)
+            attr.append(alloc.intersperse(decls, sep))
         } else {
-            use_decls
+            attr.append(use_decls)
                 .append(alloc.line())
                 .append(alloc.line())
                 .append(alloc.intersperse(decls, sep))
@@ -471,21 +549,53 @@ pub struct Infix {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub attr: Attributes,
+    pub fixity: Fixity,
     pub lhs: String,
     pub rhs: String,
 }
 
 impl Print for Infix {
     fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Infix { lhs, rhs, .. } = self;
+        let Infix { fixity, lhs, rhs, .. } = self;
+        let keyword = match fixity.associativity {
+            Associativity::Left => INFIXL,
+            Associativity::Right => INFIXR,
+            Associativity::None => INFIX,
+        };
         alloc
-            .keyword(INFIX)
-            .append(format!(" _ {} _ ", lhs))
+            .keyword(keyword)
+            .append(format!(" {} _ {} _ ", fixity.precedence, lhs))
             .append(COLONEQ)
             .append(format!(" {}(_,_)", rhs))
     }
 }
 
+/// The precedence and associativity of an infix operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Fixity {
+    /// Operators with a higher precedence bind more tightly
+    pub precedence: u32,
+    pub associativity: Associativity,
+}
+
+impl Fixity {
+    /// The precedence of operators declared without an explicit precedence
+    pub const DEFAULT_PRECEDENCE: u32 = 9;
+    /// The highest precedence an operator can be declared with
+    pub const MAX_PRECEDENCE: u32 = 9;
+}
+
+/// The associativity of an infix operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Associativity {
+    /// `a + b + c` means `(a + b) + c`
+    Left,
+    /// `a -> b -> c` means `a -> (b -> c)`
+    Right,
+    /// `a == b == c` is ambiguous
+    None,
+}
+
 impl ContainsMetaVars for Infix {
     fn contains_metavars(&self) -> bool {
         false
@@ -796,6 +906,8 @@ pub struct Def {
     pub self_param: SelfParam,
     pub ret_typ: Box<Exp>,
     pub cases: Vec<Case>,
+    /// The catch-all clause, until the typechecker has expanded it to cases
+    pub catch_all: Option<CatchAll>,
 }
 
 impl Def {
@@ -813,7 +925,7 @@ impl Def {
 
 impl Print for Def {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Def { span: _, doc, name, attr, params, self_param, ret_typ, cases } = self;
+        let Def { span: _, doc, name, attr, params, self_param, ret_typ, cases, catch_all } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -830,7 +942,7 @@ impl Print for Def {
             .append(print_return_type(cfg, alloc, ret_typ))
             .group();
 
-        let body = print_cases(cases, cfg, alloc);
+        let body = print_cases(cases, catch_all.as_ref(), cfg, alloc);
 
         doc.append(head).append(alloc.space()).append(body)
     }
@@ -838,25 +950,47 @@ impl Print for Def {
 
 impl Zonk for Def {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Def { span: _, doc: _, name: _, attr: _, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span: _,
+            doc: _,
+            name: _,
+            attr: _,
+            params,
+            self_param,
+            ret_typ,
+            cases,
+            catch_all,
+        } = self;
         params.zonk(meta_vars)?;
         self_param.zonk(meta_vars)?;
         ret_typ.zonk(meta_vars)?;
         for case in cases {
             case.zonk(meta_vars)?;
         }
+        catch_all.zonk(meta_vars)?;
         Ok(())
     }
 }
 
 impl ContainsMetaVars for Def {
     fn contains_metavars(&self) -> bool {
-        let Def { span: _, doc: _, name: _, attr: _, params, self_param, ret_typ, cases } = self;
+        let Def {
+            span: _,
+            doc: _,
+            name: _,
+            attr: _,
+            params,
+            self_param,
+            ret_typ,
+            cases,
+            catch_all,
+        } = self;
 
         params.contains_metavars()
             || self_param.contains_metavars()
             || ret_typ.contains_metavars()
             || cases.contains_metavars()
+            || catch_all.contains_metavars()
     }
 }
 
@@ -866,6 +1000,7 @@ impl Rename for Def {
         ctx.bind_iter(self.params.params.iter(), |new_ctx| {
             self.self_param.rename_in_ctx(new_ctx);
             self.cases.rename_in_ctx(new_ctx);
+            self.catch_all.rename_in_ctx(new_ctx);
 
             new_ctx.bind_single(&self.self_param, |new_ctx| self.ret_typ.rename_in_ctx(new_ctx))
         })
@@ -885,6 +1020,8 @@ pub struct Codef {
     pub params: Telescope,
     pub typ: TypCtor,
     pub cases: Vec<Case>,
+    /// The catch-all clause, until the typechecker has expanded it to cases
+    pub catch_all: Option<CatchAll>,
 }
 
 impl Codef {
@@ -901,7 +1038,7 @@ impl Codef {
 
 impl Print for Codef {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Codef { span: _, doc, name, attr, params, typ, cases } = self;
+        let Codef { span: _, doc, name, attr, params, typ, cases, catch_all } = self;
         if !attr.is_visible() {
             return alloc.nil();
         }
@@ -920,7 +1057,7 @@ impl Print for Codef {
             ))
             .group();
 
-        let body = print_cases(cases, cfg, alloc);
+        let body = print_cases(cases, catch_all.as_ref(), cfg, alloc);
 
         doc.append(head).append(alloc.space()).append(body)
     }
@@ -928,21 +1065,25 @@ impl Print for Codef {
 
 impl Zonk for Codef {
     fn zonk(&mut self, meta_vars: &HashMap<MetaVar, MetaVarState>) -> Result<(), crate::ZonkError> {
-        let Codef { span: _, doc: _, name: _, attr: _, params, typ, cases } = self;
+        let Codef { span: _, doc: _, name: _, attr: _, params, typ, cases, catch_all } = self;
         params.zonk(meta_vars)?;
         typ.zonk(meta_vars)?;
         for case in cases {
             case.zonk(meta_vars)?;
         }
+        catch_all.zonk(meta_vars)?;
         Ok(())
     }
 }
 
 impl ContainsMetaVars for Codef {
     fn contains_metavars(&self) -> bool {
-        let Codef { span: _, doc: _, name: _, attr: _, params, typ, cases } = self;
+        let Codef { span: _, doc: _, name: _, attr: _, params, typ, cases, catch_all } = self;
 
-        params.contains_metavars() || typ.contains_metavars() || cases.contains_metavars()
+        params.contains_metavars()
+            || typ.contains_metavars()
+            || cases.contains_metavars()
+            || catch_all.contains_metavars()
     }
 }
 
@@ -953,6 +1094,7 @@ impl Rename for Codef {
         ctx.bind_iter(self.params.params.iter(), |new_ctx| {
             self.typ.rename_in_ctx(new_ctx);
             self.cases.rename_in_ctx(new_ctx);
+            self.catch_all.rename_in_ctx(new_ctx);
         })
     }
 }
@@ -1156,24 +1298,27 @@ impl Print for Telescope {
     ///
     /// 2) We cannot chunk two parameters if one is implicit and the other isn't, even if they have
     ///    the same type. For example: `implicit a: Type, b: Type` cannot be chunked.
+    ///    The same holds for erased parameters, as in `erased x: Nat, y: Nat`.
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
         let Telescope { params } = self;
         let mut output = alloc.nil();
         if params.is_empty() {
             return output;
         };
-        // Running stands for the type and implicitness of the current "chunk" we are building.
-        let mut running: Option<(&Exp, bool)> = None;
-        for Param { implicit, name, typ, erased: _ } in params {
+        // Running stands for the type, implicitness and erasure of the current "chunk" we are building.
+        let mut running: Option<(&Exp, bool, bool)> = None;
+        for Param { implicit, name, typ, erased } in params {
             match running {
                 // We need to shift before comparing to ensure we compare the correct De-Bruijn indices
-                Some((rtype, rimplicit))
-                    if shift_and_clone(rtype, (0, 1)) == **typ && rimplicit == *implicit =>
+                Some((rtype, rimplicit, rerased))
+                    if shift_and_clone(rtype, (0, 1)) == **typ
+                        && rimplicit == *implicit
+                        && rerased == *erased =>
                 {
                     // We are adding another parameter of the same type.
                     output = output.append(alloc.space()).append(name.print(cfg, alloc));
                 }
-                Some((rtype, _)) => {
+                Some((rtype, _, _)) => {
                     // We are adding another parameter with a different type,
                     // and have to close the previous list first.
                     output = output
@@ -1181,33 +1326,25 @@ impl Print for Telescope {
                         .append(alloc.space())
                         .append(rtype.print(cfg, alloc))
                         .append(COMMA)
-                        .append(alloc.line());
-                    if *implicit {
-                        output = output
-                            .append(IMPLICIT)
-                            .append(alloc.space())
-                            .append(name.print(cfg, alloc));
-                    } else {
-                        output = output.append(name.print(cfg, alloc));
-                    }
+                        .append(alloc.line())
+                        .append(print_modifiers(*implicit, *erased, alloc))
+                        .append(name.print(cfg, alloc));
                 }
                 None => {
                     // We are starting a new chunk and adding the very first parameter.
-                    // If we are starting a chunk of implicit parameters then we also have to
-                    // add the "implicit" keyword at this point.
-                    if *implicit {
-                        output = output.append(IMPLICIT).append(alloc.space())
-                    }
-
-                    output = output.append(name.print(cfg, alloc));
+                    // If we are starting a chunk of implicit or erased parameters then we also have to
+                    // add the "implicit" or "erased" keyword at this point.
+                    output = output
+                        .append(print_modifiers(*implicit, *erased, alloc))
+                        .append(name.print(cfg, alloc));
                 }
             }
-            running = Some((typ, *implicit));
+            running = Some((typ, *implicit, *erased));
         }
         // Close the last parameter
         match running {
             None => {}
-            Some((rtype, _)) => {
+            Some((rtype, _, _)) => {
                 output = output.append(COLON).append(alloc.space()).append(rtype.print(cfg, alloc));
             }
         }
@@ -1332,6 +1469,42 @@ mod print_telescope_tests {
         assert_eq!(tele.print_to_string(Default::default()), "(x: Type, implicit y: Type)")
     }
 
+    #[test]
+    fn print_simple_erased_chunk() {
+        let param1 = Param {
+            implicit: true,
+            name: VarBind::from_string("x"),
+            typ: Box::new(TypeUniv::new().into()),
+            erased: true,
+        };
+        let param2 = Param {
+            implicit: true,
+            name: VarBind::from_string("y"),
+            typ: Box::new(TypeUniv::new().into()),
+            erased: true,
+        };
+        let tele = Telescope { params: vec![param1, param2] };
+        assert_eq!(tele.print_to_string(Default::default()), "(implicit erased x y: Type)")
+    }
+
+    #[test]
+    fn print_mixed_erased_chunk() {
+        let param1 = Param {
+            implicit: false,
+            name: VarBind::from_string("x"),
+            typ: Box::new(TypeUniv::new().into()),
+            erased: true,
+        };
+        let param2 = Param {
+            implicit: false,
+            name: VarBind::from_string("y"),
+            typ: Box::new(TypeUniv::new().into()),
+            erased: false,
+        };
+        let tele = Telescope { params: vec![param1, param2] };
+        assert_eq!(tele.print_to_string(Default::default()), "(erased x: Type, y: Type)")
+    }
+
     #[test]
     fn print_shifting_example() {
         let param1 = Param {
@@ -1378,7 +1551,9 @@ pub struct Param {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub name: VarBind,
     pub typ: Box<Exp>,
-    /// Whether the parameter is erased during compilation.
+    /// Whether the parameter is annotated with the "erased" keyword.
+    /// Such parameters may only be used in erased positions and are erased during compilation,
+    /// in addition to the parameters which are erased because of their type.
     pub erased: bool,
 }
 
@@ -1397,19 +1572,25 @@ impl Substitutable for Param {
 
 impl Print for Param {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Param { implicit, name, typ, erased: _ } = self;
-        if *implicit {
-            alloc
-                .text(IMPLICIT)
-                .append(alloc.space())
-                .append(name.print(cfg, alloc))
-                .append(COLON)
-                .append(alloc.space())
-                .append(typ.print(cfg, alloc))
-        } else {
-            name.print(cfg, alloc).append(COLON).append(alloc.space()).append(typ.print(cfg, alloc))
-        }
+        let Param { implicit, name, typ, erased } = self;
+        print_modifiers(*implicit, *erased, alloc)
+            .append(name.print(cfg, alloc))
+            .append(COLON)
+            .append(alloc.space())
+            .append(typ.print(cfg, alloc))
+    }
+}
+
+/// Print the keywords `implicit` and `erased` which precede the name of a parameter
+fn print_modifiers<'a>(implicit: bool, erased: bool, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    let mut output = alloc.nil();
+    if implicit {
+        output = output.append(IMPLICIT).append(alloc.space());
+    }
+    if erased {
+        output = output.append(ERASED).append(alloc.space());
     }
+    output
 }
 
 impl Zonk for Param {
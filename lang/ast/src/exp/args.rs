@@ -3,7 +3,7 @@ use miette_util::codespan::Span;
 use pretty::DocAllocator;
 use printer::{
     tokens::{COLONEQ, COMMA},
-    Alloc, Builder, Print, PrintCfg,
+    Alloc, Builder, Precedence, Print, PrintCfg,
 };
 
 use crate::{
@@ -121,9 +121,14 @@ impl Substitutable for Arg {
 }
 
 impl Print for Arg {
-    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
         match self {
-            Arg::UnnamedArg { arg, .. } => arg.print(cfg, alloc),
+            Arg::UnnamedArg { arg, .. } => arg.print_prec(cfg, alloc, prec),
             Arg::NamedArg { name: var, arg, .. } => {
                 alloc.text(&var.id).append(COLONEQ).append(arg.print(cfg, alloc))
             }
@@ -296,6 +301,7 @@ mod args_tests {
                     span: None,
                     id: "T".to_owned(),
                     uri: Url::parse("inmemory:///scratch.pol").unwrap(),
+                    qualifier: None,
                 },
                 args: Args { args: vec![] },
                 inferred_type: None,
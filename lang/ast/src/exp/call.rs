@@ -102,7 +102,7 @@ impl Print for Call {
         _prec: Precedence,
     ) -> Builder<'a> {
         let Call { name, args, .. } = self;
-        alloc.ctor(&name.id).append(args.print(cfg, alloc))
+        alloc.ctor(&name.qualified_id()).append(args.print(cfg, alloc))
     }
 }
 
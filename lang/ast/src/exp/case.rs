@@ -3,7 +3,7 @@ use miette_util::codespan::Span;
 use pretty::DocAllocator;
 use printer::{
     theme::ThemeExt,
-    tokens::{ABSURD, COMMA, DOT, FAT_ARROW},
+    tokens::{ABSURD, COMMA, DOT, FAT_ARROW, UNDERSCORE},
     util::BracesExt,
     Alloc, Builder, Print, PrintCfg,
 };
@@ -15,7 +15,7 @@ use crate::{
     ZonkError,
 };
 
-use super::{Exp, IdBound, MetaVar, TelescopeInst};
+use super::{Exp, IdBound, LocalLet, MetaVar, TelescopeInst, VarBind};
 
 // Pattern
 //
@@ -36,7 +36,7 @@ impl Print for Pattern {
         if *is_copattern {
             alloc.text(DOT).append(alloc.ctor(&name.id)).append(params.print(cfg, alloc))
         } else {
-            alloc.ctor(&name.id).append(params.print(cfg, alloc))
+            alloc.ctor(&name.qualified_id()).append(params.print(cfg, alloc))
         }
     }
 }
@@ -63,6 +63,11 @@ pub struct Case {
     pub pattern: Pattern,
     /// Body being `None` represents an absurd pattern
     pub body: Option<Box<Exp>>,
+    /// The name of the catch-all clause `x => e` or `_ => e` from which the typechecker expanded
+    /// this case, if any. If the name is a variable, then the body binds it to the matched value
+    /// by a local let.
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub expanded_from: Option<VarBind>,
 }
 
 impl Shift for Case {
@@ -86,7 +91,7 @@ impl Occurs for Case {
 impl Substitutable for Case {
     type Target = Case;
     fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
-        let Case { span, pattern, body } = self;
+        let Case { span, pattern, body, expanded_from } = self;
         ctx.bind_iter(pattern.params.params.iter(), |ctx| {
             Ok(Case {
                 span: *span,
@@ -99,6 +104,7 @@ impl Substitutable for Case {
                         body.subst(ctx, &by)
                     })
                     .transpose()?,
+                expanded_from: expanded_from.clone(),
             })
         })
     }
@@ -111,7 +117,7 @@ pub fn empty_braces<'a>(alloc: &'a Alloc<'a>) -> Builder<'a> {
 
 impl Print for Case {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Case { span: _, pattern, body } = self;
+        let Case { span: _, pattern, body, expanded_from: _ } = self;
 
         let body = match body {
             None => alloc.keyword(ABSURD),
@@ -131,20 +137,45 @@ impl Zonk for Case {
         &mut self,
         meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
     ) -> Result<(), ZonkError> {
-        let Case { span: _, pattern, body } = self;
+        let Case { span: _, pattern, body, expanded_from: _ } = self;
         pattern.zonk(meta_vars)?;
         body.zonk(meta_vars)?;
         Ok(())
     }
 }
 
-pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-    match cases.len() {
+/// Print the cases of a (co)pattern match together with its catch-all clause, if any
+///
+/// The cases which the typechecker expanded from a catch-all clause are printed as that clause.
+pub fn print_cases<'a>(
+    cases: &'a [Case],
+    catch_all: Option<&'a CatchAll>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let expanded = cases.iter().filter(|case| case.expanded_from.is_some());
+    let catch_all = match catch_all {
+        Some(catch_all) => Some(catch_all.print(cfg, alloc)),
+        // A case for which the clause is not absurd shows its body.
+        None => expanded
+            .clone()
+            .find(|case| case.body.is_some())
+            .or(expanded.clone().next())
+            .map(|case| print_expanded(case, cfg, alloc)),
+    };
+    let mut docs: Vec<_> = cases
+        .iter()
+        .filter(|case| case.expanded_from.is_none())
+        .map(|case| case.print(cfg, alloc))
+        .chain(catch_all)
+        .collect();
+
+    match docs.len() {
         0 => empty_braces(alloc),
 
         1 => alloc
             .line()
-            .append(cases[0].print(cfg, alloc))
+            .append(docs.remove(0))
             .nest(cfg.indent)
             .append(alloc.line())
             .braces_anno()
@@ -153,7 +184,7 @@ pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>)
             let sep = alloc.text(COMMA).append(alloc.hardline());
             alloc
                 .hardline()
-                .append(alloc.intersperse(cases.iter().map(|x| x.print(cfg, alloc)), sep))
+                .append(alloc.intersperse(docs, sep))
                 .append(alloc.text(COMMA).flat_alt(alloc.nil()))
                 .nest(cfg.indent)
                 .append(alloc.hardline())
@@ -162,9 +193,132 @@ pub fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>)
     }
 }
 
+/// Print a case which was expanded from a catch-all clause as that clause
+fn print_expanded<'a>(case: &'a Case, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+    match (&case.expanded_from, &case.body) {
+        (Some(VarBind::Var { .. }), Some(body)) => match &**body {
+            Exp::LocalLet(LocalLet { name, body, .. }) => {
+                print_catch_all(&name.to_string(), Some(body), cfg, alloc)
+            }
+            body => print_catch_all(UNDERSCORE, Some(body), cfg, alloc),
+        },
+        (Some(VarBind::Var { id, .. }), None) => print_catch_all(id, None, cfg, alloc),
+        (_, body) => print_catch_all(UNDERSCORE, body.as_deref(), cfg, alloc),
+    }
+}
+
+fn print_catch_all<'a>(
+    name: &str,
+    body: Option<&'a Exp>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let body = match body {
+        None => alloc.keyword(ABSURD),
+        Some(body) => alloc
+            .text(FAT_ARROW)
+            .append(alloc.line())
+            .append(body.print(cfg, alloc))
+            .nest(cfg.indent),
+    };
+    alloc.text(name.to_owned()).append(alloc.space()).append(body).group()
+}
+
+// CatchAll
+//
+//
+
+/// A catch-all clause `x => e` or `_ => e` of a (co)pattern match, which matches every constructor
+/// or destructor without a case
+///
+/// The typechecker expands the clause to a case for each of them, see [`Case::expanded_from`].
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Eq, PartialEq, Hash)]
+pub struct CatchAll {
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub span: Option<Span>,
+    /// The name of the matched value, which is bound as the only binder of a telescope in the body.
+    /// In a copattern match, there is no matched value and the name is a wildcard.
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub name: VarBind,
+    /// Body being `None` represents an absurd clause
+    pub body: Option<Box<Exp>>,
+}
+
+impl Shift for CatchAll {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.body.shift_in_range(&range.clone().shift(1), by);
+    }
+}
+
+impl Occurs for CatchAll {
+    fn occurs<F>(&self, ctx: &mut LevelCtx, f: &F) -> bool
+    where
+        F: Fn(&LevelCtx, &Exp) -> bool,
+    {
+        let CatchAll { name, body, .. } = self;
+        ctx.bind_single(name.clone(), |ctx| body.as_ref().is_some_and(|b| b.occurs(ctx, f)))
+    }
+}
+
+impl Substitutable for CatchAll {
+    type Target = CatchAll;
+    fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
+        let CatchAll { span, name, body } = self;
+        ctx.bind_single(name.clone(), |ctx| {
+            Ok(CatchAll {
+                span: *span,
+                name: name.clone(),
+                body: body
+                    .as_ref()
+                    .map(|body| {
+                        let mut by = (*by).clone();
+                        by.shift((1, 0));
+                        body.subst(ctx, &by)
+                    })
+                    .transpose()?,
+            })
+        })
+    }
+}
+
+impl Print for CatchAll {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let CatchAll { span: _, name, body } = self;
+        print_catch_all(&name.to_string(), body.as_deref(), cfg, alloc)
+    }
+}
+
+impl Zonk for CatchAll {
+    fn zonk(
+        &mut self,
+        meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+    ) -> Result<(), ZonkError> {
+        self.body.zonk(meta_vars)
+    }
+}
+
+impl ContainsMetaVars for CatchAll {
+    fn contains_metavars(&self) -> bool {
+        self.body.contains_metavars()
+    }
+}
+
+impl Rename for CatchAll {
+    fn rename_in_ctx(&mut self, ctx: &mut RenameCtx) {
+        // A wildcard is kept, since a copattern match has no matched value to name.
+        if let VarBind::Var { .. } = self.name {
+            self.name = ctx.disambiguate_var_bind(self.name.clone());
+        }
+        ctx.bind_single(self.name.clone(), |new_ctx| {
+            self.body.rename_in_ctx(new_ctx);
+        })
+    }
+}
+
 impl ContainsMetaVars for Case {
     fn contains_metavars(&self) -> bool {
-        let Case { span: _, pattern: _, body } = self;
+        let Case { span: _, pattern: _, body, expanded_from: _ } = self;
 
         body.contains_metavars()
     }
@@ -1,7 +1,9 @@
 use derivative::Derivative;
 use miette_util::codespan::Span;
 use pretty::DocAllocator;
-use printer::{theme::ThemeExt, tokens::DOT, Alloc, Builder, Precedence, Print, PrintCfg};
+use printer::{
+    theme::ThemeExt, tokens::DOT, Alloc, Builder, Precedence, Print, PrintCfg, PREC_ATOMIC,
+};
 
 use crate::{
     ctx::LevelCtx,
@@ -132,20 +134,27 @@ impl Print for DotCall {
         // First DotCall
         dtors_group = alloc
             .text(DOT)
-            .append(alloc.dtor(&self.name.id))
+            .append(alloc.dtor(&self.name.qualified_id()))
             .append(self.args.print(cfg, alloc))
             .append(dtors_group);
 
         // Remaining DotCalls
         let mut dtor: &Exp = &self.exp;
-        while let Exp::DotCall(DotCall { exp, name, args, .. }) = &dtor {
+        while let Exp::DotCall(DotCall { span, exp, name, args, .. }) = &dtor {
+            // A highlighted destructor call is printed on its own, such that it can be annotated
+            if cfg.highlight.is_some() && *span == cfg.highlight {
+                break;
+            }
             let psubst = if args.is_empty() { alloc.nil() } else { args.print(cfg, alloc) };
             dtors_group = alloc.line_().append(dtors_group);
-            dtors_group =
-                alloc.text(DOT).append(alloc.dtor(&name.id)).append(psubst).append(dtors_group);
+            dtors_group = alloc
+                .text(DOT)
+                .append(alloc.dtor(&name.qualified_id()))
+                .append(psubst)
+                .append(dtors_group);
             dtor = exp;
         }
-        dtor.print(cfg, alloc).append(dtors_group.align().group())
+        dtor.print_prec(cfg, alloc, PREC_ATOMIC).append(dtors_group.align().group())
     }
 }
 
@@ -0,0 +1,304 @@
+use derivative::Derivative;
+use miette_util::codespan::Span;
+use pretty::DocAllocator;
+use printer::{
+    theme::ThemeExt,
+    tokens::{COMMA, LEVEL, MAX, PLUS},
+    Alloc, Builder, Precedence, Print, PrintCfg,
+};
+
+use crate::{
+    ctx::LevelCtx,
+    rename::{Rename, RenameCtx},
+    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable, Substitution,
+    Zonk, ZonkError,
+};
+
+use super::{Exp, MetaVar, TypeUniv};
+
+// LevelUniv
+//
+//
+
+/// The sort "Level" of universe levels.
+/// Parameters of this sort bind level variables which can be used in universes such as `Type l`.
+/// Universe levels are only available in modules with the `#![predicative]` attribute.
+/// We have `Level : Type 0`.
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Eq, PartialEq, Hash)]
+pub struct LevelUniv {
+    /// Source code location
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub span: Option<Span>,
+}
+
+impl HasSpan for LevelUniv {
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl From<LevelUniv> for Exp {
+    fn from(val: LevelUniv) -> Self {
+        Exp::LevelUniv(val)
+    }
+}
+
+impl Shift for LevelUniv {
+    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+}
+
+impl HasType for LevelUniv {
+    fn typ(&self) -> Option<Box<Exp>> {
+        Some(Box::new(TypeUniv::with_level(Level::constant(0).into()).into()))
+    }
+}
+
+impl Substitutable for LevelUniv {
+    type Target = LevelUniv;
+
+    fn subst<S: Substitution>(&self, _ctx: &mut LevelCtx, _by: &S) -> Result<Self::Target, S::Err> {
+        Ok(self.clone())
+    }
+}
+
+impl Print for LevelUniv {
+    fn print_prec<'a>(
+        &'a self,
+        _cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        _prec: Precedence,
+    ) -> Builder<'a> {
+        alloc.keyword(LEVEL)
+    }
+}
+
+impl Zonk for LevelUniv {
+    fn zonk(
+        &mut self,
+        _meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+    ) -> Result<(), ZonkError> {
+        Ok(())
+    }
+}
+
+impl ContainsMetaVars for LevelUniv {
+    fn contains_metavars(&self) -> bool {
+        false
+    }
+}
+
+impl Rename for LevelUniv {
+    fn rename_in_ctx(&mut self, _ctx: &mut RenameCtx) {}
+}
+
+// Level
+//
+//
+
+/// A universe level `max(n, l₁ + k₁, ..., lₘ + kₘ)`.
+/// The summands `lᵢ` are expressions of type `Level`, usually level variables.
+///
+/// Most functions on levels expect the level to be canonical, see [Level::canonicalize].
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Eq, PartialEq, Hash)]
+pub struct Level {
+    /// Source code location
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub span: Option<Span>,
+    /// The constant lower bound `n`
+    pub constant: usize,
+    /// The summands `lᵢ` together with their offsets `kᵢ`
+    pub summands: Vec<(Box<Exp>, usize)>,
+}
+
+impl Level {
+    /// The constant level `n`
+    pub fn constant(n: usize) -> Level {
+        Level { span: None, constant: n, summands: vec![] }
+    }
+
+    /// View an expression of type `Level` as a canonical level
+    pub fn from_exp(exp: &Exp) -> Level {
+        match exp {
+            Exp::Level(level) => level.canonicalize(),
+            _ => {
+                Level { span: exp.span(), constant: 0, summands: vec![(Box::new(exp.clone()), 0)] }
+            }
+        }
+    }
+
+    /// The level `self + n`
+    pub fn add(&self, n: usize) -> Level {
+        let Level { span, constant, summands } = self;
+        let summands = summands.iter().map(|(exp, k)| (exp.clone(), k + n)).collect();
+        Level { span: *span, constant: constant + n, summands }.canonicalize()
+    }
+
+    /// The level `max(self, other)`
+    pub fn max(&self, other: &Level) -> Level {
+        let mut summands = self.summands.clone();
+        summands.extend(other.summands.iter().cloned());
+        Level { span: self.span, constant: self.constant.max(other.constant), summands }
+            .canonicalize()
+    }
+
+    /// Flatten nested levels, merge duplicate summands and drop the constant if it is redundant
+    pub fn canonicalize(&self) -> Level {
+        let mut constant = self.constant;
+        let mut summands: Vec<(Box<Exp>, usize)> = vec![];
+        for (exp, k) in self.summands.iter() {
+            let flattened = match &**exp {
+                Exp::Level(level) => level.canonicalize().add(*k),
+                _ => Level { span: None, constant: 0, summands: vec![(exp.clone(), *k)] },
+            };
+            constant = constant.max(flattened.constant);
+            for (exp, k) in flattened.summands {
+                match summands.iter_mut().find(|(other, _)| *other == exp) {
+                    Some((_, other_k)) => *other_k = (*other_k).max(k),
+                    None => summands.push((exp, k)),
+                }
+            }
+        }
+        // Every summand `l + k` is at least `k`.
+        if summands.iter().any(|(_, k)| *k >= constant) {
+            constant = 0;
+        }
+        Level { span: self.span, constant, summands }
+    }
+
+    /// Whether `self ≤ other` holds for all values of the summands
+    ///
+    /// Both levels must be canonical.
+    pub fn leq(&self, other: &Level) -> bool {
+        let other_min = other.summands.iter().map(|(_, k)| *k).fold(other.constant, usize::max);
+        self.constant <= other_min
+            && self.summands.iter().all(|(exp, k)| {
+                other.summands.iter().any(|(other_exp, other_k)| exp == other_exp && k <= other_k)
+            })
+    }
+
+    /// Whether the level is printed without parentheses in `Type l`
+    pub fn is_atomic(&self) -> bool {
+        match self.summands.as_slice() {
+            [] => true,
+            [(_, 0)] => self.constant == 0,
+            _ => false,
+        }
+    }
+
+    /// Convert the level to an expression, collapsing levels which consist of a single summand
+    pub fn into_exp(self) -> Exp {
+        match self.summands.as_slice() {
+            [(exp, 0)] if self.constant == 0 => (**exp).clone(),
+            _ => Exp::Level(self),
+        }
+    }
+}
+
+impl HasSpan for Level {
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl From<Level> for Exp {
+    fn from(val: Level) -> Self {
+        Exp::Level(val)
+    }
+}
+
+impl Shift for Level {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        for (exp, _) in self.summands.iter_mut() {
+            exp.shift_in_range(range, by);
+        }
+    }
+}
+
+impl Occurs for Level {
+    fn occurs<F>(&self, ctx: &mut LevelCtx, f: &F) -> bool
+    where
+        F: Fn(&LevelCtx, &Exp) -> bool,
+    {
+        self.summands.iter().any(|(exp, _)| exp.occurs(ctx, f))
+    }
+}
+
+impl HasType for Level {
+    fn typ(&self) -> Option<Box<Exp>> {
+        Some(Box::new(LevelUniv { span: None }.into()))
+    }
+}
+
+impl Substitutable for Level {
+    type Target = Exp;
+
+    fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
+        let Level { span, constant, summands } = self;
+        let summands = summands
+            .iter()
+            .map(|(exp, k)| Ok((exp.subst(ctx, by)?, *k)))
+            .collect::<Result<_, _>>()?;
+        Ok(Level { span: *span, constant: *constant, summands }.canonicalize().into_exp())
+    }
+}
+
+impl Print for Level {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        _prec: Precedence,
+    ) -> Builder<'a> {
+        let Level { constant, summands, .. } = self;
+        let print_summand = |(exp, k): &'a (Box<Exp>, usize)| {
+            if *k == 0 {
+                exp.print(cfg, alloc)
+            } else {
+                exp.print(cfg, alloc)
+                    .append(alloc.space())
+                    .append(PLUS)
+                    .append(alloc.space())
+                    .append(k.to_string())
+            }
+        };
+        match summands.as_slice() {
+            [] => alloc.text(constant.to_string()),
+            [summand] if *constant == 0 => print_summand(summand),
+            _ => {
+                let constant = (*constant > 0).then(|| alloc.text(constant.to_string()));
+                let args = constant.into_iter().chain(summands.iter().map(print_summand));
+                alloc.keyword(MAX).append(
+                    alloc.intersperse(args, alloc.text(COMMA).append(alloc.space())).parens(),
+                )
+            }
+        }
+    }
+}
+
+impl Zonk for Level {
+    fn zonk(
+        &mut self,
+        meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+    ) -> Result<(), ZonkError> {
+        for (exp, _) in self.summands.iter_mut() {
+            exp.zonk(meta_vars)?;
+        }
+        Ok(())
+    }
+}
+
+impl ContainsMetaVars for Level {
+    fn contains_metavars(&self) -> bool {
+        self.summands.iter().any(|(exp, _)| exp.contains_metavars())
+    }
+}
+
+impl Rename for Level {
+    fn rename_in_ctx(&mut self, ctx: &mut RenameCtx) {
+        for (exp, _) in self.summands.iter_mut() {
+            exp.rename_in_ctx(ctx);
+        }
+    }
+}
@@ -15,7 +15,7 @@ use crate::{
     Zonk, ZonkError,
 };
 
-use super::{print_cases, Case, Exp, Label, MetaVar, TypCtor};
+use super::{print_cases, Case, CatchAll, Exp, Label, MetaVar, TypCtor};
 
 #[derive(Debug, Clone, Derivative)]
 #[derivative(Eq, PartialEq, Hash)]
@@ -27,6 +27,8 @@ pub struct LocalComatch {
     pub name: Label,
     pub is_lambda_sugar: bool,
     pub cases: Vec<Case>,
+    /// The catch-all clause, until the typechecker has expanded it to cases
+    pub catch_all: Option<CatchAll>,
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub inferred_type: Option<TypCtor>,
 }
@@ -47,6 +49,7 @@ impl Shift for LocalComatch {
     fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
         self.ctx = None;
         self.cases.shift_in_range(range, by);
+        self.catch_all.shift_in_range(range, by);
         self.inferred_type = None;
     }
 }
@@ -56,8 +59,9 @@ impl Occurs for LocalComatch {
     where
         F: Fn(&LevelCtx, &Exp) -> bool,
     {
-        let LocalComatch { cases, .. } = self;
+        let LocalComatch { cases, catch_all, .. } = self;
         cases.iter().any(|case| case.occurs(ctx, f))
+            || catch_all.as_ref().is_some_and(|c| c.occurs(ctx, f))
     }
 }
 
@@ -71,13 +75,14 @@ impl Substitutable for LocalComatch {
     type Target = LocalComatch;
 
     fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
-        let LocalComatch { span, name, is_lambda_sugar, cases, .. } = self;
+        let LocalComatch { span, name, is_lambda_sugar, cases, catch_all, .. } = self;
         Ok(LocalComatch {
             span: *span,
             ctx: None,
             name: name.clone(),
             is_lambda_sugar: *is_lambda_sugar,
             cases: cases.iter().map(|case| case.subst(ctx, by)).collect::<Result<Vec<_>, _>>()?,
+            catch_all: catch_all.as_ref().map(|c| c.subst(ctx, by)).transpose()?,
             inferred_type: None,
         })
     }
@@ -88,7 +93,7 @@ impl Substitutable for LocalComatch {
 /// one cocase "ap" with three arguments; the function will
 /// panic otherwise.
 fn print_lambda_sugar<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-    let Case { span: _, pattern, body } =
+    let Case { span: _, pattern, body, expanded_from: _ } =
         cases.first().expect("Empty comatch marked as lambda sugar");
 
     let body = match body {
@@ -108,11 +113,17 @@ impl Print for LocalComatch {
         &'a self,
         cfg: &PrintCfg,
         alloc: &'a Alloc<'a>,
-        _prec: Precedence,
+        prec: Precedence,
     ) -> Builder<'a> {
-        let LocalComatch { name, is_lambda_sugar, cases, .. } = self;
+        let LocalComatch { name, is_lambda_sugar, cases, catch_all, .. } = self;
         if *is_lambda_sugar && cfg.print_lambda_sugar {
-            print_lambda_sugar(cases, cfg, alloc)
+            let doc = print_lambda_sugar(cases, cfg, alloc);
+            // The body of a lambda extends as far to the right as possible
+            if prec > 0 {
+                doc.align().parens()
+            } else {
+                doc
+            }
         } else {
             alloc
                 .keyword(COMATCH)
@@ -121,7 +132,7 @@ impl Print for LocalComatch {
                     None => alloc.nil(),
                 })
                 .append(alloc.space())
-                .append(print_cases(cases, cfg, alloc))
+                .append(print_cases(cases, catch_all.as_ref(), cfg, alloc))
         }
     }
 }
@@ -131,22 +142,39 @@ impl Zonk for LocalComatch {
         &mut self,
         meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
     ) -> Result<(), ZonkError> {
-        let LocalComatch { span: _, ctx: _, name: _, is_lambda_sugar: _, cases, inferred_type } =
-            self;
+        let LocalComatch {
+            span: _,
+            ctx: _,
+            name: _,
+            is_lambda_sugar: _,
+            cases,
+            catch_all,
+            inferred_type,
+        } = self;
         inferred_type.zonk(meta_vars)?;
         for case in cases {
             case.zonk(meta_vars)?;
         }
+        catch_all.zonk(meta_vars)?;
         Ok(())
     }
 }
 
 impl ContainsMetaVars for LocalComatch {
     fn contains_metavars(&self) -> bool {
-        let LocalComatch { span: _, ctx: _, name: _, is_lambda_sugar: _, cases, inferred_type } =
-            self;
-
-        cases.contains_metavars() || inferred_type.contains_metavars()
+        let LocalComatch {
+            span: _,
+            ctx: _,
+            name: _,
+            is_lambda_sugar: _,
+            cases,
+            catch_all,
+            inferred_type,
+        } = self;
+
+        cases.contains_metavars()
+            || catch_all.contains_metavars()
+            || inferred_type.contains_metavars()
     }
 }
 
@@ -155,5 +183,6 @@ impl Rename for LocalComatch {
         self.ctx = None;
         self.inferred_type = None;
         self.cases.rename_in_ctx(ctx);
+        self.catch_all.rename_in_ctx(ctx);
     }
 }
@@ -0,0 +1,178 @@
+use derivative::Derivative;
+use miette_util::codespan::Span;
+use pretty::DocAllocator;
+use printer::{
+    theme::ThemeExt,
+    tokens::{COLON, COLONEQ, LET, SEMICOLON},
+    Alloc, Builder, Precedence, Print, PrintCfg,
+};
+
+use crate::{
+    ctx::{BindContext, LevelCtx},
+    rename::{Rename, RenameCtx},
+    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, ShiftRangeExt, Substitutable,
+    Substitution, VarBind, Zonk, ZonkError,
+};
+
+use super::{Exp, MetaVar};
+
+/// Local let binding `let x : t := e; body`
+///
+/// The variable `x` is bound as a one-element telescope in `body`.
+#[derive(Debug, Clone, Derivative)]
+#[derivative(Eq, PartialEq, Hash)]
+pub struct LocalLet {
+    /// Source code location
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub span: Option<Span>,
+    /// The bound variable, i.e. `x` in `let x : t := e; body`
+    pub name: VarBind,
+    /// The optional type annotation, i.e. `t` in `let x : t := e; body`
+    pub typ: Option<Box<Exp>>,
+    /// The bound expression, i.e. `e` in `let x : t := e; body`
+    pub bound: Box<Exp>,
+    /// The body in which `x` is bound
+    pub body: Box<Exp>,
+    /// The type of the bound expression, which is inferred during elaboration if no annotation is given
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub bound_type: Option<Box<Exp>>,
+    /// The inferred type of the whole expression
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub inferred_type: Option<Box<Exp>>,
+}
+
+impl HasSpan for LocalLet {
+    fn span(&self) -> Option<Span> {
+        self.span
+    }
+}
+
+impl From<LocalLet> for Exp {
+    fn from(val: LocalLet) -> Self {
+        Exp::LocalLet(val)
+    }
+}
+
+impl Shift for LocalLet {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.typ.shift_in_range(range, by);
+        self.bound.shift_in_range(range, by);
+        self.body.shift_in_range(&range.clone().shift(1), by);
+        self.bound_type = None;
+        self.inferred_type = None;
+    }
+}
+
+impl Occurs for LocalLet {
+    fn occurs<F>(&self, ctx: &mut LevelCtx, f: &F) -> bool
+    where
+        F: Fn(&LevelCtx, &Exp) -> bool,
+    {
+        let LocalLet { name, typ, bound, body, .. } = self;
+        typ.as_ref().is_some_and(|typ| typ.occurs(ctx, f))
+            || bound.occurs(ctx, f)
+            || ctx.bind_single(name.clone(), |ctx| body.occurs(ctx, f))
+    }
+}
+
+impl HasType for LocalLet {
+    fn typ(&self) -> Option<Box<Exp>> {
+        self.inferred_type.clone()
+    }
+}
+
+impl Substitutable for LocalLet {
+    type Target = LocalLet;
+
+    fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
+        let LocalLet { span, name, typ, bound, body, .. } = self;
+        Ok(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ.as_ref().map(|typ| typ.subst(ctx, by)).transpose()?,
+            bound: bound.subst(ctx, by)?,
+            body: ctx.bind_single(name.clone(), |ctx| {
+                let mut by = (*by).clone();
+                by.shift((1, 0));
+                body.subst(ctx, &by)
+            })?,
+            bound_type: None,
+            inferred_type: None,
+        })
+    }
+}
+
+impl Print for LocalLet {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
+        let LocalLet { name, typ, bound, body, .. } = self;
+        let typ = match typ {
+            Some(typ) => {
+                alloc.space().append(COLON).append(alloc.space()).append(typ.print(cfg, alloc))
+            }
+            None => alloc.nil(),
+        };
+        let doc = alloc
+            .keyword(LET)
+            .append(alloc.space())
+            .append(name.to_string())
+            .append(typ)
+            .append(alloc.space())
+            .append(COLONEQ)
+            .append(alloc.line().append(bound.print(cfg, alloc)).nest(cfg.indent))
+            .append(SEMICOLON)
+            .group()
+            .append(alloc.hardline())
+            .append(body.print(cfg, alloc));
+        // The body of a let binding extends as far to the right as possible.
+        if prec > 0 {
+            doc.align().parens()
+        } else {
+            doc
+        }
+    }
+}
+
+impl Zonk for LocalLet {
+    fn zonk(
+        &mut self,
+        meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+    ) -> Result<(), ZonkError> {
+        let LocalLet { span: _, name: _, typ, bound, body, bound_type, inferred_type } = self;
+        typ.zonk(meta_vars)?;
+        bound.zonk(meta_vars)?;
+        body.zonk(meta_vars)?;
+        bound_type.zonk(meta_vars)?;
+        inferred_type.zonk(meta_vars)?;
+        Ok(())
+    }
+}
+
+impl ContainsMetaVars for LocalLet {
+    fn contains_metavars(&self) -> bool {
+        let LocalLet { span: _, name: _, typ, bound, body, bound_type, inferred_type } = self;
+
+        typ.contains_metavars()
+            || bound.contains_metavars()
+            || body.contains_metavars()
+            || bound_type.contains_metavars()
+            || inferred_type.contains_metavars()
+    }
+}
+
+impl Rename for LocalLet {
+    fn rename_in_ctx(&mut self, ctx: &mut RenameCtx) {
+        self.typ.rename_in_ctx(ctx);
+        self.bound.rename_in_ctx(ctx);
+        self.bound_type.rename_in_ctx(ctx);
+        self.inferred_type.rename_in_ctx(ctx);
+        self.name = ctx.disambiguate_var_bind(self.name.clone());
+        ctx.bind_single(self.name.clone(), |new_ctx| {
+            self.body.rename_in_ctx(new_ctx);
+        })
+    }
+}
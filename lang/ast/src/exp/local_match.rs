@@ -4,7 +4,7 @@ use pretty::DocAllocator;
 use printer::{
     theme::ThemeExt,
     tokens::{DOT, MATCH},
-    Alloc, Builder, Precedence, Print, PrintCfg,
+    Alloc, Builder, Precedence, Print, PrintCfg, PREC_ATOMIC,
 };
 
 use crate::{
@@ -14,7 +14,7 @@ use crate::{
     Zonk, ZonkError,
 };
 
-use super::{print_cases, Case, Exp, Label, MetaVar, Motive, TypCtor};
+use super::{print_cases, Case, CatchAll, Exp, Label, MetaVar, Motive, TypCtor};
 
 #[derive(Debug, Clone, Derivative)]
 #[derivative(Eq, PartialEq, Hash)]
@@ -29,6 +29,8 @@ pub struct LocalMatch {
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub ret_typ: Option<Box<Exp>>,
     pub cases: Vec<Case>,
+    /// The catch-all clause, until the typechecker has expanded it to cases
+    pub catch_all: Option<CatchAll>,
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub inferred_type: Option<TypCtor>,
 }
@@ -52,6 +54,7 @@ impl Shift for LocalMatch {
         self.motive.shift_in_range(range, by);
         self.ret_typ = None;
         self.cases.shift_in_range(range, by);
+        self.catch_all.shift_in_range(range, by);
         self.inferred_type = None;
     }
 }
@@ -61,11 +64,12 @@ impl Occurs for LocalMatch {
     where
         F: Fn(&LevelCtx, &Exp) -> bool,
     {
-        let LocalMatch { on_exp, motive, ret_typ, cases, .. } = self;
+        let LocalMatch { on_exp, motive, ret_typ, cases, catch_all, .. } = self;
         on_exp.occurs(ctx, f)
             || motive.as_ref().is_some_and(|m| m.occurs(ctx, f))
             || ret_typ.as_ref().is_some_and(|t| t.occurs(ctx, f))
             || cases.iter().any(|case| case.occurs(ctx, f))
+            || catch_all.as_ref().is_some_and(|c| c.occurs(ctx, f))
     }
 }
 
@@ -78,7 +82,7 @@ impl HasType for LocalMatch {
 impl Substitutable for LocalMatch {
     type Target = LocalMatch;
     fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
-        let LocalMatch { span, name, on_exp, motive, ret_typ, cases, .. } = self;
+        let LocalMatch { span, name, on_exp, motive, ret_typ, cases, catch_all, .. } = self;
         Ok(LocalMatch {
             span: *span,
             ctx: None,
@@ -87,6 +91,7 @@ impl Substitutable for LocalMatch {
             motive: motive.as_ref().map(|m| m.subst(ctx, by)).transpose()?,
             ret_typ: ret_typ.as_ref().map(|t| t.subst(ctx, by)).transpose()?,
             cases: cases.iter().map(|case| case.subst(ctx, by)).collect::<Result<Vec<_>, _>>()?,
+            catch_all: catch_all.as_ref().map(|c| c.subst(ctx, by)).transpose()?,
             inferred_type: None,
         })
     }
@@ -99,9 +104,9 @@ impl Print for LocalMatch {
         alloc: &'a Alloc<'a>,
         _prec: Precedence,
     ) -> Builder<'a> {
-        let LocalMatch { name, on_exp, motive, cases, .. } = self;
+        let LocalMatch { name, on_exp, motive, cases, catch_all, .. } = self;
         on_exp
-            .print(cfg, alloc)
+            .print_prec(cfg, alloc, PREC_ATOMIC)
             .append(DOT)
             .append(alloc.keyword(MATCH))
             .append(match &name.user_name {
@@ -110,7 +115,7 @@ impl Print for LocalMatch {
             })
             .append(motive.as_ref().map(|m| m.print(cfg, alloc)).unwrap_or(alloc.nil()))
             .append(alloc.space())
-            .append(print_cases(cases, cfg, alloc))
+            .append(print_cases(cases, catch_all.as_ref(), cfg, alloc))
     }
 }
 
@@ -119,8 +124,17 @@ impl Zonk for LocalMatch {
         &mut self,
         meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
     ) -> Result<(), ZonkError> {
-        let LocalMatch { span: _, ctx: _, name: _, on_exp, motive, ret_typ, cases, inferred_type } =
-            self;
+        let LocalMatch {
+            span: _,
+            ctx: _,
+            name: _,
+            on_exp,
+            motive,
+            ret_typ,
+            cases,
+            catch_all,
+            inferred_type,
+        } = self;
         on_exp.zonk(meta_vars)?;
         motive.zonk(meta_vars)?;
         ret_typ.zonk(meta_vars)?;
@@ -128,19 +142,30 @@ impl Zonk for LocalMatch {
         for case in cases {
             case.zonk(meta_vars)?;
         }
+        catch_all.zonk(meta_vars)?;
         Ok(())
     }
 }
 
 impl ContainsMetaVars for LocalMatch {
     fn contains_metavars(&self) -> bool {
-        let LocalMatch { span: _, ctx: _, name: _, on_exp, motive, ret_typ, cases, inferred_type } =
-            self;
+        let LocalMatch {
+            span: _,
+            ctx: _,
+            name: _,
+            on_exp,
+            motive,
+            ret_typ,
+            cases,
+            catch_all,
+            inferred_type,
+        } = self;
 
         on_exp.contains_metavars()
             || motive.contains_metavars()
             || ret_typ.contains_metavars()
             || cases.contains_metavars()
+            || catch_all.contains_metavars()
             || inferred_type.contains_metavars()
     }
 }
@@ -153,5 +178,6 @@ impl Rename for LocalMatch {
         self.motive.rename_in_ctx(ctx);
         self.ret_typ.rename_in_ctx(ctx);
         self.cases.rename_in_ctx(ctx);
+        self.catch_all.rename_in_ctx(ctx);
     }
 }
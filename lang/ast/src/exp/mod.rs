@@ -23,7 +23,9 @@ mod call;
 mod case;
 mod dot_call;
 mod hole;
+mod level;
 mod local_comatch;
+mod local_let;
 mod local_match;
 mod telescope_inst;
 mod typ_ctor;
@@ -35,7 +37,9 @@ pub use call::*;
 pub use case::*;
 pub use dot_call::*;
 pub use hole::*;
+pub use level::*;
 pub use local_comatch::*;
+pub use local_let::*;
 pub use local_match::*;
 pub use telescope_inst::*;
 pub use typ_ctor::*;
@@ -74,8 +78,11 @@ pub enum Exp {
     DotCall(DotCall),
     Anno(Anno),
     TypeUniv(TypeUniv),
+    LevelUniv(LevelUniv),
+    Level(Level),
     LocalMatch(LocalMatch),
     LocalComatch(LocalComatch),
+    LocalLet(LocalLet),
     Hole(Hole),
 }
 
@@ -97,8 +104,11 @@ impl HasSpan for Exp {
             Exp::DotCall(e) => e.span(),
             Exp::Anno(e) => e.span(),
             Exp::TypeUniv(e) => e.span(),
+            Exp::LevelUniv(e) => e.span(),
+            Exp::Level(e) => e.span(),
             Exp::LocalMatch(e) => e.span(),
             Exp::LocalComatch(e) => e.span(),
+            Exp::LocalLet(e) => e.span(),
             Exp::Hole(e) => e.span(),
         }
     }
@@ -113,8 +123,11 @@ impl Shift for Exp {
             Exp::DotCall(e) => e.shift_in_range(range, by),
             Exp::Anno(e) => e.shift_in_range(range, by),
             Exp::TypeUniv(e) => e.shift_in_range(range, by),
+            Exp::LevelUniv(e) => e.shift_in_range(range, by),
+            Exp::Level(e) => e.shift_in_range(range, by),
             Exp::LocalMatch(e) => e.shift_in_range(range, by),
             Exp::LocalComatch(e) => e.shift_in_range(range, by),
+            Exp::LocalLet(e) => e.shift_in_range(range, by),
             Exp::Hole(e) => e.shift_in_range(range, by),
         }
     }
@@ -137,12 +150,15 @@ impl Occurs for Exp {
             Exp::Call(e) => e.occurs(ctx, f),
             Exp::DotCall(e) => e.occurs(ctx, f),
             Exp::Anno(e) => e.occurs(ctx, f),
-            Exp::TypeUniv(_) => {
-                // The type universe has no subexpressions, therefore the check above is sufficient
+            Exp::TypeUniv(e) => e.occurs(ctx, f),
+            Exp::LevelUniv(_) => {
+                // The sort of levels has no subexpressions, therefore the check above is sufficient
                 false
             }
+            Exp::Level(e) => e.occurs(ctx, f),
             Exp::LocalMatch(e) => e.occurs(ctx, f),
             Exp::LocalComatch(e) => e.occurs(ctx, f),
+            Exp::LocalLet(e) => e.occurs(ctx, f),
             Exp::Hole(e) => e.occurs(ctx, f),
         }
     }
@@ -157,8 +173,11 @@ impl HasType for Exp {
             Exp::DotCall(e) => e.typ(),
             Exp::Anno(e) => e.typ(),
             Exp::TypeUniv(e) => e.typ(),
+            Exp::LevelUniv(e) => e.typ(),
+            Exp::Level(e) => e.typ(),
             Exp::LocalMatch(e) => e.typ(),
             Exp::LocalComatch(e) => e.typ(),
+            Exp::LocalLet(e) => e.typ(),
             Exp::Hole(e) => e.typ(),
         }
     }
@@ -174,8 +193,11 @@ impl Substitutable for Exp {
             Exp::DotCall(e) => Ok(e.subst(ctx, by)?.into()),
             Exp::Anno(e) => Ok(e.subst(ctx, by)?.into()),
             Exp::TypeUniv(e) => Ok(e.subst(ctx, by)?.into()),
+            Exp::LevelUniv(e) => Ok(e.subst(ctx, by)?.into()),
+            Exp::Level(e) => e.subst(ctx, by),
             Exp::LocalMatch(e) => Ok(e.subst(ctx, by)?.into()),
             Exp::LocalComatch(e) => Ok(e.subst(ctx, by)?.into()),
+            Exp::LocalLet(e) => Ok(e.subst(ctx, by)?.into()),
             Exp::Hole(e) => Ok(e.subst(ctx, by)?.into()),
         }
     }
@@ -188,16 +210,24 @@ impl Print for Exp {
         alloc: &'a Alloc<'a>,
         prec: Precedence,
     ) -> Builder<'a> {
-        match self {
+        let doc = match self {
             Exp::Variable(e) => e.print_prec(cfg, alloc, prec),
             Exp::TypCtor(e) => e.print_prec(cfg, alloc, prec),
             Exp::Call(e) => e.print_prec(cfg, alloc, prec),
             Exp::DotCall(e) => e.print_prec(cfg, alloc, prec),
             Exp::Anno(e) => e.print_prec(cfg, alloc, prec),
             Exp::TypeUniv(e) => e.print_prec(cfg, alloc, prec),
+            Exp::LevelUniv(e) => e.print_prec(cfg, alloc, prec),
+            Exp::Level(e) => e.print_prec(cfg, alloc, prec),
             Exp::LocalMatch(e) => e.print_prec(cfg, alloc, prec),
             Exp::LocalComatch(e) => e.print_prec(cfg, alloc, prec),
+            Exp::LocalLet(e) => e.print_prec(cfg, alloc, prec),
             Exp::Hole(e) => e.print_prec(cfg, alloc, prec),
+        };
+        if cfg.highlight.is_some() && cfg.highlight == self.span() {
+            doc.annotate(printer::Anno::Highlight)
+        } else {
+            doc
         }
     }
 }
@@ -214,8 +244,11 @@ impl Zonk for Exp {
             Exp::DotCall(e) => e.zonk(meta_vars),
             Exp::Anno(e) => e.zonk(meta_vars),
             Exp::TypeUniv(e) => e.zonk(meta_vars),
+            Exp::LevelUniv(e) => e.zonk(meta_vars),
+            Exp::Level(e) => e.zonk(meta_vars),
             Exp::LocalMatch(e) => e.zonk(meta_vars),
             Exp::LocalComatch(e) => e.zonk(meta_vars),
+            Exp::LocalLet(e) => e.zonk(meta_vars),
             Exp::Hole(e) => e.zonk(meta_vars),
         }
     }
@@ -230,8 +263,11 @@ impl ContainsMetaVars for Exp {
             Exp::DotCall(dot_call) => dot_call.contains_metavars(),
             Exp::Anno(anno) => anno.contains_metavars(),
             Exp::TypeUniv(type_univ) => type_univ.contains_metavars(),
+            Exp::LevelUniv(level_univ) => level_univ.contains_metavars(),
+            Exp::Level(level) => level.contains_metavars(),
             Exp::LocalMatch(local_match) => local_match.contains_metavars(),
             Exp::LocalComatch(local_comatch) => local_comatch.contains_metavars(),
+            Exp::LocalLet(local_let) => local_let.contains_metavars(),
             Exp::Hole(hole) => hole.contains_metavars(),
         }
     }
@@ -242,10 +278,13 @@ impl Rename for Exp {
         match self {
             Exp::Variable(e) => e.rename_in_ctx(ctx),
             Exp::LocalComatch(e) => e.rename_in_ctx(ctx),
+            Exp::LocalLet(e) => e.rename_in_ctx(ctx),
             Exp::Anno(e) => e.rename_in_ctx(ctx),
             Exp::TypCtor(e) => e.rename_in_ctx(ctx),
             Exp::Hole(e) => e.rename_in_ctx(ctx),
             Exp::TypeUniv(e) => e.rename_in_ctx(ctx),
+            Exp::LevelUniv(e) => e.rename_in_ctx(ctx),
+            Exp::Level(e) => e.rename_in_ctx(ctx),
             Exp::Call(e) => e.rename_in_ctx(ctx),
             Exp::LocalMatch(e) => e.rename_in_ctx(ctx),
             Exp::DotCall(e) => e.rename_in_ctx(ctx),
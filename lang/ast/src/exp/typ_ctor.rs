@@ -6,8 +6,8 @@ use printer::{theme::ThemeExt, Alloc, Builder, Precedence, Print, PrintCfg};
 use crate::{
     ctx::LevelCtx,
     rename::{Rename, RenameCtx},
-    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable, Substitution,
-    Zonk, ZonkError,
+    Associativity, ContainsMetaVars, Fixity, HasSpan, HasType, Occurs, Shift, ShiftRange,
+    Substitutable, Substitution, Zonk, ZonkError,
 };
 
 use super::{Args, Exp, IdBound, MetaVar, TypeUniv};
@@ -27,8 +27,15 @@ pub struct TypCtor {
     pub args: Args,
     /// If this TypCtor has been lowered from a binary operator.
     ///
-    /// If the user has written "->" then we populate this field with `Some("->")`
-    pub is_bin_op: Option<String>,
+    /// If the user has written "->" then we populate this field with the operator `->` and its fixity.
+    pub is_bin_op: Option<BinOp>,
+}
+
+/// A binary operator together with its fixity, used to print type constructors in infix notation
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BinOp {
+    pub operator: String,
+    pub fixity: Fixity,
 }
 
 impl TypCtor {
@@ -98,19 +105,28 @@ impl Print for TypCtor {
     ) -> Builder<'a> {
         let TypCtor { span: _, name, args, is_bin_op } = self;
         match is_bin_op {
-            Some(op) if cfg.print_function_sugar => {
+            Some(BinOp { operator, fixity }) if cfg.print_function_sugar => {
                 assert!(args.len() == 2);
-                let arg = args.args[0].print_prec(cfg, alloc, 1);
-                let res = args.args[1].print_prec(cfg, alloc, 0);
-                let fun = arg.append(alloc.space()).append(op).append(alloc.space()).append(res);
-                if prec == 0 {
-                    fun
+                // Operators of precedence `p` are printed at precedence level `p + 1`,
+                // such that the top-level precedence `0` never requires parentheses.
+                let level = fixity.precedence + 1;
+                let (lhs_prec, rhs_prec) = match fixity.associativity {
+                    Associativity::Left => (level, level + 1),
+                    Associativity::Right => (level + 1, level),
+                    Associativity::None => (level + 1, level + 1),
+                };
+                let lhs = args.args[0].print_prec(cfg, alloc, lhs_prec);
+                let rhs = args.args[1].print_prec(cfg, alloc, rhs_prec);
+                let op =
+                    lhs.append(alloc.space()).append(operator).append(alloc.space()).append(rhs);
+                if prec <= level {
+                    op
                 } else {
-                    fun.parens()
+                    op.parens()
                 }
             }
             _ => alloc
-                .typ(&name.id)
+                .typ(&name.qualified_id())
                 .annotate(printer::Anno::Reference {
                     module_uri: name.uri.to_owned(),
                     name: name.id.clone(),
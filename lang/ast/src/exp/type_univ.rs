@@ -1,32 +1,43 @@
 use derivative::Derivative;
 use miette_util::codespan::Span;
+use pretty::DocAllocator;
 use printer::{theme::ThemeExt, tokens::TYPE, Alloc, Builder, Precedence, Print, PrintCfg};
 
 use crate::{
     ctx::LevelCtx,
     rename::{Rename, RenameCtx},
-    ContainsMetaVars, HasSpan, HasType, Shift, ShiftRange, Substitutable, Substitution, Zonk,
-    ZonkError,
+    ContainsMetaVars, HasSpan, HasType, Occurs, Shift, ShiftRange, Substitutable, Substitution,
+    Zonk, ZonkError,
 };
 
-use super::{Exp, MetaVar};
+use super::{Exp, Level, MetaVar};
 
 /// The impredicative type universe "Type" is used
 /// for typing data and codata types. I.e. we have
 /// - `Nat : Type`
 /// - `Stream(Nat) : Type`
 /// - `Type : Type`
+///
+/// In modules with the `#![predicative]` attribute, every universe carries a level instead.
+/// We then have `Type l : Type (l + 1)`, and `Type l` is a subtype of `Type k` if `l ≤ k`.
 #[derive(Debug, Clone, Derivative)]
 #[derivative(Eq, PartialEq, Hash)]
 pub struct TypeUniv {
     /// Source code location
     #[derivative(PartialEq = "ignore", Hash = "ignore")]
     pub span: Option<Span>,
+    /// The universe level, an expression of type `Level`.
+    /// The impredicative universe does not have a level.
+    pub level: Option<Box<Exp>>,
 }
 
 impl TypeUniv {
     pub fn new() -> TypeUniv {
-        TypeUniv { span: None }
+        TypeUniv { span: None, level: None }
+    }
+
+    pub fn with_level(level: Exp) -> TypeUniv {
+        TypeUniv { span: None, level: Some(Box::new(level)) }
     }
 }
 
@@ -49,32 +60,53 @@ impl Default for TypeUniv {
 }
 
 impl Shift for TypeUniv {
-    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.level.shift_in_range(range, by);
+    }
+}
+
+impl Occurs for TypeUniv {
+    fn occurs<F>(&self, ctx: &mut LevelCtx, f: &F) -> bool
+    where
+        F: Fn(&LevelCtx, &Exp) -> bool,
+    {
+        self.level.as_ref().is_some_and(|level| level.occurs(ctx, f))
+    }
 }
 
 impl HasType for TypeUniv {
     fn typ(&self) -> Option<Box<Exp>> {
-        Some(Box::new(TypeUniv::new().into()))
+        let level = self.level.as_ref().map(|level| Level::from_exp(level).add(1).into_exp());
+        Some(Box::new(TypeUniv { span: None, level: level.map(Box::new) }.into()))
     }
 }
 
 impl Substitutable for TypeUniv {
     type Target = TypeUniv;
 
-    fn subst<S: Substitution>(&self, _ctx: &mut LevelCtx, _by: &S) -> Result<Self::Target, S::Err> {
-        let TypeUniv { span } = self;
-        Ok(TypeUniv { span: *span })
+    fn subst<S: Substitution>(&self, ctx: &mut LevelCtx, by: &S) -> Result<Self::Target, S::Err> {
+        let TypeUniv { span, level } = self;
+        Ok(TypeUniv { span: *span, level: level.subst(ctx, by)? })
     }
 }
 
 impl Print for TypeUniv {
     fn print_prec<'a>(
         &'a self,
-        _cfg: &PrintCfg,
+        cfg: &PrintCfg,
         alloc: &'a Alloc<'a>,
         _prec: Precedence,
     ) -> Builder<'a> {
-        alloc.keyword(TYPE)
+        match &self.level {
+            None => alloc.keyword(TYPE),
+            Some(level) => {
+                let level_doc = match &**level {
+                    Exp::Level(level) if !level.is_atomic() => level.print(cfg, alloc).parens(),
+                    _ => level.print(cfg, alloc),
+                };
+                alloc.keyword(TYPE).append(alloc.space()).append(level_doc)
+            }
+        }
     }
 }
 
@@ -82,19 +114,20 @@ impl Print for TypeUniv {
 impl Zonk for TypeUniv {
     fn zonk(
         &mut self,
-        _meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
+        meta_vars: &crate::HashMap<MetaVar, crate::MetaVarState>,
     ) -> Result<(), ZonkError> {
-        // TypeUniv has no fields that require zonking
-        Ok(())
+        self.level.zonk(meta_vars)
     }
 }
 
 impl ContainsMetaVars for TypeUniv {
     fn contains_metavars(&self) -> bool {
-        false
+        self.level.contains_metavars()
     }
 }
 
 impl Rename for TypeUniv {
-    fn rename_in_ctx(&mut self, _ctx: &mut RenameCtx) {}
+    fn rename_in_ctx(&mut self, ctx: &mut RenameCtx) {
+        self.level.rename_in_ctx(ctx);
+    }
 }
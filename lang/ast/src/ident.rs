@@ -168,6 +168,19 @@ pub struct IdBound {
     pub id: String,
     /// The URI of the module where the identifier was defined
     pub uri: Url,
+    /// The alias of the imported module if the identifier is qualified, as in `L.Cons`
+    #[derivative(PartialEq = "ignore", Hash = "ignore")]
+    pub qualifier: Option<String>,
+}
+
+impl IdBound {
+    /// The identifier as written in the source, including its qualifier
+    pub fn qualified_id(&self) -> String {
+        match &self.qualifier {
+            Some(qualifier) => format!("{qualifier}.{}", self.id),
+            None => self.id.clone(),
+        }
+    }
 }
 
 impl fmt::Display for IdBound {
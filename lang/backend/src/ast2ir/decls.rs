@@ -1,3 +1,6 @@
+use elaborator::typechecker::erasure::{relevance, Relevance};
+use elaborator::TypeInfoTable;
+
 use crate::ir;
 use crate::result::BackendError;
 
@@ -6,18 +9,25 @@ use super::traits::ToIR;
 impl ToIR for ast::Module {
     type Target = ir::Module;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
-        let ast::Module { uri, use_decls, decls, meta_vars: _ } = self;
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
+        let ast::Module { uri, attr: _, use_decls, decls, meta_vars: _ } = self;
 
         let mut def_decls = Vec::new();
         let mut codef_decls = Vec::new();
         let mut let_decls = Vec::new();
 
+        // Declarations which compute types are only used in erased positions and are hence
+        // omitted from the IR.
+        let is_type_level = |typ: &ast::Exp| relevance(typ, info_table) == Relevance::Type;
+
         for decl in decls {
             match decl {
-                ast::Decl::Def(def) => def_decls.push(def.to_ir()?),
-                ast::Decl::Codef(codef) => codef_decls.push(codef.to_ir()?),
-                ast::Decl::Let(tl_let) => let_decls.push(tl_let.to_ir()?),
+                ast::Decl::Def(def) if is_type_level(&def.ret_typ) => {}
+                ast::Decl::Codef(codef) if is_type_level(&codef.typ.to_exp()) => {}
+                ast::Decl::Let(tl_let) if is_type_level(&tl_let.typ) => {}
+                ast::Decl::Def(def) => def_decls.push(def.to_ir(info_table)?),
+                ast::Decl::Codef(codef) => codef_decls.push(codef.to_ir(info_table)?),
+                ast::Decl::Let(tl_let) => let_decls.push(tl_let.to_ir(info_table)?),
                 ast::Decl::Data(_) => {}
                 ast::Decl::Codata(_) => {}
                 ast::Decl::Infix(_) => {}
@@ -37,11 +47,11 @@ impl ToIR for ast::Module {
 impl ToIR for ast::Def {
     type Target = ir::Def;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Def { name, params, cases, .. } = self;
 
-        let params = params.to_ir()?;
-        let cases = cases.to_ir()?;
+        let params = params.to_ir(info_table)?;
+        let cases = cases.to_ir(info_table)?;
 
         Ok(ir::Def { name: name.to_string(), params, cases })
     }
@@ -50,11 +60,11 @@ impl ToIR for ast::Def {
 impl ToIR for ast::Codef {
     type Target = ir::Codef;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Codef { name, params, cases, .. } = self;
 
-        let params = params.to_ir()?;
-        let cases = cases.to_ir()?;
+        let params = params.to_ir(info_table)?;
+        let cases = cases.to_ir(info_table)?;
 
         Ok(ir::Codef { name: name.to_string(), params, cases })
     }
@@ -63,11 +73,11 @@ impl ToIR for ast::Codef {
 impl ToIR for ast::Let {
     type Target = ir::Let;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Let { name, params, body, .. } = self;
 
-        let params = params.to_ir()?;
-        let body = Box::new(body.to_ir()?);
+        let params = params.to_ir(info_table)?;
+        let body = Box::new(body.to_ir(info_table)?);
 
         Ok(ir::Let { name: name.to_string(), params, body })
     }
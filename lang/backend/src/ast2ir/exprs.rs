@@ -1,4 +1,7 @@
 use ast::LocalComatch;
+use elaborator::result::TypeError;
+use elaborator::typechecker::erasure::{self, relevance, Relevance};
+use elaborator::TypeInfoTable;
 
 use crate::ir;
 use crate::result::BackendError;
@@ -8,17 +11,24 @@ use super::traits::ToIR;
 impl ToIR for ast::Exp {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let out = match self {
-            ast::Exp::Variable(variable) => ir::Exp::Variable(variable.to_ir()?),
-            ast::Exp::TypCtor(typ_ctor) => typ_ctor.to_ir()?,
-            ast::Exp::Call(call) => call.to_ir()?,
-            ast::Exp::DotCall(dot_call) => dot_call.to_ir()?,
-            ast::Exp::Anno(anno) => anno.to_ir()?,
-            ast::Exp::TypeUniv(type_univ) => type_univ.to_ir()?,
-            ast::Exp::LocalMatch(local_match) => ir::Exp::LocalMatch(local_match.to_ir()?),
-            ast::Exp::LocalComatch(local_comatch) => ir::Exp::LocalComatch(local_comatch.to_ir()?),
-            ast::Exp::Hole(hole) => hole.to_ir()?,
+            ast::Exp::Variable(variable) => variable.to_ir(info_table)?,
+            ast::Exp::TypCtor(typ_ctor) => typ_ctor.to_ir(info_table)?,
+            ast::Exp::Call(call) => call.to_ir(info_table)?,
+            ast::Exp::DotCall(dot_call) => dot_call.to_ir(info_table)?,
+            ast::Exp::Anno(anno) => anno.to_ir(info_table)?,
+            ast::Exp::TypeUniv(type_univ) => type_univ.to_ir(info_table)?,
+            ast::Exp::LevelUniv(level_univ) => level_univ.to_ir(info_table)?,
+            ast::Exp::Level(level) => level.to_ir(info_table)?,
+            ast::Exp::LocalMatch(local_match) => {
+                ir::Exp::LocalMatch(local_match.to_ir(info_table)?)
+            }
+            ast::Exp::LocalComatch(local_comatch) => {
+                ir::Exp::LocalComatch(local_comatch.to_ir(info_table)?)
+            }
+            ast::Exp::LocalLet(local_let) => local_let.to_ir(info_table)?,
+            ast::Exp::Hole(hole) => hole.to_ir(info_table)?,
         };
 
         Ok(out)
@@ -26,19 +36,35 @@ impl ToIR for ast::Exp {
 }
 
 impl ToIR for ast::Variable {
-    type Target = ir::Variable;
+    type Target = ir::Exp;
+
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
+        let ast::Variable { name, inferred_type, .. } = self;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
-        let ast::Variable { name, .. } = self;
+        let relevance = match inferred_type {
+            Some(typ) => relevance(typ, info_table),
+            None => Relevance::Relevant,
+        };
 
-        Ok(ir::Variable { name: name.to_string() })
+        Ok(match relevance {
+            Relevance::Relevant => ir::Exp::Variable(ir::Variable { name: name.to_string() }),
+            // Variables standing for types are bound by erased parameters. They only occur
+            // in positions whose type is not known to be a type, such as the argument `x` of
+            // `id(a: Type, x: a)` where `a` is instantiated to `Type`.
+            Relevance::Type => ir::Exp::ZST,
+            // Variables of propositional type are bound by erased parameters, but all values
+            // of the proposition are equal.
+            Relevance::Prop(ctor) => {
+                ir::Exp::CtorCall(ir::Call { name: ctor.id, module_uri: ctor.uri, args: vec![] })
+            }
+        })
     }
 }
 
 impl ToIR for ast::TypCtor {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, _info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         // Type constructors have no runtime relevance and is hence replaced by a zero-sized term.
         Ok(ir::Exp::ZST)
     }
@@ -47,10 +73,19 @@ impl ToIR for ast::TypCtor {
 impl ToIR for ast::Call {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Call { kind, name, args, .. } = self;
 
-        let args = args.to_ir()?;
+        let params = match kind {
+            ast::CallKind::Constructor | ast::CallKind::Codefinition => {
+                info_table.lookup_ctor_or_codef(name).map(|meta| meta.params)
+            }
+            ast::CallKind::LetBound => {
+                info_table.lookup_let(name).map(|tl_let| tl_let.params.clone())
+            }
+        }
+        .map_err(impossible)?;
+        let args = args_to_ir(args, &params, info_table)?;
 
         let call = ir::Call { name: name.to_string(), module_uri: name.uri.clone(), args };
 
@@ -65,11 +100,12 @@ impl ToIR for ast::Call {
 impl ToIR for ast::DotCall {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::DotCall { kind, exp, name, args, .. } = self;
 
-        let args = args.to_ir()?;
-        let exp = Box::new(exp.to_ir()?);
+        let params = info_table.lookup_dtor_or_def(name).map_err(impossible)?.params;
+        let args = args_to_ir(args, &params, info_table)?;
+        let exp = Box::new(exp.to_ir(info_table)?);
 
         let dot_call =
             ir::DotCall { exp, module_uri: name.uri.clone(), name: name.to_string(), args };
@@ -84,30 +120,48 @@ impl ToIR for ast::DotCall {
 impl ToIR for ast::Anno {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Anno { exp, .. } = self;
         // For type annotations `e: t`, we throw away the type `t` and convert `e` to IR.
-        exp.to_ir()
+        exp.to_ir(info_table)
     }
 }
 
 impl ToIR for ast::TypeUniv {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, _info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         // The universe has no runtime relevance and is hence replaced by a zero-sized term.
         Ok(ir::Exp::ZST)
     }
 }
 
+impl ToIR for ast::LevelUniv {
+    type Target = ir::Exp;
+
+    fn to_ir(&self, _info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
+        // The sort of universe levels has no runtime relevance and is hence replaced by a zero-sized term.
+        Ok(ir::Exp::ZST)
+    }
+}
+
+impl ToIR for ast::Level {
+    type Target = ir::Exp;
+
+    fn to_ir(&self, _info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
+        // Universe levels have no runtime relevance and are hence replaced by a zero-sized term.
+        Ok(ir::Exp::ZST)
+    }
+}
+
 impl ToIR for ast::LocalMatch {
     type Target = ir::LocalMatch;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::LocalMatch { on_exp, cases, .. } = self;
 
-        let on_exp = Box::new(on_exp.to_ir()?);
-        let cases = cases.to_ir()?;
+        let on_exp = Box::new(on_exp.to_ir(info_table)?);
+        let cases = cases.to_ir(info_table)?;
 
         Ok(ir::LocalMatch { on_exp, cases })
     }
@@ -116,33 +170,53 @@ impl ToIR for ast::LocalMatch {
 impl ToIR for ast::LocalComatch {
     type Target = ir::LocalComatch;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let LocalComatch { cases, .. } = self;
 
-        let cases = cases.to_ir()?;
+        let cases = cases.to_ir(info_table)?;
 
         Ok(ir::LocalComatch { cases })
     }
 }
 
+impl ToIR for ast::LocalLet {
+    type Target = ir::Exp;
+
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
+        let ast::LocalLet { name, bound, body, bound_type, .. } = self;
+
+        // A let-binding of a runtime-irrelevant term is erased together with the term.
+        if bound_type.as_ref().is_some_and(|typ| erasure::is_runtime_irrelevant(typ, info_table)) {
+            return body.to_ir(info_table);
+        }
+
+        let bound = Box::new(bound.to_ir(info_table)?);
+        let body = Box::new(body.to_ir(info_table)?);
+
+        Ok(ir::Exp::LocalLet(ir::LocalLet { name: name.to_string(), bound, body }))
+    }
+}
+
 impl ToIR for ast::Hole {
     type Target = ir::Exp;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Hole { kind, solution, .. } = self;
 
         let res =
             match kind {
                 ast::MetaVarKind::MustSolve | ast::MetaVarKind::Inserted => match solution {
-                    Some(solution) => solution.to_ir()?,
+                    Some(solution) => solution.to_ir(info_table)?,
                     None => return Err(BackendError::Impossible(
                         "Encountered hole without solution that must be solved during typechecking"
                             .to_owned(),
                     )),
                 },
-                ast::MetaVarKind::CanSolve => {
-                    ir::Exp::Panic(ir::Panic { message: "not yet implemented".to_owned() })
-                }
+                // A typed hole may be solved by unification, otherwise it fails at runtime.
+                ast::MetaVarKind::CanSolve => match solution {
+                    Some(solution) => solution.to_ir(info_table)?,
+                    None => ir::Exp::Panic(ir::Panic { message: "not yet implemented".to_owned() }),
+                },
             };
 
         Ok(res)
@@ -152,11 +226,22 @@ impl ToIR for ast::Hole {
 impl ToIR for ast::Case {
     type Target = ir::Case;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Case { pattern, body, .. } = self;
         let ast::Pattern { span: _, is_copattern, params, name } = pattern;
 
-        let params = params.to_ir()?;
+        let signature = if *is_copattern {
+            info_table.lookup_dtor_or_def(name).map_err(impossible)?.params
+        } else {
+            info_table.lookup_ctor_or_codef(name).map_err(impossible)?.params
+        };
+        let params = params
+            .params
+            .iter()
+            .zip(&signature.params)
+            .filter(|(_, param)| !erasure::is_erased(param, info_table))
+            .map(|(param, _)| param.name.to_string())
+            .collect();
 
         let pattern = ir::Pattern {
             is_copattern: *is_copattern,
@@ -166,7 +251,7 @@ impl ToIR for ast::Case {
         };
 
         let body = match body {
-            Some(body) => Some(Box::new(body.to_ir()?)),
+            Some(body) => Some(Box::new(body.to_ir(info_table)?)),
             None => None,
         };
 
@@ -174,44 +259,38 @@ impl ToIR for ast::Case {
     }
 }
 
-impl ToIR for ast::Args {
-    type Target = Vec<ir::Exp>;
-
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
-        let ast::Args { args, .. } = self;
-
-        args.iter()
-            .filter(|arg| !arg.erased())
-            .map(|arg| arg.exp())
-            .map(|arg| arg.to_ir())
-            .collect()
-    }
-}
-
 impl ToIR for ast::Telescope {
     type Target = Vec<String>;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         let ast::Telescope { params, .. } = self;
 
         Ok(params
             .iter()
-            .filter(|param| !param.erased)
+            .filter(|param| !erasure::is_erased(param, info_table))
             .map(|param| param.name.to_string())
             .collect())
     }
 }
 
-impl ToIR for ast::TelescopeInst {
-    type Target = Vec<String>;
-
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
-        let ast::TelescopeInst { params, .. } = self;
+/// Convert the arguments `args` of a call to IR
+///
+/// The erased parameters are determined from the signature `params` of the called declaration:
+/// The arguments only record whether their parameter is annotated as erased, but not whether it is
+/// runtime-irrelevant because of its type.
+fn args_to_ir(
+    args: &ast::Args,
+    params: &ast::Telescope,
+    info_table: &TypeInfoTable,
+) -> Result<Vec<ir::Exp>, BackendError> {
+    args.args
+        .iter()
+        .zip(&params.params)
+        .filter(|(_, param)| !erasure::is_erased(param, info_table))
+        .map(|(arg, _)| arg.exp().to_ir(info_table))
+        .collect()
+}
 
-        Ok(params
-            .iter()
-            .filter(|param| !param.erased)
-            .map(|param| param.name.to_string())
-            .collect())
-    }
+fn impossible(err: Box<TypeError>) -> BackendError {
+    BackendError::Impossible(err.to_string())
 }
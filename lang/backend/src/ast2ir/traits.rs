@@ -1,29 +1,33 @@
+use elaborator::TypeInfoTable;
+
 use crate::result::BackendError;
 
 /// Convert AST to IR (intermediate representation)
 ///
-/// Takes into account the erasure information annotated in the AST.
-/// Nodes annotated with `erased: true` won't occur in the generated IR.
+/// Erasure is type-directed: Runtime-irrelevant parameters and arguments, declarations which compute
+/// types and let-bindings of runtime-irrelevant terms won't occur in the generated IR.
+/// Their types are looked up in the type info table of the module and its dependencies, see
+/// `elaborator::typechecker::erasure`.
 pub trait ToIR {
     type Target;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError>;
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError>;
 }
 
 impl<T: ToIR> ToIR for Vec<T> {
     type Target = Vec<T::Target>;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
-        self.iter().map(|x| x.to_ir()).collect()
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
+        self.iter().map(|x| x.to_ir(info_table)).collect()
     }
 }
 
 impl<T: ToIR> ToIR for Option<T> {
     type Target = Option<T::Target>;
 
-    fn to_ir(&self) -> Result<Self::Target, BackendError> {
+    fn to_ir(&self, info_table: &TypeInfoTable) -> Result<Self::Target, BackendError> {
         match self {
-            Some(x) => Ok(Some(x.to_ir()?)),
+            Some(x) => Ok(Some(x.to_ir(info_table)?)),
             None => Ok(None),
         }
     }
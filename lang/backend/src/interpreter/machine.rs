@@ -0,0 +1,249 @@
+use std::rc::Rc;
+use std::slice;
+
+use crate::ir;
+use crate::result::BackendError;
+
+use super::value::{Env, Val};
+use super::Interpreter;
+
+/// The state of the machine: either an expression is evaluated in an environment, or a value is
+/// returned to the topmost frame of the stack
+enum Control<'a> {
+    Eval(&'a ir::Exp, Env<'a>),
+    Return(Rc<Val<'a>>),
+}
+
+/// A frame of the explicit stack, which describes what to do with the value that is returned
+enum Frame<'a> {
+    /// Evaluate the arguments `todo` from left to right and collect their values in `done`
+    Args { todo: slice::Iter<'a, ir::Exp>, env: Env<'a>, done: Vec<Rc<Val<'a>>>, then: Apply<'a> },
+    /// The returned value is the scrutinee of the destructor or definition call
+    DotCall { dot_call: &'a ir::DotCall, is_def: bool, env: Env<'a> },
+    /// The returned value is the scrutinee of a local match
+    LocalMatch { cases: &'a [ir::Case], env: Env<'a> },
+    /// The returned value is bound to `name` in the body of a local let
+    LocalLet { name: &'a String, body: &'a ir::Exp, env: Env<'a> },
+}
+
+/// What to do with the values of the arguments once they are evaluated
+enum Apply<'a> {
+    Ctor(&'a ir::Call),
+    Codef(&'a ir::Call),
+    Let(&'a ir::Call),
+    DotCall { dot_call: &'a ir::DotCall, is_def: bool, on: Rc<Val<'a>> },
+}
+
+/// The state of the CEK machine
+pub(super) struct Machine<'a, 'i> {
+    interpreter: &'i Interpreter<'a>,
+    stack: Vec<Frame<'a>>,
+    steps: u64,
+    #[cfg(not(target_arch = "wasm32"))]
+    started: std::time::Instant,
+}
+
+impl<'a, 'i> Machine<'a, 'i> {
+    pub(super) fn new(interpreter: &'i Interpreter<'a>) -> Self {
+        Machine {
+            interpreter,
+            stack: vec![],
+            steps: 0,
+            #[cfg(not(target_arch = "wasm32"))]
+            started: std::time::Instant::now(),
+        }
+    }
+
+    /// Evaluate the closed expression `exp` to a value
+    pub(super) fn run(mut self, exp: &'a ir::Exp) -> Result<Rc<Val<'a>>, BackendError> {
+        let mut control = Control::Eval(exp, Env::default());
+        loop {
+            control = match control {
+                Control::Eval(exp, env) => {
+                    self.consume_fuel()?;
+                    self.eval(exp, env)?
+                }
+                Control::Return(val) => match self.stack.pop() {
+                    Some(frame) => self.ret(frame, val)?,
+                    None => return Ok(val),
+                },
+            }
+        }
+    }
+
+    fn eval(&mut self, exp: &'a ir::Exp, env: Env<'a>) -> Result<Control<'a>, BackendError> {
+        match exp {
+            ir::Exp::Variable(ir::Variable { name }) => {
+                let val = env.lookup(name).ok_or_else(|| {
+                    BackendError::Impossible(format!("Unbound variable {name} during evaluation"))
+                })?;
+                Ok(Control::Return(val))
+            }
+            ir::Exp::CtorCall(call) => self.args(&call.args, env, Apply::Ctor(call)),
+            ir::Exp::CodefCall(call) => self.args(&call.args, env, Apply::Codef(call)),
+            ir::Exp::LetCall(call) => self.args(&call.args, env, Apply::Let(call)),
+            ir::Exp::DtorCall(dot_call) | ir::Exp::DefCall(dot_call) => {
+                let is_def = matches!(exp, ir::Exp::DefCall(_));
+                self.stack.push(Frame::DotCall { dot_call, is_def, env: env.clone() });
+                Ok(Control::Eval(&dot_call.exp, env))
+            }
+            ir::Exp::LocalMatch(ir::LocalMatch { on_exp, cases }) => {
+                self.stack.push(Frame::LocalMatch { cases, env: env.clone() });
+                Ok(Control::Eval(on_exp, env))
+            }
+            ir::Exp::LocalComatch(ir::LocalComatch { cases }) => {
+                Ok(Control::Return(Rc::new(Val::Comatch { cases, env })))
+            }
+            ir::Exp::LocalLet(ir::LocalLet { name, bound, body }) => {
+                self.stack.push(Frame::LocalLet { name, body, env: env.clone() });
+                Ok(Control::Eval(bound, env))
+            }
+            ir::Exp::Panic(ir::Panic { message }) => Err(BackendError::Panic(message.clone())),
+            ir::Exp::ZST => Ok(Control::Return(Rc::new(Val::Zst))),
+        }
+    }
+
+    fn ret(&mut self, frame: Frame<'a>, val: Rc<Val<'a>>) -> Result<Control<'a>, BackendError> {
+        match frame {
+            Frame::Args { todo, env, mut done, then } => {
+                done.push(val);
+                self.next_arg(todo, env, done, then)
+            }
+            Frame::DotCall { dot_call, is_def, env } => {
+                self.args(&dot_call.args, env, Apply::DotCall { dot_call, is_def, on: val })
+            }
+            Frame::LocalMatch { cases, env } => {
+                let Val::Ctor { name, module_uri, args } = &*val else {
+                    return Err(impossible("Cannot match on a value which is not a constructor"));
+                };
+                let (params, body) = select_case(cases, name, module_uri)?;
+                Ok(Control::Eval(body, env.bind(params, args.clone())))
+            }
+            Frame::LocalLet { name, body, env } => {
+                Ok(Control::Eval(body, env.bind(slice::from_ref(name), vec![val])))
+            }
+        }
+    }
+
+    /// Evaluate the arguments `args` and then continue with `then`
+    fn args(
+        &mut self,
+        args: &'a [ir::Exp],
+        env: Env<'a>,
+        then: Apply<'a>,
+    ) -> Result<Control<'a>, BackendError> {
+        self.next_arg(args.iter(), env, Vec::with_capacity(args.len()), then)
+    }
+
+    fn next_arg(
+        &mut self,
+        mut todo: slice::Iter<'a, ir::Exp>,
+        env: Env<'a>,
+        done: Vec<Rc<Val<'a>>>,
+        then: Apply<'a>,
+    ) -> Result<Control<'a>, BackendError> {
+        match todo.next() {
+            Some(arg) => {
+                self.stack.push(Frame::Args { todo, env: env.clone(), done, then });
+                Ok(Control::Eval(arg, env))
+            }
+            None => self.apply(then, done),
+        }
+    }
+
+    fn apply(
+        &mut self,
+        then: Apply<'a>,
+        args: Vec<Rc<Val<'a>>>,
+    ) -> Result<Control<'a>, BackendError> {
+        match then {
+            Apply::Ctor(ir::Call { name, module_uri, .. }) => {
+                Ok(Control::Return(Rc::new(Val::Ctor { name, module_uri, args })))
+            }
+            Apply::Codef(ir::Call { name, module_uri, .. }) => {
+                Ok(Control::Return(Rc::new(Val::Codef { name, module_uri, args })))
+            }
+            Apply::Let(ir::Call { name, module_uri, .. }) => {
+                let ir::Let { params, body, .. } = self.interpreter.lookup_let(module_uri, name)?;
+                Ok(Control::Eval(body, Env::default().bind(params, args)))
+            }
+            Apply::DotCall { dot_call, is_def: true, on } => {
+                // The definition `d` is called on the constructor `C`:
+                //
+                // ```text
+                // C(t_1,...).d(e_1,...)
+                // ```
+                let Val::Ctor { name: ctor_name, module_uri: ctor_uri, args: ctor_args } = &*on
+                else {
+                    return Err(impossible("Cannot call a definition on a non-constructor"));
+                };
+                let ir::Def { params, cases, .. } =
+                    self.interpreter.lookup_def(&dot_call.module_uri, &dot_call.name)?;
+                let (case_params, body) = select_case(cases, ctor_name, ctor_uri)?;
+                let env = Env::default().bind(params, args).bind(case_params, ctor_args.clone());
+                Ok(Control::Eval(body, env))
+            }
+            Apply::DotCall { dot_call, is_def: false, on } => match &*on {
+                // The destructor `d` is called on the codefinition `C`:
+                //
+                // ```text
+                // C(t_1,...).d(e_1,...)
+                // ```
+                Val::Codef { name, module_uri, args: codef_args } => {
+                    let ir::Codef { params, cases, .. } =
+                        self.interpreter.lookup_codef(module_uri, name)?;
+                    let (case_params, body) =
+                        select_case(cases, &dot_call.name, &dot_call.module_uri)?;
+                    let env =
+                        Env::default().bind(params, codef_args.clone()).bind(case_params, args);
+                    Ok(Control::Eval(body, env))
+                }
+                // The destructor `d` is called on a local comatch:
+                //
+                // ```text
+                // comatch { ... }.d(e_1,...)
+                // ```
+                Val::Comatch { cases, env } => {
+                    let (case_params, body) =
+                        select_case(cases, &dot_call.name, &dot_call.module_uri)?;
+                    Ok(Control::Eval(body, env.bind(case_params, args)))
+                }
+                _ => Err(impossible("Cannot call a destructor on a non-codata value")),
+            },
+        }
+    }
+
+    fn consume_fuel(&mut self) -> Result<(), BackendError> {
+        self.steps += 1;
+        if let Some(max_steps) = self.interpreter.max_steps {
+            if self.steps > max_steps {
+                return Err(BackendError::OutOfFuel(format!("{max_steps} steps")));
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = self.interpreter.timeout {
+            if self.started.elapsed() > timeout {
+                return Err(BackendError::OutOfFuel(format!("{} ms", timeout.as_millis())));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The parameters and the body of the case for the constructor or destructor `name`
+fn select_case<'a>(
+    cases: &'a [ir::Case],
+    name: &str,
+    module_uri: &url::Url,
+) -> Result<(&'a [String], &'a ir::Exp), BackendError> {
+    let case = cases
+        .iter()
+        .find(|case| case.pattern.name == name && case.pattern.module_uri == *module_uri)
+        .ok_or_else(|| impossible(&format!("Missing case for {name}")))?;
+    let body = case.body.as_ref().ok_or_else(|| impossible("Reached an absurd case"))?;
+    Ok((&case.pattern.params, body))
+}
+
+fn impossible(message: &str) -> BackendError {
+    BackendError::Impossible(message.to_owned())
+}
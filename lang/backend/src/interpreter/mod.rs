@@ -0,0 +1,89 @@
+//! An interpreter for the intermediate representation
+//!
+//! The interpreter evaluates the erased IR of a program with a call-by-value CEK machine.
+//! Since the continuation is an explicit stack on the heap, deep recursion in the evaluated
+//! program cannot overflow the stack of the interpreter.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use url::Url;
+
+use crate::ir;
+use crate::result::BackendError;
+
+mod machine;
+mod value;
+
+pub use value::Value;
+
+use machine::Machine;
+
+/// An interpreter for the declarations of a set of IR modules
+pub struct Interpreter<'a> {
+    defs: HashMap<(&'a Url, &'a str), &'a ir::Def>,
+    codefs: HashMap<(&'a Url, &'a str), &'a ir::Codef>,
+    lets: HashMap<(&'a Url, &'a str), &'a ir::Let>,
+    /// The maximal number of evaluation steps, or `None` for no limit
+    pub max_steps: Option<u64>,
+    /// The maximal duration of evaluation, or `None` for no limit
+    ///
+    /// The timeout is not supported on `wasm32` targets, where it is ignored.
+    pub timeout: Option<Duration>,
+}
+
+impl<'a> Interpreter<'a> {
+    /// Create an interpreter for the declarations of `modules`
+    ///
+    /// The modules must contain all declarations which are used by the evaluated expressions,
+    /// including the declarations of imported modules.
+    pub fn new(modules: impl IntoIterator<Item = &'a ir::Module>) -> Self {
+        let mut interpreter = Interpreter {
+            defs: HashMap::new(),
+            codefs: HashMap::new(),
+            lets: HashMap::new(),
+            max_steps: None,
+            timeout: None,
+        };
+        for module in modules {
+            let uri = &module.uri;
+            interpreter.defs.extend(module.def_decls.iter().map(|d| ((uri, d.name.as_str()), d)));
+            interpreter
+                .codefs
+                .extend(module.codef_decls.iter().map(|d| ((uri, d.name.as_str()), d)));
+            interpreter.lets.extend(module.let_decls.iter().map(|d| ((uri, d.name.as_str()), d)));
+        }
+        interpreter
+    }
+
+    /// Evaluate the closed expression `exp`
+    pub fn eval(&self, exp: &'a ir::Exp) -> Result<Value, BackendError> {
+        Machine::new(self).run(exp).map(|val| val.to_value())
+    }
+
+    /// Evaluate the main expression of `module`
+    ///
+    /// Returns `None` if the module has no main expression.
+    pub fn eval_main(&self, module: &'a ir::Module) -> Result<Option<Value>, BackendError> {
+        match self.lets.get(&(&module.uri, "main")) {
+            Some(ir::Let { params, body, .. }) if params.is_empty() => self.eval(body).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    fn lookup_def(&self, uri: &Url, name: &str) -> Result<&'a ir::Def, BackendError> {
+        self.defs.get(&(uri, name)).copied().ok_or_else(|| undefined(uri, name))
+    }
+
+    fn lookup_codef(&self, uri: &Url, name: &str) -> Result<&'a ir::Codef, BackendError> {
+        self.codefs.get(&(uri, name)).copied().ok_or_else(|| undefined(uri, name))
+    }
+
+    fn lookup_let(&self, uri: &Url, name: &str) -> Result<&'a ir::Let, BackendError> {
+        self.lets.get(&(uri, name)).copied().ok_or_else(|| undefined(uri, name))
+    }
+}
+
+fn undefined(uri: &Url, name: &str) -> BackendError {
+    BackendError::Impossible(format!("Declaration {name} of module {uri} is not loaded"))
+}
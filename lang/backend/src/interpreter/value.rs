@@ -0,0 +1,252 @@
+use std::mem;
+use std::rc::Rc;
+
+use url::Url;
+
+use printer::theme::ThemeExt;
+use printer::tokens::COMMA;
+use printer::{Alloc, Builder, DocAllocator, Precedence, Print, PrintCfg};
+
+use crate::ir;
+
+/// A runtime value of the interpreter
+#[derive(Debug)]
+pub(super) enum Val<'a> {
+    /// A constructor applied to its arguments
+    Ctor { name: &'a str, module_uri: &'a Url, args: Vec<Rc<Val<'a>>> },
+    /// A codefinition applied to its arguments
+    Codef { name: &'a str, module_uri: &'a Url, args: Vec<Rc<Val<'a>>> },
+    /// The closure of a local comatch
+    Comatch { cases: &'a [ir::Case], env: Env<'a> },
+    /// The value of a zero-sized term
+    Zst,
+}
+
+impl<'a> Val<'a> {
+    /// The observable part of the value, see [`Value`]
+    ///
+    /// Values can be arbitrarily deep, so they are traversed with an explicit stack.
+    pub(super) fn to_value(&self) -> Value {
+        enum Work<'v, 'a> {
+            /// Convert the value and push the result
+            Visit(&'v Val<'a>),
+            /// Pop the converted arguments and push the constructor or codefinition applied to them
+            Apply { name: &'a str, is_codef: bool, arity: usize },
+        }
+
+        let mut work = vec![Work::Visit(self)];
+        let mut done: Vec<Value> = vec![];
+        while let Some(item) = work.pop() {
+            match item {
+                Work::Visit(Val::Ctor { name, args, .. }) => {
+                    work.push(Work::Apply { name, is_codef: false, arity: visible(args).count() });
+                    work.extend(visible(args).rev().map(Work::Visit));
+                }
+                Work::Visit(Val::Codef { name, args, .. }) => {
+                    work.push(Work::Apply { name, is_codef: true, arity: visible(args).count() });
+                    work.extend(visible(args).rev().map(Work::Visit));
+                }
+                Work::Visit(Val::Comatch { .. }) => done.push(Value::Comatch),
+                Work::Visit(Val::Zst) => done.push(Value::ZST),
+                Work::Apply { name, is_codef, arity } => {
+                    let args = done.split_off(done.len() - arity);
+                    let name = name.to_owned();
+                    done.push(if is_codef {
+                        Value::Codef { name, args }
+                    } else {
+                        Value::Ctor { name, args }
+                    });
+                }
+            }
+        }
+        // Unwrap is safe here because the value itself is always converted last.
+        done.pop().unwrap()
+    }
+
+    /// Move the values and bindings which are directly owned by this value to `parts`
+    fn take_parts(&mut self, parts: &mut Vec<Part<'a>>) {
+        match self {
+            Val::Ctor { args, .. } | Val::Codef { args, .. } => {
+                parts.extend(mem::take(args).into_iter().map(Part::Val))
+            }
+            Val::Comatch { env, .. } => parts.extend(env.head.take().map(Part::Binding)),
+            Val::Zst => {}
+        }
+    }
+}
+
+/// The arguments which are not zero-sized terms
+fn visible<'v, 'a>(args: &'v [Rc<Val<'a>>]) -> impl DoubleEndedIterator<Item = &'v Val<'a>> {
+    args.iter().map(|arg| &**arg).filter(|arg| !matches!(arg, Val::Zst))
+}
+
+impl Drop for Val<'_> {
+    fn drop(&mut self) {
+        let mut parts = vec![];
+        self.take_parts(&mut parts);
+        drop_parts(parts);
+    }
+}
+
+/// The environment of the interpreter, which binds variables by their names
+///
+/// The environment is a persistent list, so that it can be shared between closures.
+/// Like values, environments are dropped iteratively, since they can be arbitrarily long.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Env<'a> {
+    head: Option<Rc<Binding<'a>>>,
+}
+
+#[derive(Debug)]
+struct Binding<'a> {
+    name: &'a str,
+    val: Rc<Val<'a>>,
+    next: Env<'a>,
+}
+
+impl<'a> Env<'a> {
+    /// The value of the innermost variable called `name`
+    pub(super) fn lookup(&self, name: &str) -> Option<Rc<Val<'a>>> {
+        let mut env = self;
+        while let Some(binding) = &env.head {
+            if binding.name == name {
+                return Some(binding.val.clone());
+            }
+            env = &binding.next;
+        }
+        None
+    }
+
+    /// Extend the environment by binding the variables `names` to the values `vals`
+    pub(super) fn bind(&self, names: &'a [String], vals: Vec<Rc<Val<'a>>>) -> Self {
+        names.iter().zip(vals).fold(self.clone(), |env, (name, val)| Env {
+            head: Some(Rc::new(Binding { name, val, next: env })),
+        })
+    }
+}
+
+impl Drop for Env<'_> {
+    fn drop(&mut self) {
+        drop_parts(self.head.take().map(Part::Binding).into_iter().collect());
+    }
+}
+
+/// A value or binding which is dropped by [`drop_parts`]
+enum Part<'a> {
+    Val(Rc<Val<'a>>),
+    Binding(Rc<Binding<'a>>),
+}
+
+/// Drop the given parts of a value without recursion
+///
+/// If a part is not shared, then its own parts are moved to the stack before it is dropped, so
+/// that dropping it does not recurse.
+fn drop_parts(mut parts: Vec<Part>) {
+    while let Some(part) = parts.pop() {
+        match part {
+            Part::Val(val) => {
+                if let Ok(mut val) = Rc::try_unwrap(val) {
+                    val.take_parts(&mut parts);
+                }
+            }
+            Part::Binding(binding) => {
+                if let Ok(Binding { val, mut next, .. }) = Rc::try_unwrap(binding) {
+                    parts.push(Part::Val(val));
+                    parts.extend(next.head.take().map(Part::Binding));
+                }
+            }
+        }
+    }
+}
+
+/// The result of evaluating an expression
+///
+/// Only the constructors and codefinitions of a value are observable, while the closures of local
+/// comatches are opaque. Arguments which are zero-sized terms carry no information at runtime and
+/// are omitted.
+///
+/// Values can be arbitrarily deep, so they are compared, printed and dropped without recursion.
+#[derive(Debug)]
+pub enum Value {
+    Ctor { name: String, args: Vec<Value> },
+    Codef { name: String, args: Vec<Value> },
+    Comatch,
+    ZST,
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        let mut todo = vec![(self, other)];
+        while let Some(pair) = todo.pop() {
+            match pair {
+                (Value::Ctor { name, args }, Value::Ctor { name: name2, args: args2 })
+                | (Value::Codef { name, args }, Value::Codef { name: name2, args: args2 }) => {
+                    if name != name2 || args.len() != args2.len() {
+                        return false;
+                    }
+                    todo.extend(args.iter().zip(args2));
+                }
+                (Value::Comatch, Value::Comatch) | (Value::ZST, Value::ZST) => {}
+                _ => return false,
+            }
+        }
+        true
+    }
+}
+
+impl Eq for Value {}
+
+impl Drop for Value {
+    fn drop(&mut self) {
+        let mut todo = vec![];
+        if let Value::Ctor { args, .. } | Value::Codef { args, .. } = self {
+            todo.append(args);
+        }
+        while let Some(mut value) = todo.pop() {
+            if let Value::Ctor { args, .. } | Value::Codef { args, .. } = &mut value {
+                todo.append(args);
+            }
+        }
+    }
+}
+
+impl Print for Value {
+    fn print_prec<'a>(
+        &'a self,
+        _cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        _prec: Precedence,
+    ) -> Builder<'a> {
+        enum Work<'a> {
+            /// Print the value and push the result
+            Visit(&'a Value),
+            /// Pop the printed arguments and push the constructor or codefinition applied to them
+            Apply { name: &'a str, arity: usize },
+        }
+
+        let mut work = vec![Work::Visit(self)];
+        let mut done: Vec<Builder<'a>> = vec![];
+        while let Some(item) = work.pop() {
+            match item {
+                Work::Visit(Value::Ctor { name, args } | Value::Codef { name, args }) => {
+                    work.push(Work::Apply { name, arity: args.len() });
+                    work.extend(args.iter().rev().map(Work::Visit));
+                }
+                Work::Visit(Value::Comatch) => done.push(alloc.keyword("<comatch>")),
+                Work::Visit(Value::ZST) => done.push(alloc.keyword("<ZST>")),
+                Work::Apply { name, arity } => {
+                    let head = alloc.ctor(name);
+                    if arity == 0 {
+                        done.push(head);
+                        continue;
+                    }
+                    let args = done.split_off(done.len() - arity);
+                    let sep = alloc.text(COMMA).append(alloc.line());
+                    done.push(head.append(alloc.intersperse(args, sep).align().parens().group()));
+                }
+            }
+        }
+        // Unwrap is safe here because the value itself is always printed last.
+        done.pop().unwrap()
+    }
+}
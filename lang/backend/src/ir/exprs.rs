@@ -16,9 +16,11 @@ pub enum Exp {
     DefCall(DotCall),
     LocalMatch(LocalMatch),
     LocalComatch(LocalComatch),
+    LocalLet(LocalLet),
     Panic(Panic),
     /// Zero-Sized Term
-    /// This term has no runtime effect and is generated as a placeholder whenever types cannot be erased by the current implementation.
+    /// This term has no runtime effect and is generated as a placeholder for types which occur in runtime-relevant positions,
+    /// such as the argument `Nat` of `id(Type, Nat)` for `id(a: Type, x: a)`.
     ZST,
 }
 
@@ -38,6 +40,7 @@ impl Print for Exp {
             Exp::DefCall(d) => d.print_prec(cfg, alloc, prec),
             Exp::LocalMatch(m) => m.print_prec(cfg, alloc, prec),
             Exp::LocalComatch(m) => m.print_prec(cfg, alloc, prec),
+            Exp::LocalLet(l) => l.print_prec(cfg, alloc, prec),
             Exp::Panic(p) => p.print_prec(cfg, alloc, prec),
             Exp::ZST => alloc.keyword("<ZST>"),
         }
@@ -117,7 +120,7 @@ impl Print for DotCall {
                 alloc.text(DOT).append(alloc.dtor(name)).append(psubst).append(dtors_group);
             dtor = exp;
         }
-        dtor.print(cfg, alloc).append(dtors_group.align().group())
+        dtor.print_prec(cfg, alloc, 1).append(dtors_group.align().group())
     }
 }
 
@@ -136,7 +139,7 @@ impl Print for LocalMatch {
     ) -> Builder<'a> {
         let LocalMatch { on_exp, cases, .. } = self;
         on_exp
-            .print(cfg, alloc)
+            .print_prec(cfg, alloc, 1)
             .append(DOT)
             .append(alloc.keyword(MATCH))
             .append(alloc.space())
@@ -161,6 +164,40 @@ impl Print for LocalComatch {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct LocalLet {
+    pub name: String,
+    pub bound: Box<Exp>,
+    pub body: Box<Exp>,
+}
+
+impl Print for LocalLet {
+    fn print_prec<'a>(
+        &'a self,
+        cfg: &PrintCfg,
+        alloc: &'a Alloc<'a>,
+        prec: Precedence,
+    ) -> Builder<'a> {
+        let LocalLet { name, bound, body } = self;
+        let doc = alloc
+            .keyword(LET)
+            .append(alloc.space())
+            .append(alloc.text(name))
+            .append(alloc.space())
+            .append(COLONEQ)
+            .append(alloc.line().append(bound.print(cfg, alloc)).nest(cfg.indent))
+            .append(SEMICOLON)
+            .group()
+            .append(alloc.hardline())
+            .append(body.print(cfg, alloc));
+        if prec > 0 {
+            doc.align().parens()
+        } else {
+            doc
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Panic {
     pub message: String,
@@ -0,0 +1,483 @@
+use std::collections::HashMap;
+
+use url::Url;
+
+use printer::{Alloc, Builder, DocAllocator, PrintCfg};
+
+use crate::ir;
+
+use super::names::{string_literal, Names};
+use super::Globals;
+
+/// The statements of a block of the generated code
+type Stmts<'a> = Vec<Builder<'a>>;
+
+/// The state of the translation of a set of modules
+pub struct Gen<'g, 'a> {
+    globals: &'g Globals,
+    cfg: &'g PrintCfg,
+    alloc: &'a Alloc<'a>,
+    /// The names of constructors and codefinitions, indexed by their tag
+    ///
+    /// The tag `0` is used by the closures of local comatches.
+    tag_names: Vec<String>,
+    tags: HashMap<(Url, String), usize>,
+    dtors: HashMap<(Url, String), usize>,
+    /// The number of identifiers which were tried for the code of local comatches
+    comatches: usize,
+    /// The signatures of the generated functions
+    signatures: Vec<String>,
+    /// The generated functions
+    pub functions: Vec<Builder<'a>>,
+}
+
+/// The state of the translation of a single C function
+struct FnCx {
+    /// The identifiers which are used in the function, including all top-level identifiers
+    names: Names,
+    /// The identifiers of the local variables which are in scope
+    scope: Vec<(String, String)>,
+}
+
+impl FnCx {
+    /// Bind the variable `name` to a fresh identifier
+    fn bind(&mut self, name: &str) -> String {
+        let ident = self.names.fresh(name);
+        self.scope.push((name.to_owned(), ident.clone()));
+        ident
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.scope.iter().rev().find(|(bound, _)| bound == name).map(|(_, ident)| ident.clone())
+    }
+}
+
+impl<'g, 'a> Gen<'g, 'a> {
+    pub fn new(globals: &'g Globals, cfg: &'g PrintCfg, alloc: &'a Alloc<'a>) -> Self {
+        Self {
+            globals,
+            cfg,
+            alloc,
+            tag_names: vec!["<comatch>".to_owned()],
+            tags: HashMap::new(),
+            dtors: HashMap::new(),
+            comatches: 0,
+            signatures: vec![],
+            functions: vec![],
+        }
+    }
+
+    /// A definition becomes a function which switches on the tag of its first argument
+    pub fn def(&mut self, ident: &str, def: &ir::Def) {
+        let ir::Def { params, cases, .. } = def;
+        let mut cx = self.fn_cx();
+        let params: Vec<_> = params.iter().map(|param| cx.bind(param)).collect();
+        let mut stmts = vec![];
+        let result = self.match_stmts(&mut cx, "self", true, cases, &mut stmts);
+        let signature = format!(
+            "static pol_obj *{ident}({})",
+            std::iter::once("self")
+                .chain(params.iter().map(|param| param.as_str()))
+                .map(|param| format!("pol_obj *{param}"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+        self.function(&mut cx, signature, stmts, result, params);
+    }
+
+    /// A codefinition becomes a function which switches on the called destructor
+    ///
+    /// The arguments of the codefinition are the fields of the object.
+    pub fn codef(&mut self, ident: &str, codef: &ir::Codef) {
+        let ir::Codef { params, cases, .. } = codef;
+        let mut cx = self.fn_cx();
+        self.code(&mut cx, ident, params, cases);
+    }
+
+    /// A top-level let-binding becomes a function
+    pub fn tl_let(&mut self, ident: &str, tl_let: &ir::Let) {
+        let ir::Let { params, body, .. } = tl_let;
+        let mut cx = self.fn_cx();
+        let params: Vec<_> = params.iter().map(|param| cx.bind(param)).collect();
+        let mut stmts = vec![];
+        let result = self.exp(&mut cx, body, &mut stmts);
+        let signature = if params.is_empty() {
+            format!("static pol_obj *{ident}(void)")
+        } else {
+            let params: Vec<_> = params.iter().map(|param| format!("pol_obj *{param}")).collect();
+            format!("static pol_obj *{ident}({})", params.join(", "))
+        };
+        self.function(&mut cx, signature, stmts, result, params);
+    }
+
+    /// The entry point of the program, which prints the value of the let-binding `ident`
+    pub fn main(&self, ident: &str) -> Builder<'a> {
+        let stmts = vec![
+            self.alloc.text(format!("pol_obj *result = {ident}();")),
+            self.alloc.text("pol_print(stdout, result, pol_names);"),
+            self.alloc.text("putchar('\\n');"),
+            self.alloc.text("pol_drop(result);"),
+            self.alloc.text("return 0;"),
+        ];
+        self.alloc.text("int main(void) ").append(self.block(stmts))
+    }
+
+    /// The table of the names of all constructors and codefinitions, indexed by their tag
+    pub fn names_table(&self) -> Builder<'a> {
+        let names = self.tag_names.iter().map(|name| self.alloc.text(string_literal(name)));
+        let sep = self.alloc.text(",").append(self.alloc.line());
+        self.alloc
+            .text("static const char *const pol_names[] = {")
+            .append(
+                self.alloc.line().append(self.alloc.intersperse(names, sep)).nest(self.indent()),
+            )
+            .append(self.alloc.line())
+            .append("};")
+            .group()
+    }
+
+    /// The declarations of all generated functions, so that they can be defined in any order
+    pub fn prototypes(&self) -> Builder<'a> {
+        let prototypes =
+            self.signatures.iter().map(|signature| self.alloc.text(format!("{signature};")));
+        self.alloc.intersperse(prototypes, self.alloc.hardline())
+    }
+
+    fn fn_cx(&self) -> FnCx {
+        FnCx { names: self.globals.names.clone(), scope: vec![] }
+    }
+
+    /// Define a function which returns `result` after the statements `stmts` and drops the
+    /// variables `owned`
+    fn function(
+        &mut self,
+        cx: &mut FnCx,
+        signature: String,
+        mut stmts: Stmts<'a>,
+        result: String,
+        owned: Vec<String>,
+    ) {
+        // The result has to be computed before the variables are dropped
+        let is_variable = result.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let result = if is_variable && !owned.contains(&result) {
+            result
+        } else {
+            self.bind_tmp(cx, result, &mut stmts)
+        };
+        stmts.extend(owned.iter().map(|ident| self.alloc.text(format!("pol_drop({ident});"))));
+        stmts.push(self.alloc.text(format!("return {result};")));
+        let function = self.alloc.text(format!("{signature} ")).append(self.block(stmts));
+        self.signatures.push(signature);
+        self.functions.push(function);
+    }
+
+    /// Define the code of a codefinition or local comatch, whose fields are bound to `fields`
+    fn code(&mut self, cx: &mut FnCx, ident: &str, fields: &[String], cases: &[ir::Case]) {
+        let mut stmts = vec![];
+        let mut owned = vec![];
+        for (i, field) in fields.iter().enumerate() {
+            let field = cx.bind(field);
+            stmts.push(self.alloc.text(format!("pol_obj *{field} = pol_field(self, {i});")));
+            owned.push(field);
+        }
+        stmts.push(self.alloc.text("pol_drop(self);"));
+
+        let result = cx.names.fresh("r");
+        stmts.push(self.alloc.text(format!("pol_obj *{result};")));
+        let mut switch = self.alloc.text("switch (dtor) {");
+        for ir::Case { pattern, body } in cases {
+            let Some(body) = body else { continue };
+            let dtor = self.dtor(&pattern.module_uri, &pattern.name);
+            let len = cx.scope.len();
+            let mut case_stmts = vec![];
+            let mut params = vec![];
+            for (i, param) in pattern.params.iter().enumerate() {
+                let param = cx.bind(param);
+                case_stmts.push(self.alloc.text(format!("pol_obj *{param} = args[{i}];")));
+                params.push(param);
+            }
+            let value = self.exp(cx, body, &mut case_stmts);
+            self.assign_and_drop(&result, value, params, &mut case_stmts);
+            cx.scope.truncate(len);
+            let label = format!("case {dtor}: /* {} */ ", pattern.name);
+            switch = switch.append(self.case(label, case_stmts));
+        }
+        stmts.push(switch.append(self.default_case()).append(self.alloc.hardline()).append("}"));
+
+        let signature =
+            format!("static pol_obj *{ident}(pol_obj *self, uint32_t dtor, pol_obj **args)");
+        self.function(cx, signature, stmts, result, owned);
+    }
+
+    /// A switch statement on the tag of `scrutinee`, whose cases assign the value of the match to
+    /// the returned variable
+    ///
+    /// If `owned` is set, the reference to the scrutinee is dropped.
+    fn match_stmts(
+        &mut self,
+        cx: &mut FnCx,
+        scrutinee: &str,
+        owned: bool,
+        cases: &[ir::Case],
+        stmts: &mut Stmts<'a>,
+    ) -> String {
+        let result = cx.names.fresh("r");
+        stmts.push(self.alloc.text(format!("pol_obj *{result};")));
+        let mut switch = self.alloc.text(format!("switch ({scrutinee}->tag) {{"));
+        for ir::Case { pattern, body } in cases {
+            let Some(body) = body else { continue };
+            let tag = self.tag(&pattern.module_uri, &pattern.name);
+            let len = cx.scope.len();
+            let mut case_stmts = vec![];
+            let mut params = vec![];
+            for (i, param) in pattern.params.iter().enumerate() {
+                let param = cx.bind(param);
+                case_stmts.push(
+                    self.alloc.text(format!("pol_obj *{param} = pol_field({scrutinee}, {i});")),
+                );
+                params.push(param);
+            }
+            if owned {
+                case_stmts.push(self.alloc.text(format!("pol_drop({scrutinee});")));
+            }
+            let value = self.exp(cx, body, &mut case_stmts);
+            self.assign_and_drop(&result, value, params, &mut case_stmts);
+            cx.scope.truncate(len);
+            let label = format!("case {tag}: /* {} */ ", pattern.name);
+            switch = switch.append(self.case(label, case_stmts));
+        }
+        stmts.push(switch.append(self.default_case()).append(self.alloc.hardline()).append("}"));
+        result
+    }
+
+    /// Assign `value` to `result` and drop the variables `owned` at the end of a case
+    fn assign_and_drop(
+        &self,
+        result: &str,
+        value: String,
+        owned: Vec<String>,
+        stmts: &mut Stmts<'a>,
+    ) {
+        stmts.push(self.alloc.text(format!("{result} = {value};")));
+        stmts.extend(owned.iter().map(|ident| self.alloc.text(format!("pol_drop({ident});"))));
+        stmts.push(self.alloc.text("break;"));
+    }
+
+    fn case(&self, label: String, stmts: Stmts<'a>) -> Builder<'a> {
+        self.alloc
+            .hardline()
+            .append(self.alloc.text(label))
+            .append(self.block(stmts))
+            .nest(self.indent())
+    }
+
+    fn default_case(&self) -> Builder<'a> {
+        self.alloc
+            .hardline()
+            .append("default:")
+            .append(self.alloc.hardline().append("pol_panic(\"unreachable\");").nest(self.indent()))
+            .nest(self.indent())
+    }
+
+    /// An expression which owns a reference to the value of `exp`
+    ///
+    /// The statements which compute the value are appended to `stmts`. Calls are always bound to
+    /// a variable, so that they are evaluated from left to right.
+    fn exp(&mut self, cx: &mut FnCx, exp: &ir::Exp, stmts: &mut Stmts<'a>) -> String {
+        match exp {
+            ir::Exp::Variable(ir::Variable { name }) => match cx.lookup(name) {
+                Some(ident) => format!("pol_dup({ident})"),
+                // Variables which are bound by erased parameters still occur in the IR where
+                // types are not erased yet, but they have no value at runtime.
+                None => "NULL".to_owned(),
+            },
+            ir::Exp::CtorCall(ir::Call { name, module_uri, args }) => {
+                let tag = self.tag(module_uri, name);
+                let args = self.exps(cx, args, stmts);
+                self.bind_tmp(cx, format!("pol_ctor({tag} /* {name} */, {})", array(&args)), stmts)
+            }
+            ir::Exp::CodefCall(ir::Call { name, module_uri, args }) => {
+                let tag = self.tag(module_uri, name);
+                let ident = &self.globals.codefs[&(module_uri.clone(), name.clone())];
+                let args = self.exps(cx, args, stmts);
+                let call = format!("pol_codef({tag} /* {name} */, {ident}, {})", array(&args));
+                self.bind_tmp(cx, call, stmts)
+            }
+            ir::Exp::LetCall(ir::Call { name, module_uri, args }) => {
+                let ident = &self.globals.lets[&(module_uri.clone(), name.clone())];
+                let args = self.exps(cx, args, stmts);
+                self.bind_tmp(cx, format!("{ident}({})", args.join(", ")), stmts)
+            }
+            ir::Exp::DefCall(ir::DotCall { exp, module_uri, name, args }) => {
+                let ident = &self.globals.defs[&(module_uri.clone(), name.clone())];
+                let mut exps = vec![self.exp(cx, exp, stmts)];
+                exps.extend(self.exps(cx, args, stmts));
+                self.bind_tmp(cx, format!("{ident}({})", exps.join(", ")), stmts)
+            }
+            ir::Exp::DtorCall(ir::DotCall { exp, module_uri, name, args }) => {
+                let dtor = self.dtor(module_uri, name);
+                let on = self.exp(cx, exp, stmts);
+                let args = self.exps(cx, args, stmts);
+                let args = if args.is_empty() {
+                    "NULL".to_owned()
+                } else {
+                    format!("(pol_obj *[]){{{}}}", args.join(", "))
+                };
+                self.bind_tmp(cx, format!("pol_call({on}, {dtor} /* {name} */, {args})"), stmts)
+            }
+            ir::Exp::LocalComatch(ir::LocalComatch { cases }) => {
+                // The closure captures the variables which occur in the comatch
+                let mut captured = vec![];
+                for case in cases {
+                    free_vars_case(case, &mut vec![], &mut captured);
+                }
+                captured.retain(|name| cx.lookup(name).is_some());
+                let ident = self.comatch_ident(cx);
+                let mut comatch_cx = self.fn_cx();
+                self.code(&mut comatch_cx, &ident, &captured, cases);
+                let captured: Vec<_> = captured
+                    .iter()
+                    .filter_map(|name| cx.lookup(name))
+                    .map(|ident| format!("pol_dup({ident})"))
+                    .collect();
+                let len = captured.len();
+                let closure = if captured.is_empty() {
+                    format!("pol_comatch({ident}, 0, NULL)")
+                } else {
+                    format!("pol_comatch({ident}, {len}, (pol_obj *[]){{{}}})", captured.join(", "))
+                };
+                self.bind_tmp(cx, closure, stmts)
+            }
+            ir::Exp::LocalMatch(ir::LocalMatch { on_exp, cases }) => {
+                if let ir::Exp::Variable(ir::Variable { name }) = &**on_exp {
+                    if let Some(scrutinee) = cx.lookup(name) {
+                        return self.match_stmts(cx, &scrutinee, false, cases, stmts);
+                    }
+                }
+                let on = self.exp(cx, on_exp, stmts);
+                let scrutinee = self.bind_tmp(cx, on, stmts);
+                self.match_stmts(cx, &scrutinee, true, cases, stmts)
+            }
+            ir::Exp::LocalLet(ir::LocalLet { name, bound, body }) => {
+                let bound = self.exp(cx, bound, stmts);
+                let len = cx.scope.len();
+                let ident = cx.bind(name);
+                stmts.push(self.alloc.text(format!("pol_obj *{ident} = {bound};")));
+                let body = self.exp(cx, body, stmts);
+                cx.scope.truncate(len);
+                let body = self.bind_tmp(cx, body, stmts);
+                stmts.push(self.alloc.text(format!("pol_drop({ident});")));
+                body
+            }
+            ir::Exp::Panic(ir::Panic { message }) => {
+                stmts.push(self.alloc.text(format!("pol_panic({});", string_literal(message))));
+                "NULL".to_owned()
+            }
+            ir::Exp::ZST => "NULL".to_owned(),
+        }
+    }
+
+    fn exps(&mut self, cx: &mut FnCx, exps: &[ir::Exp], stmts: &mut Stmts<'a>) -> Vec<String> {
+        exps.iter().map(|exp| self.exp(cx, exp, stmts)).collect()
+    }
+
+    /// Bind `value` to a fresh variable
+    fn bind_tmp(&self, cx: &mut FnCx, value: String, stmts: &mut Stmts<'a>) -> String {
+        let ident = cx.names.fresh("t");
+        stmts.push(self.alloc.text(format!("pol_obj *{ident} = {value};")));
+        ident
+    }
+
+    /// A fresh identifier for the code of a local comatch, which is not used in `cx`
+    fn comatch_ident(&mut self, cx: &FnCx) -> String {
+        loop {
+            let ident = format!("comatch_{}", self.comatches);
+            self.comatches += 1;
+            if !self.globals.names.contains(&ident) && !cx.names.contains(&ident) {
+                return ident;
+            }
+        }
+    }
+
+    /// The tag of the constructor or codefinition `name`
+    fn tag(&mut self, module_uri: &Url, name: &str) -> usize {
+        let next = self.tag_names.len();
+        let tag = *self.tags.entry((module_uri.clone(), name.to_owned())).or_insert(next);
+        if tag == next {
+            self.tag_names.push(name.to_owned());
+        }
+        tag
+    }
+
+    /// The number of the destructor `name`
+    fn dtor(&mut self, module_uri: &Url, name: &str) -> usize {
+        let next = self.dtors.len();
+        *self.dtors.entry((module_uri.clone(), name.to_owned())).or_insert(next)
+    }
+
+    fn block(&self, stmts: Stmts<'a>) -> Builder<'a> {
+        self.alloc
+            .text("{")
+            .append(
+                self.alloc
+                    .hardline()
+                    .append(self.alloc.intersperse(stmts, self.alloc.hardline()))
+                    .nest(self.indent()),
+            )
+            .append(self.alloc.hardline())
+            .append("}")
+    }
+
+    fn indent(&self) -> isize {
+        self.cfg.indent
+    }
+}
+
+/// The number of `args` followed by an array literal which contains them
+fn array(args: &[String]) -> String {
+    if args.is_empty() {
+        "0, NULL".to_owned()
+    } else {
+        format!("{}, (pol_obj *[]){{{}}}", args.len(), args.join(", "))
+    }
+}
+
+/// Collect the variables which occur free in `exp` and are not bound by `bound` in `free`
+fn free_vars<'e>(exp: &'e ir::Exp, bound: &mut Vec<&'e str>, free: &mut Vec<String>) {
+    match exp {
+        ir::Exp::Variable(ir::Variable { name }) => {
+            if !bound.contains(&name.as_str()) && !free.contains(name) {
+                free.push(name.clone());
+            }
+        }
+        ir::Exp::CtorCall(call) | ir::Exp::CodefCall(call) | ir::Exp::LetCall(call) => {
+            call.args.iter().for_each(|arg| free_vars(arg, bound, free));
+        }
+        ir::Exp::DtorCall(dot_call) | ir::Exp::DefCall(dot_call) => {
+            free_vars(&dot_call.exp, bound, free);
+            dot_call.args.iter().for_each(|arg| free_vars(arg, bound, free));
+        }
+        ir::Exp::LocalMatch(ir::LocalMatch { on_exp, cases }) => {
+            free_vars(on_exp, bound, free);
+            cases.iter().for_each(|case| free_vars_case(case, bound, free));
+        }
+        ir::Exp::LocalComatch(ir::LocalComatch { cases }) => {
+            cases.iter().for_each(|case| free_vars_case(case, bound, free));
+        }
+        ir::Exp::LocalLet(ir::LocalLet { name, bound: exp, body }) => {
+            free_vars(exp, bound, free);
+            bound.push(name);
+            free_vars(body, bound, free);
+            bound.pop();
+        }
+        ir::Exp::Panic(_) | ir::Exp::ZST => {}
+    }
+}
+
+fn free_vars_case<'e>(case: &'e ir::Case, bound: &mut Vec<&'e str>, free: &mut Vec<String>) {
+    let Some(body) = &case.body else { return };
+    let len = bound.len();
+    bound.extend(case.pattern.params.iter().map(|param| param.as_str()));
+    free_vars(body, bound, free);
+    bound.truncate(len);
+}
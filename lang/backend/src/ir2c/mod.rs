@@ -0,0 +1,120 @@
+//! C code generation from the intermediate representation
+//!
+//! A module is translated together with the modules it imports into a single C translation unit,
+//! which evaluates the main expression of the module and prints its value. The generated code
+//! uses the runtime in `runtime.c`:
+//!
+//! * Constructors, codefinitions and the closures of local comatches are reference counted heap
+//!   objects, see `pol_obj`.
+//! * Definitions become functions which take the scrutinee as their first argument and switch on
+//!   its tag.
+//! * Codefinitions and local comatches become functions which switch on the called destructor.
+//!   The object stores the arguments of the codefinition or the variables captured by the
+//!   comatch.
+//! * Top-level let-bindings become functions.
+//! * `panic!` prints the message and exits with status 1.
+//!
+//! All generated functions take ownership of their arguments, and every variable owns a
+//! reference which is dropped at the end of its scope.
+
+use std::collections::HashMap;
+
+use url::Url;
+
+use printer::{Alloc, Builder, DocAllocator, Print, PrintCfg};
+
+use crate::ir;
+
+mod exprs;
+mod names;
+
+use exprs::Gen;
+use names::Names;
+
+/// The runtime which is included in every generated program
+const RUNTIME: &str = include_str!("runtime.c");
+
+/// The C program for a set of IR modules
+pub struct CModule<'a> {
+    modules: Vec<&'a ir::Module>,
+}
+
+impl<'a> CModule<'a> {
+    /// Translate `modules` to C
+    ///
+    /// The main expression of the first module is evaluated by the program. The other modules
+    /// must contain all declarations which are used by the first module, including those of
+    /// indirect imports.
+    pub fn new(modules: impl IntoIterator<Item = &'a ir::Module>) -> Self {
+        CModule { modules: modules.into_iter().collect() }
+    }
+
+    /// The main expression of the first module, if there is one
+    pub fn main(&self) -> Option<&'a ir::Let> {
+        let module = self.modules.first()?;
+        module.let_decls.iter().find(|tl_let| tl_let.name == "main" && tl_let.params.is_empty())
+    }
+}
+
+/// The identifiers of the top-level declarations
+#[derive(Default)]
+struct Globals {
+    names: Names,
+    defs: HashMap<(Url, String), String>,
+    codefs: HashMap<(Url, String), String>,
+    lets: HashMap<(Url, String), String>,
+}
+
+impl Globals {
+    fn new(modules: &[&ir::Module]) -> Self {
+        let mut globals = Globals::default();
+        for module in modules {
+            let uri = &module.uri;
+            for ir::Def { name, .. } in &module.def_decls {
+                let ident = globals.names.fresh(&format!("def_{name}"));
+                globals.defs.insert((uri.clone(), name.clone()), ident);
+            }
+            for ir::Codef { name, .. } in &module.codef_decls {
+                let ident = globals.names.fresh(&format!("codef_{name}"));
+                globals.codefs.insert((uri.clone(), name.clone()), ident);
+            }
+            for ir::Let { name, .. } in &module.let_decls {
+                let ident = globals.names.fresh(&format!("let_{name}"));
+                globals.lets.insert((uri.clone(), name.clone()), ident);
+            }
+        }
+        globals
+    }
+}
+
+impl Print for CModule<'_> {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let globals = Globals::new(&self.modules);
+        let mut gen = Gen::new(&globals, cfg, alloc);
+
+        for module in &self.modules {
+            let uri = &module.uri;
+            for def in &module.def_decls {
+                gen.def(&globals.defs[&(uri.clone(), def.name.clone())], def);
+            }
+            for codef in &module.codef_decls {
+                gen.codef(&globals.codefs[&(uri.clone(), codef.name.clone())], codef);
+            }
+            for tl_let in &module.let_decls {
+                gen.tl_let(&globals.lets[&(uri.clone(), tl_let.name.clone())], tl_let);
+            }
+        }
+        let main = self.main().map(|main| {
+            let uri = &self.modules[0].uri;
+            gen.main(&globals.lets[&(uri.clone(), main.name.clone())])
+        });
+
+        let runtime =
+            alloc.intersperse(RUNTIME.lines().map(|line| alloc.text(line)), alloc.hardline());
+        let mut parts = vec![runtime, gen.names_table(), gen.prototypes()];
+        parts.extend(gen.functions);
+        parts.extend(main);
+
+        alloc.intersperse(parts, alloc.hardline().append(alloc.hardline())).append(alloc.hardline())
+    }
+}
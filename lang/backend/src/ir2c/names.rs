@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+
+/// Reserved words of C and the identifiers of the runtime and the C standard library which are
+/// used by the generated code
+const RESERVED: &[&str] = &[
+    "FILE",
+    "NULL",
+    "POL_CODEF",
+    "POL_COMATCH",
+    "POL_CTOR",
+    "args",
+    "auto",
+    "break",
+    "case",
+    "char",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "dtor",
+    "else",
+    "enum",
+    "exit",
+    "extern",
+    "fflush",
+    "float",
+    "for",
+    "fprintf",
+    "fputc",
+    "fputs",
+    "free",
+    "goto",
+    "if",
+    "inline",
+    "int",
+    "long",
+    "main",
+    "malloc",
+    "pol_alloc",
+    "pol_call",
+    "pol_code",
+    "pol_codef",
+    "pol_comatch",
+    "pol_ctor",
+    "pol_drop",
+    "pol_drop_cap",
+    "pol_drop_len",
+    "pol_drop_stack",
+    "pol_dup",
+    "pol_field",
+    "pol_kind",
+    "pol_names",
+    "pol_obj",
+    "pol_panic",
+    "pol_print",
+    "putchar",
+    "realloc",
+    "register",
+    "restrict",
+    "result",
+    "return",
+    "self",
+    "short",
+    "signed",
+    "size_t",
+    "sizeof",
+    "static",
+    "stderr",
+    "stdout",
+    "struct",
+    "switch",
+    "typedef",
+    "uint32_t",
+    "uint8_t",
+    "union",
+    "unsigned",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// The identifiers which are already used in a function of the generated code
+///
+/// Identifiers are generated from the names of the source program by replacing characters which
+/// are not allowed in C identifiers, and are made unique by a numeric suffix.
+#[derive(Debug, Clone)]
+pub struct Names {
+    used: HashSet<String>,
+}
+
+impl Default for Names {
+    fn default() -> Self {
+        Self { used: RESERVED.iter().map(|word| word.to_string()).collect() }
+    }
+}
+
+impl Names {
+    /// A fresh identifier for the name `name`
+    pub fn fresh(&mut self, name: &str) -> String {
+        let base = sanitize(name);
+        let mut ident = base.clone();
+        let mut n = 1;
+        while self.used.contains(&ident) {
+            ident = format!("{base}_{n}");
+            n += 1;
+        }
+        self.used.insert(ident.clone());
+        ident
+    }
+
+    /// Whether the identifier `ident` is already used
+    pub fn contains(&self, ident: &str) -> bool {
+        self.used.contains(ident)
+    }
+}
+
+/// Replace the characters of `name` which are not allowed in a C identifier by `_`
+fn sanitize(name: &str) -> String {
+    let mut ident: String =
+        name.chars().map(|c| if c.is_ascii_alphanumeric() { c } else { '_' }).collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// A string literal with the UTF-8 encoding of `s`
+pub fn string_literal(s: &str) -> String {
+    let mut literal = String::from("\"");
+    for byte in s.bytes() {
+        match byte {
+            b'"' | b'\\' => {
+                literal.push('\\');
+                literal.push(byte as char);
+            }
+            b' '..=b'~' => literal.push(byte as char),
+            _ => literal.push_str(&format!("\\{byte:03o}")),
+        }
+    }
+    literal.push('"');
+    literal
+}
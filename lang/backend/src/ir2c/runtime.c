@@ -0,0 +1,182 @@
+/* The runtime of programs which are compiled to C by `pol compile --target c` */
+
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+typedef struct pol_obj pol_obj;
+
+/* The code of a codefinition or local comatch.
+ *
+ * The code is called with the object, the destructor and the arguments of the destructor, and
+ * takes ownership of the object and the arguments. */
+typedef pol_obj *(*pol_code)(pol_obj *self, uint32_t dtor, pol_obj **args);
+
+enum pol_kind { POL_CTOR, POL_CODEF, POL_COMATCH };
+
+/* A heap object, which is either a constructor applied to its arguments, a codefinition applied
+ * to its arguments or the closure of a local comatch.
+ *
+ * Objects are reference counted. Zero-sized terms are represented by `NULL`. */
+struct pol_obj {
+    uint32_t rc;
+    uint8_t kind;
+    /* The name of the constructor or codefinition, as an index into the table of names */
+    uint32_t tag;
+    uint32_t arity;
+    pol_code code;
+    pol_obj *fields[];
+};
+
+static _Noreturn void pol_panic(const char *message) {
+    fflush(stdout);
+    fprintf(stderr, "Evaluation panicked: %s\n", message);
+    exit(1);
+}
+
+static pol_obj *pol_alloc(uint8_t kind, uint32_t tag, pol_code code, uint32_t arity) {
+    pol_obj *obj = malloc(sizeof(pol_obj) + arity * sizeof(pol_obj *));
+    if (obj == NULL) {
+        pol_panic("out of memory");
+    }
+    obj->rc = 1;
+    obj->kind = kind;
+    obj->tag = tag;
+    obj->arity = arity;
+    obj->code = code;
+    return obj;
+}
+
+static pol_obj *pol_dup(pol_obj *obj) {
+    if (obj != NULL) {
+        obj->rc++;
+    }
+    return obj;
+}
+
+/* The objects whose reference count dropped to zero, and whose fields are not dropped yet.
+ * The stack is explicit so that dropping a long list cannot overflow the C stack. */
+static pol_obj **pol_drop_stack = NULL;
+static size_t pol_drop_len = 0;
+static size_t pol_drop_cap = 0;
+
+static void pol_drop(pol_obj *obj) {
+    if (obj == NULL || --obj->rc > 0) {
+        return;
+    }
+    size_t base = pol_drop_len;
+    for (;;) {
+        for (uint32_t i = 0; i < obj->arity; i++) {
+            pol_obj *field = obj->fields[i];
+            if (field == NULL || --field->rc > 0) {
+                continue;
+            }
+            if (pol_drop_len == pol_drop_cap) {
+                pol_drop_cap = pol_drop_cap == 0 ? 64 : 2 * pol_drop_cap;
+                pol_drop_stack = realloc(pol_drop_stack, pol_drop_cap * sizeof(pol_obj *));
+                if (pol_drop_stack == NULL) {
+                    pol_panic("out of memory");
+                }
+            }
+            pol_drop_stack[pol_drop_len++] = field;
+        }
+        free(obj);
+        if (pol_drop_len == base) {
+            return;
+        }
+        obj = pol_drop_stack[--pol_drop_len];
+    }
+}
+
+/* The field `i` of `obj`, whose reference is shared with `obj` */
+static pol_obj *pol_field(pol_obj *obj, uint32_t i) {
+    return pol_dup(obj->fields[i]);
+}
+
+static pol_obj *pol_ctor(uint32_t tag, uint32_t arity, pol_obj **args) {
+    pol_obj *obj = pol_alloc(POL_CTOR, tag, NULL, arity);
+    for (uint32_t i = 0; i < arity; i++) {
+        obj->fields[i] = args[i];
+    }
+    return obj;
+}
+
+static pol_obj *pol_codef(uint32_t tag, pol_code code, uint32_t arity, pol_obj **args) {
+    pol_obj *obj = pol_alloc(POL_CODEF, tag, code, arity);
+    for (uint32_t i = 0; i < arity; i++) {
+        obj->fields[i] = args[i];
+    }
+    return obj;
+}
+
+static pol_obj *pol_comatch(pol_code code, uint32_t arity, pol_obj **captured) {
+    pol_obj *obj = pol_alloc(POL_COMATCH, 0, code, arity);
+    for (uint32_t i = 0; i < arity; i++) {
+        obj->fields[i] = captured[i];
+    }
+    return obj;
+}
+
+/* Call the destructor `dtor` on the codata object `obj` */
+static pol_obj *pol_call(pol_obj *obj, uint32_t dtor, pol_obj **args) {
+    if (obj == NULL || obj->kind == POL_CTOR) {
+        pol_panic("cannot call a destructor on a non-codata value");
+    }
+    return obj->code(obj, dtor, args);
+}
+
+/* An object whose fields are printed, and the index of the next field to print */
+typedef struct {
+    pol_obj *obj;
+    uint32_t next;
+    int first;
+} pol_print_frame;
+
+/* Print `obj` in the same format as `pol run --backend ir`
+ *
+ * The objects whose fields are printed are kept on an explicit stack, so that printing a long
+ * list cannot overflow the C stack. */
+static void pol_print(FILE *out, pol_obj *obj, const char *const *names) {
+    pol_print_frame *stack = NULL;
+    size_t len = 0;
+    size_t cap = 0;
+    for (;;) {
+        if (obj == NULL) {
+            fputs("<ZST>", out);
+        } else if (obj->kind == POL_COMATCH) {
+            fputs("<comatch>", out);
+        } else {
+            fputs(names[obj->tag], out);
+            if (len == cap) {
+                cap = cap == 0 ? 64 : 2 * cap;
+                stack = realloc(stack, cap * sizeof(pol_print_frame));
+                if (stack == NULL) {
+                    pol_panic("out of memory");
+                }
+            }
+            stack[len++] = (pol_print_frame){obj, 0, 1};
+        }
+        /* Continue with the next field which is not zero-sized, and close the parentheses of the
+         * objects whose fields are all printed */
+        for (;;) {
+            if (len == 0) {
+                free(stack);
+                return;
+            }
+            pol_print_frame *frame = &stack[len - 1];
+            while (frame->next < frame->obj->arity && frame->obj->fields[frame->next] == NULL) {
+                frame->next++;
+            }
+            if (frame->next < frame->obj->arity) {
+                fputs(frame->first ? "(" : ", ", out);
+                frame->first = 0;
+                obj = frame->obj->fields[frame->next++];
+                break;
+            }
+            if (!frame->first) {
+                fputc(')', out);
+            }
+            len--;
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use printer::tokens::COMMA;
+use printer::{Alloc, Builder, DocAllocator, PrintCfg};
+
+use crate::ir;
+
+use super::names::{is_property_name, string_literal, Names};
+use super::{Globals, PANIC};
+
+/// The state of the translation of a single top-level declaration
+pub struct DeclCx<'g, 'a> {
+    globals: &'g Globals,
+    cfg: &'g PrintCfg,
+    alloc: &'a Alloc<'a>,
+    /// The identifiers which are used in the declaration, including all top-level identifiers
+    names: Names,
+    /// The identifiers of the local variables which are in scope
+    scope: Vec<(String, String)>,
+    /// Whether the declaration uses the runtime function which throws an error
+    pub uses_panic: bool,
+}
+
+impl<'g, 'a> DeclCx<'g, 'a> {
+    pub fn new(globals: &'g Globals, cfg: &'g PrintCfg, alloc: &'a Alloc<'a>) -> Self {
+        Self { globals, cfg, alloc, names: globals.names.clone(), scope: vec![], uses_panic: false }
+    }
+
+    /// A definition becomes a function which switches on the tag of its first argument:
+    ///
+    /// ```text
+    /// function d(self, y_1, ...) { switch (self.tag) { case "C": ... } }
+    /// ```
+    pub fn def(&mut self, ident: &str, def: &ir::Def) -> Builder<'a> {
+        let ir::Def { params, cases, .. } = def;
+        let scrutinee = self.bind("self");
+        let mut idents = vec![scrutinee.clone()];
+        idents.extend(params.iter().map(|param| self.bind(param)));
+        let body = self.match_stmt(&scrutinee, cases);
+        self.function(ident, idents, body)
+    }
+
+    /// A codefinition becomes a function which returns an object with a method for each
+    /// destructor:
+    ///
+    /// ```text
+    /// function C(x_1, ...) { return { d: (y_1, ...) => ..., ... }; }
+    /// ```
+    pub fn codef(&mut self, ident: &str, codef: &ir::Codef) -> Builder<'a> {
+        let ir::Codef { params, cases, .. } = codef;
+        let idents = params.iter().map(|param| self.bind(param)).collect();
+        let body = self.alloc.text("return ").append(self.comatch(cases)).append(";");
+        self.function(ident, idents, body)
+    }
+
+    /// A top-level let-binding becomes a function
+    pub fn tl_let(&mut self, ident: &str, tl_let: &ir::Let) -> Builder<'a> {
+        let ir::Let { params, body, .. } = tl_let;
+        let idents = params.iter().map(|param| self.bind(param)).collect();
+        let body = self.tail(body);
+        self.function(ident, idents, body)
+    }
+
+    fn function(&self, ident: &str, params: Vec<String>, body: Builder<'a>) -> Builder<'a> {
+        let params = self.params(params);
+        self.alloc
+            .text(format!("function {ident}"))
+            .append(params)
+            .append(" ")
+            .append(self.block(body))
+    }
+
+    /// The statements which compute `exp` and return its value
+    fn tail(&mut self, exp: &ir::Exp) -> Builder<'a> {
+        match exp {
+            ir::Exp::LocalLet(ir::LocalLet { name, bound, body }) => {
+                let bound = self.exp(bound);
+                let len = self.scope.len();
+                let ident = self.bind(name);
+                let body = self.tail(body);
+                self.scope.truncate(len);
+                self.alloc
+                    .text(format!("const {ident} = "))
+                    .append(bound)
+                    .append(";")
+                    .append(self.alloc.hardline())
+                    .append(body)
+            }
+            ir::Exp::LocalMatch(ir::LocalMatch { on_exp, cases }) => {
+                let variable = match &**on_exp {
+                    ir::Exp::Variable(ir::Variable { name }) => self.lookup(name),
+                    _ => None,
+                };
+                if let Some(scrutinee) = variable {
+                    return self.match_stmt(&scrutinee, cases);
+                }
+                let on_exp = self.exp(on_exp);
+                let scrutinee = self.names.fresh("scrutinee");
+                self.alloc
+                    .text(format!("const {scrutinee} = "))
+                    .append(on_exp)
+                    .append(";")
+                    .append(self.alloc.hardline())
+                    .append(self.match_stmt(&scrutinee, cases))
+            }
+            ir::Exp::Panic(ir::Panic { message }) => {
+                self.alloc.text(format!("throw new Error({});", string_literal(message)))
+            }
+            exp => self.alloc.text("return ").append(self.exp(exp)).append(";"),
+        }
+    }
+
+    /// A switch statement on the tag of `scrutinee`, whose cases return the value of the match
+    fn match_stmt(&mut self, scrutinee: &str, cases: &[ir::Case]) -> Builder<'a> {
+        let mut doc = self.alloc.text(format!("switch ({scrutinee}.tag) {{"));
+        for ir::Case { pattern, body } in cases {
+            let Some(body) = body else { continue };
+            let label = format!("case {}:", string_literal(&pattern.name));
+            let case = if pattern.params.is_empty() {
+                let body = self.tail(body);
+                self.alloc
+                    .text(label)
+                    .append(self.alloc.hardline().append(body).nest(self.indent()))
+            } else {
+                let len = self.scope.len();
+                let idents: Vec<_> = pattern.params.iter().map(|param| self.bind(param)).collect();
+                let body = self.tail(body);
+                self.scope.truncate(len);
+                let args = format!("const [{}] = {scrutinee}.args;", idents.join(", "));
+                let body = self.alloc.text(args).append(self.alloc.hardline()).append(body);
+                self.alloc.text(label).append(" ").append(self.block(body))
+            };
+            doc = doc.append(self.alloc.hardline().append(case).nest(self.indent()));
+        }
+        doc.append(self.alloc.hardline()).append("}")
+    }
+
+    /// The expression which computes the value of `exp`
+    fn exp(&mut self, exp: &ir::Exp) -> Builder<'a> {
+        match exp {
+            ir::Exp::Variable(ir::Variable { name }) => match self.lookup(name) {
+                Some(ident) => self.alloc.text(ident),
+                // Variables which are bound by erased parameters still occur in the IR where
+                // types are not erased yet, but they have no value at runtime.
+                None => self.alloc.text("null"),
+            },
+            ir::Exp::CtorCall(ir::Call { name, args, .. }) => {
+                let args = self.exps(args);
+                let args = self.delimited("[", args, "]");
+                self.alloc
+                    .text(format!("{{ tag: {}, args: ", string_literal(name)))
+                    .append(args)
+                    .append(" }")
+                    .group()
+            }
+            ir::Exp::CodefCall(ir::Call { name, module_uri, args }) => {
+                let ident = &self.globals.codefs[&(module_uri.clone(), name.clone())];
+                let args = self.exps(args);
+                self.alloc.text(ident.clone()).append(self.delimited("(", args, ")"))
+            }
+            ir::Exp::LetCall(ir::Call { name, module_uri, args }) => {
+                let ident = &self.globals.lets[&(module_uri.clone(), name.clone())];
+                let args = self.exps(args);
+                self.alloc.text(ident.clone()).append(self.delimited("(", args, ")"))
+            }
+            ir::Exp::DefCall(ir::DotCall { exp, module_uri, name, args }) => {
+                let ident = &self.globals.defs[&(module_uri.clone(), name.clone())];
+                let mut exps = vec![self.exp(exp)];
+                exps.extend(self.exps(args));
+                self.alloc.text(ident.clone()).append(self.delimited("(", exps, ")"))
+            }
+            ir::Exp::DtorCall(ir::DotCall { exp, name, args, .. }) => {
+                let exp = self.exp(exp);
+                let args = self.exps(args);
+                let member = if is_property_name(name) {
+                    format!(".{name}")
+                } else {
+                    format!("[{}]", string_literal(name))
+                };
+                exp.append(member).append(self.delimited("(", args, ")"))
+            }
+            ir::Exp::LocalComatch(ir::LocalComatch { cases }) => self.comatch(cases),
+            ir::Exp::LocalMatch(_) | ir::Exp::LocalLet(_) => {
+                // Statements are turned into an expression by an immediately invoked function
+                let body = self.tail(exp);
+                self.alloc.text("(() => ").append(self.block(body)).append(")()")
+            }
+            ir::Exp::Panic(ir::Panic { message }) => {
+                self.uses_panic = true;
+                self.alloc.text(format!("{PANIC}({})", string_literal(message)))
+            }
+            ir::Exp::ZST => self.alloc.text("null"),
+        }
+    }
+
+    fn exps(&mut self, exps: &[ir::Exp]) -> Vec<Builder<'a>> {
+        exps.iter().map(|exp| self.exp(exp)).collect()
+    }
+
+    /// An object with a method for each destructor of `cases`
+    fn comatch(&mut self, cases: &[ir::Case]) -> Builder<'a> {
+        let mut methods = vec![];
+        for ir::Case { pattern, body } in cases {
+            let Some(body) = body else { continue };
+            let key = if is_property_name(&pattern.name) {
+                pattern.name.clone()
+            } else if pattern.name == "__proto__" {
+                format!("[{}]", string_literal(&pattern.name))
+            } else {
+                string_literal(&pattern.name)
+            };
+            let len = self.scope.len();
+            let idents = pattern.params.iter().map(|param| self.bind(param)).collect();
+            let params = self.params(idents);
+            let body = self.arrow_body(body);
+            self.scope.truncate(len);
+            methods.push(
+                self.alloc.text(format!("{key}: ")).append(params).append(" => ").append(body),
+            );
+        }
+        if methods.is_empty() {
+            return self.alloc.text("{}");
+        }
+        let sep = self.alloc.text(COMMA).append(self.alloc.line());
+        self.alloc
+            .text("{")
+            .append(
+                self.alloc.line().append(self.alloc.intersperse(methods, sep)).nest(self.indent()),
+            )
+            .append(self.alloc.line())
+            .append("}")
+            .group()
+    }
+
+    /// The body of an arrow function which returns the value of `exp`
+    fn arrow_body(&mut self, exp: &ir::Exp) -> Builder<'a> {
+        match exp {
+            ir::Exp::LocalMatch(_) | ir::Exp::LocalLet(_) | ir::Exp::Panic(_) => {
+                let body = self.tail(exp);
+                self.block(body)
+            }
+            // An object literal would be parsed as a block
+            exp if starts_with_brace(exp) => self.exp(exp).parens(),
+            exp => self.exp(exp),
+        }
+    }
+
+    fn params(&self, idents: Vec<String>) -> Builder<'a> {
+        self.alloc.text(format!("({})", idents.join(", ")))
+    }
+
+    fn delimited(
+        &self,
+        open: &'static str,
+        docs: Vec<Builder<'a>>,
+        close: &'static str,
+    ) -> Builder<'a> {
+        if docs.is_empty() {
+            return self.alloc.text(open).append(close);
+        }
+        let sep = self.alloc.text(COMMA).append(self.alloc.line());
+        self.alloc
+            .text(open)
+            .append(
+                self.alloc.line_().append(self.alloc.intersperse(docs, sep)).nest(self.indent()),
+            )
+            .append(self.alloc.line_())
+            .append(close)
+            .group()
+    }
+
+    fn block(&self, body: Builder<'a>) -> Builder<'a> {
+        self.alloc
+            .text("{")
+            .append(self.alloc.hardline().append(body).nest(self.indent()))
+            .append(self.alloc.hardline())
+            .append("}")
+    }
+
+    fn indent(&self) -> isize {
+        self.cfg.indent
+    }
+
+    /// Bind the variable `name` to a fresh identifier
+    fn bind(&mut self, name: &str) -> String {
+        let ident = self.names.fresh(name);
+        self.scope.push((name.to_owned(), ident.clone()));
+        ident
+    }
+
+    fn lookup(&self, name: &str) -> Option<String> {
+        self.scope.iter().rev().find(|(bound, _)| bound == name).map(|(_, ident)| ident.clone())
+    }
+}
+
+/// Whether the JavaScript expression for `exp` starts with `{`
+fn starts_with_brace(exp: &ir::Exp) -> bool {
+    match exp {
+        ir::Exp::CtorCall(_) | ir::Exp::LocalComatch(_) => true,
+        ir::Exp::DtorCall(ir::DotCall { exp, .. }) => starts_with_brace(exp),
+        _ => false,
+    }
+}
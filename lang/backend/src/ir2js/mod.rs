@@ -0,0 +1,168 @@
+//! JavaScript code generation from the intermediate representation
+//!
+//! A module is translated together with the modules it imports into a single ES module, which
+//! exports the declarations of the translated module:
+//!
+//! * Constructors become tagged objects `{ tag: "C", args: [...] }`.
+//! * Definitions become functions which take the scrutinee as their first argument and switch
+//!   on its tag.
+//! * Codefinitions and local comatches become objects with a method for each destructor.
+//! * Top-level let-bindings become functions.
+//! * `panic!` throws an `Error`.
+//!
+//! Every binder of a declaration is given a distinct identifier, so that the scoping rules of
+//! JavaScript, such as the temporal dead zone of `const`, never affect the generated code.
+
+use std::collections::HashMap;
+
+use url::Url;
+
+use printer::tokens::COMMA;
+use printer::{Alloc, Builder, DocAllocator, Print, PrintCfg};
+
+use crate::ir;
+
+mod exprs;
+mod names;
+
+use exprs::DeclCx;
+use names::{string_literal, Names};
+
+/// The identifier of the runtime function which throws an error
+const PANIC: &str = "$panic";
+
+/// The ES module for a set of IR modules
+pub struct JsModule<'a> {
+    modules: Vec<&'a ir::Module>,
+}
+
+impl<'a> JsModule<'a> {
+    /// Translate `modules` to JavaScript
+    ///
+    /// The declarations of the first module are exported. The other modules must contain all
+    /// declarations which are used by the first module, including those of indirect imports.
+    pub fn new(modules: impl IntoIterator<Item = &'a ir::Module>) -> Self {
+        JsModule { modules: modules.into_iter().collect() }
+    }
+}
+
+/// The identifiers of the top-level declarations
+#[derive(Default)]
+struct Globals {
+    names: Names,
+    defs: HashMap<(Url, String), String>,
+    codefs: HashMap<(Url, String), String>,
+    lets: HashMap<(Url, String), String>,
+}
+
+impl Globals {
+    fn new(modules: &[&ir::Module]) -> Self {
+        let mut globals = Globals::default();
+        for module in modules {
+            let uri = &module.uri;
+            for ir::Def { name, .. } in &module.def_decls {
+                let ident = globals.names.fresh(name);
+                globals.defs.insert((uri.clone(), name.clone()), ident);
+            }
+            for ir::Codef { name, .. } in &module.codef_decls {
+                let ident = globals.names.fresh(name);
+                globals.codefs.insert((uri.clone(), name.clone()), ident);
+            }
+            for ir::Let { name, .. } in &module.let_decls {
+                let ident = globals.names.fresh(name);
+                globals.lets.insert((uri.clone(), name.clone()), ident);
+            }
+        }
+        globals
+    }
+}
+
+impl Print for JsModule<'_> {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let globals = Globals::new(&self.modules);
+        let mut uses_panic = false;
+        let mut decls = vec![];
+
+        for module in &self.modules {
+            let uri = &module.uri;
+            for def in &module.def_decls {
+                let ident = &globals.defs[&(uri.clone(), def.name.clone())];
+                let mut cx = DeclCx::new(&globals, cfg, alloc);
+                decls.push(cx.def(ident, def));
+                uses_panic |= cx.uses_panic;
+            }
+            for codef in &module.codef_decls {
+                let ident = &globals.codefs[&(uri.clone(), codef.name.clone())];
+                let mut cx = DeclCx::new(&globals, cfg, alloc);
+                decls.push(cx.codef(ident, codef));
+                uses_panic |= cx.uses_panic;
+            }
+            for tl_let in &module.let_decls {
+                let ident = &globals.lets[&(uri.clone(), tl_let.name.clone())];
+                let mut cx = DeclCx::new(&globals, cfg, alloc);
+                decls.push(cx.tl_let(ident, tl_let));
+                uses_panic |= cx.uses_panic;
+            }
+        }
+
+        if uses_panic {
+            let panic = alloc
+                .text(format!("function {PANIC}(message) {{"))
+                .append(alloc.hardline().append("throw new Error(message);").nest(cfg.indent))
+                .append(alloc.hardline())
+                .append("}");
+            decls.insert(0, panic);
+        }
+        if let Some(module) = self.modules.first() {
+            decls.push(exports(&globals, module, cfg, alloc));
+        }
+
+        alloc.intersperse(decls, alloc.hardline().append(alloc.hardline())).append(alloc.hardline())
+    }
+}
+
+/// The export declaration for the declarations of `module`
+fn exports<'a>(
+    globals: &Globals,
+    module: &ir::Module,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
+    let uri = &module.uri;
+    let names =
+        module
+            .def_decls
+            .iter()
+            .map(|def| (&def.name, &globals.defs[&(uri.clone(), def.name.clone())]))
+            .chain(
+                module.codef_decls.iter().map(|codef| {
+                    (&codef.name, &globals.codefs[&(uri.clone(), codef.name.clone())])
+                }),
+            )
+            .chain(
+                module.let_decls.iter().map(|tl_let| {
+                    (&tl_let.name, &globals.lets[&(uri.clone(), tl_let.name.clone())])
+                }),
+            );
+    let specifiers: Vec<_> = names
+        .map(|(name, ident)| {
+            if name == ident {
+                alloc.text(ident.clone())
+            } else if names::is_property_name(name) {
+                alloc.text(format!("{ident} as {name}"))
+            } else {
+                alloc.text(format!("{ident} as {}", string_literal(name)))
+            }
+        })
+        .collect();
+    if specifiers.is_empty() {
+        return alloc.text("export {};");
+    }
+    let specifiers = alloc.intersperse(specifiers, alloc.text(COMMA).append(alloc.line()));
+    alloc
+        .text("export {")
+        .append(alloc.line().append(specifiers).nest(cfg.indent))
+        .append(alloc.line())
+        .append("};")
+        .group()
+}
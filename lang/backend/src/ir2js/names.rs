@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+
+/// Reserved words of JavaScript and the globals used by the generated code
+const RESERVED: &[&str] = &[
+    "arguments",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "Error",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "Infinity",
+    "instanceof",
+    "interface",
+    "let",
+    "NaN",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+/// The identifiers which are already used in a scope of the generated code
+///
+/// Identifiers are generated from the names of the source program by replacing characters which
+/// are not allowed in JavaScript identifiers. Since the names of the source program never contain
+/// `$`, the suffix `$n` which distinguishes identifiers with the same name never clashes with a
+/// name of the source program, and identifiers of the runtime start with `$`.
+#[derive(Debug, Clone)]
+pub struct Names {
+    used: HashSet<String>,
+}
+
+impl Default for Names {
+    fn default() -> Self {
+        Self { used: RESERVED.iter().map(|word| word.to_string()).collect() }
+    }
+}
+
+impl Names {
+    /// A fresh identifier for the name `name`
+    pub fn fresh(&mut self, name: &str) -> String {
+        let base = sanitize(name);
+        let mut ident = base.clone();
+        let mut n = 1;
+        while self.used.contains(&ident) {
+            ident = format!("{base}${n}");
+            n += 1;
+        }
+        self.used.insert(ident.clone());
+        ident
+    }
+}
+
+/// Replace the characters of `name` which may not be allowed in a JavaScript identifier by `_`
+fn sanitize(name: &str) -> String {
+    let mut ident: String = name
+        .chars()
+        .map(|c| if c.is_alphabetic() || c.is_ascii_digit() || c == '_' { c } else { '_' })
+        .collect();
+    if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+        ident.insert(0, '_');
+    }
+    ident
+}
+
+/// Whether `name` can be used as a property name in a member access `e.name`
+pub fn is_property_name(name: &str) -> bool {
+    sanitize(name) == name && name != "__proto__"
+}
+
+/// A string literal with the contents `s`
+pub fn string_literal(s: &str) -> String {
+    format!("\"{}\"", s.escape_default())
+}
@@ -1,3 +1,6 @@
 pub mod ast2ir;
+pub mod interpreter;
 pub mod ir;
+pub mod ir2c;
+pub mod ir2js;
 pub mod result;
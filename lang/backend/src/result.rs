@@ -5,4 +5,11 @@ use thiserror::Error;
 pub enum BackendError {
     #[error("Impossible: {0}")]
     Impossible(String),
+    #[error("Evaluation panicked: {0}")]
+    Panic(String),
+    #[error("Evaluation did not finish within {0}")]
+    #[diagnostic(help(
+        "The evaluation may not terminate. The limits can be changed with the `--max-steps` and `--timeout` options of `pol run`."
+    ))]
+    OutOfFuel(String),
 }
@@ -25,7 +25,7 @@ async fn write_modules() {
     let path_list = get_files(folders.clone());
     let list = generate_html_from_paths(folders);
     for (source_path, target_path) in path_list {
-        let mut db = Database::from_path(&source_path);
+        let mut db = Database::from_path(&source_path).expect("Failed to load project");
         let uri = db.resolve_path(&source_path).expect("Failed to resolve path");
         let prg = db.ust(&uri).await.expect("Failed to get UST");
 
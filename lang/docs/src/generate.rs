@@ -1,4 +1,4 @@
-use ast::{Case, Ctor, DocComment, Dtor};
+use ast::{Case, CatchAll, Ctor, DocComment, Dtor};
 
 use comrak::{markdown_to_html, Options};
 use printer::PrintCfg;
@@ -82,6 +82,12 @@ impl Generate for Vec<Case> {
     }
 }
 
+impl Generate for CatchAll {
+    fn generate(&self) -> String {
+        format!("<li>{}</li>", print_html_to_string(self, Some(&PrintCfg::default())))
+    }
+}
+
 impl Generate for Vec<Ctor> {
     fn generate(&self) -> String {
         self.iter().map(|value| value.generate()).collect::<Vec<String>>().join("")
@@ -1,6 +1,7 @@
 use askama::Template;
 
-use ast::{Codata, Codef, Data, Decl, Def, Infix, Let, Module};
+use ast::{Associativity, Codata, Codef, Data, Decl, Def, Infix, Let, Module};
+use printer::tokens::{INFIX, INFIXL, INFIXR};
 use printer::PrintCfg;
 
 use crate::generate::Generate;
@@ -70,7 +71,8 @@ impl GenerateDocs for Codata {
 
 impl GenerateDocs for Def {
     fn generate_docs(&self) -> String {
-        let Def { span: _, doc, name, attr: _, params, self_param, ret_typ, cases } = self;
+        let Def { span: _, doc, name, attr: _, params, self_param, ret_typ, cases, catch_all } =
+            self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
@@ -78,10 +80,10 @@ impl GenerateDocs for Def {
         let self_param: String = print_html_to_string(self_param, Some(&PrintCfg::default()));
         let ret_typ: String = print_html_to_string(ret_typ, Some(&PrintCfg::default()));
 
-        let body = if cases.is_empty() {
+        let body = if cases.is_empty() && catch_all.is_none() {
             "{}".to_string()
         } else {
-            format!("<ul>{}</ul>", cases.generate())
+            format!("<ul>{}{}</ul>", cases.generate(), catch_all.generate())
         };
 
         let def = DefTemplate {
@@ -98,17 +100,17 @@ impl GenerateDocs for Def {
 
 impl GenerateDocs for Codef {
     fn generate_docs(&self) -> String {
-        let Codef { span: _, doc, name, attr: _, params, typ, cases } = self;
+        let Codef { span: _, doc, name, attr: _, params, typ, cases, catch_all } = self;
 
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
         let name = &name.id;
         let params: String = print_html_to_string(params, Some(&PrintCfg::default()));
         let typ: String = print_html_to_string(typ, Some(&PrintCfg::default()));
 
-        let body = if cases.is_empty() {
+        let body = if cases.is_empty() && catch_all.is_none() {
             "{}".to_string()
         } else {
-            format!("<ul>{}</ul>", cases.generate())
+            format!("<ul>{}{}</ul>", cases.generate(), catch_all.generate())
         };
 
         let codef = CodefTemplate { doc: &doc, name, params: &params, typ: &typ, body: &body };
@@ -133,11 +135,22 @@ impl GenerateDocs for Let {
 
 impl GenerateDocs for Infix {
     fn generate_docs(&self) -> String {
-        let Infix { span: _, doc, attr: _, lhs, rhs } = self;
+        let Infix { span: _, doc, attr: _, fixity, lhs, rhs } = self;
         let doc = if doc.is_none() { "".to_string() } else { format!("{}<br>", doc.generate()) };
+        let keyword = match fixity.associativity {
+            Associativity::Left => INFIXL,
+            Associativity::Right => INFIXR,
+            Associativity::None => INFIX,
+        };
         let lhs = print_html_to_string(lhs, Some(&PrintCfg::default()));
         let rhs = print_html_to_string(rhs, Some(&PrintCfg::default()));
-        let infix_template = InfixTemplate { doc: &doc, lhs: &lhs, rhs: &rhs };
+        let infix_template = InfixTemplate {
+            doc: &doc,
+            keyword,
+            precedence: fixity.precedence,
+            lhs: &lhs,
+            rhs: &rhs,
+        };
         infix_template.render().unwrap()
     }
 }
@@ -197,6 +210,8 @@ struct LetTemplate<'a> {
 #[template(path = "infix.html", escape = "none")]
 struct InfixTemplate<'a> {
     pub doc: &'a str,
+    pub keyword: &'a str,
+    pub precedence: u32,
     pub lhs: &'a str,
     pub rhs: &'a str,
 }
@@ -32,7 +32,7 @@ where
     }
 
     fn fail_doc(&self) -> Self::Error {
-        io::Error::new(io::ErrorKind::Other, "Document failed to render")
+        io::Error::other("Document failed to render")
     }
 }
 
@@ -52,6 +52,7 @@ where
             Anno::BraceOpen => "",
             Anno::BraceClose => "",
             Anno::Error => "<span class=\"error\">",
+            Anno::Highlight => "<span class=\"highlight\">",
             Anno::Reference { module_uri, name } => &format!(
                 "<a href=\"{}#{}\">",
                 get_target_path(Path::new(module_uri.as_str())).to_string_lossy(),
@@ -0,0 +1,40 @@
+//! Embeds the modules of the standard library into the compiler.
+//!
+//! Generates the file `std_files.rs` which defines the constant `STD_FILES`, a list of pairs of the
+//! path of a module relative to the `std` directory and its contents.
+
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::{env, fs};
+
+fn main() {
+    let std_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../std").canonicalize().unwrap();
+    println!("cargo:rerun-if-changed={}", std_dir.display());
+
+    let mut files = Vec::new();
+    collect_modules(&std_dir, &mut files);
+    files.sort();
+
+    let mut out = String::from("pub const STD_FILES: &[(&str, &str)] = &[\n");
+    for file in files {
+        let relative = file.strip_prefix(&std_dir).unwrap();
+        let name =
+            relative.components().map(|c| c.as_os_str().to_str().unwrap()).collect::<Vec<_>>();
+        writeln!(out, "    ({:?}, include_str!({:?})),", name.join("/"), file.display()).unwrap();
+    }
+    out.push_str("];\n");
+
+    let out_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("std_files.rs");
+    fs::write(out_path, out).unwrap();
+}
+
+fn collect_modules(dir: &Path, files: &mut Vec<PathBuf>) {
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            collect_modules(&path, files);
+        } else if path.extension().is_some_and(|ext| ext == "pol") {
+            files.push(path);
+        }
+    }
+}
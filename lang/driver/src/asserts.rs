@@ -1,12 +1,10 @@
 use crate::database::Database;
 
 const _: () = {
-    fn assert_send<T: Send>() {}
-    fn assert_sync<T: Sync>() {}
+    const fn assert_send<T: Send>() {}
+    const fn assert_sync<T: Sync>() {}
 
     // RFC 2056
-    fn assert_all() {
-        assert_send::<Database>();
-        assert_sync::<Database>();
-    }
+    assert_send::<Database>();
+    assert_sync::<Database>();
 };
@@ -0,0 +1,56 @@
+use std::path::Path;
+use std::process::Command;
+
+use crate::result::DriverError;
+
+/// Compile the C file `c_path` to the executable `bin_path`
+///
+/// The C compiler is `cc`, unless another one is set by the `CC` environment variable.
+pub fn compile_c(c_path: &Path, bin_path: &Path) -> Result<(), DriverError> {
+    let mut cmd = c_compiler();
+    let cc = cmd.get_program().to_string_lossy().into_owned();
+    let output = cmd
+        .args(["-std=c11", "-O2", "-o"])
+        .arg(bin_path)
+        .arg(c_path)
+        .output()
+        .map_err(|err| DriverError::CCompiler(format!("Failed to run `{cc}`: {err}")))?;
+    if !output.status.success() {
+        return Err(DriverError::CCompiler(String::from_utf8_lossy(&output.stderr).into_owned()));
+    }
+    Ok(())
+}
+
+/// The command which runs the C compiler
+///
+/// The C compiler is `cc`, unless another one is set by the `CC` environment variable.
+/// As in `make`, the variable is split at whitespace, so that it can contain a wrapper or
+/// arguments of the compiler, such as `CC="ccache gcc"` or `CC="clang -m32"`.
+pub fn c_compiler() -> Command {
+    let cc = std::env::var("CC").unwrap_or_default();
+    let (program, args) = split_cc(&cc);
+    let mut cmd = Command::new(program);
+    cmd.args(args);
+    cmd
+}
+
+/// Split the value of the `CC` environment variable into the program and its arguments
+fn split_cc(cc: &str) -> (&str, Vec<&str>) {
+    let mut words = cc.split_whitespace();
+    match words.next() {
+        Some(program) => (program, words.collect()),
+        None => ("cc", vec![]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_cc_with_arguments() {
+        assert_eq!(split_cc("ccache  gcc -m32 "), ("ccache", vec!["gcc", "-m32"]));
+        assert_eq!(split_cc("clang"), ("clang", vec![]));
+        assert_eq!(split_cc(" "), ("cc", vec![]));
+    }
+}
@@ -10,16 +10,22 @@ use ast::Exp;
 use ast::HashSet;
 use ast::Zonk;
 use backend::ast2ir::traits::ToIR;
+use backend::interpreter::{Interpreter, Value};
 use backend::ir;
+use backend::ir2c::CModule;
+use backend::ir2js::JsModule;
+use elaborator::normalizer::fuel::EvalLimits;
 use elaborator::normalizer::normalize::Normalize;
+use elaborator::normalizer::step::Steps;
 use elaborator::{build_type_info_table, ModuleTypeInfoTable, TypeInfoTable};
-use lowering::{ModuleSymbolTable, SymbolTable};
+use lowering::{Export, Import, ModuleSymbolTable, SymbolTable};
 use parser::cst;
 use parser::cst::decls::UseDecl;
 
 use crate::dependency_graph::DependencyGraph;
 use crate::fs::*;
 use crate::info::*;
+use crate::manifest::Manifest;
 use crate::result::DriverError;
 use crate::{cache::*, Error, FileSource};
 
@@ -29,18 +35,34 @@ use rust_lapper::Lapper;
 pub struct Database {
     /// The source provider of the files (file system or in-memory)
     pub source: Box<dyn FileSource>,
+    /// The limits on evaluation during typechecking and `run`
+    pub eval_limits: EvalLimits,
+    /// The manifest of the project, if any
+    ///
+    /// The source roots and dependencies of the manifest are searched when resolving `use` declarations.
+    pub manifest: Option<Manifest>,
     /// Dependency graph for each module
     pub deps: DependencyGraph,
     /// The source code text of each file
     pub files: Cache<crate::codespan::File>,
     /// The CST of each file (once parsed)
+    ///
+    /// Declarations and cases with syntax errors are replaced by error nodes.
     pub cst: Cache<Result<Arc<cst::decls::Module>, Error>>,
+    /// The syntax errors of each file from which the parser recovered
+    pub parse_errors: Cache<Vec<parser::ParseError>>,
     /// The symbol table constructed during lowering
     pub symbol_table: Cache<Arc<lowering::ModuleSymbolTable>>,
     /// The lowered, but not yet typechecked, UST
     pub ust: Cache<Result<Arc<ast::Module>, Error>>,
     /// The typechecked AST of a module
     pub ast: Cache<Result<Arc<ast::Module>, Error>>,
+    /// The typechecked AST of all declarations of a module that could be parsed
+    ///
+    /// Declarations which fail to typecheck are contained as they were lowered.
+    pub partial_ast: Cache<Result<Arc<ast::Module>, Error>>,
+    /// The type errors of each file whose declarations could be lowered
+    pub type_errors: Cache<Vec<Box<elaborator::result::TypeError>>>,
     /// The IR of a module
     pub ir: Cache<Result<Arc<ir::Module>, Error>>,
     /// The type info table, either open or closed
@@ -118,7 +140,17 @@ impl Database {
     //
     //
 
+    /// The CST of a module, which is only returned if the module contains no syntax errors
     pub async fn cst(&mut self, uri: &Url) -> Result<Arc<cst::decls::Module>, Error> {
+        let cst = self.partial_cst(uri).await?;
+        self.check_syntax(uri)?;
+        Ok(cst)
+    }
+
+    /// The CST of a module, in which declarations and cases with syntax errors are replaced by error nodes
+    ///
+    /// The syntax errors are reported when the module is typechecked.
+    pub async fn partial_cst(&mut self, uri: &Url) -> Result<Arc<cst::decls::Module>, Error> {
         match self.cst.get_unless_stale(uri) {
             Some(cst) => {
                 log::debug!("Found cst in cache: {}", uri);
@@ -131,12 +163,29 @@ impl Database {
     async fn recompute_cst(&mut self, uri: &Url) -> Result<Arc<cst::decls::Module>, Error> {
         log::debug!("Recomputing cst for: {}", uri);
         let source = self.source(uri).await?;
-        let module =
-            parser::parse_module(uri.clone(), &source).map_err(Error::Parser).map(Arc::new);
+        let module = match parser::parse_module(uri.clone(), &source) {
+            (Some(module), errors) => {
+                self.parse_errors.insert(uri.clone(), errors);
+                Ok(Arc::new(module))
+            }
+            // The parser could not recover, so all syntax errors are contained in the error.
+            (None, errors) => {
+                self.parse_errors.insert(uri.clone(), vec![]);
+                Err(Error::from_parse_errors(errors))
+            }
+        };
         self.cst.insert(uri.clone(), module.clone());
         module
     }
 
+    /// Fail with all syntax errors of a module that has already been parsed
+    fn check_syntax(&self, uri: &Url) -> Result<(), Error> {
+        match self.parse_errors.get_even_if_stale(uri) {
+            Some(errors) if !errors.is_empty() => Err(Error::from_parse_errors(errors.clone())),
+            _ => Ok(()),
+        }
+    }
+
     // Core API: SymbolTable
     //
     //
@@ -153,7 +202,7 @@ impl Database {
 
     async fn recompute_symbol_table(&mut self, uri: &Url) -> Result<Arc<ModuleSymbolTable>, Error> {
         log::debug!("Recomputing symbol table for: {}", uri);
-        let cst = self.cst(uri).await?;
+        let cst = self.partial_cst(uri).await?;
         let module_symbol_table = lowering::build_symbol_table(&cst).map(Arc::new)?;
         self.symbol_table.insert(uri.clone(), module_symbol_table.clone());
         Ok(module_symbol_table)
@@ -163,7 +212,14 @@ impl Database {
     //
     //
 
+    /// The UST of a module, which is only returned if the module contains no syntax errors
     pub async fn ust(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
+        self.cst(uri).await?;
+        self.partial_ust(uri).await
+    }
+
+    /// The UST of all declarations of a module that could be parsed
+    pub async fn partial_ust(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         match self.ust.get_unless_stale(uri) {
             Some(ust) => {
                 log::debug!("Found ust in cache: {}", uri);
@@ -175,19 +231,11 @@ impl Database {
 
     pub async fn recompute_ust(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         log::debug!("Recomputing ust for: {}", uri);
-        let cst = self.cst(uri).await?;
-        let deps = self.deps(uri).await?;
+        let cst = self.partial_cst(uri).await?;
+        // Check the imports for cycles before resolving them.
+        self.deps(uri).await?;
 
-        // Compute the SymbolTable consisting of all the
-        // ModuleSymbolTables of all direct dependencies
-        // and the SymbolTable from the module itself.
-        let mut symbol_table = SymbolTable::default();
-        let module_symbol_table = self.symbol_table(uri).await?;
-        symbol_table.insert(uri.clone(), module_symbol_table);
-        for dep in deps {
-            let module_symbol_table = self.symbol_table(&dep).await?;
-            symbol_table.insert(dep.clone(), module_symbol_table);
-        }
+        let symbol_table = self.symbol_table_in_scope(uri).await?;
 
         let ust = lowering::lower_module_with_symbol_table(&cst, &symbol_table)
             .map_err(Error::Lowering)
@@ -197,17 +245,54 @@ impl Database {
         ust
     }
 
+    /// The symbol table of all names which are in scope in a module
+    ///
+    /// It consists of the symbol table of the module itself and the symbol tables of all
+    /// direct dependencies, which are brought into scope as specified by the use declarations.
+    pub async fn symbol_table_in_scope(&mut self, uri: &Url) -> Result<SymbolTable, Error> {
+        let cst = self.partial_cst(uri).await?;
+        let module_symbol_table = self.symbol_table(uri).await?;
+        let mut symbol_table = SymbolTable::new(uri.clone(), module_symbol_table);
+        for use_decl in &cst.use_decls {
+            let dep = self.resolve_module_name(&use_decl.path, uri)?;
+            let exports = self.exports(&dep).await?;
+            for export in &exports {
+                let module_symbol_table = self.symbol_table(&export.uri).await?;
+                symbol_table.insert(export.uri.clone(), module_symbol_table);
+            }
+            symbol_table.import(Import::new(use_decl, dep, exports));
+        }
+        Ok(symbol_table)
+    }
+
     // Core API: TypeInfoTable
     //
     //
 
     pub async fn type_info_table(&mut self, uri: &Url) -> Result<TypeInfoTable, Error> {
+        self.assemble_type_info_table(uri, false).await
+    }
+
+    /// Get the type info table of a module and its dependencies after the module has been typechecked
+    ///
+    /// Unlike in [`Database::type_info_table`], the signatures of the module itself contain no
+    /// unsolved metavariables, so that erasure is determined by the elaborated signatures.
+    pub async fn closed_type_info_table(&mut self, uri: &Url) -> Result<TypeInfoTable, Error> {
+        self.assemble_type_info_table(uri, true).await
+    }
+
+    async fn assemble_type_info_table(
+        &mut self,
+        uri: &Url,
+        closed: bool,
+    ) -> Result<TypeInfoTable, Error> {
         Box::pin(async move {
             let deps = self.deps(uri).await?;
 
             // Compute the type info table
             let mut info_table = TypeInfoTable::default();
-            let mod_info_table = self.module_type_info_table(uri, false).await?;
+            info_table.set_eval_limits(self.eval_limits);
+            let mod_info_table = self.module_type_info_table(uri, closed).await?;
             info_table.insert(uri.clone(), mod_info_table);
             for dep_url in deps {
                 let mod_info_table = self.module_type_info_table(&dep_url, true).await?;
@@ -285,7 +370,7 @@ impl Database {
             if closed { "closed" } else { "open" },
             uri
         );
-        let ust = self.ust(uri).await?;
+        let ust = self.partial_ust(uri).await?;
         let mut info_table = build_type_info_table(&ust);
         self.type_info_table.insert(uri.clone(), OpenClosed::Open(info_table.clone()));
         if closed {
@@ -302,6 +387,7 @@ impl Database {
     //
     //
 
+    /// The typechecked AST of a module, which is only returned if the module contains no syntax errors
     pub async fn ast(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         match self.ast.get_unless_stale(uri) {
             Some(ast) => {
@@ -315,16 +401,64 @@ impl Database {
     pub async fn recompute_ast(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         log::debug!("Recomputing ast for: {}", uri);
 
+        let ast = self.partial_ast(uri).await;
+
+        // The syntax errors are reported together with the errors in the declarations that could be parsed.
+        let mut errors: Vec<Error> = self
+            .parse_errors
+            .get_even_if_stale(uri)
+            .into_iter()
+            .flatten()
+            .cloned()
+            .map(Error::Parser)
+            .collect();
+        errors.extend(
+            self.type_errors.get_unless_stale(uri).into_iter().flatten().cloned().map(Error::Type),
+        );
+        let ast = match ast {
+            Ok(ast) if errors.is_empty() => Ok(ast),
+            Ok(_) => Err(Error::from_errors(errors)),
+            Err(err) => {
+                errors.extend(err.into_errors());
+                Err(Error::from_errors(errors))
+            }
+        };
+        self.ast.insert(uri.clone(), ast.clone());
+        ast
+    }
+
+    /// The typechecked AST of all declarations of a module that could be parsed
+    ///
+    /// Unlike [`Database::ast`], this does not fail if the parser recovered from syntax errors or
+    /// some declarations fail to typecheck, so that the language server can provide information
+    /// about the other declarations.
+    pub async fn partial_ast(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
+        match self.partial_ast.get_unless_stale(uri) {
+            Some(ast) => {
+                log::debug!("Found partial ast in cache: {}", uri);
+                ast.clone()
+            }
+            None => self.recompute_partial_ast(uri).await,
+        }
+    }
+
+    async fn recompute_partial_ast(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
+        log::debug!("Recomputing partial ast for: {}", uri);
+        let ast = self.check_module(uri).await;
+        self.partial_ast.insert(uri.clone(), ast.clone());
+        ast
+    }
+
+    async fn check_module(&mut self, uri: &Url) -> Result<Arc<ast::Module>, Error> {
         // Compute the type info table
         let info_table = self.type_info_table(uri).await?;
 
         // Typecheck module
-        let ust = self.ust(uri).await.map(|x| (*x).clone())?;
-        let ast = elaborator::typechecker::check_with_lookup_table(Rc::new(ust), &info_table)
-            .map(Arc::new)
-            .map_err(Error::Type);
-        self.ast.insert(uri.clone(), ast.clone());
-        ast
+        let ust = self.partial_ust(uri).await.map(|x| (*x).clone())?;
+        let (ast, errors) =
+            elaborator::typechecker::check_partially_with_lookup_table(Rc::new(ust), &info_table);
+        self.type_errors.insert(uri.clone(), errors);
+        Ok(Arc::new(ast))
     }
 
     // Core API: ir
@@ -345,9 +479,10 @@ impl Database {
         log::debug!("Recomputing ir for: {}", uri);
 
         let module = self.ast(uri).await?;
+        let info_table = self.closed_type_info_table(uri).await?;
 
         // Convert to intermediate representation (IR)
-        let ir = module.to_ir().map(Arc::new).map_err(Error::Backend);
+        let ir = module.to_ir(&info_table).map(Arc::new).map_err(Error::Backend);
 
         self.ir.insert(uri.clone(), ir.clone());
 
@@ -441,6 +576,22 @@ impl Database {
         self.deps(uri).await
     }
 
+    /// The modules whose names are exported by the module `uri`
+    ///
+    /// A module exports its own names and the names exported by the modules it re-exports with `pub use`.
+    pub async fn exports(&mut self, uri: &Url) -> Result<Vec<Export>, Error> {
+        let cst = self.partial_cst(uri).await?;
+        let mut exports = vec![Export { uri: uri.clone(), filters: vec![] }];
+        for use_decl in cst.use_decls.iter().filter(|use_decl| use_decl.public) {
+            let dep = self.resolve_module_name(&use_decl.path, uri)?;
+            for mut export in Box::pin(self.exports(&dep)).await? {
+                export.filters.extend(use_decl.filter.clone());
+                exports.push(export);
+            }
+        }
+        Ok(exports)
+    }
+
     // Creation
     //
     // The following methods provide various means to construct a driver instance.
@@ -454,12 +605,17 @@ impl Database {
     pub fn from_source(source: impl FileSource + 'static) -> Self {
         Self {
             source: Box::new(source),
+            eval_limits: EvalLimits::default(),
+            manifest: None,
             files: Cache::default(),
             deps: DependencyGraph::default(),
             cst: Cache::default(),
+            parse_errors: Cache::default(),
             symbol_table: Cache::default(),
             ust: Cache::default(),
             ast: Cache::default(),
+            partial_ast: Cache::default(),
+            type_errors: Cache::default(),
             ir: Cache::default(),
             type_info_table: Cache::default(),
             hover_by_id: Cache::default(),
@@ -497,13 +653,24 @@ impl Database {
         }
     }
 
+    /// Invalidate all files, for example because the limits on evaluation have changed
+    pub fn invalidate_all(&mut self) {
+        let uris: Vec<Url> = self.files.keys().cloned().collect();
+        for uri in &uris {
+            self.invalidate_impl(uri);
+        }
+    }
+
     fn invalidate_impl(&mut self, uri: &Url) {
         self.files.invalidate(uri);
         self.deps.invalidate(uri);
         self.cst.invalidate(uri);
+        self.parse_errors.invalidate(uri);
         self.symbol_table.invalidate(uri);
         self.ust.invalidate(uri);
         self.ast.invalidate(uri);
+        self.partial_ast.invalidate(uri);
+        self.type_errors.invalidate(uri);
         self.type_info_table.invalidate(uri);
         self.hover_by_id.invalidate(uri);
         self.goto_by_id.invalidate(uri);
@@ -525,6 +692,63 @@ impl Database {
         }
     }
 
+    /// Evaluate the main expression of the module `uri` with the interpreter for the IR
+    ///
+    /// Returns `None` if the module has no main expression.
+    pub async fn run_ir(&mut self, uri: &Url) -> Result<Option<Value>, Error> {
+        let modules = self.ir_with_deps(uri).await?;
+        let mut interpreter = Interpreter::new(modules.iter().map(|module| &**module));
+        interpreter.max_steps = self.eval_limits.max_steps;
+        interpreter.timeout = self.eval_limits.timeout;
+        interpreter.eval_main(&modules[0]).map_err(Error::Backend)
+    }
+
+    /// The JavaScript code of the module `uri`, which is an ES module that includes the code of all
+    /// modules it imports directly or indirectly
+    pub async fn js(&mut self, uri: &Url) -> Result<String, Error> {
+        let modules = self.ir_with_deps(uri).await?;
+        let js = JsModule::new(modules.iter().map(|module| &**module));
+        Ok(printer::Print::print_to_string(&js, None))
+    }
+
+    /// The C code of the module `uri`, which is a program that prints the value of its main
+    /// expression
+    ///
+    /// Returns `None` if the module has no main expression.
+    pub async fn c(&mut self, uri: &Url) -> Result<Option<String>, Error> {
+        let modules = self.ir_with_deps(uri).await?;
+        let c = CModule::new(modules.iter().map(|module| &**module));
+        Ok(c.main().is_some().then(|| printer::Print::print_to_string(&c, None)))
+    }
+
+    /// The IR of the module `uri`, followed by the IR of all modules it imports directly or indirectly
+    pub async fn ir_with_deps(&mut self, uri: &Url) -> Result<Vec<Arc<ir::Module>>, Error> {
+        let mut uris = vec![uri.clone()];
+        let mut i = 0;
+        while i < uris.len() {
+            for dep in self.deps(&uris[i].clone()).await? {
+                if !uris.contains(&dep) {
+                    uris.push(dep);
+                }
+            }
+            i += 1;
+        }
+        let mut modules = Vec::with_capacity(uris.len());
+        for uri in &uris {
+            modules.push(self.ir(uri).await?);
+        }
+        Ok(modules)
+    }
+
+    /// The reduction steps from the main expression of the module `uri` to its normal form
+    ///
+    /// Returns `None` if the module has no main expression.
+    pub async fn run_steps(&mut self, uri: &Url) -> Result<Option<Steps>, Error> {
+        let ast = self.ast(uri).await?;
+        let info_table = self.type_info_table(uri).await?;
+        Ok(ast.find_main().map(|exp| Steps::new(exp, Rc::new(info_table))))
+    }
+
     pub fn pretty_error(&self, uri: &Url, err: Error) -> miette::Report {
         let miette_error: miette::Error = err.into();
         let source = &self.files.get_even_if_stale(uri).unwrap().source;
@@ -595,18 +819,24 @@ impl Database {
         visited.insert(module_uri.clone());
         stack.push(module_uri.clone());
 
-        let module = self.cst(module_uri).await?;
+        let module = self.partial_cst(module_uri).await?;
 
         // Collect dependencies from `use` declarations
-        let mut dependencies = Vec::new();
+        let mut dependencies: Vec<Url> = Vec::new();
         for use_decl in &module.use_decls {
             let UseDecl { path, .. } = use_decl;
             // Resolve the module name to a `Url`
             let dep_url = self.resolve_module_name(path, module_uri)?;
-            dependencies.push(dep_url.clone());
 
             // Recursively visit the dependency
             Box::pin(self.visit_module(&dep_url, visited, stack, graph)).await?;
+
+            // The modules re-exported by the dependency are dependencies as well
+            for export in self.exports(&dep_url).await? {
+                if !dependencies.contains(&export.uri) {
+                    dependencies.push(export.uri);
+                }
+            }
         }
 
         // Add the module and its dependencies to the graph
@@ -616,9 +846,25 @@ impl Database {
         Ok(())
     }
 
-    /// Resolves a module name to a `Url` relative to the current module.
+    /// Resolve the module name of a `use` declaration in `current_module`
+    ///
+    /// Names are resolved relative to `current_module`.
+    /// A name which does not start with `./` or `../` and which does not exist relative to
+    /// `current_module` is searched in the dependencies and source roots of the manifest, if any.
+    /// Finally, a name `std/...` is searched in the bundled standard library.
     pub fn resolve_module_name(&self, name: &str, current_module: &Url) -> Result<Url, Error> {
-        current_module.join(name).map_err(|err| DriverError::Url(err).into())
+        let relative = current_module.join(name).map_err(DriverError::Url)?;
+        if name.starts_with("./") || name.starts_with("../") || self.source.manages(&relative) {
+            return Ok(relative);
+        }
+        let bundled = name.strip_prefix("std/").and_then(StdSource::uri);
+        let found = self
+            .manifest
+            .iter()
+            .flat_map(|manifest| manifest.candidates(name))
+            .chain(bundled)
+            .find(|uri| self.source.manages(uri));
+        Ok(found.unwrap_or(relative))
     }
 }
 
@@ -629,18 +875,24 @@ mod path_support {
     impl Database {
         /// Create a new database tracking the folder at the given path
         /// If the path is a file, the parent directory is tracked
-        pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Self {
+        ///
+        /// The manifest of the project which contains the path is discovered and used to resolve imports.
+        pub fn from_path<P: AsRef<std::path::Path>>(path: P) -> Result<Self, Error> {
             let path = path.as_ref();
+            let manifest = Manifest::discover(path)?;
             let path = if path.is_dir() {
                 path
             } else {
                 path.parent().expect("Could not get parent directory")
             };
-            Self::from_source(FileSystemSource::new(path))
+            let source = StdSource::new().fallback_to(FileSystemSource::new(path));
+            let mut db = Self::from_source(source);
+            db.manifest = manifest;
+            Ok(db)
         }
 
         /// Create a new database tracking the current working directory
-        pub fn from_cwd() -> Self {
+        pub fn from_cwd() -> Result<Self, Error> {
             Self::from_path(std::env::current_dir().expect("Could not get current directory"))
         }
 
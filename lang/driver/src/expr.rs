@@ -0,0 +1,75 @@
+use std::rc::Rc;
+
+use url::Url;
+
+use ast::{Exp, HasType, Zonk};
+use elaborator::normalizer::normalize::Normalize;
+use elaborator::result::TypeError;
+use elaborator::typechecker::ctx::Ctx;
+use elaborator::typechecker::decls::check_metavars_solved;
+use elaborator::typechecker::exprs::CheckInfer;
+use elaborator::TypeInfoTable;
+use printer::Print;
+
+use crate::{database::Database, result::DriverError, Error};
+
+impl Database {
+    /// Infer the type of the expression `src` in the scope of the module `uri`
+    ///
+    /// Returns the printed normal form of the type.
+    /// The spans of errors in the expression refer to `src`, see [`Database::pretty_expr_error`].
+    pub async fn infer_expr(&mut self, uri: &Url, src: &str) -> Result<String, Error> {
+        let (exp, info_table) = self.elaborate_expr(uri, src).await?;
+        let typ = exp.typ().ok_or_else(|| {
+            DriverError::Impossible(
+                "The elaborated expression is not annotated with its type".to_owned(),
+            )
+        })?;
+        info_table.fuel().refill();
+        let typ = typ.normalize_in_empty_env(&info_table)?;
+        Ok(typ.print_to_string(None))
+    }
+
+    /// Evaluate the expression `src` in the scope of the module `uri`
+    ///
+    /// The expression is typechecked before it is evaluated. Returns the printed normal form.
+    /// The spans of errors in the expression refer to `src`, see [`Database::pretty_expr_error`].
+    pub async fn normalize_expr(&mut self, uri: &Url, src: &str) -> Result<String, Error> {
+        let (exp, info_table) = self.elaborate_expr(uri, src).await?;
+        info_table.fuel().refill();
+        let nf = exp.normalize_in_empty_env(&info_table)?;
+        Ok(nf.print_to_string(None))
+    }
+
+    /// Associate an error of [`Database::infer_expr`] or [`Database::normalize_expr`] with the expression for pretty-printing
+    pub fn pretty_expr_error(&self, src: &str, err: Error) -> miette::Report {
+        let miette_error: miette::Error = err.into();
+        miette_error.with_source_code(miette::NamedSource::new("<expression>", src.to_owned()))
+    }
+
+    /// Parse, lower and typecheck the expression `src` in the scope of the module `uri`
+    ///
+    /// Returns an error if a metavariable of the expression remains unsolved.
+    async fn elaborate_expr(
+        &mut self,
+        uri: &Url,
+        src: &str,
+    ) -> Result<(Exp, Rc<TypeInfoTable>), Error> {
+        let module = self.ast(uri).await?;
+        let cst = self.cst(uri).await?;
+        let symbol_table = self.symbol_table_in_scope(uri).await?;
+        let info_table = self.type_info_table(uri).await?;
+
+        let exp = parser::parse_exp(src)?;
+        let (exp, meta_vars) = lowering::lower_exp_with_symbol_table(&exp, &cst, &symbol_table)?;
+
+        let mut ctx = Ctx::new(meta_vars, info_table, Rc::new((*module).clone()));
+        ctx.type_info_table.fuel().refill();
+        let mut exp = exp.infer(&mut ctx)?;
+        exp.zonk(&ctx.meta_vars).map_err(|err| {
+            Box::new(TypeError::Impossible { message: err.to_string(), span: None })
+        })?;
+        check_metavars_solved(&ctx.meta_vars).map_err(Error::from_type_errors)?;
+        Ok((exp, ctx.type_info_table))
+    }
+}
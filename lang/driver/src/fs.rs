@@ -57,22 +57,21 @@ mod file_system {
     #[async_trait]
     impl FileSource for FileSystemSource {
         fn manage(&mut self, uri: &Url) -> bool {
-            let filepath = uri.to_file_path().expect("Failed to convert URI to filepath");
-            self.root.join(filepath).exists()
+            self.manages(uri)
         }
 
         fn manages(&self, uri: &Url) -> bool {
-            let filepath = uri.to_file_path().expect("Failed to convert URI to filepath");
-            self.root.join(filepath).exists()
+            // URIs with a scheme other than `file`, such as those of the bundled standard library, are not managed
+            uri.to_file_path().is_ok_and(|filepath| self.root.join(filepath).exists())
         }
 
         fn forget(&mut self, uri: &Url) -> bool {
-            let filepath = uri.to_file_path().expect("Failed to convert URI to filepath");
-            self.root.join(filepath).exists()
+            self.manages(uri)
         }
 
         async fn read_to_string(&mut self, uri: &Url) -> Result<String, DriverError> {
-            let filepath = uri.to_file_path().expect("Failed to convert URI to filepath");
+            let filepath =
+                uri.to_file_path().map_err(|_| DriverError::FileNotFound(uri.to_owned()))?;
             let path = self.root.join(filepath);
             let source =
                 std::fs::read_to_string(&path).map_err(Arc::new).map_err(DriverError::Io)?;
@@ -83,7 +82,8 @@ mod file_system {
         }
 
         async fn write_string(&mut self, uri: &Url, source: &str) -> Result<(), DriverError> {
-            let filepath = uri.to_file_path().expect("Failed to convert URI to filepath");
+            let filepath =
+                uri.to_file_path().map_err(|_| DriverError::InvalidUri(uri.to_owned()))?;
             let path = self.root.join(filepath);
             std::fs::write(&path, source).map_err(Arc::new).map_err(DriverError::Io)?;
             Ok(())
@@ -148,6 +148,64 @@ impl FileSource for InMemorySource {
     }
 }
 
+/// The URI scheme of the modules of the standard library which is bundled with the compiler
+pub const STD_SCHEME: &str = "std";
+
+mod std_files {
+    include!(concat!(env!("OUT_DIR"), "/std_files.rs"));
+}
+
+/// A read-only file source which contains the standard library bundled with the compiler
+///
+/// The module `std/data/nat.pol` is available under the URI `std:///data/nat.pol`.
+#[derive(Default)]
+pub struct StdSource;
+
+impl StdSource {
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The URI of the module with the given path relative to the `std` directory, if there is such a module
+    pub fn uri(path: &str) -> Option<Url> {
+        let uri = Url::parse(&format!("{STD_SCHEME}:///{path}")).ok()?;
+        Self::file(&uri).map(|_| uri)
+    }
+
+    fn file(uri: &Url) -> Option<&'static str> {
+        if uri.scheme() != STD_SCHEME {
+            return None;
+        }
+        let path = uri.path().strip_prefix('/')?;
+        std_files::STD_FILES.iter().find(|(name, _)| *name == path).map(|(_, source)| *source)
+    }
+}
+
+#[async_trait]
+impl FileSource for StdSource {
+    fn manage(&mut self, uri: &Url) -> bool {
+        self.manages(uri)
+    }
+
+    fn manages(&self, uri: &Url) -> bool {
+        Self::file(uri).is_some()
+    }
+
+    fn forget(&mut self, _uri: &Url) -> bool {
+        false
+    }
+
+    async fn read_to_string(&mut self, uri: &Url) -> Result<String, DriverError> {
+        Self::file(uri)
+            .map(|source| source.replace("\r\n", "\n"))
+            .ok_or_else(|| DriverError::FileNotFound(uri.to_owned()))
+    }
+
+    async fn write_string(&mut self, uri: &Url, _source: &str) -> Result<(), DriverError> {
+        Err(DriverError::ReadOnly(uri.to_owned()))
+    }
+}
+
 /// A source that first tries to access files from the first source, and falls back to the second
 pub struct OverlaySource<S1, S2> {
     first: S1,
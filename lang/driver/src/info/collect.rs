@@ -2,6 +2,7 @@ use lsp_types::{HoverContents, LanguageString, MarkedString, MarkupContent, Mark
 use miette_util::codespan::Span;
 use rust_lapper::{Interval, Lapper};
 
+use ast::ctx::{BindContext, LevelCtx};
 use ast::*;
 use printer::{Print, PrintCfg};
 use url::Url;
@@ -13,17 +14,21 @@ use super::lookup::{lookup_codef, lookup_ctor, lookup_decl, lookup_def, lookup_d
 use super::{Binder, Ctx};
 
 /// Traverse the program and collect information for the LSP server.
+///
+/// Declarations with syntax errors are skipped, so that information about the other
+/// declarations is available while the module is being edited.
 #[allow(clippy::type_complexity)]
 pub async fn collect_info(
     db: &mut Database,
     uri: &Url,
 ) -> Result<(Lapper<u32, HoverContents>, Lapper<u32, (Url, Span)>, Lapper<u32, Item>), Error> {
-    let module = db.ast(uri).await?;
-    let mut collector = InfoCollector::new(module.meta_vars.clone());
+    let module = db.partial_ast(uri).await?;
+    let mut collector = InfoCollector::new(uri.clone(), module.meta_vars.clone());
 
     for use_decl in module.use_decls.iter() {
         // Add hover info
-        let content = MarkedString::String(format!("Import module `{}`", use_decl.path));
+        let verb = if use_decl.public { "Re-export" } else { "Import" };
+        let content = MarkedString::String(format!("{verb} module `{}`", use_decl.path));
         let hover_content = HoverContents::Scalar(content);
         collector.add_hover(use_decl.span, hover_content);
 
@@ -65,6 +70,7 @@ fn separated<I: IntoIterator<Item = String>>(s: &str, iter: I) -> String {
 }
 
 struct InfoCollector {
+    uri: Url,
     meta_vars: HashMap<MetaVar, MetaVarState>,
     hover_spans: Vec<Interval<u32, HoverContents>>,
     location_spans: Vec<Interval<u32, (Url, Span)>>,
@@ -72,8 +78,14 @@ struct InfoCollector {
 }
 
 impl InfoCollector {
-    fn new(meta_vars: HashMap<MetaVar, MetaVarState>) -> Self {
-        InfoCollector { meta_vars, hover_spans: vec![], location_spans: vec![], item_spans: vec![] }
+    fn new(uri: Url, meta_vars: HashMap<MetaVar, MetaVarState>) -> Self {
+        InfoCollector {
+            uri,
+            meta_vars,
+            hover_spans: vec![],
+            location_spans: vec![],
+            item_spans: vec![],
+        }
     }
 
     fn add_hover(&mut self, span: Span, hover: HoverContents) {
@@ -314,9 +326,12 @@ impl CollectInfo for Exp {
             Exp::DotCall(e) => e.collect_info(db, collector),
             Exp::Hole(e) => e.collect_info(db, collector),
             Exp::TypeUniv(e) => e.collect_info(db, collector),
+            Exp::LevelUniv(e) => e.collect_info(db, collector),
+            Exp::Level(e) => e.collect_info(db, collector),
             Exp::Anno(e) => e.collect_info(db, collector),
             Exp::LocalMatch(e) => e.collect_info(db, collector),
             Exp::LocalComatch(e) => e.collect_info(db, collector),
+            Exp::LocalLet(e) => e.collect_info(db, collector),
         }
     }
 }
@@ -477,6 +492,41 @@ impl CollectInfo for DotCall {
     }
 }
 
+impl CollectInfo for LocalLet {
+    fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
+        let LocalLet { name, typ, bound, body, bound_type, .. } = self;
+        if let (VarBind::Var { span: Some(span), id }, Some(typ)) = (name, bound_type) {
+            // Add hover info
+            let typ = typ.print_to_string(None);
+            let header = MarkedString::String(format!("Let-bound variable: `{}`", id));
+            let typ = string_to_language_string(typ);
+            let hover_content = HoverContents::Array(vec![header, typ]);
+            collector.add_hover(*span, hover_content);
+
+            // Add goto info for every occurrence of the variable in the body
+            let occurrences = std::cell::RefCell::new(Vec::new());
+            let mut ctx = LevelCtx::empty();
+            ctx.bind_single(name.clone(), |ctx| {
+                body.occurs(ctx, &|ctx, exp| {
+                    if let Exp::Variable(Variable { span: Some(var_span), idx, .. }) = exp {
+                        // The variable refers to `name` if it skips all telescopes bound in `body`
+                        if idx.fst == ctx.len() - 1 && idx.snd == 0 {
+                            occurrences.borrow_mut().push(*var_span);
+                        }
+                    }
+                    false
+                })
+            });
+            for occurrence in occurrences.into_inner() {
+                collector.add_goto(occurrence, (collector.uri.clone(), *span));
+            }
+        }
+        typ.collect_info(db, collector);
+        bound.collect_info(db, collector);
+        body.collect_info(db, collector)
+    }
+}
+
 fn ctx_to_markdown(ctx: &Ctx, value: &mut String) {
     value.push_str("**Context**\n\n");
     value.push_str("| | |\n");
@@ -558,23 +608,57 @@ impl CollectInfo for Hole {
 }
 
 impl CollectInfo for TypeUniv {
+    fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
+        let TypeUniv { span, level } = self;
+        if let Some(span) = span {
+            let content: Vec<MarkedString> = match level {
+                None => vec![
+                    MarkedString::String("Universe: `Type`".to_owned()),
+                    MarkedString::String("---".to_owned()),
+                    MarkedString::String(
+                        "The impredicative universe whose terms are types or the universe `Type` itself."
+                            .to_owned(),
+                    ),
+                ],
+                Some(_) => vec![
+                    MarkedString::String(format!("Universe: `{}`", self.print_to_string(None))),
+                    MarkedString::String("---".to_owned()),
+                    MarkedString::String(
+                        "A predicative universe whose terms are types of a smaller level.".to_owned(),
+                    ),
+                ],
+            };
+            let hover_content = HoverContents::Array(content);
+            collector.add_hover(*span, hover_content);
+        }
+        level.collect_info(db, collector)
+    }
+}
+
+impl CollectInfo for LevelUniv {
     fn collect_info(&self, _db: &Database, collector: &mut InfoCollector) {
-        let TypeUniv { span } = self;
+        let LevelUniv { span } = self;
         if let Some(span) = span {
             let content: Vec<MarkedString> = vec![
-            MarkedString::String("Universe: `Type`".to_owned()),
-            MarkedString::String("---".to_owned()),
-            MarkedString::String(
-                "The impredicative universe whose terms are types or the universe `Type` itself."
-                    .to_owned(),
-            ),
-        ];
+                MarkedString::String("Sort: `Level`".to_owned()),
+                MarkedString::String("---".to_owned()),
+                MarkedString::String("The sort of universe levels.".to_owned()),
+            ];
             let hover_content = HoverContents::Array(content);
             collector.add_hover(*span, hover_content);
         }
     }
 }
 
+impl CollectInfo for Level {
+    fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
+        let Level { summands, .. } = self;
+        for (exp, _) in summands {
+            exp.collect_info(db, collector)
+        }
+    }
+}
+
 impl CollectInfo for Anno {
     fn collect_info(&self, db: &Database, collector: &mut InfoCollector) {
         let Anno { span, exp, typ, normalized_type } = self;
@@ -690,3 +774,28 @@ impl CollectInfo for SelfParam {
         typ.collect_info(db, collector);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use crate::{Database, InMemorySource};
+
+    use super::*;
+
+    #[test]
+    fn collect_info_despite_syntax_errors() {
+        let uri = Url::parse("file:///scratch.pol").unwrap();
+        let text = "data Bool { T, F }\n\ndef Bool.neg: Bool { T => }\n\nlet x: Bool { F }\n";
+        let mut source = InMemorySource::new();
+        source.insert(uri.clone(), text.to_owned());
+        let mut db = Database::from_source(source);
+
+        let offset = text.rfind('F').unwrap() as u32;
+        let hover = block_on(db.hover_by_id(&uri)).unwrap();
+        assert!(hover.find(offset, offset + 1).next().is_some());
+        let goto = block_on(db.goto_by_id(&uri)).unwrap();
+        assert!(goto.find(offset, offset + 1).next().is_some());
+        assert!(block_on(db.ast(&uri)).is_err());
+    }
+}
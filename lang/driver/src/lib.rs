@@ -2,23 +2,31 @@ pub use result::Error;
 
 mod asserts;
 mod cache;
+mod cc;
 mod codespan;
 mod database;
 mod dependency_graph;
 mod edit;
+mod expr;
 mod fs;
 mod info;
 mod lift;
+mod manifest;
 pub mod paths;
 mod result;
 mod spans;
 mod xfunc;
 
+pub use backend::interpreter::Value;
+pub use cc::{c_compiler, compile_c};
 pub use database::Database;
 
 pub use edit::*;
+pub use elaborator::normalizer::fuel::EvalLimits;
+pub use elaborator::normalizer::step::{Rule, Step, Steps, REDEX_SPAN};
 pub use fs::*;
 pub use info::*;
+pub use manifest::*;
 pub use paths::*;
-pub use result::DriverError;
+pub use result::{DriverError, Errors};
 pub use xfunc::*;
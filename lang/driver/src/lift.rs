@@ -54,7 +54,8 @@ fn generate_edits(
 
     let new_items = Module {
         uri: module.uri.clone(),
-        // Use declarations don't change, and we are only printing an excerpt of the module
+        // Attributes and use declarations don't change; we only print an excerpt of the module
+        attr: Attributes::default(),
         use_decls: vec![],
         decls: new_decls,
         meta_vars: module.meta_vars.clone(),
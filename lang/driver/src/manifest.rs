@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+
+use serde_derive::Deserialize;
+use url::Url;
+
+use crate::result::DriverError;
+
+/// The file name of the project manifest
+pub const MANIFEST_FILE: &str = "polarity.toml";
+
+/// A project manifest, read from a `polarity.toml` file such as
+///
+/// ```toml
+/// [package]
+/// name = "example"
+/// entry = "src/main.pol"
+/// source-roots = ["src"]
+///
+/// [dependencies]
+/// std = { path = "../polarity/std" }
+/// ```
+///
+/// All paths are relative to the directory which contains the manifest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifest {
+    /// The directory which contains the manifest
+    pub root: Url,
+    /// The name of the project
+    pub name: Option<String>,
+    /// The module which `pol check` and `pol run` use if no file is given
+    pub entry: Option<Url>,
+    /// The directories in which modules are searched
+    ///
+    /// If the manifest does not declare any source roots, modules are searched in `root`.
+    pub source_roots: Vec<Url>,
+    /// The directories of the library dependencies by their name
+    ///
+    /// A module `data/nat.pol` of the dependency `std` is imported with `use "std/data/nat.pol"`.
+    pub dependencies: BTreeMap<String, Url>,
+}
+
+/// The contents of a `polarity.toml` file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ManifestFile {
+    #[serde(default)]
+    package: PackageSection,
+    #[serde(default)]
+    dependencies: BTreeMap<String, DependencySection>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+struct PackageSection {
+    name: Option<String>,
+    entry: Option<String>,
+    #[serde(default)]
+    source_roots: Vec<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DependencySection {
+    path: String,
+}
+
+impl Manifest {
+    /// Parse the contents of the manifest file `uri`
+    pub fn parse(uri: &Url, source: &str) -> Result<Self, DriverError> {
+        let file: ManifestFile = toml::from_str(source)
+            .map_err(|err| DriverError::InvalidManifest(uri.clone(), err.to_string()))?;
+        let root = uri.join(".")?;
+
+        let entry = file.package.entry.map(|entry| root.join(&entry)).transpose()?;
+        let mut source_roots = file
+            .package
+            .source_roots
+            .iter()
+            .map(|path| directory(&root, path))
+            .collect::<Result<Vec<_>, _>>()?;
+        if source_roots.is_empty() {
+            source_roots.push(root.clone());
+        }
+        let dependencies = file
+            .dependencies
+            .into_iter()
+            .map(|(name, dep)| Ok((name, directory(&root, &dep.path)?)))
+            .collect::<Result<_, DriverError>>()?;
+
+        Ok(Manifest { root, name: file.package.name, entry, source_roots, dependencies })
+    }
+
+    /// The locations at which the module `name` is searched, in order
+    ///
+    /// A name whose first segment is the name of a dependency is searched in that dependency.
+    /// Afterwards, the name is searched in each of the source roots.
+    pub fn candidates(&self, name: &str) -> Vec<Url> {
+        let dependency = name
+            .split_once('/')
+            .and_then(|(first, rest)| Some((self.dependencies.get(first)?, rest)))
+            .and_then(|(dir, rest)| dir.join(rest).ok());
+        dependency
+            .into_iter()
+            .chain(self.source_roots.iter().filter_map(|dir| dir.join(name).ok()))
+            .collect()
+    }
+}
+
+/// The URI of the directory `path` relative to `root`, with a trailing slash so that it can be joined with module names
+fn directory(root: &Url, path: &str) -> Result<Url, DriverError> {
+    if path.ends_with('/') {
+        Ok(root.join(path)?)
+    } else {
+        Ok(root.join(&format!("{path}/"))?)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod discovery {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use super::*;
+
+    impl Manifest {
+        /// Find the manifest of the project which contains the given file or directory
+        ///
+        /// The manifest is searched in the directory itself and then in each of its ancestors.
+        pub fn discover<P: AsRef<Path>>(path: P) -> Result<Option<Self>, DriverError> {
+            let path = path.as_ref().canonicalize().map_err(Arc::new)?;
+            for dir in path.ancestors().filter(|dir| dir.is_dir()) {
+                let manifest_path = dir.join(MANIFEST_FILE);
+                if manifest_path.is_file() {
+                    let source = std::fs::read_to_string(&manifest_path).map_err(Arc::new)?;
+                    let uri = Url::from_file_path(&manifest_path)
+                        .map_err(|_| DriverError::Impossible(format!("{manifest_path:?}")))?;
+                    return Self::parse(&uri, &source).map(Some);
+                }
+            }
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_dependencies_and_source_roots() {
+        let uri = Url::parse("file:///project/polarity.toml").unwrap();
+        let source = r#"
+            [package]
+            entry = "src/main.pol"
+            source-roots = ["src", "vendor/"]
+
+            [dependencies]
+            std = { path = "../std" }
+        "#;
+        let manifest = Manifest::parse(&uri, source).unwrap();
+        assert_eq!(manifest.entry.as_ref().unwrap().as_str(), "file:///project/src/main.pol");
+        let candidates: Vec<String> =
+            manifest.candidates("std/data/nat.pol").iter().map(|uri| uri.to_string()).collect();
+        assert_eq!(
+            candidates,
+            vec![
+                "file:///std/data/nat.pol",
+                "file:///project/src/std/data/nat.pol",
+                "file:///project/vendor/std/data/nat.pol",
+            ]
+        );
+    }
+
+    #[test]
+    fn default_source_root() {
+        let uri = Url::parse("file:///project/polarity.toml").unwrap();
+        let manifest = Manifest::parse(&uri, "").unwrap();
+        assert_eq!(manifest.source_roots, vec![Url::parse("file:///project/").unwrap()]);
+    }
+
+    #[test]
+    fn reject_unknown_fields() {
+        let uri = Url::parse("file:///project/polarity.toml").unwrap();
+        assert!(Manifest::parse(&uri, "[package]\nentry-point = \"main.pol\"").is_err());
+    }
+}
@@ -2,6 +2,12 @@ pub const TARGET_PATH: &str = "target_pol/";
 
 pub const IR_PATH: &str = "target_pol/ir/";
 
+pub const JS_PATH: &str = "target_pol/js/";
+
+pub const C_PATH: &str = "target_pol/c/";
+
+pub const BIN_PATH: &str = "target_pol/bin/";
+
 pub const DOCS_PATH: &str = "target_pol/docs/";
 
 pub const CSS_PATH: &str = "target_pol/docs/style.css";
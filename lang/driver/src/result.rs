@@ -16,6 +16,46 @@ pub enum Error {
     Xfunc(#[from] transformations::result::XfuncError),
     Driver(#[from] DriverError),
     Backend(#[from] BackendError),
+    Many(#[from] Errors),
+}
+
+impl Error {
+    /// Combine a non-empty list of errors into a single error
+    pub fn from_errors(mut errors: Vec<Error>) -> Self {
+        if errors.len() == 1 {
+            errors.pop().unwrap()
+        } else {
+            Errors { errors }.into()
+        }
+    }
+
+    /// Combine the syntax errors of a module into a single error
+    pub fn from_parse_errors(errors: Vec<parser::ParseError>) -> Self {
+        Self::from_errors(errors.into_iter().map(Error::Parser).collect())
+    }
+
+    /// Combine the type errors of a module into a single error
+    pub fn from_type_errors(errors: Vec<Box<elaborator::result::TypeError>>) -> Self {
+        Self::from_errors(errors.into_iter().map(Error::Type).collect())
+    }
+
+    /// The list of all errors contained in this error
+    pub fn into_errors(self) -> Vec<Error> {
+        match self {
+            Error::Many(Errors { errors }) => {
+                errors.into_iter().flat_map(Error::into_errors).collect()
+            }
+            err => vec![err],
+        }
+    }
+}
+
+/// Several errors which are reported together, such as all type errors of a module
+#[derive(Error, Diagnostic, Debug, Clone)]
+#[error("Found {} errors", errors.len())]
+pub struct Errors {
+    #[related]
+    pub errors: Vec<Error>,
 }
 
 #[derive(Error, Debug, Diagnostic, Clone)]
@@ -26,10 +66,14 @@ pub enum DriverError {
     InvalidUri(Url),
     #[error("File not found: {0}")]
     FileNotFound(Url),
+    #[error("Cannot write to the read-only file {0}")]
+    ReadOnly(Url),
     #[error("IO error: {0}")]
     Io(#[from] Arc<std::io::Error>),
     #[error("URL error: {0}")]
     Url(#[from] url::ParseError),
+    #[error("Invalid manifest {0}: {1}")]
+    InvalidManifest(Url, String),
     #[error("Impossible: {0}")]
     Impossible(String),
     #[error("The file is present, but does not contain the specified byte index.")]
@@ -40,4 +84,9 @@ pub enum DriverError {
         "The given index is contained in the file, but is not a boundary of a UTF-8 code point."
     )]
     InvalidCharBoundary { given: usize },
+    #[error("The C compiler failed: {0}")]
+    #[diagnostic(help(
+        "The C compiler is `cc`, unless another one is set by the `CC` environment variable."
+    ))]
+    CCompiler(String),
 }
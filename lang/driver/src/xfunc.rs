@@ -108,7 +108,8 @@ fn generate_edits(
     // Here we rewrite the entire (co)data declaration and its associated (co)definitions
     let new_items = Module {
         uri: module.uri.clone(),
-        // Use declarations don't change, and we are only printing an excerpt of the module
+        // Attributes and use declarations don't change; we only print an excerpt of the module
+        attr: Attributes::default(),
         use_decls: vec![],
         decls: new_decls,
         meta_vars: module.meta_vars.clone(),
@@ -121,7 +122,12 @@ fn generate_edits(
     // Here we surgically rewrite only the declarations that have been changed
     for name in dirty_decls {
         let decl: &Decl = module
-            .lookup_decl(&IdBound { span: None, id: name.id.clone(), uri: module.uri.clone() })
+            .lookup_decl(&IdBound {
+                span: None,
+                id: name.id.clone(),
+                uri: module.uri.clone(),
+                qualifier: None,
+            })
             .unwrap();
         let mut decl = decl.clone();
         decl.rename();
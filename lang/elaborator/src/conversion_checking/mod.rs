@@ -54,7 +54,7 @@
 
 use log::trace;
 
-use ast::{ctx::values::TypeCtx, Exp, HasSpan, HashMap, MetaVar, MetaVarState};
+use ast::{ctx::values::TypeCtx, Exp, HasSpan, HashMap, Level, MetaVar, MetaVarState, TypeUniv};
 use miette_util::{codespan::Span, ToMiette};
 use printer::Print;
 
@@ -74,6 +74,18 @@ pub fn convert(
     while_elaborating_span: &Option<Span>,
 ) -> TcResult {
     trace!("{} |- {} =? {}", ctx.print_trace(), this.print_trace(), other.print_trace());
+    // In predicative modules, universes are cumulative: `Type l₁` may be used where `Type l₂` is
+    // expected if `l₁ ≤ l₂`. A universe with a level is never convertible to the universe `Type`
+    // without a level, which contains itself.
+    if let (
+        Exp::TypeUniv(TypeUniv { level: Some(l1), .. }),
+        Exp::TypeUniv(TypeUniv { level: Some(l2), .. }),
+    ) = (&*this, other)
+    {
+        if Level::from_exp(l1).leq(&Level::from_exp(l2)) {
+            return Ok(());
+        }
+    }
     // Convertibility is checked using the unification algorithm.
     let constraint: Constraint =
         Constraint::Equality { ctx, lhs: this.clone(), rhs: Box::new(other.clone()) };
@@ -165,7 +177,7 @@ mod test {
         let ctx = vec![vec![
             Binder {
                 name: VarBind::Var { span: None, id: "a".to_string() },
-                content: Binding::from_type(Box::new(TypeUniv { span: None }.into())),
+                content: Binding::from_type(Box::new(TypeUniv::new().into())),
             },
             Binder {
                 name: VarBind::Var { span: None, id: "v".to_string() },
@@ -203,7 +215,7 @@ mod test {
         let ctx = vec![vec![
             Binder {
                 name: VarBind::Var { span: None, id: "a".to_string() },
-                content: Binding::from_type(Box::new(TypeUniv { span: None }.into())),
+                content: Binding::from_type(Box::new(TypeUniv::new().into())),
             },
             Binder {
                 name: VarBind::Var { span: None, id: "v'".to_string() },
@@ -237,7 +249,7 @@ mod test {
     /// Check that `[] |- Type =? Type` holds.
     #[test]
     fn convert_type_type() {
-        let t = TypeUniv { span: None };
+        let t = TypeUniv::new();
         let ctx = vec![];
         check_eq(&ctx.into(), t.clone(), t);
     }
@@ -6,7 +6,10 @@
 //! * Adam Gundry and Conor McBride. "A tutorial implementation of dynamic pattern unification." (2013).
 //! * András Kovács's elaboration-zoo (https://github.com/AndrasKovacs/elaboration-zoo)
 
-use ast::{ctx::values::Binder, Variable};
+use ast::{
+    ctx::values::{Binder, TypeCtx},
+    Variable,
+};
 use ctx::LevelCtx;
 use miette_util::{codespan::Span, ToMiette};
 
@@ -97,6 +100,14 @@ impl<'a> Ctx<'a> {
 
                     Ok(())
                 }
+                // Levels are compared up to the laws of `max` and `+`, so a level variable `l` may
+                // stand for the level `max(l, 0)`.
+                (Exp::Level(_), _) | (_, Exp::Level(_)) => self.unify_levels(
+                    constraint_cxt,
+                    Level::from_exp(lhs),
+                    Level::from_exp(rhs),
+                    while_elaborating_span,
+                ),
                 (
                     Exp::Variable(v1 @ Variable { idx: idx_1, .. }),
                     Exp::Variable(v2 @ Variable { idx: idx_2, .. }),
@@ -176,7 +187,24 @@ impl<'a> Ctx<'a> {
                     };
                     self.add_constraint(constraint)
                 }
-                (Exp::TypeUniv(_), Exp::TypeUniv(_)) => Ok(()),
+                (
+                    Exp::TypeUniv(TypeUniv { level: Some(level), .. }),
+                    Exp::TypeUniv(TypeUniv { level: Some(level2), .. }),
+                ) => self.add_constraint(Constraint::Equality {
+                    ctx: constraint_cxt,
+                    lhs: level.clone(),
+                    rhs: level2.clone(),
+                }),
+                (
+                    Exp::TypeUniv(TypeUniv { level: None, .. }),
+                    Exp::TypeUniv(TypeUniv { level: None, .. }),
+                ) => Ok(()),
+                (Exp::TypeUniv(t1), Exp::TypeUniv(t2)) => Err(TypeError::NotEqInternal {
+                    lhs: t1.print_to_string(None),
+                    rhs: t2.print_to_string(None),
+                }
+                .into()),
+                (Exp::LevelUniv(_), Exp::LevelUniv(_)) => Ok(()),
                 (Exp::Anno(Anno { exp, .. }), rhs) => self.add_constraint(Constraint::Equality {
                     ctx: constraint_cxt,
                     lhs: exp.clone(),
@@ -201,6 +229,35 @@ impl<'a> Ctx<'a> {
                         });
                     self.add_constraints(new_eqns)
                 }
+                (
+                    Exp::LocalMatch(LocalMatch {
+                        name: name_lhs,
+                        on_exp: on_exp_lhs,
+                        cases: cases_lhs,
+                        ..
+                    }),
+                    Exp::LocalMatch(LocalMatch {
+                        name: name_rhs,
+                        on_exp: on_exp_rhs,
+                        cases: cases_rhs,
+                        ..
+                    }),
+                ) if name_lhs == name_rhs => {
+                    self.add_constraint(Constraint::Equality {
+                        ctx: constraint_cxt,
+                        lhs: on_exp_lhs.clone(),
+                        rhs: on_exp_rhs.clone(),
+                    })?;
+                    let new_eqns =
+                        zip_cases_by_xtors(cases_lhs, cases_rhs).filter_map(|(lhs, rhs)| {
+                            if let (Some(lhs), Some(rhs)) = (lhs.body, rhs.body) {
+                                Some(Constraint::Equality { ctx: constraint_cxt, lhs, rhs })
+                            } else {
+                                None
+                            }
+                        });
+                    self.add_constraints(new_eqns)
+                }
                 (_, _) => Err(TypeError::cannot_decide(lhs, rhs, while_elaborating_span)),
             },
             Constraint::EqualityArgs { ctx: constraint_ctx, lhs, rhs } => {
@@ -218,6 +275,44 @@ impl<'a> Ctx<'a> {
         }
     }
 
+    /// Unify two canonical levels
+    ///
+    /// Levels which are equal for all values of their summands are unified. Otherwise, only levels
+    /// of the form `e₁ + k` and `e₂ + k` are unified by unifying `e₁` and `e₂`, which suffices to
+    /// solve holes which stand for levels such as `_ + 1`.
+    fn unify_levels(
+        &mut self,
+        ctx: &'a TypeCtx,
+        l1: Level,
+        l2: Level,
+        while_elaborating_span: &Option<Span>,
+    ) -> TcResult {
+        if l1.leq(&l2) && l2.leq(&l1) {
+            return Ok(());
+        }
+        match (l1.summands.as_slice(), l2.summands.as_slice()) {
+            // `e₁ + k =? e₂ + k` is solved by `e₁ =? e₂`
+            ([(e1, k1)], [(e2, k2)]) if k1 == k2 && l1.constant == l2.constant => {
+                self.add_constraint(Constraint::Equality { ctx, lhs: e1.clone(), rhs: e2.clone() })
+            }
+            // Other levels with holes may be equal for some solution of the holes, which we do
+            // not attempt to find.
+            _ if l1.contains_metavars() || l2.contains_metavars() => {
+                Err(TypeError::CannotUnifyLevels {
+                    lhs: l1.print_to_string(None),
+                    rhs: l2.print_to_string(None),
+                    while_elaborating_span: while_elaborating_span.to_miette(),
+                }
+                .into())
+            }
+            _ => Err(TypeError::NotEqInternal {
+                lhs: l1.print_to_string(None),
+                rhs: l2.print_to_string(None),
+            }
+            .into()),
+        }
+    }
+
     fn add_constraint(&mut self, eqn: Constraint<'a>) -> TcResult {
         self.add_constraints([eqn])
     }
@@ -573,7 +668,7 @@ mod tests {
 
     fn true_exp() -> Box<Exp> {
         let uri = dummy_uri();
-        let name = IdBound { span: None, id: "T".to_owned(), uri };
+        let name = IdBound { span: None, id: "T".to_owned(), uri, qualifier: None };
         Box::new(Exp::TypCtor(TypCtor {
             span: None,
             name,
@@ -584,7 +679,7 @@ mod tests {
 
     fn bool_type() -> Box<Exp> {
         let uri = dummy_uri();
-        let name = IdBound { span: None, id: "Bool".to_owned(), uri };
+        let name = IdBound { span: None, id: "Bool".to_owned(), uri, qualifier: None };
         Box::new(Exp::TypCtor(TypCtor {
             span: None,
             name,
@@ -595,7 +690,7 @@ mod tests {
 
     fn fun_type(a: Box<Exp>, b: Box<Exp>) -> Box<Exp> {
         let uri = dummy_uri();
-        let name = IdBound { span: None, id: "Fun".to_owned(), uri };
+        let name = IdBound { span: None, id: "Fun".to_owned(), uri, qualifier: None };
         Box::new(Exp::TypCtor(TypCtor {
             span: None,
             name,
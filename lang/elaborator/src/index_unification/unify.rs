@@ -148,7 +148,25 @@ impl Ctx {
                         Constraint::EqualityArgs { lhs: args.clone(), rhs: args2.clone() };
                     self.add_constraint(constraint)
                 }
+                (
+                    Exp::TypeUniv(TypeUniv { level: Some(level), .. }),
+                    Exp::TypeUniv(TypeUniv { level: Some(level2), .. }),
+                ) => self.add_constraint(Constraint::Equality {
+                    lhs: level.clone(),
+                    rhs: level2.clone(),
+                }),
                 (Exp::TypeUniv(_), Exp::TypeUniv(_)) => Ok(Yes(())),
+                (Exp::LevelUniv(_), Exp::LevelUniv(_)) => Ok(Yes(())),
+                (Exp::Level(level), Exp::Level(level2)) => {
+                    let (level, level2) = (level.canonicalize(), level2.canonicalize());
+                    if level.leq(&level2) && level2.leq(&level) {
+                        Ok(Yes(()))
+                    } else if level.summands.is_empty() && level2.summands.is_empty() {
+                        Ok(No)
+                    } else {
+                        Err(TypeError::cannot_decide(lhs, rhs, while_elaborating_span))
+                    }
+                }
                 (Exp::Anno(Anno { exp, .. }), rhs) => self.add_constraint(Constraint::Equality {
                     lhs: exp.clone(),
                     rhs: Box::new(rhs.clone()),
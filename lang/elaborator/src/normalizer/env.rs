@@ -1,3 +1,5 @@
+use std::rc::Rc;
+
 use ast::{Lvl, Shift, ShiftRange, VarBound};
 use pretty::DocAllocator;
 
@@ -8,16 +10,20 @@ use ast::{Idx, Var};
 use printer::tokens::COMMA;
 use printer::Print;
 
+use crate::normalizer::thunk::Thunk;
 use crate::normalizer::val::*;
 
 #[derive(Debug, Clone)]
 pub struct Env {
     /// Environment for locally bound variables
-    bound_vars: Vec<Vec<Binder<Box<Val>>>>,
+    ///
+    /// The binders are shared between clones of the environment, such as the environments of
+    /// closures and thunks, and only copied when they are modified.
+    bound_vars: Vec<Rc<Vec<Binder<Thunk>>>>,
 }
 
 impl Env {
-    pub fn lookup<V: Into<Var>>(&self, idx: V) -> Binder<Box<Val>> {
+    pub fn lookup<V: Into<Var>>(&self, idx: V) -> Binder<Thunk> {
         let lvl = self.var_to_lvl(idx.into());
         self.bound_vars
             .get(lvl.fst)
@@ -29,10 +35,10 @@ impl Env {
     /// Bind an iterator `iter` of binders
     pub fn bind_iter<I, O, F>(&mut self, iter: I, f: F) -> O
     where
-        I: Iterator<Item = Binder<Box<Val>>>,
+        I: Iterator<Item = Binder<Thunk>>,
         F: FnOnce(&mut Self) -> O,
     {
-        self.bound_vars.push(iter.collect());
+        self.bound_vars.push(Rc::new(iter.collect()));
         let res = f(self);
         self.bound_vars.pop().unwrap();
         res
@@ -45,24 +51,34 @@ impl Env {
     }
 
     pub fn from_vec(bound: Vec<Vec<Box<Val>>>) -> Self {
-        let bound_vars: Vec<Vec<_>> = bound
+        let bound_vars: Vec<Rc<Vec<_>>> = bound
             .into_iter()
             .map(|inner| {
-                inner
-                    .into_iter()
-                    .map(|v| Binder { name: ast::VarBind::Wildcard { span: None }, content: v })
-                    .collect()
+                Rc::new(
+                    inner
+                        .into_iter()
+                        .map(|v| Binder {
+                            name: ast::VarBind::Wildcard { span: None },
+                            content: v.into(),
+                        })
+                        .collect(),
+                )
             })
             .collect();
         Self { bound_vars }
     }
 
+    /// Replace the value bound at level `lvl` by `thunk`
+    pub fn set(&mut self, lvl: Lvl, thunk: Thunk) {
+        Rc::make_mut(&mut self.bound_vars[lvl.fst])[lvl.snd].content = thunk;
+    }
+
     pub(super) fn for_each<F>(&mut self, f: F)
     where
-        F: Fn(&mut Box<Val>),
+        F: Fn(&mut Thunk),
     {
         for outer in self.bound_vars.iter_mut() {
-            for inner in outer {
+            for inner in Rc::make_mut(outer) {
                 f(&mut inner.content)
             }
         }
@@ -8,12 +8,18 @@ use miette_util::ToMiette;
 use printer::types::Print;
 
 use crate::normalizer::env::*;
+use crate::normalizer::thunk::Thunk;
 use crate::normalizer::val::{self, Closure, Val};
 
 use crate::{result::*, TypeInfoTable};
 
 use super::val::AnnoNeu;
 
+/// The minimal amount of stack space which must be left before evaluating a subexpression
+const STACK_RED_ZONE: usize = 256 * 1024;
+/// The size of the stack segments allocated when the remaining stack space is too small
+const STACK_SEGMENT_SIZE: usize = 4 * 1024 * 1024;
+
 pub trait Eval {
     type Val;
 
@@ -21,28 +27,29 @@ pub trait Eval {
 }
 
 pub trait Apply {
-    fn apply(
-        self,
-        info_table: &Rc<TypeInfoTable>,
-        args: Vec<Binder<Box<Val>>>,
-    ) -> TcResult<Box<Val>>;
+    fn apply(self, info_table: &Rc<TypeInfoTable>, args: Vec<Binder<Thunk>>) -> TcResult<Box<Val>>;
 }
 
 impl Eval for Exp {
     type Val = Box<Val>;
 
     fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
-        let e = match self {
+        info_table.fuel().consume(self.span())?;
+        // The depth of the recursion is only bounded by the fuel, so we grow the stack on demand
+        let e = stacker::maybe_grow(STACK_RED_ZONE, STACK_SEGMENT_SIZE, || match self {
             Exp::Variable(e) => e.eval(info_table, env),
             Exp::TypCtor(e) => e.eval(info_table, env),
             Exp::Call(e) => e.eval(info_table, env),
             Exp::DotCall(e) => e.eval(info_table, env),
             Exp::Anno(e) => e.eval(info_table, env),
             Exp::TypeUniv(e) => e.eval(info_table, env),
+            Exp::LevelUniv(e) => e.eval(info_table, env),
+            Exp::Level(e) => e.eval(info_table, env),
             Exp::LocalMatch(e) => e.eval(info_table, env),
             Exp::LocalComatch(e) => e.eval(info_table, env),
+            Exp::LocalLet(e) => e.eval(info_table, env),
             Exp::Hole(e) => e.eval(info_table, env),
-        };
+        });
         trace!(
             "{} |- {} ▷ {}",
             env.print_to_colored_string(None),
@@ -56,9 +63,9 @@ impl Eval for Exp {
 impl Eval for Variable {
     type Val = Box<Val>;
 
-    fn eval(&self, _info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
+    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
         let Variable { idx, .. } = self;
-        Ok(env.lookup(*idx).content)
+        env.lookup(*idx).content.force(info_table)
     }
 }
 
@@ -92,13 +99,13 @@ impl Eval for Call {
                 // arguments for the body of the definition. If it is opaque, then
                 // the further computation is blocked so we return a neutral value.
                 if attr.attrs.contains(&Attribute::Transparent) {
-                    let args = args.eval(info_table, env)?;
                     let binders = params
                         .params
                         .iter()
-                        .zip(args.to_vals())
-                        .map(|(param, arg)| Binder { name: param.name.clone(), content: arg });
-                    env.bind_iter(binders, |env| body.eval(info_table, env))
+                        .zip(delay(args, env))
+                        .map(|(param, arg)| Binder { name: param.name.clone(), content: arg })
+                        .collect::<Vec<_>>();
+                    env.bind_iter(binders.into_iter(), |env| body.eval(info_table, env))
                 } else {
                     Ok(Box::new(Val::Neu(
                         val::OpaqueCall {
@@ -138,13 +145,13 @@ impl Eval for DotCall {
     fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
         let DotCall { span, kind, exp, name, args, .. } = self;
 
-        // We first evaluate `exp` and then the arguments `args` to `d` from left to right.
-        let exp = exp.eval(info_table, env)?;
-        let args = args.eval(info_table, env)?;
+        // We first evaluate `exp`. The arguments `args` to `d` are only evaluated once they are
+        // used, or right away if the evaluation is blocked.
+        let val = exp.eval(info_table, env)?;
 
         // If possible, strip away all annotations from the expression.
         // For example, we need to strip away the annotation around `T` in  `(T : Bool).match { T => F, F => T }` before we can evaluate further.
-        let exp = strip_annotations(&exp);
+        let exp = strip_annotations(&val);
 
         match exp {
             Val::Call(val::Call { name: call_name, kind, args: call_args, .. }) => {
@@ -165,28 +172,32 @@ impl Eval for DotCall {
                         // data type, and `d` is the name of a toplevel definition.
 
                         // First, we have to find the corresponding case in the toplevel definition `d`.
-                        let Def { cases, params, .. } = info_table.lookup_def(&name.clone())?;
+                        let Def { cases, catch_all, params, .. } =
+                            info_table.lookup_def(&name.clone())?;
+                        let args = delay(args, env);
                         let mut env = Env::empty();
                         let binders = params
                             .params
                             .iter()
-                            .zip(args.to_vals())
+                            .zip(args)
                             .map(|(param, arg)| Binder { name: param.name.clone(), content: arg });
-                        let cases = env.bind_iter(binders, |env| cases.eval(info_table, env))?;
-                        let val::Case { body, params, .. } = cases
-                            .clone()
-                            .into_iter()
-                            .find(|case| case.name == call_name)
-                            .ok_or_else(|| TypeError::MissingCase { name: call_name.id.clone() })?;
+                        let (cases, catch_all) = env.bind_iter(binders, |env| {
+                            Ok::<_, Box<TypeError>>((
+                                cases.eval(info_table, env)?,
+                                catch_all.eval(info_table, env)?,
+                            ))
+                        })?;
 
                         // Then we apply the body to the `call_args`.
-                        let binders = params
-                            .params
-                            .iter()
-                            .zip(call_args.to_vals())
-                            .map(|(param, arg)| Binder { name: param.name.clone(), content: arg })
-                            .collect::<Vec<_>>();
-                        body.clone().unwrap().apply(info_table, binders)
+                        apply_case(
+                            info_table,
+                            cases,
+                            catch_all,
+                            &call_name,
+                            call_args.to_vals().into_iter().map(Thunk::from).collect(),
+                            val,
+                        )
+                        .ok_or_else(|| TypeError::MissingCase { name: call_name.id.clone() })?
                     }
                     CallKind::Codefinition => {
                         // The specific instance of the DotCall we are evaluating is:
@@ -205,28 +216,24 @@ impl Eval for DotCall {
 
                         // First, we have to find the corresponding cocase in the toplevel
                         // codefinition `C`.
-                        let Codef { cases, params, .. } =
+                        let Codef { cases, catch_all, params, .. } =
                             info_table.lookup_codef(&call_name.clone())?;
+                        let args = delay(args, env);
                         let mut env = Env::empty();
                         let binders =
                             params.params.iter().zip(call_args.to_vals()).map(|(param, arg)| {
-                                Binder { name: param.name.clone(), content: arg }
+                                Binder { name: param.name.clone(), content: arg.into() }
                             });
-                        let cases = env.bind_iter(binders, |env| cases.eval(info_table, env))?;
-                        let val::Case { body, params, .. } = cases
-                            .clone()
-                            .into_iter()
-                            .find(|cocase| cocase.name == *name)
-                            .ok_or_else(|| TypeError::MissingCocase { name: name.id.clone() })?;
+                        let (cases, catch_all) = env.bind_iter(binders, |env| {
+                            Ok::<_, Box<TypeError>>((
+                                cases.eval(info_table, env)?,
+                                catch_all.eval(info_table, env)?,
+                            ))
+                        })?;
 
                         // Then we apply the body to the `args`.
-                        let binders = params
-                            .params
-                            .iter()
-                            .zip(args.to_vals())
-                            .map(|(param, arg)| Binder { name: param.name.clone(), content: arg })
-                            .collect::<Vec<_>>();
-                        body.clone().unwrap().apply(info_table, binders)
+                        apply_case(info_table, cases, catch_all, name, args, val)
+                            .ok_or_else(|| TypeError::MissingCocase { name: name.id.clone() })?
                     }
                     CallKind::LetBound => {
                         // This case is unreachable because all let-bound calls have either already
@@ -236,7 +243,7 @@ impl Eval for DotCall {
                     }
                 }
             }
-            Val::LocalComatch(val::LocalComatch { cases, .. }) => {
+            Val::LocalComatch(val::LocalComatch { cases, catch_all, .. }) => {
                 // The specific instance of the DotCall we are evaluating is:
                 //
                 // ```text
@@ -250,21 +257,9 @@ impl Eval for DotCall {
                 // where `d` is the name of a destructor declared in a
                 // codata type.
 
-                // First, we have to select the correct case from the comatch.
-                let val::Case { body, params, .. } = cases
-                    .clone()
-                    .into_iter()
-                    .find(|cocase| cocase.name == *name)
-                    .ok_or_else(|| TypeError::MissingCocase { name: name.id.clone() })?;
-
-                // Then we apply the body to the `args`.
-                let binders = params
-                    .params
-                    .iter()
-                    .zip(args.to_vals())
-                    .map(|(param, arg)| Binder { name: param.name.clone(), content: arg })
-                    .collect::<Vec<_>>();
-                body.clone().unwrap().apply(info_table, binders)
+                // We select the correct case from the comatch and apply its body to the `args`.
+                apply_case(info_table, cases, catch_all, name, delay(args, env), val)
+                    .ok_or_else(|| TypeError::MissingCocase { name: name.id.clone() })?
             }
 
             Val::Neu(exp) => {
@@ -284,7 +279,7 @@ impl Eval for DotCall {
                         kind: *kind,
                         exp: Box::new(exp),
                         name: name.to_owned(),
-                        args,
+                        args: args.eval(info_table, env)?,
                     }
                     .into(),
                 )))
@@ -304,6 +299,11 @@ impl Eval for DotCall {
                 span: span.to_miette(),
             }
             .into()),
+            Val::LevelUniv(_) | Val::Level(_) => Err(TypeError::Impossible {
+                message: "Cannot apply DotCall to universe level".to_owned(),
+                span: span.to_miette(),
+            }
+            .into()),
         }
     }
 }
@@ -338,9 +338,68 @@ impl Eval for Anno {
 impl Eval for TypeUniv {
     type Val = Box<Val>;
 
+    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
+        let TypeUniv { span, level } = self;
+        let level = level.as_ref().map(|level| level.eval(info_table, env)).transpose()?;
+        Ok(Box::new(val::TypeUniv { span: *span, level }.into()))
+    }
+}
+
+impl Eval for LevelUniv {
+    type Val = Box<Val>;
+
     fn eval(&self, _info_table: &Rc<TypeInfoTable>, _env: &mut Env) -> TcResult<Self::Val> {
-        let TypeUniv { span } = self;
-        Ok(Box::new(val::TypeUniv { span: *span }.into()))
+        let LevelUniv { span } = self;
+        Ok(Box::new(val::LevelUniv { span: *span }.into()))
+    }
+}
+
+impl Eval for Level {
+    type Val = Box<Val>;
+
+    /// Evaluate a universe level:
+    /// Levels which are substituted for level variables are flattened into the result.
+    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
+        let Level { span, constant, summands } = self;
+        let mut constant = *constant;
+        let mut summands_out = vec![];
+        for (exp, k) in summands {
+            match *exp.eval(info_table, env)? {
+                Val::Level(level) => {
+                    constant = constant.max(level.constant + k);
+                    summands_out.extend(level.summands.into_iter().map(|(val, l)| (val, l + k)));
+                }
+                val => summands_out.push((Box::new(val), *k)),
+            }
+        }
+        match summands_out.as_slice() {
+            [(val, 0)] if constant == 0 => Ok(val.clone()),
+            _ => Ok(Box::new(val::Level { span: *span, constant, summands: summands_out }.into())),
+        }
+    }
+}
+
+impl Eval for LocalLet {
+    type Val = Box<Val>;
+
+    /// Evaluate a LocalLet:
+    ///
+    /// ```text
+    /// let x : t := e; body
+    ///     ┳       ┳   ━┳━━
+    ///     ┃       ┃    ┗━━━ body
+    ///     ┃       ┗━━━━━━━━ bound
+    ///     ┗━━━━━━━━━━━━━━━━ name
+    /// ```
+    fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
+        let LocalLet { name, bound, body, .. } = self;
+        // The bound expression is evaluated when `x` is first used in the body, and its value is
+        // shared between all uses of `x`.
+        let binder = Binder {
+            name: name.clone(),
+            content: Thunk::delay(bound.clone(), Rc::new(env.clone())),
+        };
+        env.bind_iter(std::iter::once(binder), |env| body.eval(info_table, env))
     }
 }
 
@@ -356,12 +415,13 @@ impl Eval for LocalMatch {
     /// ┗━━━━━━━━━━━━━━━ on_exp
     /// ```
     fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
-        let LocalMatch { name: match_name, on_exp, cases, .. } = self;
+        let LocalMatch { name: match_name, on_exp, cases, catch_all, .. } = self;
         // We first evaluate `on_exp` and `cases`
-        let on_exp = on_exp.eval(info_table, env)?;
+        let val = on_exp.eval(info_table, env)?;
         let cases = cases.eval(info_table, env)?;
+        let catch_all = catch_all.eval(info_table, env)?;
 
-        let on_exp = strip_annotations(&on_exp);
+        let on_exp = strip_annotations(&val);
 
         match on_exp {
             Val::Call(val::Call { name: ctor_name, args, .. }) => {
@@ -377,21 +437,10 @@ impl Eval for LocalMatch {
                 // where `C` is the name of a constructor declared in a data
                 // type declaration.
 
-                // We first look up the correct case.
-                let val::Case { body, params, .. } = cases
-                    .clone()
-                    .into_iter()
-                    .find(|case| case.name == ctor_name)
-                    .ok_or_else(|| TypeError::MissingCase { name: ctor_name.id.clone() })?;
-
-                // Then we substitute the `args` in the body.
-                let binders = params
-                    .params
-                    .iter()
-                    .zip(args.to_vals())
-                    .map(|(param, arg)| Binder { name: param.name.clone(), content: arg })
-                    .collect::<Vec<_>>();
-                body.clone().unwrap().apply(info_table, binders)
+                // We look up the correct case and substitute the `args` in its body.
+                let args = args.to_vals().into_iter().map(Thunk::from).collect();
+                apply_case(info_table, cases, catch_all, &ctor_name, args, val)
+                    .ok_or_else(|| TypeError::MissingCase { name: ctor_name.id.clone() })?
             }
             Val::Neu(exp) => {
                 // The specific instance of the LocalMatch we are evaluating is:
@@ -409,6 +458,7 @@ impl Eval for LocalMatch {
                         name: match_name.to_owned(),
                         on_exp: Box::new(exp),
                         cases,
+                        catch_all,
                     }
                     .into(),
                 )))
@@ -423,6 +473,13 @@ impl Eval for LocalMatch {
                 span: type_univ.span.to_miette(),
             }
             .into()),
+            Val::LevelUniv(val::LevelUniv { span }) | Val::Level(val::Level { span, .. }) => {
+                Err(TypeError::Impossible {
+                    message: "Cannot match on a universe level".to_owned(),
+                    span: span.to_miette(),
+                }
+                .into())
+            }
             Val::LocalComatch(local_comatch) => Err(TypeError::Impossible {
                 message: "Cannot match on a local comatch".to_owned(),
                 span: local_comatch.span.to_miette(),
@@ -441,13 +498,14 @@ impl Eval for LocalComatch {
     type Val = Box<Val>;
 
     fn eval(&self, info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
-        let LocalComatch { span, name, is_lambda_sugar, cases, .. } = self;
+        let LocalComatch { span, name, is_lambda_sugar, cases, catch_all, .. } = self;
         Ok(Box::new(
             val::LocalComatch {
                 span: *span,
                 name: name.clone(),
                 is_lambda_sugar: *is_lambda_sugar,
                 cases: cases.eval(info_table, env)?,
+                catch_all: catch_all.eval(info_table, env)?,
             }
             .into(),
         ))
@@ -481,7 +539,7 @@ impl Eval for Case {
     type Val = val::Case;
 
     fn eval(&self, _info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
-        let Case { span, pattern, body } = self;
+        let Case { span, pattern, body, expanded_from } = self;
 
         let body = body.as_ref().map(|body| Closure {
             body: body.clone(),
@@ -495,10 +553,53 @@ impl Eval for Case {
             name: pattern.name.clone(),
             params: pattern.params.clone(),
             body,
+            expanded_from: expanded_from.clone(),
         })
     }
 }
 
+impl Eval for CatchAll {
+    type Val = val::CatchAll;
+
+    fn eval(&self, _info_table: &Rc<TypeInfoTable>, env: &mut Env) -> TcResult<Self::Val> {
+        let CatchAll { span, name, body } = self;
+
+        let body = body.as_ref().map(|body| Closure {
+            body: body.clone(),
+            params: vec![name.clone()],
+            env: env.clone(),
+        });
+
+        Ok(val::CatchAll { span: *span, name: name.clone(), body })
+    }
+}
+
+/// Apply the body of the case for `name` to the `args`
+///
+/// If there is no such case, the body of the catch-all clause is applied to the matched value
+/// `matched` instead. Returns `None` if there is neither.
+fn apply_case(
+    info_table: &Rc<TypeInfoTable>,
+    cases: Vec<val::Case>,
+    catch_all: Option<val::CatchAll>,
+    name: &IdBound,
+    args: Vec<Thunk>,
+    matched: Box<Val>,
+) -> Option<TcResult<Box<Val>>> {
+    if let Some(val::Case { body, params, .. }) = cases.into_iter().find(|case| case.name == *name)
+    {
+        let binders = params
+            .params
+            .iter()
+            .zip(args)
+            .map(|(param, arg)| Binder { name: param.name.clone(), content: arg })
+            .collect::<Vec<_>>();
+        return Some(body.unwrap().apply(info_table, binders));
+    }
+    let val::CatchAll { name, body, .. } = catch_all?;
+    Some(body.unwrap().apply(info_table, vec![Binder { name, content: matched.into() }]))
+}
+
 impl Eval for Args {
     type Val = val::Args;
 
@@ -527,12 +628,29 @@ impl Apply for Closure {
     fn apply(
         mut self,
         info_table: &Rc<TypeInfoTable>,
-        args: Vec<Binder<Box<Val>>>,
+        args: Vec<Binder<Thunk>>,
     ) -> TcResult<Box<Val>> {
         self.env.bind_iter(args.into_iter(), |env| self.body.eval(info_table, env))
     }
 }
 
+/// Delay the evaluation of the arguments `args` in the environment `env`
+///
+/// An argument which is a variable shares the thunk of the variable.
+fn delay(args: &Args, env: &Env) -> Vec<Thunk> {
+    let mut shared_env = None;
+    args.to_exps()
+        .into_iter()
+        .map(|arg| match *arg {
+            Exp::Variable(Variable { idx, .. }) => env.lookup(idx).content,
+            _ => {
+                let env = shared_env.get_or_insert_with(|| Rc::new(env.clone()));
+                Thunk::delay(arg, env.clone())
+            }
+        })
+        .collect()
+}
+
 impl<T: Eval> Eval for Vec<T> {
     type Val = Vec<T::Val>;
 
@@ -0,0 +1,105 @@
+//! Bounds on the evaluation performed by the normalizer
+//!
+//! Evaluation does not terminate for every well-typed program, for example if a declaration is
+//! annotated with `#[non_terminating]`. Evaluation can therefore be bounded by [`EvalLimits`]:
+//! every evaluation step consumes one unit of fuel, and evaluation fails with
+//! [`TypeError::OutOfFuel`] once the fuel is used up or the time limit is exceeded.
+//! By default, evaluation is not limited.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+#[cfg(not(target_arch = "wasm32"))]
+use std::sync::Mutex;
+use std::time::Duration;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+
+use crate::result::{TcResult, TypeError};
+
+/// The number of evaluation steps between two checks of the time limit
+///
+/// Reading the clock is much more expensive than an evaluation step.
+#[cfg(not(target_arch = "wasm32"))]
+const TIMEOUT_CHECK_INTERVAL: u64 = 1024;
+
+/// Limits on the evaluation performed by the normalizer
+///
+/// The limits apply separately to the typechecking of each declaration and to each expression evaluated by `pol run`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EvalLimits {
+    /// The maximal number of evaluation steps, or `None` for no limit
+    pub max_steps: Option<u64>,
+    /// The maximal duration of evaluation, or `None` for no limit
+    ///
+    /// The timeout is not supported on `wasm32` targets, where it is ignored.
+    pub timeout: Option<Duration>,
+}
+
+/// The fuel of the normalizer
+///
+/// All clones of a `Fuel` share the same counter.
+#[derive(Debug, Clone)]
+pub struct Fuel {
+    limits: EvalLimits,
+    /// The number of evaluation steps since the fuel was last refilled
+    steps: Arc<AtomicU64>,
+    /// The time at which the fuel was last refilled
+    #[cfg(not(target_arch = "wasm32"))]
+    refilled: Arc<Mutex<Instant>>,
+}
+
+impl Default for Fuel {
+    fn default() -> Self {
+        Self::new(EvalLimits::default())
+    }
+}
+
+impl Fuel {
+    pub fn new(limits: EvalLimits) -> Self {
+        Self {
+            limits,
+            steps: Default::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            refilled: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    pub fn limits(&self) -> EvalLimits {
+        self.limits
+    }
+
+    /// Reset the number of steps and the time spent evaluating
+    pub fn refill(&self) {
+        self.steps.store(0, Ordering::Relaxed);
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            *self.refilled.lock().unwrap() = Instant::now();
+        }
+    }
+
+    /// Consume the fuel for one evaluation step of the expression at `span`
+    pub fn consume(&self, span: Option<Span>) -> TcResult {
+        let steps = self.steps.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(max_steps) = self.limits.max_steps {
+            if steps > max_steps {
+                return Err(out_of_fuel(format!("{max_steps} steps"), span));
+            }
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(timeout) = self.limits.timeout {
+            if steps % TIMEOUT_CHECK_INTERVAL == 0
+                && self.refilled.lock().unwrap().elapsed() > timeout
+            {
+                return Err(out_of_fuel(format!("{} ms", timeout.as_millis()), span));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn out_of_fuel(limit: String, span: Option<Span>) -> Box<TypeError> {
+    Box::new(TypeError::OutOfFuel { limit, span: span.to_miette() })
+}
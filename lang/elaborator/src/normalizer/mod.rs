@@ -1,4 +1,7 @@
 pub mod env;
-mod eval;
+pub(crate) mod eval;
+pub mod fuel;
 pub mod normalize;
+pub mod step;
+pub mod thunk;
 pub mod val;
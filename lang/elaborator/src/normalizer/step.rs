@@ -0,0 +1,397 @@
+//! Small-step reduction of expressions
+//!
+//! While [`Normalize`](super::normalize::Normalize) computes the normal form of an expression at
+//! once, this module reduces an expression one redex at a time, so that the individual steps of
+//! an evaluation can be shown to the user.
+//!
+//! The order of the steps agrees with the evaluator: subexpressions are reduced from left to
+//! right before the expression which contains them (call-by-value). Once the expression is in
+//! weak normal form, the bodies of local (co)matches which are stuck or part of the result are
+//! reduced, such that the final expression is the normal form computed by the evaluator.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::rc::Rc;
+
+use ast::ctx::values::Binder;
+use ast::ctx::{AsBinder, BindContext, LevelCtx};
+use ast::*;
+use miette_util::codespan::{ByteIndex, Span};
+use miette_util::ToMiette;
+
+use crate::result::{TcResult, TypeError};
+use crate::TypeInfoTable;
+
+/// The span which marks the redex in [`Step::exp`]
+///
+/// No expression in a source file has this span, so printing [`Step::exp`] with
+/// [`printer::PrintCfg::highlight`] set to this span highlights exactly the redex.
+pub const REDEX_SPAN: Span = Span { start: ByteIndex(u32::MAX), end: ByteIndex(u32::MAX) };
+
+/// The reduction rule applied in a step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    /// Unfolding a transparent let-bound definition or a local let-binding
+    Beta,
+    /// Matching on a constructor, either in a toplevel definition or a local match
+    Match,
+    /// Destructing a codefinition or a local comatch
+    Comatch,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Beta => write!(f, "beta"),
+            Rule::Match => write!(f, "match"),
+            Rule::Comatch => write!(f, "comatch"),
+        }
+    }
+}
+
+/// A single reduction step
+#[derive(Debug, Clone)]
+pub struct Step {
+    /// The reduction rule which was applied
+    pub rule: Rule,
+    /// Whether the redex is the whole expression
+    pub top_level: bool,
+    /// The expression before the step, in which the span of the redex is [`REDEX_SPAN`]
+    pub exp: Box<Exp>,
+    /// The redex which was contracted
+    pub redex: Box<Exp>,
+}
+
+/// The sequence of reduction steps from an expression to its normal form
+///
+/// Every step consumes fuel of the type info table, so the sequence ends with a
+/// [`TypeError::OutOfFuel`] error if the expression does not have a normal form.
+pub struct Steps {
+    info_table: Rc<TypeInfoTable>,
+    /// The expression after the steps performed so far
+    exp: Box<Exp>,
+    done: bool,
+}
+
+impl Steps {
+    /// The steps of the closed expression `exp`
+    pub fn new(exp: Box<Exp>, info_table: Rc<TypeInfoTable>) -> Self {
+        info_table.fuel().refill();
+        Steps { info_table, exp, done: false }
+    }
+
+    /// The expression after the steps performed so far
+    ///
+    /// Once the iterator is exhausted without an error, this is the normal form of the expression.
+    pub fn exp(&self) -> &Exp {
+        &self.exp
+    }
+
+    /// Whether the expression is in normal form, i.e. whether no further steps are possible
+    pub fn is_normal(&self) -> TcResult<bool> {
+        let mut exp = self.exp.clone();
+        let mut has_redex = false;
+        for strong in [false, true] {
+            visit(&mut exp, &mut LevelCtx::empty(), strong, &mut |exp, _| {
+                has_redex = redex_rule(exp, &self.info_table)?.is_some();
+                Ok(has_redex)
+            })?;
+        }
+        Ok(!has_redex)
+    }
+
+    fn step(&mut self) -> TcResult<Option<Step>> {
+        // Weak redexes are reduced first, and only then the redexes under binders.
+        for strong in [false, true] {
+            let mut found = None;
+            visit(&mut self.exp, &mut LevelCtx::empty(), strong, &mut |exp, _| {
+                let Some(rule) = redex_rule(exp, &self.info_table)? else { return Ok(false) };
+                found = Some((rule, exp.span()));
+                set_span(exp, Some(REDEX_SPAN));
+                Ok(true)
+            })?;
+            let Some((rule, span)) = found else { continue };
+
+            self.info_table.fuel().consume(span)?;
+            let top_level = self.exp.span() == Some(REDEX_SPAN);
+            let exp = self.exp.clone();
+            let mut redex = None;
+            visit(&mut self.exp, &mut LevelCtx::empty(), strong, &mut |exp, ctx| {
+                if exp.span() != Some(REDEX_SPAN) {
+                    return Ok(false);
+                }
+                set_span(exp, span);
+                redex = Some(Box::new(exp.clone()));
+                *exp = *contract(exp, ctx, &self.info_table)?;
+                Ok(true)
+            })?;
+            let redex = redex.expect("The marked redex is found again");
+            return Ok(Some(Step { rule, top_level, exp, redex }));
+        }
+        Ok(None)
+    }
+}
+
+impl Iterator for Steps {
+    type Item = TcResult<Step>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let step = self.step().transpose();
+        self.done = !matches!(step, Some(Ok(_)));
+        step
+    }
+}
+
+/// Call `f` on the subexpressions of `exp` in the order of evaluation, and finally on `exp` itself,
+/// until `f` returns `true`
+///
+/// If `strong` is set, the bodies of local (co)matches are visited as well.
+fn visit<F>(exp: &mut Exp, ctx: &mut LevelCtx, strong: bool, f: &mut F) -> TcResult<bool>
+where
+    F: FnMut(&mut Exp, &LevelCtx) -> TcResult<bool>,
+{
+    let found = match exp {
+        Exp::Variable(_) | Exp::TypeUniv(_) | Exp::LevelUniv(_) | Exp::Level(_) | Exp::Hole(_) => {
+            false
+        }
+        Exp::TypCtor(e) => visit_args(&mut e.args, ctx, strong, f)?,
+        Exp::Call(e) => visit_args(&mut e.args, ctx, strong, f)?,
+        Exp::DotCall(e) => {
+            visit(&mut e.exp, ctx, strong, f)? || visit_args(&mut e.args, ctx, strong, f)?
+        }
+        Exp::Anno(e) => visit(&mut e.exp, ctx, strong, f)? || visit(&mut e.typ, ctx, strong, f)?,
+        // The body of a local let is reduced after the bound expression is substituted.
+        Exp::LocalLet(e) => visit(&mut e.bound, ctx, strong, f)?,
+        Exp::LocalMatch(e) => {
+            visit(&mut e.on_exp, ctx, strong, f)?
+                || (strong && visit_cases(&mut e.cases, &mut e.catch_all, ctx, f)?)
+        }
+        Exp::LocalComatch(e) => strong && visit_cases(&mut e.cases, &mut e.catch_all, ctx, f)?,
+    };
+    if found {
+        return Ok(true);
+    }
+    f(exp, ctx)
+}
+
+fn visit_args<F>(args: &mut Args, ctx: &mut LevelCtx, strong: bool, f: &mut F) -> TcResult<bool>
+where
+    F: FnMut(&mut Exp, &LevelCtx) -> TcResult<bool>,
+{
+    for arg in args.args.iter_mut() {
+        match arg {
+            Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => {
+                if visit(arg, ctx, strong, f)? {
+                    return Ok(true);
+                }
+            }
+            // Inserted implicit arguments are solved metavariables, which are not shown when printing.
+            Arg::InsertedImplicitArg { .. } => {}
+        }
+    }
+    Ok(false)
+}
+
+fn visit_cases<F>(
+    cases: &mut [Case],
+    catch_all: &mut Option<CatchAll>,
+    ctx: &mut LevelCtx,
+    f: &mut F,
+) -> TcResult<bool>
+where
+    F: FnMut(&mut Exp, &LevelCtx) -> TcResult<bool>,
+{
+    for Case { pattern, body, .. } in cases.iter_mut() {
+        let Some(body) = body else { continue };
+        if ctx.bind_iter(pattern.params.params.iter(), |ctx| visit(body, ctx, true, f))? {
+            return Ok(true);
+        }
+    }
+    match catch_all {
+        Some(CatchAll { name, body: Some(body), .. }) => {
+            ctx.bind_single(name.clone(), |ctx| visit(body, ctx, true, f))
+        }
+        _ => Ok(false),
+    }
+}
+
+/// The rule by which `exp` reduces, if it is a redex
+///
+/// The subexpressions of `exp` are assumed to be in normal form.
+fn redex_rule(exp: &Exp, info_table: &TypeInfoTable) -> TcResult<Option<Rule>> {
+    let rule = match exp {
+        Exp::Call(Call { kind: CallKind::LetBound, name, .. }) => {
+            let Let { attr, .. } = info_table.lookup_let(name)?;
+            attr.attrs.contains(&Attribute::Transparent).then_some(Rule::Beta)
+        }
+        Exp::LocalLet(_) => Some(Rule::Beta),
+        Exp::DotCall(DotCall { exp, .. }) => match strip_annotations(exp) {
+            Exp::Call(Call { kind: CallKind::Constructor, .. }) => Some(Rule::Match),
+            Exp::Call(Call { kind: CallKind::Codefinition, .. }) => Some(Rule::Comatch),
+            Exp::LocalComatch(_) => Some(Rule::Comatch),
+            _ => None,
+        },
+        Exp::LocalMatch(LocalMatch { on_exp, .. }) => match strip_annotations(on_exp) {
+            Exp::Call(Call { kind: CallKind::Constructor, .. }) => Some(Rule::Match),
+            _ => None,
+        },
+        _ => None,
+    };
+    Ok(rule)
+}
+
+/// Contract the redex `exp` in the context `ctx`
+fn contract(exp: &Exp, ctx: &LevelCtx, info_table: &TypeInfoTable) -> TcResult<Box<Exp>> {
+    match exp {
+        Exp::Call(Call { name, args, .. }) => {
+            let Let { params, body, .. } = info_table.lookup_let(name)?;
+            Ok(instantiate(body, ctx, vec![bind_args(&params.params, args)]))
+        }
+        Exp::LocalLet(LocalLet { name, bound, body, .. }) => {
+            let binder = Binder { name: name.clone(), content: bound.clone() };
+            Ok(instantiate(body, ctx, vec![vec![binder]]))
+        }
+        Exp::DotCall(DotCall { exp, name, args, .. }) => match strip_annotations(exp) {
+            matched @ Exp::Call(Call {
+                kind: CallKind::Constructor,
+                name: ctor_name,
+                args: ctor_args,
+                ..
+            }) => {
+                let Def { params, cases, catch_all, .. } = info_table.lookup_def(name)?;
+                let (case_args, body) =
+                    select_case(cases, catch_all, ctor_name, ctor_args, matched, true)?;
+                Ok(instantiate(body, ctx, vec![bind_args(&params.params, args), case_args]))
+            }
+            matched @ Exp::Call(Call { name: codef_name, args: codef_args, .. }) => {
+                let Codef { params, cases, catch_all, .. } = info_table.lookup_codef(codef_name)?;
+                let (case_args, body) = select_case(cases, catch_all, name, args, matched, false)?;
+                Ok(instantiate(body, ctx, vec![bind_args(&params.params, codef_args), case_args]))
+            }
+            matched @ Exp::LocalComatch(LocalComatch { cases, catch_all, .. }) => {
+                let (case_args, body) = select_case(cases, catch_all, name, args, matched, false)?;
+                Ok(instantiate(body, ctx, vec![case_args]))
+            }
+            _ => Err(impossible(exp)),
+        },
+        Exp::LocalMatch(LocalMatch { on_exp, cases, catch_all, .. }) => {
+            match strip_annotations(on_exp) {
+                matched @ Exp::Call(Call { name: ctor_name, args: ctor_args, .. }) => {
+                    let (case_args, body) =
+                        select_case(cases, catch_all, ctor_name, ctor_args, matched, true)?;
+                    Ok(instantiate(body, ctx, vec![case_args]))
+                }
+                _ => Err(impossible(exp)),
+            }
+        }
+        _ => Err(impossible(exp)),
+    }
+}
+
+/// The body of the case for the constructor or destructor `name`, together with the arguments
+/// `args` bound to its parameters
+///
+/// If there is no such case, then the body of the catch-all clause is used instead, whose
+/// parameter is bound to the matched value `matched`.
+fn select_case<'a>(
+    cases: &'a [Case],
+    catch_all: &'a Option<CatchAll>,
+    name: &IdBound,
+    args: &Args,
+    matched: &Exp,
+    is_match: bool,
+) -> TcResult<(Vec<Binder<Box<Exp>>>, &'a Exp)> {
+    let missing = || match is_match {
+        true => TypeError::MissingCase { name: name.id.clone() },
+        false => TypeError::MissingCocase { name: name.id.clone() },
+    };
+    if let Some(case) = cases.iter().find(|case| case.pattern.name == *name) {
+        let body = case.body.as_ref().ok_or_else(missing)?;
+        return Ok((bind_args(&case.pattern.params.params, args), body));
+    }
+    let CatchAll { name, body, .. } = catch_all.as_ref().ok_or_else(missing)?;
+    let body = body.as_ref().ok_or_else(missing)?;
+    Ok((vec![Binder { name: name.clone(), content: Box::new(matched.clone()) }], body))
+}
+
+fn bind_args<P: AsBinder<()>>(
+    params: impl IntoIterator<Item = P>,
+    args: &Args,
+) -> Vec<Binder<Box<Exp>>> {
+    params
+        .into_iter()
+        .zip(args.to_exps())
+        .map(|(param, arg)| Binder { name: param.as_binder().name, content: arg })
+        .collect()
+}
+
+/// Substitute the arguments `args` for the variables bound by the innermost binders of `body`
+///
+/// The body is an expression in the context `ctx` extended by one binder for each element of
+/// `args`, and the arguments are expressions in the context `ctx`.
+fn instantiate(body: &Exp, ctx: &LevelCtx, args: Vec<Vec<Binder<Box<Exp>>>>) -> Box<Exp> {
+    let n = args.len() as isize;
+    let names: Vec<Vec<VarBind>> =
+        args.iter().map(|binders| binders.iter().map(|b| b.name.clone()).collect()).collect();
+    let mut subst_ctx = ctx.append(&LevelCtx::from(names));
+    let mut args: Vec<Vec<Exp>> =
+        args.into_iter().map(|binders| binders.into_iter().map(|b| *b.content).collect()).collect();
+    args.shift((n, 0));
+    let subst = Instantiate { base: ctx.len(), args };
+    // Unwrap is safe here because we are unwrapping an infallible result
+    let mut exp = body.subst(&mut subst_ctx, &subst).unwrap();
+    exp.shift((-n, 0));
+    Box::new(exp)
+}
+
+/// The substitution of [`instantiate`] for the binders with the levels `base`, `base + 1`, ...
+#[derive(Debug, Clone)]
+struct Instantiate {
+    base: usize,
+    args: Vec<Vec<Exp>>,
+}
+
+impl Shift for Instantiate {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.args.shift_in_range(range, by);
+    }
+}
+
+impl Substitution for Instantiate {
+    type Err = Infallible;
+
+    fn get_subst(&self, _ctx: &LevelCtx, lvl: Lvl) -> Result<Option<Box<Exp>>, Self::Err> {
+        // Variables bound outside of the substituted binders or within the body are unchanged
+        match lvl.fst.checked_sub(self.base).and_then(|fst| self.args.get(fst)) {
+            Some(args) => Ok(Some(Box::new(args[lvl.snd].clone()))),
+            None => Ok(None),
+        }
+    }
+}
+
+fn strip_annotations(exp: &Exp) -> &Exp {
+    match exp {
+        Exp::Anno(anno) => strip_annotations(&anno.exp),
+        exp => exp,
+    }
+}
+
+fn set_span(exp: &mut Exp, span: Option<Span>) {
+    match exp {
+        Exp::Call(e) => e.span = span,
+        Exp::DotCall(e) => e.span = span,
+        Exp::LocalMatch(e) => e.span = span,
+        Exp::LocalLet(e) => e.span = span,
+        _ => {}
+    }
+}
+
+fn impossible(exp: &Exp) -> Box<TypeError> {
+    Box::new(TypeError::Impossible {
+        message: "Attempted to contract an expression which is not a redex".to_owned(),
+        span: exp.span().to_miette(),
+    })
+}
@@ -0,0 +1,93 @@
+//! Lazily evaluated values bound in an [`Env`]
+//!
+//! The arguments of calls and the expressions bound by local let-bindings are not evaluated
+//! before they are bound in the environment. Instead, they are bound to a [`Thunk`] which is
+//! evaluated the first time the variable is used. All clones of a thunk share the result of the
+//! evaluation, so that an argument is evaluated at most once (call-by-need), and not at all if
+//! the variable is never used.
+
+use std::cell::RefCell;
+use std::ops::Bound;
+use std::rc::Rc;
+
+use ast::{Exp, Shift, ShiftRange};
+use pretty::DocAllocator;
+use printer::{Alloc, Builder, Print, PrintCfg};
+
+use crate::normalizer::env::Env;
+use crate::normalizer::eval::Eval;
+use crate::normalizer::val::Val;
+use crate::result::TcResult;
+use crate::TypeInfoTable;
+
+/// A value which is evaluated on demand and shared between all of its clones
+#[derive(Debug, Clone)]
+pub struct Thunk {
+    state: Rc<RefCell<ThunkState>>,
+}
+
+#[derive(Debug)]
+enum ThunkState {
+    /// The expression `exp` has not been evaluated in the environment `env` yet
+    ///
+    /// The environment is shared between the arguments of a call.
+    Delayed { exp: Box<Exp>, env: Rc<Env> },
+    /// The value of `thunk` shifted by `by` in `range`, which has not been computed yet
+    ///
+    /// Since evaluation commutes with shifting, shifting a thunk neither forces it nor
+    /// affects the other clones of the thunk.
+    Shifted { thunk: Thunk, range: (Bound<usize>, Bound<usize>), by: (isize, isize) },
+    /// The value of the thunk
+    Forced(Box<Val>),
+}
+
+impl Thunk {
+    /// Delay the evaluation of `exp` in the environment `env`
+    pub fn delay(exp: Box<Exp>, env: Rc<Env>) -> Self {
+        Self::from_state(ThunkState::Delayed { exp, env })
+    }
+
+    /// The value of the thunk, which is computed at the first call
+    pub fn force(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Box<Val>> {
+        let mut state = self.state.borrow_mut();
+        // A thunk is never reachable from its own environment, so forcing it never forces the
+        // thunk which is currently borrowed.
+        let val = match &*state {
+            ThunkState::Forced(val) => return Ok(val.clone()),
+            ThunkState::Delayed { exp, env } => exp.eval(info_table, &mut (**env).clone())?,
+            ThunkState::Shifted { thunk, range, by } => {
+                let mut val = thunk.force(info_table)?;
+                val.shift_in_range(range, *by);
+                val
+            }
+        };
+        *state = ThunkState::Forced(val.clone());
+        Ok(val)
+    }
+
+    fn from_state(state: ThunkState) -> Self {
+        Self { state: Rc::new(RefCell::new(state)) }
+    }
+}
+
+impl From<Box<Val>> for Thunk {
+    fn from(val: Box<Val>) -> Self {
+        Self::from_state(ThunkState::Forced(val))
+    }
+}
+
+impl Shift for Thunk {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        let range = (range.start_bound().cloned(), range.end_bound().cloned());
+        *self = Self::from_state(ThunkState::Shifted { thunk: self.clone(), range, by });
+    }
+}
+
+impl Print for Thunk {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        match &*self.state.borrow() {
+            ThunkState::Forced(val) => alloc.text(val.print_to_string(Some(cfg))),
+            ThunkState::Delayed { .. } | ThunkState::Shifted { .. } => alloc.text("..."),
+        }
+    }
+}
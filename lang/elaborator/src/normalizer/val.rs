@@ -25,15 +25,18 @@ use crate::TypeInfoTable;
 use super::eval::Eval;
 use crate::result::*;
 
-fn print_cases<'a>(cases: &'a [Case], cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+fn print_cases<'a>(
+    cases: &'a [Case],
+    catch_all: Option<&'a CatchAll>,
+    cfg: &PrintCfg,
+    alloc: &'a Alloc<'a>,
+) -> Builder<'a> {
     let sep = alloc.text(COMMA).append(alloc.hardline());
+    let docs =
+        cases.iter().map(|x| x.print(cfg, alloc)).chain(catch_all.map(|x| x.print(cfg, alloc)));
     alloc
         .hardline()
-        .append(
-            alloc
-                .intersperse(cases.iter().map(|x| x.print(cfg, alloc)), sep)
-                .append(alloc.text(COMMA).flat_alt(alloc.nil())),
-        )
+        .append(alloc.intersperse(docs, sep).append(alloc.text(COMMA).flat_alt(alloc.nil())))
         .nest(cfg.indent)
         .append(alloc.hardline())
         .braces_anno()
@@ -87,6 +90,8 @@ pub enum Val {
     // A call is only a value if it is a constructor or a codefinition.
     Call(Call),
     TypeUniv(TypeUniv),
+    LevelUniv(LevelUniv),
+    Level(Level),
     LocalComatch(LocalComatch),
     Anno(AnnoVal),
     Neu(Neu),
@@ -98,6 +103,8 @@ impl Shift for Val {
             Val::TypCtor(e) => e.shift_in_range(range, by),
             Val::Call(e) => e.shift_in_range(range, by),
             Val::TypeUniv(e) => e.shift_in_range(range, by),
+            Val::LevelUniv(e) => e.shift_in_range(range, by),
+            Val::Level(e) => e.shift_in_range(range, by),
             Val::LocalComatch(e) => e.shift_in_range(range, by),
             Val::Anno(e) => e.shift_in_range(range, by),
             Val::Neu(exp) => exp.shift_in_range(range, by),
@@ -111,6 +118,8 @@ impl Print for Val {
             Val::TypCtor(e) => e.print(cfg, alloc),
             Val::Call(e) => e.print(cfg, alloc),
             Val::TypeUniv(e) => e.print(cfg, alloc),
+            Val::LevelUniv(e) => e.print(cfg, alloc),
+            Val::Level(e) => e.print(cfg, alloc),
             Val::LocalComatch(e) => e.print(cfg, alloc),
             Val::Anno(e) => e.print(cfg, alloc),
             Val::Neu(exp) => exp.print(cfg, alloc),
@@ -126,6 +135,8 @@ impl ReadBack for Val {
             Val::TypCtor(e) => e.read_back(info_table)?.into(),
             Val::Call(e) => e.read_back(info_table)?.into(),
             Val::TypeUniv(e) => e.read_back(info_table)?.into(),
+            Val::LevelUniv(e) => e.read_back(info_table)?.into(),
+            Val::Level(e) => e.read_back(info_table)?,
             Val::LocalComatch(e) => e.read_back(info_table)?.into(),
             Val::Anno(e) => e.read_back(info_table)?.into(),
             Val::Neu(exp) => exp.read_back(info_table)?,
@@ -144,7 +155,7 @@ pub struct TypCtor {
     pub span: Option<Span>,
     pub name: ast::IdBound,
     pub args: Args,
-    pub is_bin_op: Option<String>,
+    pub is_bin_op: Option<ast::BinOp>,
 }
 
 impl Shift for TypCtor {
@@ -235,15 +246,21 @@ impl ReadBack for Call {
 #[derive(Debug, Clone)]
 pub struct TypeUniv {
     pub span: Option<Span>,
+    pub level: Option<Box<Val>>,
 }
 
 impl Shift for TypeUniv {
-    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        self.level.shift_in_range(range, by);
+    }
 }
 
 impl Print for TypeUniv {
-    fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        alloc.typ(TYPE)
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        match &self.level {
+            None => alloc.typ(TYPE),
+            Some(level) => alloc.typ(TYPE).append(alloc.space()).append(level.print(cfg, alloc)),
+        }
     }
 }
 
@@ -256,9 +273,100 @@ impl From<TypeUniv> for Val {
 impl ReadBack for TypeUniv {
     type Nf = ast::TypeUniv;
 
+    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
+        let TypeUniv { span, level } = self;
+        Ok(ast::TypeUniv { span: *span, level: level.read_back(info_table)? })
+    }
+}
+
+// LevelUniv
+//
+//
+
+#[derive(Debug, Clone)]
+pub struct LevelUniv {
+    pub span: Option<Span>,
+}
+
+impl Shift for LevelUniv {
+    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+}
+
+impl Print for LevelUniv {
+    fn print<'a>(&'a self, _cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        alloc.typ(LEVEL)
+    }
+}
+
+impl From<LevelUniv> for Val {
+    fn from(value: LevelUniv) -> Self {
+        Val::LevelUniv(value)
+    }
+}
+
+impl ReadBack for LevelUniv {
+    type Nf = ast::LevelUniv;
+
     fn read_back(&self, _info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
-        let TypeUniv { span } = self;
-        Ok(ast::TypeUniv { span: *span })
+        let LevelUniv { span } = self;
+        Ok(ast::LevelUniv { span: *span })
+    }
+}
+
+// Level
+//
+//
+
+/// A universe level `max(n, l₁ + k₁, ..., lₘ + kₘ)` whose summands are neutral
+#[derive(Debug, Clone)]
+pub struct Level {
+    pub span: Option<Span>,
+    pub constant: usize,
+    pub summands: Vec<(Box<Val>, usize)>,
+}
+
+impl Shift for Level {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        for (val, _) in self.summands.iter_mut() {
+            val.shift_in_range(range, by);
+        }
+    }
+}
+
+impl Print for Level {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let Level { constant, summands, .. } = self;
+        let constant = alloc.text(constant.to_string());
+        let summands = summands.iter().map(|(val, k)| {
+            val.print(cfg, alloc).append(alloc.space()).append(PLUS).append(format!(" {k}"))
+        });
+        alloc.keyword(MAX).append(
+            alloc
+                .intersperse(
+                    std::iter::once(constant).chain(summands),
+                    alloc.text(COMMA).append(alloc.space()),
+                )
+                .parens(),
+        )
+    }
+}
+
+impl From<Level> for Val {
+    fn from(value: Level) -> Self {
+        Val::Level(value)
+    }
+}
+
+impl ReadBack for Level {
+    type Nf = ast::Exp;
+
+    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
+        let Level { span, constant, summands } = self;
+        let summands = summands
+            .iter()
+            .map(|(val, k)| Ok((val.read_back(info_table)?, *k)))
+            .collect::<TcResult<_>>()?;
+        Ok(ast::Level { span: *span, constant: *constant, summands }.canonicalize().into_exp())
     }
 }
 
@@ -272,23 +380,25 @@ pub struct LocalComatch {
     pub name: ast::Label,
     pub is_lambda_sugar: bool,
     pub cases: Vec<Case>,
+    pub catch_all: Option<CatchAll>,
 }
 
 impl Shift for LocalComatch {
     fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
         self.cases.shift_in_range(range, by);
+        self.catch_all.shift_in_range(range, by);
     }
 }
 
 impl Print for LocalComatch {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let LocalComatch { span: _, name, is_lambda_sugar: _, cases } = self;
+        let LocalComatch { span: _, name, is_lambda_sugar: _, cases, catch_all } = self;
         alloc
             .keyword(COMATCH)
             .append(alloc.space())
             .append(alloc.text(name.to_string()))
             .append(alloc.space())
-            .append(print_cases(cases, cfg, alloc))
+            .append(print_cases(cases, catch_all.as_ref(), cfg, alloc))
     }
 }
 
@@ -301,13 +411,14 @@ impl From<LocalComatch> for Val {
 impl ReadBack for LocalComatch {
     type Nf = ast::LocalComatch;
     fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
-        let LocalComatch { span, name, is_lambda_sugar, cases } = self;
+        let LocalComatch { span, name, is_lambda_sugar, cases, catch_all } = self;
         Ok(ast::LocalComatch {
             span: *span,
             ctx: None,
             name: name.clone(),
             is_lambda_sugar: *is_lambda_sugar,
             cases: cases.read_back(info_table)?,
+            catch_all: catch_all.read_back(info_table)?,
             inferred_type: None,
         })
     }
@@ -523,18 +634,20 @@ pub struct LocalMatch {
     pub name: ast::Label,
     pub on_exp: Box<Neu>,
     pub cases: Vec<Case>,
+    pub catch_all: Option<CatchAll>,
 }
 
 impl Shift for LocalMatch {
     fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
         self.on_exp.shift_in_range(range, by);
         self.cases.shift_in_range(range, by);
+        self.catch_all.shift_in_range(range, by);
     }
 }
 
 impl Print for LocalMatch {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let LocalMatch { span: _, name, on_exp, cases } = self;
+        let LocalMatch { span: _, name, on_exp, cases, catch_all } = self;
         on_exp
             .print(cfg, alloc)
             .append(DOT)
@@ -542,7 +655,7 @@ impl Print for LocalMatch {
             .append(alloc.space())
             .append(alloc.text(name.to_string()))
             .append(alloc.space())
-            .append(print_cases(cases, cfg, alloc))
+            .append(print_cases(cases, catch_all.as_ref(), cfg, alloc))
     }
 }
 
@@ -556,7 +669,7 @@ impl ReadBack for LocalMatch {
     type Nf = ast::LocalMatch;
 
     fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
-        let LocalMatch { span, name, on_exp, cases } = self;
+        let LocalMatch { span, name, on_exp, cases, catch_all } = self;
         Ok(ast::LocalMatch {
             span: *span,
             ctx: None,
@@ -565,6 +678,7 @@ impl ReadBack for LocalMatch {
             name: name.clone(),
             on_exp: on_exp.read_back(info_table)?,
             cases: cases.read_back(info_table)?,
+            catch_all: catch_all.read_back(info_table)?,
             inferred_type: None,
         })
     }
@@ -647,6 +761,7 @@ pub struct Case {
     pub params: ast::TelescopeInst,
     /// Body being `None` represents an absurd pattern
     pub body: Option<Closure>,
+    pub expanded_from: Option<VarBind>,
 }
 
 impl Shift for Case {
@@ -658,7 +773,7 @@ impl Shift for Case {
 
 impl Print for Case {
     fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
-        let Case { span: _, is_copattern: _, name, params, body } = self;
+        let Case { span: _, is_copattern: _, name, params, body, expanded_from: _ } = self;
 
         let body = match body {
             None => alloc.keyword(ABSURD),
@@ -682,7 +797,7 @@ impl ReadBack for Case {
     type Nf = ast::Case;
 
     fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
-        let Case { span, is_copattern, name, params, body } = self;
+        let Case { span, is_copattern, name, params, body, expanded_from } = self;
 
         Ok(ast::Case {
             span: *span,
@@ -693,6 +808,54 @@ impl ReadBack for Case {
                 params: params.clone(),
             },
             body: body.read_back(info_table)?,
+            expanded_from: expanded_from.clone(),
+        })
+    }
+}
+
+// CatchAll
+//
+//
+
+/// A catch-all clause, whose body binds the matched value
+#[derive(Debug, Clone)]
+pub struct CatchAll {
+    pub span: Option<Span>,
+    /// The name of the matched value
+    pub name: VarBind,
+    /// Body being `None` represents an absurd clause
+    pub body: Option<Closure>,
+}
+
+impl Shift for CatchAll {
+    fn shift_in_range<R: ShiftRange>(&mut self, range: &R, by: (isize, isize)) {
+        let range = (*range).clone();
+        self.body.shift_in_range(&range.shift(1), by);
+    }
+}
+
+impl Print for CatchAll {
+    fn print<'a>(&'a self, cfg: &PrintCfg, alloc: &'a Alloc<'a>) -> Builder<'a> {
+        let body = match &self.body {
+            None => alloc.keyword(ABSURD),
+            Some(body) => alloc
+                .text(FAT_ARROW)
+                .append(alloc.line())
+                .append(body.print(cfg, alloc))
+                .nest(cfg.indent),
+        };
+        self.name.print(cfg, alloc).append(alloc.space()).append(body).group()
+    }
+}
+
+impl ReadBack for CatchAll {
+    type Nf = ast::CatchAll;
+
+    fn read_back(&self, info_table: &Rc<TypeInfoTable>) -> TcResult<Self::Nf> {
+        Ok(ast::CatchAll {
+            span: self.span,
+            name: self.name.clone(),
+            body: self.body.read_back(info_table)?,
         })
     }
 }
@@ -953,7 +1116,7 @@ impl ReadBack for Closure {
             .params
             .iter()
             .zip(args)
-            .map(|(name, arg)| Binder { name: name.clone(), content: arg });
+            .map(|(name, arg)| Binder { name: name.clone(), content: arg.into() });
         let mut shifted_env = shift_and_clone(&self.env, (1, 0));
         shifted_env.bind_iter(binders, |env| self.body.eval(info_table, env))?.read_back(info_table)
     }
@@ -241,6 +241,85 @@ pub enum TypeError {
         #[label("While elaborating")]
         while_elaborating_span: Option<SourceSpan>,
     },
+    #[error("Cannot show that the recursive call to {name} terminates")]
+    #[diagnostic(
+        code("T-024"),
+        help("Recursive calls must be on structurally smaller arguments. Use #[non_terminating] to disable this check.")
+    )]
+    NonTerminating {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("The corecursive call to {name} is not guarded by a copattern")]
+    #[diagnostic(
+        code("T-025"),
+        help("The result of a corecursive call must not be observed. Use #[non_terminating] to disable this check.")
+    )]
+    Unguarded {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("The type {name} does not occur strictly positively")]
+    #[diagnostic(code("T-026"), help("Use #[no_positivity_check] to disable this check."))]
+    NotStrictlyPositive {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("The {name} does not fit into the universe {universe} of {typ_name}")]
+    #[diagnostic(
+        code("T-027"),
+        help("In predicative modules, the fields of a type must not live in a larger universe than the type itself.")
+    )]
+    UniverseTooLarge {
+        name: String,
+        universe: String,
+        typ_name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("Evaluation did not finish within {limit}")]
+    #[diagnostic(
+        code("T-028"),
+        help("The evaluation may not terminate. The limits can be changed with the `--max-steps` and `--timeout` options of `pol check`, `pol run` and `pol lsp`, or with the `maxSteps` and `timeoutMs` settings of the language server.")
+    )]
+    OutOfFuel {
+        limit: String,
+        #[label("Evaluation ran out of fuel here")]
+        span: Option<SourceSpan>,
+    },
+    #[error("The erased variable {name} is used at runtime")]
+    #[diagnostic(
+        code("T-029"),
+        help("Erased variables may only be used in types, as arguments of erased parameters and in declarations which compute types.")
+    )]
+    ErasedVariable {
+        name: String,
+        #[label]
+        span: Option<SourceSpan>,
+    },
+    #[error("Cannot unify the level expressions {lhs} and {rhs}")]
+    #[diagnostic(
+        code("T-030"),
+        help("Levels which contain holes can only be unified with levels of the same shape, such as `_ + 1` with `l + 1`. Provide the level explicitly.")
+    )]
+    CannotUnifyLevels {
+        lhs: String,
+        rhs: String,
+        #[label("While elaborating")]
+        while_elaborating_span: Option<SourceSpan>,
+    },
+    #[error("This clause is unreachable")]
+    #[diagnostic(
+        code("T-031"),
+        help("All values matched by this clause are already matched by previous clauses.")
+    )]
+    UnreachableClause {
+        #[label]
+        span: Option<SourceSpan>,
+    },
     #[error("An unexpected internal error occurred: {message}")]
     #[diagnostic(code("T-XXX"))]
     /// This error should not occur.
@@ -297,6 +376,17 @@ impl TypeError {
         }
         .into()
     }
+
+    /// The metavariable which could not be solved by unification, if this error reports one
+    pub fn failed_meta_var(&self) -> Option<&str> {
+        match self {
+            Self::MetaArgNotDistinct { meta_var, .. }
+            | Self::MetaArgNotVariable { meta_var, .. }
+            | Self::MetaEquatedToOutOfScope { meta_var, .. }
+            | Self::MetaOccursCheckFailed { meta_var, .. } => Some(meta_var),
+            _ => None,
+        }
+    }
 }
 
 impl From<Infallible> for TypeError {
@@ -0,0 +1,336 @@
+//! Termination checking
+//!
+//! After a module has been typechecked, we check that all recursive toplevel definitions and let-bindings terminate.
+//! We use the size-change principle: Every recursive call is abstracted to a call matrix which relates the arguments
+//! of the call to the parameters of the caller. A variable bound by a pattern is strictly smaller than the scrutinee
+//! that was matched on. A module is accepted if every recursive cycle strictly decreases some argument.
+//! This also covers mutual recursion between the definitions of a module.
+//!
+//! Calls in the body of a local comatch are only evaluated when the comatch is observed, so they are exempt
+//! from the termination check as long as they are guarded, i.e. their result is not observed in turn.
+//! Codefinitions are checked for productivity in the `productivity` module in the same way.
+//!
+//! Declarations annotated with `#[non_terminating]` are exempt from these checks.
+
+mod productivity;
+mod size_change;
+
+use ast::ctx::values::Binder;
+use ast::ctx::{BindContext, GenericCtx};
+use ast::*;
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+
+use crate::result::{TcResult, TypeError};
+
+pub use productivity::check_productivity;
+use size_change::{find_non_decreasing_loop, CallEdge, CallMatrix, Origin, Relation};
+
+/// Check that all recursive definitions and let-bindings of the module terminate
+pub fn check_termination(module: &Module) -> TcResult {
+    let nodes: Vec<Node> = module.decls.iter().filter_map(Node::from_decl).collect();
+
+    let mut calls = Vec::new();
+    for (caller, node) in nodes.iter().enumerate() {
+        let mut collector = CallCollector {
+            module,
+            nodes: &nodes,
+            caller,
+            calls: &mut calls,
+            matched: vec![],
+            guard: Guard::TopLevel,
+        };
+        collector.collect_node(node);
+    }
+
+    let Some(CallEdge { origin, .. }) = find_non_decreasing_loop(calls) else {
+        return Ok(());
+    };
+    let name = nodes[origin.callee].name.id.clone();
+    let span = origin.span.to_miette();
+    if origin.unguarded {
+        Err(TypeError::Unguarded { name, span }.into())
+    } else {
+        Err(TypeError::NonTerminating { name, span }.into())
+    }
+}
+
+/// A declaration that takes part in the termination check
+struct Node<'a> {
+    name: &'a IdBind,
+    params: &'a Telescope,
+    body: NodeBody<'a>,
+}
+
+enum NodeBody<'a> {
+    /// The cases of a definition; the self parameter is the last parameter of the node
+    Def(&'a [Case]),
+    /// The body of a let-binding
+    Let(&'a Exp),
+}
+
+impl<'a> Node<'a> {
+    fn from_decl(decl: &'a Decl) -> Option<Self> {
+        match decl {
+            Decl::Def(Def { name, attr, params, cases, .. }) if is_checked(attr) => {
+                Some(Node { name, params, body: NodeBody::Def(cases) })
+            }
+            Decl::Let(Let { name, attr, params, body, .. }) if is_checked(attr) => {
+                Some(Node { name, params, body: NodeBody::Let(body) })
+            }
+            _ => None,
+        }
+    }
+
+    /// The number of parameters, including the self parameter of definitions
+    fn arity(&self) -> usize {
+        match self.body {
+            NodeBody::Def(_) => self.params.len() + 1,
+            NodeBody::Let(_) => self.params.len(),
+        }
+    }
+}
+
+fn is_checked(attr: &Attributes) -> bool {
+    !attr.attrs.contains(&Attribute::NonTerminating)
+}
+
+/// What is known about the size of a bound variable relative to the parameters of the caller
+#[derive(Debug, Clone, Copy)]
+enum Size {
+    /// The variable is (at most as large as) the parameter with the given position
+    Param(usize),
+    /// The variable is strictly smaller than the parameter with the given position
+    Smaller(usize),
+    Unknown,
+}
+
+impl Shift for Size {
+    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+}
+
+/// A pattern that was matched on a scrutinee in the surrounding scope
+struct Matched {
+    ctor: String,
+    /// The level of the telescope which binds the pattern variables
+    fst: usize,
+    arity: usize,
+    /// The size of the scrutinee
+    size: Size,
+}
+
+impl Matched {
+    /// Whether `exp` reconstructs the matched pattern from the pattern variables
+    fn is_reconstructed_by(&self, ctx: &GenericCtx<Size>, exp: &Exp) -> bool {
+        let Exp::Call(Call { kind: CallKind::Constructor, name, args, .. }) = exp else {
+            return false;
+        };
+        name.id == self.ctor
+            && args.len() == self.arity
+            && args.args.iter().enumerate().all(|(snd, arg)| match *arg.exp() {
+                Exp::Variable(Variable { idx, .. }) => {
+                    ctx.idx_to_lvl(idx) == Lvl { fst: self.fst, snd }
+                }
+                _ => false,
+            })
+    }
+}
+
+/// Whether a call is guarded by a surrounding local comatch
+///
+/// Calls in the body of a cocase are only evaluated when the comatch is observed.
+/// They are therefore not subject to the termination check, unless their result is itself observed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Guard {
+    /// Not under a local comatch
+    TopLevel,
+    /// Under a local comatch
+    Guarded,
+    /// Under a local comatch, but the result is observed by a destructor, definition or match
+    Observed,
+}
+
+struct CallCollector<'a, 'b> {
+    module: &'a Module,
+    nodes: &'a [Node<'a>],
+    caller: usize,
+    calls: &'b mut Vec<CallEdge>,
+    matched: Vec<Matched>,
+    guard: Guard,
+}
+
+impl CallCollector<'_, '_> {
+    fn collect_node(&mut self, node: &Node) {
+        let mut ctx: GenericCtx<Size> = GenericCtx::empty();
+        let params = node
+            .params
+            .params
+            .iter()
+            .enumerate()
+            .map(|(i, param)| Binder { name: param.name.clone(), content: Size::Param(i) });
+        ctx.bind_iter(params, |ctx| match node.body {
+            NodeBody::Def(cases) => {
+                let self_size = Size::Param(node.params.len());
+                for case in cases {
+                    self.collect_case(ctx, case, self_size)
+                }
+            }
+            NodeBody::Let(body) => self.collect_exp(ctx, body),
+        })
+    }
+
+    /// Collect the calls in a case whose pattern variables are bound by matching on a scrutinee of size `on_size`
+    fn collect_case(&mut self, ctx: &mut GenericCtx<Size>, case: &Case, on_size: Size) {
+        let size = match on_size {
+            Size::Param(i) | Size::Smaller(i) => Size::Smaller(i),
+            Size::Unknown => Size::Unknown,
+        };
+        let binders = case
+            .pattern
+            .params
+            .params
+            .iter()
+            .map(|param| Binder { name: param.name.clone(), content: size });
+        ctx.bind_iter(binders, |ctx| {
+            // Within the body, the matched pattern is known to be the scrutinee itself.
+            let matched = Matched {
+                ctor: case.pattern.name.id.clone(),
+                fst: ctx.len() - 1,
+                arity: case.pattern.params.len(),
+                size: on_size,
+            };
+            self.matched.push(matched);
+            if let Some(body) = &case.body {
+                self.collect_exp(ctx, body)
+            }
+            self.matched.pop();
+        })
+    }
+
+    fn collect_args(&mut self, ctx: &mut GenericCtx<Size>, args: &Args) {
+        for arg in args.args.iter().filter(|arg| !arg.is_inserted_implicit()) {
+            self.collect_exp(ctx, &arg.exp())
+        }
+    }
+
+    fn collect_exp(&mut self, ctx: &mut GenericCtx<Size>, exp: &Exp) {
+        match exp {
+            Exp::Variable(_)
+            | Exp::TypeUniv(_)
+            | Exp::LevelUniv(_)
+            | Exp::Level(_)
+            | Exp::Hole(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) => self.collect_args(ctx, args),
+            Exp::Call(Call { span, kind, name, args, .. }) => {
+                if *kind == CallKind::LetBound {
+                    self.record_call(ctx, *span, name, args, None);
+                }
+                self.collect_args(ctx, args);
+            }
+            Exp::DotCall(DotCall { span, kind, exp, name, args, .. }) => {
+                if *kind == DotCallKind::Definition {
+                    self.record_call(ctx, *span, name, args, Some(exp));
+                }
+                self.collect_observed(ctx, exp);
+                self.collect_args(ctx, args);
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                self.collect_exp(ctx, exp);
+                self.collect_exp(ctx, typ);
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                self.collect_observed(ctx, on_exp);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    let binder = Binder { name: param.name.clone(), content: Size::Unknown };
+                    ctx.bind_single(binder, |ctx| self.collect_exp(ctx, ret_typ));
+                }
+                let on_size = self.size_of(ctx, on_exp);
+                for case in cases {
+                    self.collect_case(ctx, case, on_size);
+                }
+            }
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                if let Some(typ) = typ {
+                    self.collect_exp(ctx, typ);
+                }
+                self.collect_exp(ctx, bound);
+                // The bound variable has the same size as the bound expression.
+                let binder = Binder { name: name.clone(), content: self.size_of(ctx, bound) };
+                ctx.bind_single(binder, |ctx| self.collect_exp(ctx, body));
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => {
+                let guard = std::mem::replace(&mut self.guard, Guard::Guarded);
+                for case in cases {
+                    self.collect_case(ctx, case, Size::Unknown);
+                }
+                self.guard = guard;
+            }
+        }
+    }
+
+    /// Collect the calls in an expression whose result is observed
+    fn collect_observed(&mut self, ctx: &mut GenericCtx<Size>, exp: &Exp) {
+        let guard = self.guard;
+        if guard == Guard::Guarded {
+            self.guard = Guard::Observed;
+        }
+        self.collect_exp(ctx, exp);
+        self.guard = guard;
+    }
+
+    /// Record a call to `name` if it refers to a node of the call graph
+    fn record_call(
+        &mut self,
+        ctx: &GenericCtx<Size>,
+        span: Option<Span>,
+        name: &IdBound,
+        args: &Args,
+        self_arg: Option<&Exp>,
+    ) {
+        if name.uri != self.module.uri || self.guard == Guard::Guarded {
+            return;
+        }
+        let Some(callee) = self.nodes.iter().position(|node| node.name.id == name.id) else {
+            return;
+        };
+        let arity = self.nodes[self.caller].arity();
+        let rows = args
+            .args
+            .iter()
+            .map(|arg| self.relation_row(ctx, &arg.exp(), arity))
+            .chain(self_arg.map(|exp| self.relation_row(ctx, exp, arity)))
+            .collect();
+        self.calls.push(CallEdge {
+            caller: self.caller,
+            callee,
+            matrix: CallMatrix { rows, cols: arity },
+            origin: Origin { callee, span, unguarded: self.guard == Guard::Observed },
+        });
+    }
+
+    /// Determine the size of an expression relative to the parameters of the caller
+    fn size_of(&self, ctx: &GenericCtx<Size>, exp: &Exp) -> Size {
+        match exp {
+            Exp::Variable(Variable { idx, .. }) => ctx.lookup(*idx).content,
+            Exp::Anno(Anno { exp, .. }) => self.size_of(ctx, exp),
+            _ => self
+                .matched
+                .iter()
+                .rev()
+                .find(|matched| matched.is_reconstructed_by(ctx, exp))
+                .map(|matched| matched.size)
+                .unwrap_or(Size::Unknown),
+        }
+    }
+
+    /// Relate an argument to each of the `arity` parameters of the caller
+    fn relation_row(&self, ctx: &GenericCtx<Size>, exp: &Exp, arity: usize) -> Vec<Relation> {
+        let mut row = vec![Relation::Unknown; arity];
+        match self.size_of(ctx, exp) {
+            Size::Param(i) => row[i] = Relation::Le,
+            Size::Smaller(i) => row[i] = Relation::Lt,
+            Size::Unknown => {}
+        }
+        row
+    }
+}
@@ -0,0 +1,319 @@
+//! Productivity checking for codefinitions
+//!
+//! A corecursive call to a codefinition is guarded if its result is returned as is: it is then only
+//! unfolded when its consumer observes it. If instead the result of the call is observed within a cocase,
+//! then evaluating this cocase evaluates the observed cocase of the callee immediately. We record such
+//! observed calls between the cocases of all codefinitions of a module and reject every cycle among them.
+//! Calls in the body of a local comatch are only evaluated once the comatch is observed and are not recorded.
+//! The body of a toplevel let-bound definition is evaluated as soon as it is called, so calls to it are
+//! inlined: its arguments are observed in the same way as its parameters are observed in its body.
+
+use ast::ctx::values::Binder;
+use ast::ctx::{BindContext, GenericCtx};
+use ast::*;
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+
+use crate::result::{TcResult, TypeError};
+
+use super::is_checked;
+
+/// Check that all codefinitions of the module are productive
+pub fn check_productivity(module: &Module) -> TcResult {
+    let codefs: Vec<&Codef> = module
+        .decls
+        .iter()
+        .filter_map(|decl| match decl {
+            Decl::Codef(codef) if is_checked(&codef.attr) => Some(codef),
+            _ => None,
+        })
+        .collect();
+
+    // Every cocase of a codefinition is a node of the call graph.
+    let cocases: Vec<(usize, &Case)> = codefs
+        .iter()
+        .enumerate()
+        .flat_map(|(i, codef)| codef.cases.iter().map(move |case| (i, case)))
+        .collect();
+
+    let mut calls = Vec::new();
+    for (caller, (i, case)) in cocases.iter().enumerate() {
+        let mut collector = ObservedCalls {
+            module,
+            codefs: &codefs,
+            cocases: &cocases,
+            caller,
+            calls: &mut calls,
+            frames: Vec::new(),
+        };
+        let mut ctx = GenericCtx::empty();
+        ctx.bind_iter(
+            local_binders(codefs[*i].params.params.iter().map(|param| &param.name)),
+            |ctx| collector.collect_cases(ctx, std::slice::from_ref(case), Observation::None),
+        );
+    }
+
+    // A call is unguarded if it lies on a cycle of observed calls.
+    let mut unguarded: Vec<&ObservedCall> =
+        calls.iter().filter(|call| reaches(&calls, call.callee, call.caller)).collect();
+    unguarded.sort_by_key(|call| call.span);
+    match unguarded.first() {
+        Some(call) => Err(TypeError::Unguarded {
+            name: codefs[cocases[call.callee].0].name.id.clone(),
+            span: call.span.to_miette(),
+        }
+        .into()),
+        None => Ok(()),
+    }
+}
+
+/// How the result of an expression is observed
+#[derive(Debug, Clone, Copy)]
+enum Observation<'a> {
+    /// The result is not observed
+    None,
+    /// The result is observed by the destructor with the given name
+    Dtor(&'a str),
+    /// The result is observed in an unknown way
+    Unknown,
+}
+
+impl Observation<'_> {
+    /// The observation of a subexpression whose result is not returned as is
+    fn nested(self) -> Self {
+        match self {
+            Observation::None => Observation::None,
+            _ => Observation::Unknown,
+        }
+    }
+}
+
+/// A call from one cocase to another cocase whose result is observed
+struct ObservedCall {
+    caller: usize,
+    callee: usize,
+    span: Option<Span>,
+}
+
+/// Whether `to` is reachable from `from` via observed calls
+fn reaches(calls: &[ObservedCall], from: usize, to: usize) -> bool {
+    let mut visited = HashSet::default();
+    let mut stack = vec![from];
+    while let Some(node) = stack.pop() {
+        if node == to {
+            return true;
+        }
+        if visited.insert(node) {
+            stack.extend(calls.iter().filter(|call| call.caller == node).map(|call| call.callee));
+        }
+    }
+    false
+}
+
+struct ObservedCalls<'a, 'b> {
+    module: &'a Module,
+    codefs: &'a [&'a Codef],
+    cocases: &'a [(usize, &'a Case)],
+    caller: usize,
+    calls: &'b mut Vec<ObservedCall>,
+    /// The let-bound definitions whose bodies are currently walked, innermost last
+    frames: Vec<Frame<'a>>,
+}
+
+/// What a variable in the walked expression is bound to
+#[derive(Debug, Clone, Copy)]
+enum Bound<'a> {
+    /// A binder of the walked expression itself
+    Local,
+    /// A parameter of a let-bound definition, bound to an argument in the context of its call
+    Arg(&'a Exp),
+    /// A local let-binding, bound to an expression in the context of the binding
+    Let(&'a Exp),
+}
+
+impl Shift for Bound<'_> {
+    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+}
+
+/// A call to a let-bound definition whose body is walked
+struct Frame<'a> {
+    def: &'a Let,
+    /// The context of the call, in which its arguments are walked
+    ctx: GenericCtx<Bound<'a>>,
+}
+
+/// Binders for parameters which are bound within the walked expression
+fn local_binders<'a>(
+    names: impl Iterator<Item = &'a VarBind>,
+) -> impl Iterator<Item = Binder<Bound<'a>>> {
+    names.map(|name| Binder { name: name.clone(), content: Bound::Local })
+}
+
+impl<'a> ObservedCalls<'a, '_> {
+    fn collect_args(
+        &mut self,
+        ctx: &mut GenericCtx<Bound<'a>>,
+        args: &'a Args,
+        observation: Observation<'a>,
+    ) {
+        for arg in args.args.iter() {
+            match arg {
+                Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => {
+                    self.collect_exp(ctx, arg, observation)
+                }
+                Arg::InsertedImplicitArg { .. } => {}
+            }
+        }
+    }
+
+    fn collect_exp(
+        &mut self,
+        ctx: &mut GenericCtx<Bound<'a>>,
+        exp: &'a Exp,
+        observation: Observation<'a>,
+    ) {
+        match exp {
+            Exp::TypeUniv(_) | Exp::LevelUniv(_) | Exp::Level(_) | Exp::Hole(_) => {}
+            Exp::TypCtor(TypCtor { args, .. }) => {
+                self.collect_args(ctx, args, observation.nested())
+            }
+            Exp::Variable(Variable { idx, .. }) => self.collect_var(ctx, *idx, observation),
+            Exp::Call(Call { span, kind, name, args, .. }) => match kind {
+                CallKind::Codefinition => {
+                    if name.uri == self.module.uri {
+                        self.record_call(*span, name, observation);
+                    }
+                    self.collect_args(ctx, args, observation.nested());
+                }
+                CallKind::Constructor => self.collect_args(ctx, args, observation.nested()),
+                // The body of a let-bound definition is evaluated immediately and may observe its
+                // arguments, so we walk it with the arguments in place of its parameters.
+                CallKind::LetBound => self.collect_let(ctx, name, args, observation),
+            },
+            Exp::DotCall(DotCall { exp, name, args, .. }) => {
+                self.collect_exp(ctx, exp, Observation::Dtor(&name.id));
+                self.collect_args(ctx, args, observation.nested());
+            }
+            Exp::Anno(Anno { exp, typ, .. }) => {
+                self.collect_exp(ctx, exp, observation);
+                self.collect_exp(ctx, typ, observation.nested());
+            }
+            Exp::LocalMatch(LocalMatch { on_exp, motive, cases, .. }) => {
+                self.collect_exp(ctx, on_exp, Observation::Unknown);
+                if let Some(Motive { param, ret_typ, .. }) = motive {
+                    let binder = Binder { name: param.name.clone(), content: Bound::Local };
+                    ctx.bind_single(binder, |ctx| {
+                        self.collect_exp(ctx, ret_typ, observation.nested())
+                    });
+                }
+                self.collect_cases(ctx, cases, observation);
+            }
+            // The bound expression may be observed in an arbitrary way by the body.
+            // The bound expression is observed in the same way as the variable in the body.
+            Exp::LocalLet(LocalLet { name, typ, bound, body, .. }) => {
+                if let Some(typ) = typ {
+                    self.collect_exp(ctx, typ, observation.nested());
+                }
+                self.collect_exp(ctx, bound, Observation::None);
+                let binder = Binder { name: name.clone(), content: Bound::Let(bound) };
+                ctx.bind_single(binder, |ctx| self.collect_exp(ctx, body, observation));
+            }
+            // The cocases of a local comatch are only evaluated once the comatch is observed.
+            Exp::LocalComatch(LocalComatch { cases, .. }) => match observation {
+                Observation::None => {}
+                _ => self.collect_cases(ctx, cases, Observation::Unknown),
+            },
+        }
+    }
+
+    /// Collect the calls of the argument bound to the variable `idx` if it is a parameter of the
+    /// innermost let-bound definition
+    fn collect_var(
+        &mut self,
+        ctx: &mut GenericCtx<Bound<'a>>,
+        idx: Idx,
+        observation: Observation<'a>,
+    ) {
+        match ctx.lookup(idx).content {
+            Bound::Local => {}
+            Bound::Arg(arg) => {
+                // The argument is an expression of the context of the call.
+                let mut frame =
+                    self.frames.pop().expect("Argument bound outside of a let-bound definition");
+                self.collect_exp(&mut frame.ctx, arg, observation);
+                self.frames.push(frame);
+            }
+            Bound::Let(bound) => {
+                // The bound expression is an expression of the context of the binding.
+                let lvl = ctx.idx_to_lvl(idx);
+                let mut let_ctx = GenericCtx::from(ctx.bound[..lvl.fst].to_vec());
+                self.collect_exp(&mut let_ctx, bound, observation);
+            }
+        }
+    }
+
+    fn collect_let(
+        &mut self,
+        ctx: &mut GenericCtx<Bound<'a>>,
+        name: &IdBound,
+        args: &'a Args,
+        observation: Observation<'a>,
+    ) {
+        let def = self.module.decls.iter().find_map(|decl| match decl {
+            Decl::Let(def) if name.uri == self.module.uri && def.name.id == name.id => Some(def),
+            _ => None,
+        });
+        let recursive = |def: &Let| self.frames.iter().any(|frame| std::ptr::eq(frame.def, def));
+        let Some(def) = def.filter(|def| !recursive(def)) else {
+            // The body is unknown, so the arguments may be observed in an arbitrary way.
+            self.collect_args(ctx, args, Observation::Unknown);
+            return;
+        };
+        let binders = def.params.params.iter().zip(args.args.iter()).map(|(param, arg)| {
+            let content = match arg {
+                Arg::UnnamedArg { arg, .. } | Arg::NamedArg { arg, .. } => Bound::Arg(arg),
+                Arg::InsertedImplicitArg { .. } => Bound::Local,
+            };
+            Binder { name: param.name.clone(), content }
+        });
+        self.frames.push(Frame { def, ctx: std::mem::replace(ctx, GenericCtx::empty()) });
+        GenericCtx::empty()
+            .bind_iter(binders, |def_ctx| self.collect_exp(def_ctx, &def.body, observation));
+        let frame = self.frames.pop().expect("Frame of the let-bound definition");
+        *ctx = frame.ctx;
+    }
+
+    fn collect_cases(
+        &mut self,
+        ctx: &mut GenericCtx<Bound<'a>>,
+        cases: &'a [Case],
+        observation: Observation<'a>,
+    ) {
+        for case in cases {
+            if let Some(body) = &case.body {
+                ctx.bind_iter(
+                    local_binders(case.pattern.params.params.iter().map(|param| &param.name)),
+                    |ctx| self.collect_exp(ctx, body, observation),
+                );
+            }
+        }
+    }
+
+    /// Record a call to the codefinition `name` if its result is observed
+    fn record_call(&mut self, span: Option<Span>, name: &IdBound, observation: Observation) {
+        let Some(codef) = self.codefs.iter().position(|codef| codef.name.id == name.id) else {
+            return;
+        };
+        let callees = self.cocases.iter().enumerate().filter(|(_, (i, case))| {
+            *i == codef
+                && match observation {
+                    Observation::None => false,
+                    Observation::Dtor(dtor) => case.pattern.name.id == dtor,
+                    Observation::Unknown => true,
+                }
+        });
+        for (callee, _) in callees {
+            self.calls.push(ObservedCall { caller: self.caller, callee, span });
+        }
+    }
+}
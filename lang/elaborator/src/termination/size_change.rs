@@ -0,0 +1,205 @@
+//! Size-change graphs and their closure
+//!
+//! Every recursive call is abstracted to a call matrix which records, for every parameter of the callee,
+//! how the argument passed for it relates to the parameters of the caller.
+//! Composing the matrices along all paths of the call graph yields the size-change closure.
+//! The program terminates if every idempotent self-loop in the closure strictly decreases some parameter.
+
+use ast::{HashMap, HashSet};
+use miette_util::codespan::Span;
+
+/// How the size of an argument relates to the size of a parameter of the caller
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Relation {
+    /// Nothing is known about the relation
+    Unknown,
+    /// The argument is at most as large as the parameter
+    Le,
+    /// The argument is strictly smaller than the parameter
+    Lt,
+}
+
+impl Relation {
+    /// Sequential composition of two relations
+    fn compose(self, other: Relation) -> Relation {
+        match (self, other) {
+            (Relation::Unknown, _) | (_, Relation::Unknown) => Relation::Unknown,
+            (Relation::Le, Relation::Le) => Relation::Le,
+            _ => Relation::Lt,
+        }
+    }
+}
+
+/// A call matrix with one row per parameter of the callee and one column per parameter of the caller
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CallMatrix {
+    pub rows: Vec<Vec<Relation>>,
+    /// The number of columns, i.e. the number of parameters of the caller
+    ///
+    /// It cannot be determined from the rows if the callee has no parameters.
+    pub cols: usize,
+}
+
+impl CallMatrix {
+    /// Compose `self` (from `f` to `g`) with `other` (from `g` to `h`) to a matrix from `f` to `h`
+    fn compose(&self, other: &CallMatrix) -> CallMatrix {
+        let cols = self.cols;
+        let rows = other
+            .rows
+            .iter()
+            .map(|row| {
+                (0..cols)
+                    .map(|i| {
+                        row.iter()
+                            .zip(self.rows.iter())
+                            .map(|(rel, self_row)| rel.compose(self_row[i]))
+                            .max()
+                            .unwrap_or(Relation::Unknown)
+                    })
+                    .collect()
+            })
+            .collect();
+        CallMatrix { rows, cols }
+    }
+
+    /// Whether some parameter is passed on to itself in a strictly smaller form
+    fn is_decreasing(&self) -> bool {
+        self.rows.iter().enumerate().any(|(i, row)| row[i] == Relation::Lt)
+    }
+}
+
+/// The first call on the path that a call edge abstracts
+#[derive(Debug, Clone, Copy)]
+pub struct Origin {
+    pub callee: usize,
+    pub span: Option<Span>,
+    /// Whether the call is observed under a local comatch
+    pub unguarded: bool,
+}
+
+/// A call from one node of the call graph to another
+#[derive(Debug, Clone)]
+pub struct CallEdge {
+    pub caller: usize,
+    pub callee: usize,
+    pub matrix: CallMatrix,
+    pub origin: Origin,
+}
+
+/// Compute the size-change closure of the given calls and return the first non-decreasing loop, if any
+pub fn find_non_decreasing_loop(calls: Vec<CallEdge>) -> Option<CallEdge> {
+    let mut seen: HashSet<(usize, usize, CallMatrix)> = HashSet::default();
+    let mut by_caller: HashMap<usize, Vec<CallEdge>> = HashMap::default();
+    let mut worklist = Vec::new();
+
+    for call in calls {
+        if seen.insert((call.caller, call.callee, call.matrix.clone())) {
+            worklist.push(call);
+        }
+    }
+    let direct = worklist.clone();
+
+    while let Some(call) = worklist.pop() {
+        by_caller.entry(call.caller).or_default().push(call.clone());
+        // Extend the path by every direct call leaving its target.
+        for next in direct.iter().filter(|next| next.caller == call.callee) {
+            let matrix = call.matrix.compose(&next.matrix);
+            if seen.insert((call.caller, next.callee, matrix.clone())) {
+                worklist.push(CallEdge {
+                    caller: call.caller,
+                    callee: next.callee,
+                    matrix,
+                    origin: call.origin,
+                });
+            }
+        }
+    }
+
+    let mut loops: Vec<CallEdge> = by_caller
+        .into_values()
+        .flatten()
+        .filter(|call| call.caller == call.callee)
+        .filter(|call| call.matrix.compose(&call.matrix) == call.matrix)
+        .filter(|call| !call.matrix.is_decreasing())
+        .collect();
+    // Report the error deterministically at the earliest source location.
+    loops.sort_by_key(|call| call.origin.span);
+    loops.into_iter().next()
+}
+
+#[cfg(test)]
+mod size_change_tests {
+    use super::*;
+
+    fn call(caller: usize, callee: usize, rows: Vec<Vec<Relation>>) -> CallEdge {
+        let cols = rows.first().map(|row| row.len()).unwrap_or_default();
+        call_with_cols(caller, callee, rows, cols)
+    }
+
+    fn call_with_cols(
+        caller: usize,
+        callee: usize,
+        rows: Vec<Vec<Relation>>,
+        cols: usize,
+    ) -> CallEdge {
+        let origin = Origin { callee, span: None, unguarded: false };
+        CallEdge { caller, callee, matrix: CallMatrix { rows, cols }, origin }
+    }
+
+    #[test]
+    fn structural_recursion() {
+        let calls = vec![call(
+            0,
+            0,
+            vec![vec![Relation::Lt, Relation::Unknown], vec![Relation::Unknown, Relation::Le]],
+        )];
+        assert!(find_non_decreasing_loop(calls).is_none());
+    }
+
+    #[test]
+    fn non_decreasing_recursion() {
+        let calls = vec![call(0, 0, vec![vec![Relation::Le]])];
+        assert!(find_non_decreasing_loop(calls).is_some());
+    }
+
+    #[test]
+    fn lexicographic_recursion() {
+        // f(x, y) calls f(x - 1, z) and f(x, y - 1)
+        let calls = vec![
+            call(
+                0,
+                0,
+                vec![
+                    vec![Relation::Lt, Relation::Unknown],
+                    vec![Relation::Unknown, Relation::Unknown],
+                ],
+            ),
+            call(
+                0,
+                0,
+                vec![vec![Relation::Le, Relation::Unknown], vec![Relation::Unknown, Relation::Lt]],
+            ),
+        ];
+        assert!(find_non_decreasing_loop(calls).is_none());
+    }
+
+    #[test]
+    fn mutual_recursion() {
+        // f(x) calls g(x) and g(x) calls f(x - 1)
+        let calls =
+            vec![call(0, 1, vec![vec![Relation::Le]]), call(1, 0, vec![vec![Relation::Lt]])];
+        assert!(find_non_decreasing_loop(calls).is_none());
+
+        // f(x) calls g(x) and g(x) calls f(x)
+        let calls =
+            vec![call(0, 1, vec![vec![Relation::Le]]), call(1, 0, vec![vec![Relation::Le]])];
+        assert!(find_non_decreasing_loop(calls).is_some());
+    }
+
+    #[test]
+    fn recursion_through_nullary_callee() {
+        // f(x) calls g and g calls f(Z)
+        let calls = vec![call_with_cols(0, 1, vec![], 1), call_with_cols(1, 0, vec![vec![]], 0)];
+        assert!(find_non_decreasing_loop(calls).is_some());
+    }
+}
@@ -6,6 +6,7 @@ use std::rc::Rc;
 
 use crate::normalizer::env::{Env, ToEnv};
 use crate::normalizer::normalize::Normalize;
+use crate::normalizer::thunk::Thunk;
 use ast::ctx::values::{Binder, Binding, TypeCtx};
 use ast::ctx::{BindContext, LevelCtx};
 use ast::*;
@@ -97,7 +98,19 @@ impl BindContext for Ctx {
 
 impl ToEnv for Ctx {
     fn env(&self) -> Env {
-        self.vars.env()
+        let mut env = self.vars.env();
+        // Let-bound variables are unfolded to their values.
+        // The values are evaluated when the variables are first used, so that errors are reported
+        // by the evaluation which uses them. Since a bound value can only refer to variables bound
+        // before it, we can bind the values in order of their De Bruijn levels.
+        for (fst, stack) in self.vars.bound.iter().enumerate() {
+            for (snd, Binder { content: binding, .. }) in stack.iter().enumerate() {
+                if let Some(ctx::values::BoundValue::LetBinding { val }) = &binding.val {
+                    env.set(Lvl { fst, snd }, Thunk::delay(val.clone(), Rc::new(env.clone())));
+                }
+            }
+        }
+        env
     }
 }
 
@@ -8,12 +8,13 @@ use crate::{
     result::TcResult,
     typechecker::{
         ctx::Ctx,
-        erasure,
         exprs::{CheckInfer, InferTelescope},
         TypeError,
     },
 };
 
+use super::positivity::check_codata_positivity;
+use super::universes::check_dtor_universes;
 use super::CheckToplevel;
 
 /// Infer a codata declaration
@@ -23,6 +24,8 @@ impl CheckToplevel for Codata {
 
         let Codata { span, doc, name, attr, typ, dtors } = self;
 
+        check_codata_positivity(self, &ctx.module, &ctx.type_info_table)?;
+
         let typ_out = typ.infer_telescope(ctx, |_, params_out| Ok(params_out))?;
 
         let dtors =
@@ -55,11 +58,13 @@ fn check_dtor_wf(codata_name: &IdBind, dtor: &Dtor, ctx: &mut Ctx) -> TcResult<D
         .into());
     }
 
-    params.infer_telescope(ctx, |ctx, mut params_out| {
+    params.infer_telescope(ctx, |ctx, params_out| {
         self_param.infer_telescope(ctx, |ctx, self_param_out| {
             let ret_typ_out = ret_typ.infer(ctx)?;
 
-            erasure::mark_erased_params(&mut params_out);
+            if ctx.module.is_predicative() {
+                check_dtor_universes(ctx, &params_out, &ret_typ_out, codata_name)?;
+            }
 
             Ok(Dtor {
                 span: *span,
@@ -8,8 +8,7 @@ use crate::normalizer::env::ToEnv;
 use crate::normalizer::normalize::Normalize;
 
 use crate::result::TcResult;
-use crate::typechecker::erasure;
-use crate::typechecker::exprs::local_comatch::WithExpectedType;
+use crate::typechecker::exprs::local_comatch::{expand_catch_all, WithExpectedType};
 use crate::typechecker::{
     ctx::Ctx,
     exprs::{CheckInfer, InferTelescope},
@@ -23,24 +22,31 @@ impl CheckToplevel for Codef {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of codefinition: {}", self.name);
 
-        let Codef { span, doc, name, attr, params, typ, cases } = self;
+        let Codef { span, doc, name, attr, params, typ, cases, catch_all } = self;
 
-        let label = IdBound { span: name.span, id: name.id.clone(), uri: ctx.module.uri.clone() };
+        let label = IdBound {
+            span: name.span,
+            id: name.id.clone(),
+            uri: ctx.module.uri.clone(),
+            qualifier: None,
+        };
 
-        params.infer_telescope(ctx, |ctx, mut params_out| {
-            let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
+        params.infer_telescope(ctx, |ctx, params_out| {
+            // A type constructor is a type of the universe it lives in, which is inferred.
+            let typ_out = typ.infer(ctx)?;
             let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+            let expected_type = typ_nf.expect_typ_app()?;
+            let cases = expand_catch_all(ctx, cases, catch_all.as_ref(), &expected_type)?;
             let with_expected_type = WithExpectedType {
-                cases,
+                cases: &cases,
                 signature: Some((label, params)),
-                expected_type: typ_nf.expect_typ_app()?,
+                expected_type,
+                span: None,
             };
 
             with_expected_type.check_exhaustiveness(ctx)?;
             let cases = with_expected_type.check_type(ctx)?;
 
-            erasure::mark_erased_params(&mut params_out);
-
             Ok(Codef {
                 span: *span,
                 doc: doc.clone(),
@@ -49,6 +55,7 @@ impl CheckToplevel for Codef {
                 params: params_out,
                 typ: typ_out,
                 cases,
+                catch_all: None,
             })
         })
     }
@@ -8,12 +8,13 @@ use crate::{
     result::TcResult,
     typechecker::{
         ctx::Ctx,
-        erasure,
         exprs::{CheckInfer, InferTelescope},
         TypeError,
     },
 };
 
+use super::positivity::check_data_positivity;
+use super::universes::check_ctor_universes;
 use super::CheckToplevel;
 
 /// Check a data declaration
@@ -23,6 +24,8 @@ impl CheckToplevel for Data {
 
         let Data { span, doc, name, attr, typ, ctors } = self;
 
+        check_data_positivity(self, &ctx.module, &ctx.type_info_table)?;
+
         let typ_out = typ.infer_telescope(ctx, |_, params_out| Ok(params_out))?;
 
         let ctors =
@@ -55,10 +58,12 @@ fn check_ctor_wf(data_type_name: &IdBind, ctor: &Ctor, ctx: &mut Ctx) -> TcResul
         .into());
     }
 
-    params.infer_telescope(ctx, |ctx, mut params_out| {
+    params.infer_telescope(ctx, |ctx, params_out| {
         let typ_out = typ.infer(ctx)?;
 
-        erasure::mark_erased_params(&mut params_out);
+        if ctx.module.is_predicative() {
+            check_ctor_universes(ctx, &params_out, &typ_out)?;
+        }
 
         Ok(Ctor {
             span: *span,
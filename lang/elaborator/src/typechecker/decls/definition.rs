@@ -7,8 +7,7 @@ use super::CheckToplevel;
 use crate::normalizer::env::ToEnv;
 use crate::normalizer::normalize::Normalize;
 use crate::result::TcResult;
-use crate::typechecker::erasure;
-use crate::typechecker::exprs::local_match::WithScrutineeType;
+use crate::typechecker::exprs::local_match::{expand_catch_all, WithScrutineeType};
 use crate::typechecker::{
     ctx::Ctx,
     exprs::{CheckInfer, InferTelescope},
@@ -20,9 +19,9 @@ impl CheckToplevel for Def {
     fn check_wf(&self, ctx: &mut Ctx) -> TcResult<Self> {
         trace!("Checking well-formedness of definition: {}", self.name);
 
-        let Def { span, doc, name, attr, params, self_param, ret_typ, cases } = self;
+        let Def { span, doc, name, attr, params, self_param, ret_typ, cases, catch_all } = self;
 
-        params.infer_telescope(ctx, |ctx, mut params_out| {
+        params.infer_telescope(ctx, |ctx, params_out| {
             let self_param_nf = self_param.typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
 
             let (ret_typ_out, ret_typ_nf, self_param_out) =
@@ -32,16 +31,16 @@ impl CheckToplevel for Def {
                     Ok((ret_typ_out, ret_typ_nf, self_param_out))
                 })?;
 
+            let scrutinee_type = self_param_nf.expect_typ_app()?;
+            let cases = expand_catch_all(ctx, cases, catch_all.as_ref(), &scrutinee_type)?;
             let with_scrutinee_type = WithScrutineeType {
-                cases,
-                scrutinee_type: self_param_nf.expect_typ_app()?,
+                cases: &cases,
+                scrutinee_type,
                 scrutinee_name: self_param.name.clone(),
             };
             with_scrutinee_type.check_exhaustiveness(ctx)?;
             let cases = with_scrutinee_type.check_type(ctx, &ret_typ_nf)?;
 
-            erasure::mark_erased_params(&mut params_out);
-
             Ok(Def {
                 span: *span,
                 doc: doc.clone(),
@@ -51,6 +50,7 @@ impl CheckToplevel for Def {
                 self_param: self_param_out,
                 ret_typ: ret_typ_out,
                 cases,
+                catch_all: None,
             })
         })
     }
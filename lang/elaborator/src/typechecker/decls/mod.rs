@@ -6,65 +6,140 @@ mod datatype;
 mod definition;
 mod global_let;
 mod infix_declaration;
+mod positivity;
+mod universes;
 
+use ast::ctx::values::TypeCtx;
 use ast::*;
 use miette_util::ToMiette;
 use printer::Print;
 
 use crate::result::TcResult;
+use crate::termination::{check_productivity, check_termination};
 
+use super::erasure::check_erased_usage;
 use super::{ctx::Ctx, type_info_table::TypeInfoTable, TypeError};
 
 /// Check a module
 ///
 /// The caller of this function needs to resolve module dependencies, check all dependencies, and provide a info table with all symbols from these dependencies.
-pub fn check_with_lookup_table(prg: Rc<Module>, info_table: &TypeInfoTable) -> TcResult<Module> {
+///
+/// Checking continues after a declaration fails to typecheck: The error is recorded and the signature of the declaration is trusted
+/// while checking the remaining declarations. All errors of the module are returned in the order of the declarations.
+pub fn check_with_lookup_table(
+    prg: Rc<Module>,
+    info_table: &TypeInfoTable,
+) -> Result<Module, Vec<Box<TypeError>>> {
+    let (module, errors) = check_partially_with_lookup_table(prg, info_table);
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(module)
+}
+
+/// Check a module and return it together with all errors
+///
+/// Declarations which fail to typecheck are contained in the returned module as they were passed
+/// to the typechecker, so that the remaining declarations can be inspected by the language server.
+pub fn check_partially_with_lookup_table(
+    prg: Rc<Module>,
+    info_table: &TypeInfoTable,
+) -> (Module, Vec<Box<TypeError>>) {
     log::debug!("Checking module: {}", prg.uri);
 
     let mut ctx = Ctx::new(prg.meta_vars.clone(), info_table.clone(), prg.clone());
 
-    let mut decls =
-        prg.decls.iter().map(|decl| decl.check_wf(&mut ctx)).collect::<TcResult<Vec<_>>>()?;
+    let mut errors = vec![];
+    let mut failed = vec![];
+    let mut failed_meta_vars = vec![];
+    let mut decls = Vec::with_capacity(prg.decls.len());
+    for decl in prg.decls.iter() {
+        ctx.type_info_table.fuel().refill();
+        match decl.check_wf(&mut ctx) {
+            Ok(decl) => decls.push(decl),
+            Err(err) => {
+                failed_meta_vars.extend(err.failed_meta_var().map(str::to_owned));
+                errors.push(err);
+                failed.push(decl.span());
+                // The signature of the declaration is already contained in the info table, so the
+                // remaining declarations are checked against it.
+                decls.push(decl.clone());
+                ctx.vars = TypeCtx::empty();
+            }
+        }
+    }
 
-    decls
-        .zonk(&ctx.meta_vars)
-        .map_err(|err| TypeError::Impossible { message: err.to_string(), span: None })?;
+    if let Err(err) = decls.zonk(&ctx.meta_vars) {
+        errors.push(TypeError::Impossible { message: err.to_string(), span: None }.into());
+    }
 
-    check_metavars_solved(&ctx.meta_vars)?;
-    check_metavars_resolved(&ctx.meta_vars, &decls)?;
+    // Metavariables of declarations which failed to typecheck are usually unsolved as a consequence of the error.
+    // The same holds for metavariables which were already reported because unification failed to solve them.
+    let is_failed = |var: &MetaVar| {
+        let in_failed_decl = var.span.is_some_and(|span| {
+            failed.iter().flatten().any(|decl| decl.start <= span.start && span.end <= decl.end)
+        });
+        in_failed_decl || failed_meta_vars.contains(&var.print_to_string(None))
+    };
+    let meta_vars: HashMap<MetaVar, MetaVarState> = ctx
+        .meta_vars
+        .iter()
+        .filter(|(var, _)| !is_failed(var))
+        .map(|(var, state)| (*var, state.clone()))
+        .collect();
+    errors.extend(check_metavars_solved(&meta_vars).err().into_iter().flatten());
 
-    Ok(Module {
+    let module = Module {
         uri: prg.uri.clone(),
+        attr: prg.attr.clone(),
         use_decls: prg.use_decls.clone(),
         decls,
         meta_vars: ctx.meta_vars.clone(),
-    })
+    };
+    if !errors.is_empty() {
+        return (module, errors);
+    }
+
+    // The remaining checks require all declarations to be elaborated and stop at the first error.
+    let result = check_metavars_resolved(&ctx.meta_vars, &module.decls)
+        .and_then(|()| check_termination(&module))
+        .and_then(|()| check_productivity(&module))
+        .and_then(|()| check_erased_usage(&module, &ctx.type_info_table));
+    errors.extend(result.err());
+
+    (module, errors)
 }
 
 /// Check that there are no unresolved metavariables that remain after typechecking.
-pub fn check_metavars_solved(meta_vars: &HashMap<MetaVar, MetaVarState>) -> TcResult {
-    let mut unsolved: HashSet<MetaVar> = HashSet::default();
-    for (var, state) in meta_vars.iter() {
+///
+/// An error is returned for every unsolved metavariable, ordered by their position in the source.
+pub fn check_metavars_solved(
+    meta_vars: &HashMap<MetaVar, MetaVarState>,
+) -> Result<(), Vec<Box<TypeError>>> {
+    let mut unsolved: Vec<MetaVar> = meta_vars
+        .iter()
         // We only have to throw an error for unsolved metavars which were either
         // inserted or are holes `_` which must be solved
         // Unsolved metavariables that correspond to typed holes `?` do not lead
         // to an error.
-        if !state.is_solved() && var.must_be_solved() {
-            unsolved.insert(*var);
-        }
-    }
+        .filter(|(var, state)| !state.is_solved() && var.must_be_solved())
+        .map(|(var, _)| *var)
+        .collect();
+    unsolved.sort_by_key(|mv| (mv.span, mv.id));
 
-    // We are only throwing one error for the first unresolved metavariable in the hashset.
-    // Ideally we want to throw multiple errors here, but this functionality is
-    // not yet implemented.
-    if let Some(mv) = unsolved.iter().next() {
-        let err = TypeError::UnresolvedMeta {
-            span: mv.span.to_miette(),
-            meta_var: mv.print_to_string(None),
-        };
-        return Err(err.into());
+    if unsolved.is_empty() {
+        return Ok(());
     }
-    Ok(())
+    Err(unsolved
+        .into_iter()
+        .map(|mv| {
+            TypeError::UnresolvedMeta {
+                span: mv.span.to_miette(),
+                meta_var: mv.print_to_string(None),
+            }
+            .into()
+        })
+        .collect())
 }
 
 /// Check that there are no must-solve metavariables whose solution references
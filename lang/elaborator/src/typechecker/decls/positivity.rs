@@ -0,0 +1,334 @@
+//! Strict positivity checking for data and codata declarations
+//!
+//! A data type must only occur strictly positively in the parameter types of its constructors,
+//! and a codata type must only occur strictly positively in the return types of its destructors.
+//! An occurrence is strictly positive if it is not nested in the argument of another type constructor,
+//! or if that type constructor is itself strictly positive in the corresponding parameter.
+//! Type constructors are looked up in the `TypeInfoTable`, so this also sees through types from imported modules.
+//! Data types (respectively codata types) of the same module which mention each other are treated as a single recursive group.
+//!
+//! Declarations annotated with `#[no_positivity_check]` are exempt from this check.
+
+use ast::ctx::{BindContext, LevelCtx};
+use ast::*;
+use miette_util::ToMiette;
+use url::Url;
+
+use crate::result::TcResult;
+use crate::typechecker::type_info_table::TypeInfoTable;
+use crate::typechecker::TypeError;
+
+/// Check that a data type only occurs strictly positively in the parameter types of its constructors
+pub fn check_data_positivity(data: &Data, module: &Module, info_table: &TypeInfoTable) -> TcResult {
+    if data.attr.attrs.contains(&Attribute::NoPositivityCheck) {
+        return Ok(());
+    }
+    let group = recursive_group(&data.name, &module.uri, &data_signatures(module));
+    let target = Target::Types { uri: &module.uri, names: &group };
+    let mut checker = Positivity { info_table, visiting: vec![] };
+
+    for ctor in data.ctors.iter() {
+        let mut ctx = LevelCtx::empty();
+        for (i, param) in ctor.params.params.iter().enumerate() {
+            let positive = ctx.bind_iter(ctor.params.params[..i].iter(), |ctx| {
+                checker.strictly_positive(ctx, &param.typ, &target)
+            });
+            if !positive {
+                return Err(TypeError::NotStrictlyPositive {
+                    name: data.name.id.clone(),
+                    span: param.typ.span().to_miette(),
+                }
+                .into());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Check that a codata type only occurs strictly positively in the return types of its destructors
+pub fn check_codata_positivity(
+    codata: &Codata,
+    module: &Module,
+    info_table: &TypeInfoTable,
+) -> TcResult {
+    if codata.attr.attrs.contains(&Attribute::NoPositivityCheck) {
+        return Ok(());
+    }
+    let group = recursive_group(&codata.name, &module.uri, &codata_signatures(module));
+    let target = Target::Types { uri: &module.uri, names: &group };
+    let mut checker = Positivity { info_table, visiting: vec![] };
+
+    for dtor in codata.dtors.iter() {
+        let mut ctx = LevelCtx::empty();
+        let positive = ctx.bind_iter(dtor.params.params.iter(), |ctx| {
+            ctx.bind_single(dtor.self_param.name.clone(), |ctx| {
+                checker.strictly_positive(ctx, &dtor.ret_typ, &target)
+            })
+        });
+        if !positive {
+            return Err(TypeError::NotStrictlyPositive {
+                name: codata.name.id.clone(),
+                span: dtor.ret_typ.span().to_miette(),
+            }
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// What we are looking for in a type
+enum Target<'a> {
+    /// Any of the given type constructors of the module with the given URI
+    Types { uri: &'a Url, names: &'a HashSet<String> },
+    /// The variable with the given De Bruijn level
+    Var(Lvl),
+}
+
+impl Target<'_> {
+    fn is_target_type(&self, name: &IdBound) -> bool {
+        match self {
+            Target::Types { uri, names } => name.uri == **uri && names.contains(&name.id),
+            Target::Var(_) => false,
+        }
+    }
+
+    fn occurs_in(&self, ctx: &mut LevelCtx, exp: &Exp) -> bool {
+        exp.occurs(ctx, &|ctx, exp| match exp {
+            Exp::TypCtor(TypCtor { name, .. }) => self.is_target_type(name),
+            Exp::Variable(Variable { idx, .. }) => match self {
+                Target::Var(lvl) => ctx.idx_to_lvl(*idx) == *lvl,
+                Target::Types { .. } => false,
+            },
+            _ => false,
+        })
+    }
+}
+
+struct Positivity<'a> {
+    info_table: &'a TypeInfoTable,
+    /// The constructor and destructor parameters which are currently being checked
+    visiting: Vec<(Url, String, usize)>,
+}
+
+/// A constructor or destructor of a type constructor
+#[derive(Clone, Copy)]
+enum Xtor<'a> {
+    Ctor(&'a Url, &'a Ctor),
+    Dtor(&'a Url, &'a Dtor),
+}
+
+impl<'a> Xtor<'a> {
+    fn params(&self) -> &'a [Param] {
+        match self {
+            Xtor::Ctor(_, ctor) => &ctor.params.params,
+            Xtor::Dtor(_, dtor) => &dtor.params.params,
+        }
+    }
+
+    /// The type that the xtor constructs or destructs, closed under its parameters
+    fn typ(&self) -> &'a TypCtor {
+        match self {
+            Xtor::Ctor(_, ctor) => &ctor.typ,
+            Xtor::Dtor(_, dtor) => &dtor.self_param.typ,
+        }
+    }
+}
+
+impl Positivity<'_> {
+    /// Whether the target only occurs strictly positively in `exp`
+    fn strictly_positive(&mut self, ctx: &mut LevelCtx, exp: &Exp, target: &Target) -> bool {
+        if !target.occurs_in(ctx, exp) {
+            return true;
+        }
+        match exp {
+            Exp::Variable(_) => true,
+            Exp::Anno(Anno { exp, .. }) => self.strictly_positive(ctx, exp, target),
+            Exp::TypCtor(TypCtor { name, args, .. }) if target.is_target_type(name) => {
+                args.args.iter().all(|arg| !target.occurs_in(ctx, &arg.exp()))
+            }
+            Exp::TypCtor(TypCtor { name, args, .. }) => {
+                if let Ok(data) = self.info_table.lookup_data(name) {
+                    data.ctors.iter().all(|ctor| {
+                        self.positive_args(ctx, Xtor::Ctor(&name.uri, ctor), args, target)
+                    })
+                } else if let Ok(codata) = self.info_table.lookup_codata(name) {
+                    codata.dtors.iter().all(|dtor| {
+                        self.positive_args(ctx, Xtor::Dtor(&name.uri, dtor), args, target)
+                    })
+                } else {
+                    false
+                }
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the target only occurs strictly positively in the arguments `args` of a type constructor,
+    /// as seen through one of its constructors or destructors
+    fn positive_args(
+        &mut self,
+        ctx: &mut LevelCtx,
+        xtor: Xtor,
+        args: &Args,
+        target: &Target,
+    ) -> bool {
+        xtor.typ()
+            .args
+            .args
+            .iter()
+            .zip(args.args.iter())
+            .all(|(pattern, arg)| self.positive_flow(ctx, xtor, &pattern.exp(), &arg.exp(), target))
+    }
+
+    /// Whether the occurrences of the target in `arg` only flow to parameters of `xtor` which occur strictly positively
+    ///
+    /// The xtor's type argument `pattern` is matched against `arg`, which determines the parameter each
+    /// occurrence of the target flows to.
+    fn positive_flow(
+        &mut self,
+        ctx: &mut LevelCtx,
+        xtor: Xtor,
+        pattern: &Exp,
+        arg: &Exp,
+        target: &Target,
+    ) -> bool {
+        if !target.occurs_in(ctx, arg) {
+            return true;
+        }
+        let len = xtor.params().len();
+        match (pattern, arg) {
+            (_, Exp::Anno(Anno { exp, .. })) => self.positive_flow(ctx, xtor, pattern, exp, target),
+            (Exp::Variable(Variable { idx: Idx { fst: 0, snd }, .. }), _) if *snd < len => {
+                let lvl = Lvl { fst: 0, snd: len - 1 - snd };
+                self.param_positive(xtor, lvl) && self.strictly_positive(ctx, arg, target)
+            }
+            (Exp::TypCtor(p), Exp::TypCtor(a))
+                if p.name == a.name && p.args.len() == a.args.len() =>
+            {
+                p.args
+                    .args
+                    .iter()
+                    .zip(a.args.args.iter())
+                    .all(|(p, a)| self.positive_flow(ctx, xtor, &p.exp(), &a.exp(), target))
+            }
+            (Exp::Call(p), Exp::Call(a)) if p.name == a.name && p.args.len() == a.args.len() => p
+                .args
+                .args
+                .iter()
+                .zip(a.args.args.iter())
+                .all(|(p, a)| self.positive_flow(ctx, xtor, &p.exp(), &a.exp(), target)),
+            _ => false,
+        }
+    }
+
+    /// Whether the parameter `lvl` of a constructor only occurs strictly positively in the types of the
+    /// constructor parameters, or whether the parameter `lvl` of a destructor does not occur in the types
+    /// of the destructor parameters and only occurs strictly positively in its return type
+    fn param_positive(&mut self, xtor: Xtor, lvl: Lvl) -> bool {
+        let key = match xtor {
+            Xtor::Ctor(uri, ctor) => (uri.clone(), ctor.name.id.clone(), lvl.snd),
+            Xtor::Dtor(uri, dtor) => (uri.clone(), dtor.name.id.clone(), lvl.snd),
+        };
+        // Nested occurrences within the same type constructor are assumed to be positive
+        if self.visiting.contains(&key) {
+            return true;
+        }
+        self.visiting.push(key);
+
+        let params = xtor.params();
+        let target = Target::Var(lvl);
+        let mut ctx = LevelCtx::empty();
+        let result = match xtor {
+            Xtor::Ctor(_, _) => params.iter().enumerate().skip(lvl.snd + 1).all(|(i, param)| {
+                ctx.bind_iter(params[..i].iter(), |ctx| {
+                    self.strictly_positive(ctx, &param.typ, &target)
+                })
+            }),
+            Xtor::Dtor(_, dtor) => {
+                let in_params = params.iter().enumerate().skip(lvl.snd + 1).any(|(i, param)| {
+                    ctx.bind_iter(params[..i].iter(), |ctx| target.occurs_in(ctx, &param.typ))
+                });
+                !in_params
+                    && ctx.bind_iter(params.iter(), |ctx| {
+                        ctx.bind_single(dtor.self_param.name.clone(), |ctx| {
+                            self.strictly_positive(ctx, &dtor.ret_typ, &target)
+                        })
+                    })
+            }
+        };
+
+        self.visiting.pop();
+        result
+    }
+}
+
+/// The types checked against the same target: the parameter types of all constructors of each data type
+fn data_signatures(module: &Module) -> Vec<(&IdBind, Vec<&Exp>)> {
+    module
+        .decls
+        .iter()
+        .filter_map(|decl| match decl {
+            Decl::Data(data) => Some((
+                &data.name,
+                data.ctors
+                    .iter()
+                    .flat_map(|ctor| ctor.params.params.iter().map(|param| &*param.typ))
+                    .collect(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The types checked against the same target: the return types of all destructors of each codata type
+fn codata_signatures(module: &Module) -> Vec<(&IdBind, Vec<&Exp>)> {
+    module
+        .decls
+        .iter()
+        .filter_map(|decl| match decl {
+            Decl::Codata(codata) => {
+                Some((&codata.name, codata.dtors.iter().map(|dtor| &*dtor.ret_typ).collect()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// All types of the same polarity which are mutually recursive with the type `name`
+///
+/// The recursive group is the strongly connected component of `name` in the graph
+/// in which each type points to all types that occur in its signatures.
+fn recursive_group(name: &IdBind, uri: &Url, types: &[(&IdBind, Vec<&Exp>)]) -> HashSet<String> {
+    let mentions = |from: &[&Exp], to: &IdBind| {
+        let names = HashSet::from_iter([to.id.clone()]);
+        let target = Target::Types { uri, names: &names };
+        // Only type constructors are targeted, so we do not need to track the binding structure.
+        from.iter().any(|exp| target.occurs_in(&mut LevelCtx::empty(), exp))
+    };
+    let reachable = |from: &IdBind, forward: bool| {
+        let mut visited: HashSet<String> = HashSet::default();
+        let mut stack = vec![from.id.clone()];
+        while let Some(current) = stack.pop() {
+            if !visited.insert(current.clone()) {
+                continue;
+            }
+            let current_sigs =
+                types.iter().find(|(name, _)| name.id == current).map(|(_, sigs)| sigs);
+            for (other, other_sigs) in types.iter() {
+                let edge = if forward {
+                    current_sigs.is_some_and(|sigs| mentions(sigs, other))
+                } else {
+                    mentions(other_sigs, &IdBind::from_string(&current))
+                };
+                if edge {
+                    stack.push(other.id.clone());
+                }
+            }
+        }
+        visited
+    };
+
+    let forward = reachable(name, true);
+    let backward = reachable(name, false);
+    forward.intersection(&backward).cloned().collect()
+}
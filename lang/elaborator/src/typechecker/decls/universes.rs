@@ -0,0 +1,112 @@
+//! Universe checks for data and codata declarations in predicative modules
+//!
+//! In a module with the `#![predicative]` attribute, an application `Tσ` of a type constructor
+//! lives in the universe computed by [universe]. The types of the parameters of the constructors
+//! and destructors of `T` must live in this universe as well, otherwise a type could contain
+//! the universe it lives in. Parameters which are passed unchanged as arguments to `T` are
+//! exempt from this check: they only determine which instance of `T` is constructed.
+//!
+//! The universe of a parameter type is only determined for universes, type constructors and
+//! variables. Other parameter types, such as neutral destructor calls, are not checked.
+
+use std::fmt;
+
+use ast::*;
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+use printer::Print;
+
+use crate::result::TcResult;
+use crate::typechecker::ctx::Ctx;
+use crate::typechecker::exprs::typ_ctor::universe;
+use crate::typechecker::TypeError;
+
+/// Check the parameters of a constructor against the universe of its return type.
+///
+/// Must be called in the context of the constructor parameters.
+pub fn check_ctor_universes(ctx: &mut Ctx, params: &Telescope, typ: &TypCtor) -> TcResult {
+    let bound = universe(typ, ctx)?;
+    check_params(ctx, params, 0, typ, &bound)
+}
+
+/// Check the parameters and the return type of a destructor against the universe of its self type.
+///
+/// Must be called in the context of the destructor parameters and the self parameter.
+pub fn check_dtor_universes(
+    ctx: &mut Ctx,
+    params: &Telescope,
+    ret_typ: &Exp,
+    codata_name: &IdBind,
+) -> TcResult {
+    let Exp::TypCtor(self_typ) = &*ctx.lookup(Idx { fst: 0, snd: 0 }) else {
+        return Ok(());
+    };
+    let bound = universe(self_typ, ctx)?;
+    check_params(ctx, params, 1, self_typ, &bound)?;
+    if let Some(level) = universe_level(ctx, ret_typ)? {
+        if !level.leq(&bound) {
+            return Err(universe_too_large("return type", &bound, codata_name, ret_typ.span()));
+        }
+    }
+    Ok(())
+}
+
+/// Check the parameters bound at de Bruijn level `fst` against the universe `bound` of `typ`.
+fn check_params(
+    ctx: &mut Ctx,
+    params: &Telescope,
+    fst: usize,
+    typ: &TypCtor,
+    bound: &Level,
+) -> TcResult {
+    let n = params.len();
+    for (i, param) in params.params.iter().enumerate() {
+        let idx = Idx { fst, snd: n - 1 - i };
+        let is_index = typ.args.args.iter().any(|arg| match &*arg.exp() {
+            Exp::Variable(Variable { idx: arg_idx, .. }) => *arg_idx == idx,
+            _ => false,
+        });
+        if is_index {
+            continue;
+        }
+        let param_typ = ctx.lookup(idx);
+        if let Some(level) = universe_level(ctx, &param_typ)? {
+            if !level.leq(bound) {
+                let name = format!("parameter {}", param.name);
+                return Err(universe_too_large(&name, bound, &typ.name, param.name.span()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The level `l` of the universe `Type l` which contains the type `typ`, if it can be determined
+fn universe_level(ctx: &mut Ctx, typ: &Exp) -> TcResult<Option<Level>> {
+    match typ {
+        Exp::TypeUniv(TypeUniv { level: Some(level), .. }) => {
+            Ok(Some(Level::from_exp(level).add(1)))
+        }
+        Exp::LevelUniv(_) => Ok(Some(Level::constant(0))),
+        Exp::TypCtor(typ_ctor) => Ok(Some(universe(typ_ctor, ctx)?)),
+        Exp::Variable(Variable { idx, .. }) => match &*ctx.lookup(*idx) {
+            Exp::TypeUniv(TypeUniv { level: Some(level), .. }) => Ok(Some(Level::from_exp(level))),
+            _ => Ok(None),
+        },
+        _ => Ok(None),
+    }
+}
+
+fn universe_too_large(
+    name: &str,
+    bound: &Level,
+    typ_name: &impl fmt::Display,
+    span: Option<Span>,
+) -> Box<TypeError> {
+    TypeError::UniverseTooLarge {
+        name: name.to_owned(),
+        typ_name: typ_name.to_string(),
+        universe: TypeUniv::with_level(bound.clone().into_exp()).print_to_string(None),
+        span: span.to_miette(),
+    }
+    .into()
+}
@@ -1,47 +1,344 @@
 //! Erasure
 //!
-//! At the moment, erasure is purely syntactic and only affects parameter and argument lists.
-//! Elaboration consults the functions in this module to determine whether a parameter or argument
-//! should be marked as erased.
+//! A term is erased if its type shows that it has no runtime relevance, or if it is bound by or
+//! passed to a parameter which is annotated with the `erased` keyword, as in `erased h: LE(x, y)`.
+//! The backend consults the functions in this module to remove the erased terms when generating code.
 //!
-//! At the moment, a parameter is erased if its type is ...
+//! The runtime relevance of a type is one of the following:
 //!
-//! * the type universe `Type`
-//! * an annotated type universe `Type : Type`
-//! * a hole that solves to `Type`
+//! * The terms of the type are types, if the type is ...
+//!   * the type universe `Type`
+//!   * the sort `Level` of universe levels
+//!   * a codata type whose destructors all return types, such as the type families `a -> Type`
+//! * The type is a proposition if it is a data type with exactly one constructor whose
+//!   parameters are all runtime-irrelevant, such as `Unit`. All terms of a proposition evaluate
+//!   to the same value, so they can be replaced by this value.
+//! * Otherwise, the type is runtime-relevant. In particular, a type variable is runtime-relevant
+//!   since it may stand for any type.
 //!
-//! An argument is erased if its corresponding parameter is erased.
+//! A parameter is erased if it is annotated as erased or if its type is not runtime-relevant,
+//! and an argument is erased if its corresponding parameter is erased.
+//!
+//! After a module has been typechecked, we check that the variables bound by parameters which are
+//! annotated as erased only occur in erased positions: in types, in arguments of erased parameters
+//! and in declarations which compute types.
+//! Since index unification substitutes the variables of a pattern into the body of a case, the
+//! indices of a constructor can only be erased if the indices of the matched type do not depend on
+//! them at runtime. For example, `y` is not erased in `LESucc(x y: Nat, h: LE(x, y)): LE(x, S(y))`,
+//! since `LE(x, y).upper(x y: Nat): Nat` refers to `S(y)` instead of `y` in this case.
 
-/// Mark runtime-irrelevant parameters as erased
-pub fn mark_erased_params(params: &mut ast::Telescope) {
-    for param in params.params.iter_mut() {
-        param.erased = is_runtime_irrelevant(&param.typ);
-    }
+use ast::ctx::values::Binder;
+use ast::ctx::{BindContext, GenericCtx};
+use ast::*;
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+
+use crate::result::{TcResult, TypeError};
+use crate::typechecker::type_info_table::TypeInfoTable;
+
+/// The runtime relevance of the terms of a type
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Relevance {
+    /// The terms of the type have runtime relevance
+    Relevant,
+    /// The terms of the type are types
+    Type,
+    /// The type is a proposition whose only value is the given constructor without arguments
+    Prop(IdBound),
+}
+
+/// The runtime relevance of the terms of type `typ`
+pub fn relevance(typ: &Exp, info_table: &TypeInfoTable) -> Relevance {
+    relevance_guarded(typ, info_table, &mut Vec::new())
 }
 
 /// If this function return true on a term of type `typ`,
 /// then it has no runtime relevance and the term can be erased.
-pub fn is_runtime_irrelevant(typ: &ast::Exp) -> bool {
-    match typ {
-        ast::Exp::Variable(_) => false,
-        ast::Exp::TypCtor(_) => false,
-        ast::Exp::Call(_) => false,
-        ast::Exp::DotCall(_) => false,
-        ast::Exp::Anno(anno) => is_runtime_irrelevant(&anno.exp),
-        ast::Exp::TypeUniv(_) => true,
-        ast::Exp::LocalMatch(_) => false,
-        ast::Exp::LocalComatch(_) => false,
-        ast::Exp::Hole(hole) => {
-            hole.solution.as_ref().map(|s| is_runtime_irrelevant(s)).unwrap_or(false)
-        }
-    }
+pub fn is_runtime_irrelevant(typ: &Exp, info_table: &TypeInfoTable) -> bool {
+    relevance(typ, info_table) != Relevance::Relevant
+}
+
+/// Whether the parameter `param` is erased, either because it is annotated as erased or because it
+/// is runtime-irrelevant
+pub fn is_erased(param: &Param, info_table: &TypeInfoTable) -> bool {
+    param.erased || is_runtime_irrelevant(&param.typ, info_table)
 }
 
-/// Mark runtime-irrelevant arguments as erased
+/// Mark the arguments of parameters which are annotated as erased
 ///
 /// We mark each argument as erased if the corresponding parameter is marked as erased.
-pub fn mark_erased_args(params: &ast::Telescope, args: &mut ast::Args) {
+pub fn mark_erased_args(params: &Telescope, args: &mut Args) {
     for (param, arg) in params.params.iter().zip(args.args.iter_mut()) {
         arg.set_erased(param.erased);
     }
 }
+
+/// The runtime relevance of the terms of type `typ`
+///
+/// The data and codata types in `visiting` are currently being inspected. Recursive occurrences of
+/// these types are runtime-relevant, such that the relevance of recursive types is well-defined.
+fn relevance_guarded(
+    typ: &Exp,
+    info_table: &TypeInfoTable,
+    visiting: &mut Vec<IdBound>,
+) -> Relevance {
+    match typ {
+        Exp::Variable(_) => Relevance::Relevant,
+        Exp::TypCtor(typ_ctor) => typ_ctor_relevance(typ_ctor, info_table, visiting),
+        Exp::Call(_) => Relevance::Relevant,
+        Exp::DotCall(_) => Relevance::Relevant,
+        Exp::Anno(anno) => relevance_guarded(&anno.exp, info_table, visiting),
+        Exp::TypeUniv(_) => Relevance::Type,
+        Exp::LevelUniv(_) => Relevance::Type,
+        Exp::Level(_) => Relevance::Relevant,
+        Exp::LocalMatch(_) => Relevance::Relevant,
+        Exp::LocalComatch(_) => Relevance::Relevant,
+        Exp::LocalLet(_) => Relevance::Relevant,
+        Exp::Hole(hole) => match &hole.solution {
+            Some(solution) => relevance_guarded(solution, info_table, visiting),
+            None => Relevance::Relevant,
+        },
+    }
+}
+
+fn typ_ctor_relevance(
+    typ_ctor: &TypCtor,
+    info_table: &TypeInfoTable,
+    visiting: &mut Vec<IdBound>,
+) -> Relevance {
+    let TypCtor { name, args, .. } = typ_ctor;
+    if visiting.contains(name) {
+        return Relevance::Relevant;
+    }
+    visiting.push(name.clone());
+    let relevance = if let Ok(data) = info_table.lookup_data(name) {
+        data_relevance(data, name, info_table, visiting)
+    } else if let Ok(codata) = info_table.lookup_codata(name) {
+        codata_relevance(codata, args, info_table, visiting)
+    } else {
+        Relevance::Relevant
+    };
+    visiting.pop();
+    relevance
+}
+
+/// A data type is a proposition if it has exactly one constructor whose parameters are all erased
+fn data_relevance(
+    data: &Data,
+    name: &IdBound,
+    info_table: &TypeInfoTable,
+    visiting: &mut Vec<IdBound>,
+) -> Relevance {
+    let [ctor] = data.ctors.as_slice() else {
+        return Relevance::Relevant;
+    };
+    let is_uninformative = ctor.params.params.iter().all(|param| {
+        param.erased || relevance_guarded(&param.typ, info_table, visiting) != Relevance::Relevant
+    });
+    if !is_uninformative {
+        return Relevance::Relevant;
+    }
+    Relevance::Prop(IdBound {
+        span: None,
+        id: ctor.name.id.clone(),
+        uri: name.uri.clone(),
+        qualifier: None,
+    })
+}
+
+/// The terms of a codata type are types if all of its destructors return types
+fn codata_relevance(
+    codata: &Codata,
+    args: &Args,
+    info_table: &TypeInfoTable,
+    visiting: &mut Vec<IdBound>,
+) -> Relevance {
+    if codata.dtors.is_empty() {
+        return Relevance::Relevant;
+    }
+    let returns_types = codata.dtors.iter().all(|dtor| {
+        let ret_typ = instantiate_ret_typ(dtor, args);
+        relevance_guarded(&ret_typ, info_table, visiting) == Relevance::Type
+    });
+    if returns_types {
+        Relevance::Type
+    } else {
+        Relevance::Relevant
+    }
+}
+
+/// The return type of the destructor `dtor` when it is called on a term of type `T(args)`
+///
+/// If the return type is a parameter of the destructor which occurs as an argument of the type of
+/// the self parameter, as `b` in `Fun(a, b).ap(a b: Type, x: a): b`, the corresponding argument of
+/// `args` is returned.
+fn instantiate_ret_typ(dtor: &Dtor, args: &Args) -> Box<Exp> {
+    let Dtor { self_param, ret_typ, .. } = dtor;
+    let Exp::Variable(Variable { idx, .. }) = &**ret_typ else {
+        return ret_typ.clone();
+    };
+    // The return type is in the scope of the parameters and the self parameter of the
+    // destructor, whereas the type of the self parameter is in the scope of the parameters only.
+    if idx.fst != 1 {
+        return ret_typ.clone();
+    }
+    let param_idx = Idx { fst: 0, snd: idx.snd };
+    let position = self_param.typ.args.args.iter().position(
+        |arg| matches!(&*arg.exp(), Exp::Variable(Variable { idx, .. }) if *idx == param_idx),
+    );
+    match position.and_then(|position| args.args.get(position)) {
+        Some(arg) => arg.exp(),
+        None => ret_typ.clone(),
+    }
+}
+
+/// Check that the variables bound by erased parameters only occur in erased positions
+pub fn check_erased_usage(module: &Module, info_table: &TypeInfoTable) -> TcResult {
+    let checker = UsageChecker { info_table };
+    for decl in module.decls.iter() {
+        checker.check_decl(decl).map_err(|ErasedUse { name, span, .. }| {
+            TypeError::ErasedVariable { name, span: span.to_miette() }
+        })?;
+    }
+    Ok(())
+}
+
+/// Whether a variable is bound by a parameter which is annotated as erased
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Quantity {
+    Erased,
+    Relevant,
+}
+
+impl Shift for Quantity {
+    fn shift_in_range<R: ShiftRange>(&mut self, _range: &R, _by: (isize, isize)) {}
+}
+
+/// An occurrence of an erased variable in a runtime-relevant position
+struct ErasedUse {
+    name: String,
+    span: Option<Span>,
+    /// The span of the binder of the variable
+    binder_span: Option<Span>,
+}
+
+struct UsageChecker<'a> {
+    info_table: &'a TypeInfoTable,
+}
+
+impl UsageChecker<'_> {
+    fn check_decl(&self, decl: &Decl) -> Result<(), ErasedUse> {
+        let mut ctx: GenericCtx<Quantity> = GenericCtx::empty();
+        match decl {
+            // Declarations which compute types are only used in erased positions.
+            Decl::Def(Def { ret_typ, .. }) if self.computes_type(ret_typ) => Ok(()),
+            Decl::Codef(Codef { typ, .. }) if self.computes_type(&typ.to_exp()) => Ok(()),
+            Decl::Let(Let { typ, .. }) if self.computes_type(typ) => Ok(()),
+            // The self parameter of a definition is not bound in its cases, but matched on.
+            Decl::Def(Def { params, cases, .. }) => ctx
+                .bind_iter(params.params.iter().map(param_binder), |ctx| {
+                    self.check_cases(ctx, cases)
+                }),
+            Decl::Codef(Codef { params, cases, .. }) => ctx
+                .bind_iter(params.params.iter().map(param_binder), |ctx| {
+                    self.check_cases(ctx, cases)
+                }),
+            Decl::Let(Let { params, body, .. }) => ctx
+                .bind_iter(params.params.iter().map(param_binder), |ctx| self.check_exp(ctx, body)),
+            Decl::Data(_) | Decl::Codata(_) | Decl::Infix(_) => Ok(()),
+        }
+    }
+
+    fn computes_type(&self, ret_typ: &Exp) -> bool {
+        relevance(ret_typ, self.info_table) == Relevance::Type
+    }
+
+    fn check_cases(&self, ctx: &mut GenericCtx<Quantity>, cases: &[Case]) -> Result<(), ErasedUse> {
+        for Case { span, pattern, body, .. } in cases {
+            let binders = pattern.params.params.iter().map(|param| Binder {
+                name: param.name.clone(),
+                content: if param.erased { Quantity::Erased } else { Quantity::Relevant },
+            });
+            ctx.bind_iter(binders, |ctx| match body {
+                Some(body) => self.check_exp(ctx, body),
+                None => Ok(()),
+            })
+            .map_err(|mut err| {
+                // Index unification substitutes the variables of a pattern into the body of the
+                // case. Such variables carry the span of the declaration of the constructor, so we
+                // report their binder in the pattern instead.
+                let is_within_case = err
+                    .span
+                    .zip(*span)
+                    .is_some_and(|(var, case)| case.start <= var.start && var.end <= case.end);
+                if !is_within_case {
+                    err.span = err.binder_span.or(*span);
+                }
+                err
+            })?;
+        }
+        Ok(())
+    }
+
+    fn check_args(&self, ctx: &mut GenericCtx<Quantity>, args: &Args) -> Result<(), ErasedUse> {
+        for arg in args.args.iter().filter(|arg| !arg.erased()) {
+            self.check_exp(ctx, &arg.exp())?;
+        }
+        Ok(())
+    }
+
+    /// Check an expression in a runtime-relevant position
+    fn check_exp(&self, ctx: &mut GenericCtx<Quantity>, exp: &Exp) -> Result<(), ErasedUse> {
+        match exp {
+            Exp::Variable(Variable { span, idx, inferred_type, .. }) => {
+                // Variables of runtime-irrelevant types are erased regardless of how they are bound.
+                let is_irrelevant = inferred_type
+                    .as_ref()
+                    .is_some_and(|typ| is_runtime_irrelevant(typ, self.info_table));
+                let binder = ctx.lookup(*idx);
+                if binder.content == Quantity::Erased && !is_irrelevant {
+                    // Index unification substitutes variables which keep the name of the
+                    // constructor parameter, so we report the name of their binder.
+                    return Err(ErasedUse {
+                        name: binder.name.to_string(),
+                        span: *span,
+                        binder_span: binder.name.span(),
+                    });
+                }
+                Ok(())
+            }
+            Exp::TypCtor(_) | Exp::TypeUniv(_) | Exp::LevelUniv(_) | Exp::Level(_) => Ok(()),
+            Exp::Call(Call { args, .. }) => self.check_args(ctx, args),
+            Exp::DotCall(DotCall { exp, args, .. }) => {
+                self.check_exp(ctx, exp)?;
+                self.check_args(ctx, args)
+            }
+            Exp::Anno(Anno { exp, .. }) => self.check_exp(ctx, exp),
+            Exp::LocalMatch(LocalMatch { on_exp, cases, .. }) => {
+                self.check_exp(ctx, on_exp)?;
+                self.check_cases(ctx, cases)
+            }
+            Exp::LocalComatch(LocalComatch { cases, .. }) => self.check_cases(ctx, cases),
+            Exp::LocalLet(LocalLet { name, bound, bound_type, body, .. }) => {
+                // A let-binding of a runtime-irrelevant term is erased together with the term.
+                let is_irrelevant = bound_type
+                    .as_ref()
+                    .is_some_and(|typ| is_runtime_irrelevant(typ, self.info_table));
+                if !is_irrelevant {
+                    self.check_exp(ctx, bound)?;
+                }
+                let binder = Binder { name: name.clone(), content: Quantity::Relevant };
+                ctx.bind_single(binder, |ctx| self.check_exp(ctx, body))
+            }
+            Exp::Hole(Hole { solution, .. }) => match solution {
+                Some(solution) => self.check_exp(ctx, solution),
+                None => Ok(()),
+            },
+        }
+    }
+}
+
+fn param_binder(param: &Param) -> Binder<Quantity> {
+    let content = if param.erased { Quantity::Erased } else { Quantity::Relevant };
+    Binder { name: param.name.clone(), content }
+}
@@ -6,7 +6,7 @@ use crate::normalizer::normalize::Normalize;
 use ast::*;
 
 use super::CheckInfer;
-use super::{super::ctx::*, ExpectType};
+use super::{super::ctx::*, check_is_type, ExpectType};
 use crate::result::TcResult;
 
 impl CheckInfer for Anno {
@@ -27,7 +27,7 @@ impl CheckInfer for Anno {
     /// ```
     fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
         let Anno { span, exp, typ, .. } = self;
-        let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
+        let typ_out = check_is_type(typ, ctx)?;
         let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
         let exp_out = (**exp).check(ctx, &typ_nf)?;
         Ok(Anno {
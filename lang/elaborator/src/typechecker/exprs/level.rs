@@ -0,0 +1,67 @@
+//! Bidirectional type checker
+
+use ast::*;
+
+use super::super::ctx::*;
+use super::CheckInfer;
+use crate::conversion_checking::convert;
+use crate::result::TcResult;
+
+// LevelUniv
+//
+//
+
+impl CheckInfer for LevelUniv {
+    /// The *checking* rule for the sort of universe levels is:
+    /// ```text
+    ///            P, Γ ⊢ τ ≃ Type 0
+    ///           ────────────────────
+    ///            P, Γ ⊢ Level ⇐ τ
+    /// ```
+    fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
+        convert(&ctx.vars, &mut ctx.meta_vars, self.typ().unwrap(), t, &self.span())?;
+        Ok(self.clone())
+    }
+
+    /// The *inference* rule for the sort of universe levels is:
+    /// ```text
+    ///           ─────────────────────────
+    ///            P, Γ ⊢ Level ⇒ Type 0
+    /// ```
+    fn infer(&self, _ctx: &mut Ctx) -> TcResult<Self> {
+        Ok(self.clone())
+    }
+}
+
+// Level
+//
+//
+
+impl CheckInfer for Level {
+    /// The *checking* rule for universe levels is:
+    /// ```text
+    ///            P, Γ ⊢ τ ≃ Level
+    ///            P, Γ ⊢ lᵢ ⇐ Level
+    ///           ───────────────────────────────────────
+    ///            P, Γ ⊢ max(n, l₁ + k₁, ..., lₘ + kₘ) ⇐ τ
+    /// ```
+    fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
+        convert(&ctx.vars, &mut ctx.meta_vars, self.typ().unwrap(), t, &self.span())?;
+        self.infer(ctx)
+    }
+
+    /// The *inference* rule for universe levels is:
+    /// ```text
+    ///            P, Γ ⊢ lᵢ ⇐ Level
+    ///           ───────────────────────────────────────────
+    ///            P, Γ ⊢ max(n, l₁ + k₁, ..., lₘ + kₘ) ⇒ Level
+    /// ```
+    fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
+        let Level { span, constant, summands } = self;
+        let summands = summands
+            .iter()
+            .map(|(exp, k)| Ok((exp.check(ctx, &LevelUniv { span: None }.into())?, *k)))
+            .collect::<TcResult<_>>()?;
+        Ok(Level { span: *span, constant: *constant, summands })
+    }
+}
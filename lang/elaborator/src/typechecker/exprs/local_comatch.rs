@@ -9,6 +9,7 @@ use crate::typechecker::exprs::CheckTelescope;
 use crate::typechecker::type_info_table::DtorMeta;
 use ast::ctx::LevelCtx;
 use ast::*;
+use miette_util::codespan::Span;
 use miette_util::ToMiette;
 
 use super::super::ctx::*;
@@ -18,7 +19,7 @@ use crate::result::TypeError;
 
 impl CheckInfer for LocalComatch {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
-        let LocalComatch { span, name, is_lambda_sugar, cases, .. } = self;
+        let LocalComatch { span, name, is_lambda_sugar, cases, catch_all, .. } = self;
         // The expected type that we check against should be a type constructor applied to
         // arguments.
         let expected_type_app: TypCtor = t.expect_typ_app()?.infer(ctx)?;
@@ -33,8 +34,13 @@ impl CheckInfer for LocalComatch {
             .into());
         }
 
-        let with_expected_type =
-            WithExpectedType { cases, signature: None, expected_type: expected_type_app.clone() };
+        let cases = expand_catch_all(ctx, cases, catch_all.as_ref(), &expected_type_app)?;
+        let with_expected_type = WithExpectedType {
+            cases: &cases,
+            signature: None,
+            expected_type: expected_type_app.clone(),
+            span: *span,
+        };
 
         with_expected_type.check_exhaustiveness(ctx)?;
         let cases = with_expected_type.check_type(ctx)?;
@@ -45,6 +51,7 @@ impl CheckInfer for LocalComatch {
             name: name.clone(),
             is_lambda_sugar: *is_lambda_sugar,
             cases,
+            catch_all: None,
             inferred_type: Some(expected_type_app),
         })
     }
@@ -55,6 +62,57 @@ impl CheckInfer for LocalComatch {
     }
 }
 
+/// Expand the catch-all clause of a copattern match on the codata type `typ` to a case for every
+/// destructor without a case
+pub fn expand_catch_all(
+    ctx: &Ctx,
+    cases: &[Case],
+    catch_all: Option<&CatchAll>,
+    typ: &TypCtor,
+) -> TcResult<Vec<Case>> {
+    let mut cases_out = cases.to_vec();
+    let Some(CatchAll { span, name, body }) = catch_all else {
+        return Ok(cases_out);
+    };
+    let codata = ctx.type_info_table.lookup_codata(&typ.name)?;
+    let remaining: Vec<&Dtor> = codata
+        .dtors
+        .iter()
+        .filter(|dtor| !cases.iter().any(|case| case.pattern.name.id == dtor.name.id))
+        .collect();
+    if remaining.is_empty() {
+        return Err(TypeError::UnreachableClause { span: span.to_miette() }.into());
+    }
+
+    // The body does not refer to the wildcard bound by the catch-all clause, so it is valid in
+    // the context of the parameters of every destructor.
+    for dtor in remaining {
+        let params = dtor.params.params.iter().map(|_| ParamInst {
+            span: None,
+            name: VarBind::Wildcard { span: None },
+            typ: None,
+            erased: false,
+        });
+        cases_out.push(Case {
+            span: *span,
+            pattern: Pattern {
+                span: None,
+                is_copattern: true,
+                name: IdBound {
+                    span: None,
+                    id: dtor.name.id.clone(),
+                    uri: typ.name.uri.clone(),
+                    qualifier: None,
+                },
+                params: TelescopeInst { params: params.collect() },
+            },
+            body: body.clone(),
+            expanded_from: Some(name.clone()),
+        });
+    }
+    Ok(cases_out)
+}
+
 /// This struct is used to share code between the typechecking of local and global comatches.
 pub struct WithExpectedType<'a> {
     pub cases: &'a Vec<Case>,
@@ -64,6 +122,9 @@ pub struct WithExpectedType<'a> {
     pub signature: Option<(IdBound, &'a Telescope)>,
     /// The expected type of the comatch, i.e. `Stream(Int)` for `comatch { hd => 1, tl => ... }`.
     pub expected_type: TypCtor,
+    /// The span of a local comatch, at which missing destructors are reported.
+    /// This is `None` for a global comatch, for which they are reported at the expected type.
+    pub span: Option<Span>,
 }
 
 /// Infer a copattern match
@@ -99,7 +160,7 @@ impl WithExpectedType<'_> {
                 dtors_missing.map(|i| &i.id).cloned().collect(),
                 dtors_exessive.map(|i| &i.id).cloned().collect(),
                 dtors_duplicate.into_iter().map(|i| i.id).collect(),
-                &self.expected_type.span(),
+                &self.span.or(self.expected_type.span()),
             ));
         }
         Ok(())
@@ -107,7 +168,7 @@ impl WithExpectedType<'_> {
 
     /// Type-check the comatch
     pub fn check_type(&self, ctx: &mut Ctx) -> TcResult<Vec<Case>> {
-        let WithExpectedType { cases, expected_type, signature: label } = &self;
+        let WithExpectedType { cases, expected_type, signature: label, .. } = &self;
         let TypCtor { args: on_args, .. } = expected_type;
 
         // We will compare `on_args` against `def_args`. But `def_args` are defined
@@ -127,6 +188,7 @@ impl WithExpectedType<'_> {
                 span,
                 pattern: Pattern { span: pattern_span, name, params: params_inst, .. },
                 body,
+                expanded_from,
             } = &case;
             // We are in the following situation:
             //
@@ -204,6 +266,7 @@ impl WithExpectedType<'_> {
                                     params: args_out,
                                 },
                                 body: None,
+                                expanded_from: expanded_from.clone(),
                             };
 
                             cases_out.push(case_out);
@@ -314,8 +377,29 @@ impl WithExpectedType<'_> {
                             };
                             let body_out = {
                                 let res = unify(ctx.levels(), constraint, span)?;
-                                let unif = match res {
-                                    crate::index_unification::dec::Dec::Yes(unif) => unif,
+                                match res {
+                                    crate::index_unification::dec::Dec::Yes(unif) => ctx
+                                        .fork::<TcResult<_>, _>(|ctx| {
+                                            let type_info_table = ctx.type_info_table.clone();
+                                            ctx.subst(&type_info_table, &unif)?;
+                                            let body = body.subst(&mut ctx.levels(), &unif)?;
+
+                                            let t_subst =
+                                                ret_typ_nf.subst(&mut ctx.levels(), &unif)?;
+                                            let t_nf = t_subst
+                                                .normalize(&ctx.type_info_table, &mut ctx.env())?;
+
+                                            let body_out = body.check(ctx, &t_nf)?;
+
+                                            Ok(Some(body_out))
+                                        })?,
+                                    // A case expanded from a catch-all clause is absurd if
+                                    // unification fails.
+                                    crate::index_unification::dec::Dec::No
+                                        if expanded_from.is_some() =>
+                                    {
+                                        None
+                                    }
                                     crate::index_unification::dec::Dec::No => {
                                         // The pattern is absurd.
                                         let err = TypeError::PatternIsAbsurd {
@@ -324,21 +408,7 @@ impl WithExpectedType<'_> {
                                         };
                                         return Err(err.into());
                                     }
-                                };
-
-                                ctx.fork::<TcResult<_>, _>(|ctx| {
-                                    let type_info_table = ctx.type_info_table.clone();
-                                    ctx.subst(&type_info_table, &unif)?;
-                                    let body = body.subst(&mut ctx.levels(), &unif)?;
-
-                                    let t_subst = ret_typ_nf.subst(&mut ctx.levels(), &unif)?;
-                                    let t_nf =
-                                        t_subst.normalize(&ctx.type_info_table, &mut ctx.env())?;
-
-                                    let body_out = body.check(ctx, &t_nf)?;
-
-                                    Ok(Some(body_out))
-                                })?
+                                }
                             };
 
                             let case_out = Case {
@@ -350,6 +420,7 @@ impl WithExpectedType<'_> {
                                     params: args_out,
                                 },
                                 body: body_out,
+                                expanded_from: expanded_from.clone(),
                             };
 
                             cases_out.push(case_out);
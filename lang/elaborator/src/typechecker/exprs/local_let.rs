@@ -0,0 +1,107 @@
+//! Bidirectional type checking for local let bindings
+
+use ast::ctx::values::{Binder, Binding, BoundValue};
+use ast::ctx::{BindContext, LevelCtx};
+use ast::*;
+
+use super::super::ctx::*;
+use super::{check_is_type, CheckInfer, ExpectType};
+use crate::normalizer::env::ToEnv;
+use crate::normalizer::normalize::Normalize;
+use crate::result::TcResult;
+
+impl CheckInfer for LocalLet {
+    /// The *checking* rule for local let bindings is:
+    /// ```text
+    ///            P, Γ ⊢ e ⇒ τ
+    ///            P, Γ, x : τ := e ⊢ b ⇐ σ
+    ///           ──────────────────────────────
+    ///            P, Γ ⊢ let x := e; b ⇐ σ
+    /// ```
+    fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
+        let LocalLet { span, name, body, .. } = self;
+        let (typ_out, bound_out, bound_typ) = infer_bound(ctx, self)?;
+        let binder = let_binder(name, &bound_out, &bound_typ);
+        let t_shifted = shift_and_clone(t, (1, 0));
+        let body_out = ctx.bind_single(binder, |ctx| body.check(ctx, &t_shifted))?;
+        Ok(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ_out,
+            bound: bound_out,
+            body: body_out,
+            bound_type: Some(bound_typ),
+            inferred_type: Some(Box::new(t.clone())),
+        })
+    }
+
+    /// The *inference* rule for local let bindings is:
+    /// ```text
+    ///            P, Γ ⊢ e ⇒ τ
+    ///            P, Γ, x : τ := e ⊢ b ⇒ σ
+    ///            P, Γ ⊢ σ[e/x] ▷ σ'
+    ///           ──────────────────────────────
+    ///            P, Γ ⊢ let x := e; b ⇒ σ'
+    /// ```
+    fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
+        let LocalLet { span, name, body, .. } = self;
+        let (typ_out, bound_out, bound_typ) = infer_bound(ctx, self)?;
+        let binder = let_binder(name, &bound_out, &bound_typ);
+        let body_out = ctx.bind_single(binder, |ctx| body.infer(ctx))?;
+
+        // The type of the body may mention `x`, so we substitute the bound expression for it
+        // to obtain a type which is well-formed in the outer context.
+        let body_typ = body_out.expect_typ()?;
+        let binder = Binder { name: name.clone(), content: () };
+        let mut subst_ctx = ctx.levels().append(&LevelCtx::from(vec![vec![binder]]));
+        let subst = Assign {
+            lvl: Lvl { fst: subst_ctx.len() - 1, snd: 0 },
+            exp: shift_and_clone(&bound_out, (1, 0)),
+        };
+        let mut typ = body_typ.subst(&mut subst_ctx, &subst)?;
+        typ.shift((-1, 0));
+        let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+
+        Ok(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ_out,
+            bound: bound_out,
+            body: body_out,
+            bound_type: Some(bound_typ),
+            inferred_type: Some(typ_nf),
+        })
+    }
+}
+
+/// Check the bound expression against the type annotation, if present, or infer its type otherwise.
+/// Returns the elaborated annotation, the elaborated bound expression and its normalized type.
+#[allow(clippy::type_complexity)]
+fn infer_bound(
+    ctx: &mut Ctx,
+    local_let: &LocalLet,
+) -> TcResult<(Option<Box<Exp>>, Box<Exp>, Box<Exp>)> {
+    let LocalLet { typ, bound, .. } = local_let;
+    match typ {
+        Some(typ) => {
+            let typ_out = check_is_type(typ, ctx)?;
+            let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+            let bound_out = bound.check(ctx, &typ_nf)?;
+            Ok((Some(typ_out), bound_out, typ_nf))
+        }
+        None => {
+            let bound_out = bound.infer(ctx)?;
+            let typ_nf = bound_out.expect_typ()?;
+            Ok((None, bound_out, typ_nf))
+        }
+    }
+}
+
+/// The binder for the let-bound variable, which we treat as a 1-element telescope
+fn let_binder(name: &VarBind, bound: &Exp, typ: &Exp) -> Binder<Binding> {
+    let binding = Binding {
+        typ: Box::new(typ.clone()),
+        val: Some(BoundValue::LetBinding { val: Box::new(bound.clone()) }),
+    };
+    shift_and_clone(&Binder { name: name.clone(), content: binding }, (1, 0))
+}
@@ -13,7 +13,7 @@ use crate::index_unification::unify::*;
 use crate::normalizer::env::ToEnv;
 use crate::normalizer::normalize::Normalize;
 use crate::result::{TcResult, TypeError};
-use crate::typechecker::exprs::CheckTelescope;
+use crate::typechecker::exprs::{check_is_type, CheckTelescope};
 use crate::typechecker::type_info_table::CtorMeta;
 
 use super::super::ctx::*;
@@ -66,9 +66,7 @@ fn compute_motive(
         Binder { name: param.name.clone(), content: Binding::from_type(self_t_nf.clone()) };
 
     // Typecheck the motive
-    let ret_typ_out = ctx.bind_single(self_binder.clone(), |ctx| {
-        ret_typ.check(ctx, &Box::new(TypeUniv::new().into()))
-    })?;
+    let ret_typ_out = ctx.bind_single(self_binder.clone(), |ctx| check_is_type(ret_typ, ctx))?;
 
     // Ensure that the motive matches the expected type
     let motive_binder = Binder { name: motive.param.name.clone(), content: () };
@@ -98,7 +96,7 @@ fn compute_motive(
 
 impl CheckInfer for LocalMatch {
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
-        let LocalMatch { span, name, on_exp, motive, cases, .. } = self;
+        let LocalMatch { span, name, on_exp, motive, cases, catch_all, .. } = self;
 
         // Compute the type of the expression we are pattern matching on.
         // This should always be a type constructor for a data type.
@@ -117,8 +115,9 @@ impl CheckInfer for LocalMatch {
             None => (None, Box::new(shift_and_clone(t, (1, 0)))),
         };
 
+        let cases = expand_catch_all(ctx, cases, catch_all.as_ref(), &on_exp_typ)?;
         let with_scrutinee_type = WithScrutineeType {
-            cases,
+            cases: &cases,
             scrutinee_type: on_exp_typ.clone(),
             scrutinee_name: VarBind::Wildcard { span: None },
         };
@@ -133,6 +132,7 @@ impl CheckInfer for LocalMatch {
             motive: motive_out,
             ret_typ: Some(Box::new(t.clone())),
             cases,
+            catch_all: None,
             inferred_type: Some(on_exp_typ),
         })
     }
@@ -142,6 +142,109 @@ impl CheckInfer for LocalMatch {
     }
 }
 
+/// Expand the catch-all clause of a pattern match on the data type `typ` to a case for every
+/// constructor without a case
+///
+/// The case for the constructor `C` expanded from the clause `x => e` binds the matched value by a
+/// local let, as in `C(y₁, ..., yₙ) => let x := C(y₁, ..., yₙ); e`.
+pub fn expand_catch_all(
+    ctx: &Ctx,
+    cases: &[Case],
+    catch_all: Option<&CatchAll>,
+    typ: &TypCtor,
+) -> TcResult<Vec<Case>> {
+    let mut cases_out = cases.to_vec();
+    let Some(CatchAll { span, name, body }) = catch_all else {
+        return Ok(cases_out);
+    };
+    let data = ctx.type_info_table.lookup_data(&typ.name)?;
+    let remaining: Vec<&Ctor> = data
+        .ctors
+        .iter()
+        .filter(|ctor| !cases.iter().any(|case| case.pattern.name.id == ctor.name.id))
+        .collect();
+    if remaining.is_empty() {
+        return Err(TypeError::UnreachableClause { span: span.to_miette() }.into());
+    }
+
+    for ctor in remaining {
+        let ctor_name = IdBound {
+            span: None,
+            id: ctor.name.id.clone(),
+            uri: typ.name.uri.clone(),
+            qualifier: None,
+        };
+        let names: Vec<VarBind> = ctor
+            .params
+            .params
+            .iter()
+            .map(|param| match (name, &param.name) {
+                (VarBind::Wildcard { .. }, _) => VarBind::Wildcard { span: None },
+                (VarBind::Var { .. }, VarBind::Var { id, .. }) => {
+                    VarBind::Var { span: None, id: id.clone() }
+                }
+                (VarBind::Var { .. }, VarBind::Wildcard { .. }) => {
+                    VarBind::Var { span: None, id: "x".to_owned() }
+                }
+            })
+            .collect();
+        let body = body.as_ref().map(|body| match name {
+            VarBind::Wildcard { .. } => body.clone(),
+            VarBind::Var { .. } => {
+                let args = names
+                    .iter()
+                    .enumerate()
+                    .map(|(i, param)| Arg::UnnamedArg {
+                        arg: Box::new(Exp::Variable(Variable {
+                            span: None,
+                            idx: Idx { fst: 0, snd: names.len() - 1 - i },
+                            name: VarBound::from_string(&param.to_string()),
+                            inferred_type: None,
+                        })),
+                        erased: false,
+                    })
+                    .collect();
+                let value = Call {
+                    span: None,
+                    kind: CallKind::Constructor,
+                    name: ctor_name.clone(),
+                    args: Args { args },
+                    inferred_type: None,
+                };
+                // The body is moved from the context `[.., [x]]` to the context `[.., [y₁, ..., yₙ], [x]]`.
+                let mut body = body.clone();
+                body.shift_in_range(&(1..), (1, 0));
+                Box::new(
+                    LocalLet {
+                        span: None,
+                        name: name.clone(),
+                        typ: None,
+                        bound: Box::new(value.into()),
+                        body,
+                        bound_type: None,
+                        inferred_type: None,
+                    }
+                    .into(),
+                )
+            }
+        });
+        let params =
+            names.into_iter().map(|name| ParamInst { span: None, name, typ: None, erased: false });
+        cases_out.push(Case {
+            span: *span,
+            pattern: Pattern {
+                span: None,
+                is_copattern: false,
+                name: ctor_name,
+                params: TelescopeInst { params: params.collect() },
+            },
+            body,
+            expanded_from: Some(name.clone()),
+        });
+    }
+    Ok(cases_out)
+}
+
 pub struct WithScrutineeType<'a> {
     pub cases: &'a Vec<Case>,
     pub scrutinee_type: TypCtor,
@@ -200,6 +303,7 @@ impl WithScrutineeType<'_> {
                 span,
                 pattern: Pattern { name, params: args, span: pattern_span, .. },
                 body,
+                expanded_from,
             } = case;
             let CtorMeta { typ: TypCtor { args: def_args, .. }, params, .. } =
                 ctx.type_info_table.lookup_ctor(&name)?;
@@ -313,8 +417,27 @@ impl WithScrutineeType<'_> {
                             // The programmer wrote a non-absurd case. We therefore have to check
                             // that the unification succeeds.
                             let res = unify(ctx.levels(), constraint, &span)?;
-                            let unif = match res {
-                                crate::index_unification::dec::Dec::Yes(unif) => unif,
+                            match res {
+                                crate::index_unification::dec::Dec::Yes(unif) => ctx
+                                    .fork::<TcResult<_>, _>(|ctx| {
+                                        let type_info_table = ctx.type_info_table.clone();
+                                        ctx.subst(&type_info_table, &unif)?;
+                                        let body = body.subst(&mut ctx.levels(), &unif)?;
+
+                                        let t_subst = t.subst(&mut ctx.levels(), &unif)?;
+                                        let t_nf = t_subst
+                                            .normalize(&ctx.type_info_table, &mut ctx.env())?;
+
+                                        let body_out = body.check(ctx, &t_nf)?;
+
+                                        Ok(Some(body_out))
+                                    })?,
+                                // A case expanded from a catch-all clause is absurd if unification fails.
+                                crate::index_unification::dec::Dec::No
+                                    if expanded_from.is_some() =>
+                                {
+                                    None
+                                }
                                 crate::index_unification::dec::Dec::No => {
                                     // A right-hand side was provided in the clause, but unification fails.
                                     let err = TypeError::PatternIsAbsurd {
@@ -323,21 +446,7 @@ impl WithScrutineeType<'_> {
                                     };
                                     return Err(err.into());
                                 }
-                            };
-
-                            ctx.fork::<TcResult<_>, _>(|ctx| {
-                                let type_info_table = ctx.type_info_table.clone();
-                                ctx.subst(&type_info_table, &unif)?;
-                                let body = body.subst(&mut ctx.levels(), &unif)?;
-
-                                let t_subst = t.subst(&mut ctx.levels(), &unif)?;
-                                let t_nf =
-                                    t_subst.normalize(&ctx.type_info_table, &mut ctx.env())?;
-
-                                let body_out = body.check(ctx, &t_nf)?;
-
-                                Ok(Some(body_out))
-                            })?
+                            }
                         }
                         None => {
                             // The programmer wrote an absurd case. We therefore have to check whether
@@ -364,6 +473,7 @@ impl WithScrutineeType<'_> {
                             params: args_out,
                         },
                         body: body_out,
+                        expanded_from: expanded_from.clone(),
                     };
                     cases_out.push(case_out);
                     Ok(())
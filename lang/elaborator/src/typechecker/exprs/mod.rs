@@ -2,7 +2,9 @@ pub mod anno;
 pub mod call;
 pub mod dot_call;
 pub mod hole;
+pub mod level;
 pub mod local_comatch;
+pub mod local_let;
 pub mod local_match;
 pub mod typ_ctor;
 pub mod type_univ;
@@ -19,6 +21,7 @@ use ast::ctx::LevelCtx;
 use ast::*;
 
 use super::ctx::*;
+use crate::conversion_checking::convert;
 use crate::normalizer::{env::ToEnv, normalize::Normalize};
 use crate::result::{TcResult, TypeError};
 
@@ -58,6 +61,23 @@ impl<T: CheckInfer> CheckInfer for Box<T> {
     }
 }
 
+/// Check that `typ` is a type, i.e. a term of some universe
+///
+/// Without levels, every type is a term of the universe `Type`. In predicative modules, the level
+/// of the universe is inferred instead, so that both `Nat` and `Type 0` are types. Holes cannot be
+/// inferred and are checked against `Type`.
+pub fn check_is_type(typ: &Exp, ctx: &mut Ctx) -> TcResult<Box<Exp>> {
+    if !ctx.module.is_predicative() || matches!(typ, Exp::Hole(_)) {
+        return typ.check(ctx, &TypeUniv::new().into()).map(Box::new);
+    }
+    let typ_out = typ.infer(ctx)?;
+    let universe = typ_out.expect_typ()?.normalize(&ctx.type_info_table, &mut ctx.env())?;
+    if !matches!(&*universe, Exp::TypeUniv(_)) {
+        convert(&ctx.vars, &mut ctx.meta_vars, universe, &TypeUniv::new().into(), &typ.span())?;
+    }
+    Ok(Box::new(typ_out))
+}
+
 trait ExpectType {
     fn expect_typ(&self) -> TcResult<Box<Exp>>;
 }
@@ -85,9 +105,12 @@ impl CheckInfer for Exp {
             Exp::DotCall(e) => Ok(e.check(ctx, t)?.into()),
             Exp::Anno(e) => Ok(e.check(ctx, t)?.into()),
             Exp::TypeUniv(e) => Ok(e.check(ctx, t)?.into()),
+            Exp::LevelUniv(e) => Ok(e.check(ctx, t)?.into()),
+            Exp::Level(e) => Ok(e.check(ctx, t)?.into()),
             Exp::Hole(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalMatch(e) => Ok(e.check(ctx, t)?.into()),
             Exp::LocalComatch(e) => Ok(e.check(ctx, t)?.into()),
+            Exp::LocalLet(e) => Ok(e.check(ctx, t)?.into()),
         }
     }
 
@@ -99,9 +122,12 @@ impl CheckInfer for Exp {
             Exp::DotCall(e) => Ok(e.infer(ctx)?.into()),
             Exp::Anno(e) => Ok(e.infer(ctx)?.into()),
             Exp::TypeUniv(e) => Ok(e.infer(ctx)?.into()),
+            Exp::LevelUniv(e) => Ok(e.infer(ctx)?.into()),
+            Exp::Level(e) => Ok(e.infer(ctx)?.into()),
             Exp::Hole(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalMatch(e) => Ok(e.infer(ctx)?.into()),
             Exp::LocalComatch(e) => Ok(e.infer(ctx)?.into()),
+            Exp::LocalLet(e) => Ok(e.infer(ctx)?.into()),
         };
         trace!(
             "{} |- {} => {}",
@@ -231,7 +257,7 @@ impl CheckTelescope for TelescopeInst {
             |ctx, params_out, (param_actual, param_expected)| {
                 let ParamInst { span, name, .. } = param_actual;
                 let Param { typ, erased, .. } = param_expected;
-                let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
+                let typ_out = check_is_type(typ, ctx)?;
                 let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
                 let param_out = ParamInst {
                     span: *span,
@@ -264,7 +290,7 @@ impl InferTelescope for Telescope {
             vec![],
             |ctx, params_out, param| {
                 let Param { implicit, typ, name, erased } = param;
-                let typ_out = typ.check(ctx, &Box::new(TypeUniv::new().into()))?;
+                let typ_out = check_is_type(typ, ctx)?;
                 let typ_nf = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
                 let param_out = Param {
                     implicit: *implicit,
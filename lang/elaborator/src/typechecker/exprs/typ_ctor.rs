@@ -1,5 +1,6 @@
 //! Bidirectional type checking for type constructors
 
+use ast::ctx::LevelCtx;
 use ast::*;
 
 use super::super::ctx::*;
@@ -7,6 +8,7 @@ use super::check_args;
 use super::CheckInfer;
 use super::ExpectType;
 use crate::conversion_checking::convert;
+use crate::normalizer::{env::ToEnv, normalize::Normalize};
 use crate::result::TcResult;
 
 impl CheckInfer for TypCtor {
@@ -17,9 +19,15 @@ impl CheckInfer for TypCtor {
     ///           ──────────────────
     ///            P, Γ ⊢ Tσ ⇐ τ
     /// ```
+    /// In modules with the `#![predicative]` attribute, `ρ` is the universe computed by [universe].
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
         let inferred_term = self.infer(ctx)?;
-        let inferred_typ = inferred_term.expect_typ()?;
+        let inferred_typ = if ctx.module.is_predicative() {
+            let level = universe(&inferred_term, ctx)?;
+            Box::new(TypeUniv::with_level(level.into_exp()).into())
+        } else {
+            inferred_term.expect_typ()?
+        };
         convert(&ctx.vars, &mut ctx.meta_vars, inferred_typ, t, &self.span())?;
         Ok(inferred_term)
     }
@@ -44,3 +52,23 @@ impl CheckInfer for TypCtor {
         })
     }
 }
+
+/// The level `l` of the universe `Type l` which contains the type constructor application `Tσ`.
+///
+/// The level is the maximum of the levels `lᵢ` of all parameters of `T` with type `Type lᵢ`,
+/// after the arguments `σ` have been substituted into the parameters.
+pub fn universe(typ_ctor: &TypCtor, ctx: &mut Ctx) -> TcResult<Level> {
+    let TypCtor { name, args, .. } = typ_ctor;
+    let params = ctx.type_info_table.lookup_tyctor(name)?.params.clone();
+    let Telescope { params } =
+        params.subst_in_telescope(LevelCtx::empty(), &vec![args.args.clone()])?;
+
+    let mut level = Level::constant(0);
+    for Param { typ, .. } in params {
+        let typ = typ.normalize(&ctx.type_info_table, &mut ctx.env())?;
+        if let Exp::TypeUniv(TypeUniv { level: Some(param_level), .. }) = &*typ {
+            level = level.max(&Level::from_exp(param_level));
+        }
+    }
+    Ok(level)
+}
@@ -14,13 +14,15 @@ use crate::result::TcResult;
 impl CheckInfer for TypeUniv {
     /// The *checking* rule for the type universe is:
     /// ```text
-    ///            P, Γ ⊢ τ ≃ Type
+    ///            P, Γ ⊢ Type l ⇒ τ'
+    ///            P, Γ ⊢ τ' ≤ τ
     ///           ──────────────────
-    ///            P, Γ ⊢ Type ⇐ τ
+    ///            P, Γ ⊢ Type l ⇐ τ
     /// ```
     fn check(&self, ctx: &mut Ctx, t: &Exp) -> TcResult<Self> {
-        convert(&ctx.vars, &mut ctx.meta_vars, Box::new(TypeUniv::new().into()), t, &self.span())?;
-        Ok(self.clone())
+        let inferred = self.infer(ctx)?;
+        convert(&ctx.vars, &mut ctx.meta_vars, inferred.typ().unwrap(), t, &self.span())?;
+        Ok(inferred)
     }
 
     /// The *inference* rule for the type universe is:
@@ -28,9 +30,21 @@ impl CheckInfer for TypeUniv {
     ///           ─────────────────────
     ///            P, Γ ⊢ Type ⇒ Type
     /// ```
-    /// Note: The type universe is impredicative and the theory
+    /// Note: Without a level, the type universe is impredicative and the theory
     /// therefore inconsistent.
-    fn infer(&self, _ctx: &mut Ctx) -> TcResult<Self> {
-        Ok(self.clone())
+    ///
+    /// In modules with the `#![predicative]` attribute every universe has a level:
+    /// ```text
+    ///            P, Γ ⊢ l ⇐ Level
+    ///           ──────────────────────────
+    ///            P, Γ ⊢ Type l ⇒ Type (l + 1)
+    /// ```
+    fn infer(&self, ctx: &mut Ctx) -> TcResult<Self> {
+        let TypeUniv { span, level } = self;
+        let level = match level {
+            Some(level) => Some(level.check(ctx, &LevelUniv { span: None }.into())?),
+            None => None,
+        };
+        Ok(TypeUniv { span: *span, level })
     }
 }
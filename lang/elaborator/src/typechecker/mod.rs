@@ -1,9 +1,9 @@
 pub mod ctx;
 pub mod decls;
-mod erasure;
+pub mod erasure;
 pub mod exprs;
 pub mod type_info_table;
 pub mod util;
 
 pub use crate::result::TypeError;
-pub use decls::check_with_lookup_table;
+pub use decls::{check_partially_with_lookup_table, check_with_lookup_table};
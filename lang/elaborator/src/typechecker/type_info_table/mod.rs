@@ -1,6 +1,8 @@
 use ast::*;
 use url::Url;
 
+use crate::normalizer::fuel::{EvalLimits, Fuel};
+
 use super::TypeError;
 
 pub mod build;
@@ -9,12 +11,22 @@ pub mod lookup;
 #[derive(Debug, Clone, Default)]
 pub struct TypeInfoTable {
     map: HashMap<Url, ModuleTypeInfoTable>,
+    /// The fuel of the normalizer when evaluating with this table
+    fuel: Fuel,
 }
 
 impl TypeInfoTable {
     pub fn insert(&mut self, uri: Url, info_table: ModuleTypeInfoTable) {
         self.map.insert(uri, info_table);
     }
+
+    pub fn set_eval_limits(&mut self, limits: EvalLimits) {
+        self.fuel = Fuel::new(limits);
+    }
+
+    pub fn fuel(&self) -> &Fuel {
+        &self.fuel
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -3,7 +3,7 @@ use miette_util::codespan::Span;
 use miette_util::ToMiette;
 
 use ast::ctx::{BindContext, LevelCtx};
-use ast::{self, MetaVar, MetaVarKind, MetaVarState, VarBound};
+use ast::{self, MetaVar, MetaVarKind, MetaVarState, Shift, VarBound};
 use ast::{HashMap, HashSet};
 use ast::{Idx, Lvl};
 use parser::cst::ident::Ident;
@@ -19,6 +19,10 @@ pub struct Ctx {
     ///
     /// Used to convert names to De-Bruijn indices
     binders: LevelCtx,
+    /// The original names of the binders renamed by `with_renamed_binders`
+    renamed: Vec<(Lvl, ast::VarBind)>,
+    /// Local names which stand for an expression instead of a bound variable
+    aliases: Vec<Alias>,
     /// Metadata for top-level names
     pub symbol_table: SymbolTable,
     /// Counter for unique label ids
@@ -31,18 +35,23 @@ pub struct Ctx {
     pub meta_vars: HashMap<MetaVar, MetaVarState>,
     /// URI of the current module
     pub uri: Url,
+    /// Whether the current module has the `#![predicative]` attribute
+    pub predicative: bool,
 }
 
 impl Ctx {
     pub fn empty(uri: Url, symbol_table: SymbolTable) -> Self {
         Self {
             binders: LevelCtx::empty(),
+            renamed: vec![],
+            aliases: vec![],
             symbol_table,
             next_label_id: 0,
             user_labels: HashSet::default(),
             next_meta_var: 0,
             meta_vars: HashMap::default(),
             uri,
+            predicative: false,
         }
     }
 
@@ -62,6 +71,46 @@ impl Ctx {
         None
     }
 
+    /// Lookup a local name which stands for an expression.
+    ///
+    /// Returns `None` if the name is not an alias or if the alias is shadowed by a bound variable.
+    pub fn lookup_alias(&self, name: &Ident) -> Option<ast::Exp> {
+        let alias = self.aliases.iter().rev().find(|alias| alias.name == name.id)?;
+        let is_shadowed = self.binders.bound[alias.depth..].iter().any(|inner| {
+            inner.iter().any(
+                |binder| matches!(&binder.name, ast::VarBind::Var { id, .. } if id == &name.id),
+            )
+        });
+        if is_shadowed {
+            return None;
+        }
+        let mut exp = alias.exp.clone();
+        exp.shift(((self.binders.len() - alias.depth) as isize, 0));
+        Some(exp)
+    }
+
+    /// Check whether the name is the alias of an imported module which is not shadowed by a local name.
+    pub fn is_qualifier(&self, name: &Ident) -> bool {
+        self.symbol_table.is_alias(name)
+            && self.lookup_local(name).is_none()
+            && self.lookup_alias(name).is_none()
+    }
+
+    /// Run `f` with the local name `name` standing for the expression `exp`
+    ///
+    /// The expression `exp` must be well-scoped in the current context.
+    pub fn with_alias<T, F: FnOnce(&mut Ctx) -> T>(
+        &mut self,
+        name: String,
+        exp: ast::Exp,
+        f: F,
+    ) -> T {
+        self.aliases.push(Alias { name, depth: self.binders.len(), exp });
+        let res = f(self);
+        self.aliases.pop();
+        res
+    }
+
     pub fn unique_label(
         &mut self,
         user_name: Option<Ident>,
@@ -101,8 +150,47 @@ impl Ctx {
         })
     }
 
+    /// The De-Bruijn level of the next telescope bound in this context
+    pub fn next_level(&self) -> usize {
+        self.binders.len()
+    }
+
+    /// Run `f` with the local variables at the given levels renamed
+    ///
+    /// This is used to lower the body of a clause whose variables are bound by a case tree which is
+    /// shared with other clauses. Variables which the clause does not name are renamed to wildcards.
+    pub fn with_renamed_binders<T, F: FnOnce(&mut Ctx) -> T>(
+        &mut self,
+        names: Vec<(Lvl, ast::VarBind)>,
+        f: F,
+    ) -> T {
+        let renamed_len = self.renamed.len();
+        for (lvl, name) in names {
+            let binder = &mut self.binders.bound[lvl.fst][lvl.snd];
+            self.renamed.push((lvl, std::mem::replace(&mut binder.name, name)));
+        }
+        let res = f(self);
+        for (lvl, name) in self.renamed.drain(renamed_len..) {
+            self.binders.bound[lvl.fst][lvl.snd].name = name;
+        }
+        res
+    }
+
+    /// The name of the variable with the given De-Bruijn index, as it is bound in the syntax tree
+    ///
+    /// This differs from the name `name` used to refer to the variable if its binder has been renamed.
+    pub fn bound_name(&self, idx: Idx, name: &Ident) -> VarBound {
+        let lvl = self.binders.idx_to_lvl(idx);
+        match self.renamed.iter().rev().find(|(other, _)| *other == lvl) {
+            Some((_, ast::VarBind::Var { id, .. })) => {
+                VarBound { span: Some(name.span), id: id.clone() }
+            }
+            _ => VarBound { span: Some(name.span), id: name.id.clone() },
+        }
+    }
+
     /// Convert the given De-Bruijn level to a De-Bruijn index
-    fn level_to_index(&self, lvl: Lvl) -> Idx {
+    pub fn level_to_index(&self, lvl: Lvl) -> Idx {
         let fst = self.binders.len() - 1 - lvl.fst;
         let snd = self.binders.bound[lvl.fst].len() - 1 - lvl.snd;
         Idx { fst, snd }
@@ -155,6 +243,14 @@ impl Ctx {
     }
 }
 
+/// A local name which stands for an expression
+struct Alias {
+    name: String,
+    /// The number of telescopes bound when the alias was introduced
+    depth: usize,
+    exp: ast::Exp,
+}
+
 impl BindContext for Ctx {
     type Content = ();
 
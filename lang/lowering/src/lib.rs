@@ -3,7 +3,7 @@ mod lower;
 mod result;
 mod symbol_table;
 
-use ast::{self};
+use ast::{self, HashMap, MetaVar, MetaVarState};
 use parser::cst;
 
 use crate::lower::Lower;
@@ -12,6 +12,8 @@ pub use ctx::*;
 pub use result::*;
 pub use symbol_table::build::build_symbol_table;
 pub use symbol_table::DeclMeta;
+pub use symbol_table::Export;
+pub use symbol_table::Import;
 pub use symbol_table::ModuleSymbolTable;
 pub use symbol_table::SymbolTable;
 
@@ -22,10 +24,28 @@ pub fn lower_module_with_symbol_table(
     prg: &cst::decls::Module,
     symbol_table: &SymbolTable,
 ) -> LoweringResult<ast::Module> {
+    symbol_table.check_imports()?;
     let mut ctx = Ctx::empty(prg.uri.clone(), symbol_table.clone());
 
+    let attr = prg.attr.lower(&mut ctx)?;
+    ctx.predicative = attr.attrs.contains(&ast::Attribute::Predicative);
     let use_decls = prg.use_decls.lower(&mut ctx)?;
-    let decls = prg.decls.lower(&mut ctx)?;
+    let decls = prg.decls.lower(&mut ctx)?.into_iter().flatten().collect();
 
-    Ok(ast::Module { uri: prg.uri.clone(), use_decls, decls, meta_vars: ctx.meta_vars })
+    Ok(ast::Module { uri: prg.uri.clone(), attr, use_decls, decls, meta_vars: ctx.meta_vars })
+}
+
+/// Lower a standalone expression, such as the input of a REPL, in the scope of the module `prg`
+///
+/// Returns the expression together with the meta variables which were created for it.
+pub fn lower_exp_with_symbol_table(
+    exp: &cst::exp::Exp,
+    prg: &cst::decls::Module,
+    symbol_table: &SymbolTable,
+) -> LoweringResult<(ast::Exp, HashMap<MetaVar, MetaVarState>)> {
+    let mut ctx = Ctx::empty(prg.uri.clone(), symbol_table.clone());
+    let attr = prg.attr.lower(&mut ctx)?;
+    ctx.predicative = attr.attrs.contains(&ast::Attribute::Predicative);
+    let exp = exp.lower(&mut ctx)?;
+    Ok((exp, ctx.meta_vars))
 }
@@ -18,6 +18,7 @@ impl Lower for cst::decls::Codef {
             let typ_ctor = typ
                 .to_typctor()
                 .ok_or(LoweringError::ExpectedTypCtor { span: span.to_miette() })?;
+            let (cases, catch_all) = lower_cocases(cases, ctx)?;
             Ok(ast::Codef {
                 span: Some(*span),
                 doc: doc.lower(ctx)?,
@@ -25,7 +26,8 @@ impl Lower for cst::decls::Codef {
                 attr: attr.lower(ctx)?,
                 params,
                 typ: typ_ctor,
-                cases: cases.lower(ctx)?,
+                cases,
+                catch_all,
             })
         })
     }
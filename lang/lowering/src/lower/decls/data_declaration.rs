@@ -53,6 +53,7 @@ fn lower_constructor(
                             span: Some(typ_name.span),
                             id: typ_name.id.clone(),
                             uri: ctx.uri.clone(),
+                            qualifier: None,
                         },
                         args: ast::Args { args: vec![] },
                         is_bin_op: None,
@@ -1,4 +1,4 @@
-use ast::IdBind;
+use ast::{HasSpan, IdBind};
 use parser::cst::{self};
 
 use super::super::*;
@@ -16,18 +16,33 @@ impl Lower for cst::decls::Def {
         let self_param: cst::decls::SelfParam = scrutinee.clone().into();
 
         lower_telescope(params, ctx, |ctx, params| {
-            let cases = cases.lower(ctx)?;
-            lower_self_param(&self_param, ctx, |ctx, self_param| {
-                Ok(ast::Def {
-                    span: Some(*span),
-                    doc: doc.lower(ctx)?,
-                    name: IdBind { span: Some(name.span), id: name.id.clone() },
-                    attr: attr.lower(ctx)?,
-                    params,
-                    self_param,
-                    ret_typ: ret_typ.lower(ctx)?,
-                    cases,
-                })
+            let header = lower_self_param(&self_param, ctx, |ctx, self_param| {
+                Ok((self_param, ret_typ.lower(ctx)?))
+            });
+            // The return type is the motive of the cases, which may depend on the self parameter.
+            let motive = header.as_ref().ok().map(|(self_param, ret_typ)| ast::Motive {
+                span: None,
+                param: ast::ParamInst {
+                    span: self_param.name.span(),
+                    name: self_param.name.clone(),
+                    typ: None,
+                    erased: false,
+                },
+                ret_typ: ret_typ.clone(),
+            });
+            // Errors in the cases are reported before errors in the signature.
+            let (cases, catch_all) = lower_cases(cases, motive.as_ref(), ctx)?;
+            let (self_param, ret_typ) = header?;
+            Ok(ast::Def {
+                span: Some(*span),
+                doc: doc.lower(ctx)?,
+                name: IdBind { span: Some(name.span), id: name.id.clone() },
+                attr: attr.lower(ctx)?,
+                params,
+                self_param,
+                ret_typ,
+                cases,
+                catch_all,
             })
         })
     }
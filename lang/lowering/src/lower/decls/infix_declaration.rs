@@ -2,15 +2,24 @@ use miette_util::ToMiette;
 use parser::cst;
 
 use super::super::*;
+use crate::symbol_table::build::infix_fixity;
 
 impl Lower for cst::decls::Infix {
     type Target = ast::Infix;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::decls::Infix { span, doc, lhs, rhs } = self;
+        let cst::decls::Infix { span, doc, lhs, rhs, .. } = self;
 
         // Check that LHS is of the form `_ + _`
-        if !(lhs.lhs.is_underscore() && lhs.rhs.is_underscore()) {
+        let [(operator, rhs_operand)] = lhs.rest.as_slice() else {
+            return Err(LoweringError::InvalidInfixDeclaration {
+                message: "The left hand side of an infix declaration must have the form \"_ + _\"."
+                    .to_owned(),
+                span: lhs.span.to_miette(),
+            }
+            .into());
+        };
+        if !(lhs.lhs.is_underscore() && rhs_operand.is_underscore()) {
             return Err(LoweringError::InvalidInfixDeclaration {
                 message: "The left hand side of an infix declaration must have the form \"_ + _\"."
                     .to_owned(),
@@ -47,7 +56,8 @@ impl Lower for cst::decls::Infix {
             span: Some(*span),
             doc: doc.lower(ctx)?,
             attr: Default::default(),
-            lhs: lhs.operator.id.clone(),
+            fixity: infix_fixity(self)?,
+            lhs: operator.id.clone(),
             rhs: rhs.name.id.clone(),
         })
     }
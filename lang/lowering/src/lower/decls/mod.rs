@@ -35,6 +35,10 @@ fn parse_attribute(s: String) -> ast::Attribute {
         "omit_print" => ast::Attribute::OmitPrint,
         "transparent" => ast::Attribute::Transparent,
         "opaque" => ast::Attribute::Opaque,
+        "non_terminating" => ast::Attribute::NonTerminating,
+        "no_positivity_check" => ast::Attribute::NoPositivityCheck,
+        "predicative" => ast::Attribute::Predicative,
+        "private" => ast::Attribute::Private,
         v => ast::Attribute::Other(v.to_string()),
     }
 }
@@ -54,8 +58,24 @@ impl Lower for cst::decls::UseDecl {
     type Target = ast::UseDecl;
 
     fn lower(&self, _ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::decls::UseDecl { span, path } = self;
-        Ok(ast::UseDecl { span: *span, path: path.clone() })
+        let cst::decls::UseDecl { span, public, path, alias, filter } = self;
+        let lower_ident =
+            |name: &cst::ident::Ident| ast::IdBind { span: Some(name.span), id: name.id.clone() };
+        let filter = filter.as_ref().map(|filter| match filter {
+            cst::decls::ImportFilter::Only(items) => {
+                ast::ImportFilter::Only(items.iter().map(lower_ident).collect())
+            }
+            cst::decls::ImportFilter::Hiding(items) => {
+                ast::ImportFilter::Hiding(items.iter().map(lower_ident).collect())
+            }
+        });
+        Ok(ast::UseDecl {
+            span: *span,
+            public: *public,
+            path: path.clone(),
+            alias: alias.as_ref().map(lower_ident),
+            filter,
+        })
     }
 }
 
@@ -63,8 +83,9 @@ impl Lower for cst::decls::UseDecl {
 //
 //
 
+/// Declarations which could not be parsed are omitted from the lowered module.
 impl Lower for cst::decls::Decl {
-    type Target = ast::Decl;
+    type Target = Option<ast::Decl>;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         let decl = match self {
@@ -74,8 +95,9 @@ impl Lower for cst::decls::Decl {
             cst::decls::Decl::Codef(codef) => codef.lower(ctx)?.into(),
             cst::decls::Decl::Let(tl_let) => tl_let.lower(ctx)?.into(),
             cst::decls::Decl::Infix(infix) => infix.lower(ctx)?.into(),
+            cst::decls::Decl::Error(_) => return Ok(None),
         };
-        Ok(decl)
+        Ok(Some(decl))
     }
 }
 
@@ -111,9 +133,10 @@ fn desugar_telescope(tel: &cst::decls::Telescope) -> cst::decls::Telescope {
     cst::decls::Telescope(params)
 }
 fn desugar_param(param: &cst::decls::Param) -> Vec<cst::decls::Param> {
-    let cst::decls::Param { implicit, name, names, typ } = param;
+    let cst::decls::Param { implicit, erased, name, names, typ } = param;
     let mut params: Vec<cst::decls::Param> = vec![cst::decls::Param {
         implicit: *implicit,
+        erased: *erased,
         name: name.clone(),
         names: vec![],
         typ: typ.clone(),
@@ -121,6 +144,7 @@ fn desugar_param(param: &cst::decls::Param) -> Vec<cst::decls::Param> {
     for extra_name in names {
         params.push(cst::decls::Param {
             implicit: *implicit,
+            erased: *erased,
             name: extra_name.clone(),
             names: vec![],
             typ: typ.clone(),
@@ -142,11 +166,15 @@ where
         tel.0.iter(),
         vec![],
         |ctx, params_out, param| -> LoweringResult<Binder<()>> {
-            let cst::decls::Param { implicit, name, names: _, typ } = param; // The `names` field has been removed by `desugar_telescope`.
+            let cst::decls::Param { implicit, erased, name, names: _, typ } = param; // The `names` field has been removed by `desugar_telescope`.
             let typ_out = typ.lower(ctx)?;
             let name = name.lower(ctx)?;
-            let param_out =
-                ast::Param { implicit: *implicit, name: name.clone(), typ: typ_out, erased: false };
+            let param_out = ast::Param {
+                implicit: *implicit,
+                name: name.clone(),
+                typ: typ_out,
+                erased: *erased,
+            };
             params_out.push(param_out);
             Ok(Binder { name, content: () })
         },
@@ -5,6 +5,8 @@ use printer::Print;
 
 use crate::{lower::Lower, Ctx, LoweringError, LoweringResult};
 
+use super::level::lower_level;
+
 /// Lowers a list of arguments, ensuring that named arguments match the expected parameter names.
 ///
 /// This function processes a mix of named and unnamed arguments provided by the user (`given`)
@@ -80,6 +82,7 @@ pub fn lower_args(
     /// - `span`: The source span of the given argument list.
     /// - `given`: A mutable iterator over the given arguments.
     /// - `expected_bs`: The binding site of the expected parameter.
+    /// - `is_level`: Whether the expected parameter has the type `Level` of universe levels.
     /// - `args_out`: A mutable vector to collect the lowered arguments.
     /// - `ctx`: A mutable reference to the current context.
     ///
@@ -91,9 +94,18 @@ pub fn lower_args(
         span: Span,
         given: &mut impl Iterator<Item = &'a cst::exp::Arg>,
         expected_bs: &BindingSite,
+        is_level: bool,
         args_out: &mut Vec<ast::Arg>,
         ctx: &mut Ctx,
     ) -> LoweringResult {
+        // Arguments for parameters of type `Level` are universe levels.
+        let lower_arg = |exp: &cst::exp::Exp, ctx: &mut Ctx| {
+            if is_level {
+                lower_level(exp, ctx)
+            } else {
+                exp.lower(ctx)
+            }
+        };
         let Some(arg) = given.next() else {
             let expected = expected_bs.lower(ctx)?;
             let expected = expected.print_to_string(None);
@@ -103,7 +115,10 @@ pub fn lower_args(
         };
         match arg {
             cst::exp::Arg::UnnamedArg(exp) => {
-                args_out.push(ast::Arg::UnnamedArg { arg: exp.lower(ctx)?, erased: false });
+                args_out.push(ast::Arg::UnnamedArg {
+                    arg: Box::new(lower_arg(exp, ctx)?),
+                    erased: false,
+                });
             }
             cst::exp::Arg::NamedArg(name, exp) => {
                 let expected_name = match &expected_bs {
@@ -125,13 +140,19 @@ pub fn lower_args(
                     .into());
                 }
                 let name = VarBound { span: Some(name.span), id: name.id.clone() };
-                args_out.push(ast::Arg::NamedArg { name, arg: exp.lower(ctx)?, erased: false });
+                args_out.push(ast::Arg::NamedArg {
+                    name,
+                    arg: Box::new(lower_arg(exp, ctx)?),
+                    erased: false,
+                });
             }
         }
         Ok(())
     }
 
     for expected_param in expected.0.iter() {
+        let is_level = ctx.predicative
+            && matches!(&*expected_param.typ, cst::exp::Exp::Call(cst::exp::Call { name, args, .. }) if name.id == "Level" && args.is_empty());
         // Each parameter can have multiple names (e.g., aliases).
         let names_iter = std::iter::once(&expected_param.name).chain(expected_param.names.iter());
         for expected_bs in names_iter {
@@ -145,26 +166,14 @@ pub fn lower_args(
                         .into());
                     };
                     if expected_name == given_name {
-                        pop_arg(span, &mut given_iter, expected_bs, &mut args_out, ctx)?;
+                        pop_arg(span, &mut given_iter, expected_bs, is_level, &mut args_out, ctx)?;
                         continue;
                     }
                 }
 
-                let mv = ctx.fresh_metavar(Some(span), MetaVarKind::Inserted);
-                let args = ctx.subst_from_ctx();
-                let hole = Hole {
-                    span: None,
-                    kind: ast::MetaVarKind::Inserted,
-                    metavar: mv,
-                    inferred_type: None,
-                    inferred_ctx: None,
-                    args,
-                    solution: None,
-                };
-
-                args_out.push(ast::Arg::InsertedImplicitArg { hole, erased: false });
+                args_out.push(inserted_implicit_arg(span, ctx));
             } else {
-                pop_arg(span, &mut given_iter, expected_bs, &mut args_out, ctx)?;
+                pop_arg(span, &mut given_iter, expected_bs, is_level, &mut args_out, ctx)?;
             }
         }
     }
@@ -178,6 +187,24 @@ pub fn lower_args(
     Ok(ast::Args { args: args_out })
 }
 
+/// An argument for an implicit parameter which is not given explicitly
+///
+/// The argument is a fresh metavariable which is solved during typechecking.
+pub fn inserted_implicit_arg(span: Span, ctx: &mut Ctx) -> ast::Arg {
+    let mv = ctx.fresh_metavar(Some(span), MetaVarKind::Inserted);
+    let args = ctx.subst_from_ctx();
+    let hole = Hole {
+        span: None,
+        kind: ast::MetaVarKind::Inserted,
+        metavar: mv,
+        inferred_type: None,
+        inferred_ctx: None,
+        args,
+        solution: None,
+    };
+    ast::Arg::InsertedImplicitArg { hole, erased: false }
+}
+
 #[cfg(test)]
 mod lower_args_tests {
     use url::Url;
@@ -192,8 +219,9 @@ mod lower_args_tests {
     fn test_empty() {
         let given = vec![];
         let expected = Telescope(vec![]);
-        let mut ctx =
-            Ctx::empty(Url::parse("inmemory:///scratch.pol").unwrap(), SymbolTable::default());
+        let uri = Url::parse("inmemory:///scratch.pol").unwrap();
+        let symbol_table = SymbolTable::new(uri.clone(), Default::default());
+        let mut ctx = Ctx::empty(uri, symbol_table);
         let res = lower_args(Span::default(), &given, expected, &mut ctx);
         assert_eq!(res.unwrap(), ast::Args { args: vec![] })
     }
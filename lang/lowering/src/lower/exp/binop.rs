@@ -1,26 +1,126 @@
-use parser::cst::{self};
+use std::iter::Peekable;
 
-use crate::{lower::Lower, Ctx, LoweringResult};
+use ast::{Associativity, Fixity};
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+use parser::cst::{self, ident::Operator};
+
+use crate::{lower::Lower, Ctx, LoweringError, LoweringResult};
 
 impl Lower for cst::exp::BinOp {
     type Target = ast::Exp;
+
+    /// Lower a chain of binary operators
+    ///
+    /// The operands are lowered from left to right and then associated by precedence climbing
+    /// according to the fixities of the operators.
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::exp::BinOp { span, operator, lhs, rhs } = self;
-
-        let (id, _url) = ctx.symbol_table.lookup_operator(operator)?;
-        let (_, name) = ctx.symbol_table.lookup(id)?;
-
-        Ok(ast::TypCtor {
-            span: Some(*span),
-            name,
-            args: ast::Args {
-                args: vec![
-                    ast::Arg::UnnamedArg { arg: lhs.lower(ctx)?, erased: false },
-                    ast::Arg::UnnamedArg { arg: rhs.lower(ctx)?, erased: false },
-                ],
-            },
-            is_bin_op: Some(operator.id.clone()),
+        let cst::exp::BinOp { span: _, lhs, rest } = self;
+
+        let lhs = Operand { span: lhs.span(), exp: lhs.lower(ctx)? };
+        let mut rest = rest
+            .iter()
+            .map(|(operator, rhs)| {
+                let (meta, uri) = ctx.symbol_table.lookup_operator(operator)?;
+                // The operator may also stand for a name which its module imports.
+                let (_, name) = ctx
+                    .symbol_table
+                    .lookup_in(uri, &meta.name)
+                    .or_else(|_| ctx.symbol_table.lookup(&meta.name))?;
+                let operator = ResolvedOperator { operator, name, fixity: meta.fixity };
+                let rhs = Operand { span: rhs.span(), exp: rhs.lower(ctx)? };
+                Ok((operator, rhs))
+            })
+            .collect::<LoweringResult<Vec<_>>>()?
+            .into_iter()
+            .peekable();
+
+        Ok(*climb(lhs, 0, &mut rest)?.exp)
+    }
+}
+
+/// An operator together with the type constructor it stands for and its fixity
+struct ResolvedOperator<'a> {
+    operator: &'a Operator,
+    name: ast::IdBound,
+    fixity: Fixity,
+}
+
+impl ResolvedOperator<'_> {
+    /// Whether the operator `next` following this operator binds more tightly,
+    /// i.e. whether `a op b next c` is to be read as `a op (b next c)`.
+    fn yields_to(&self, next: &ResolvedOperator) -> LoweringResult<bool> {
+        let (op, next_op) = (self.fixity, next.fixity);
+        if next_op.precedence != op.precedence {
+            return Ok(next_op.precedence > op.precedence);
+        }
+        match (op.associativity, next_op.associativity) {
+            (Associativity::Left, Associativity::Left) => Ok(false),
+            (Associativity::Right, Associativity::Right) => Ok(true),
+            _ => Err(LoweringError::AmbiguousOperators {
+                lhs: self.operator.id.clone(),
+                rhs: next.operator.id.clone(),
+                span: Span { start: self.operator.span.start, end: next.operator.span.end }
+                    .to_miette(),
+            }
+            .into()),
         }
-        .into())
     }
 }
+
+/// An already lowered operand together with its source code location
+struct Operand {
+    span: Span,
+    exp: Box<ast::Exp>,
+}
+
+/// Associate the operator chain `lhs op_1 e_1 ... op_n e_n` by precedence climbing
+///
+/// Only operators with a precedence of at least `min_precedence` are consumed.
+fn climb<'a, I>(
+    mut lhs: Operand,
+    min_precedence: u32,
+    rest: &mut Peekable<I>,
+) -> LoweringResult<Operand>
+where
+    I: Iterator<Item = (ResolvedOperator<'a>, Operand)>,
+{
+    while let Some((operator, _)) = rest.peek() {
+        if operator.fixity.precedence < min_precedence {
+            break;
+        }
+        let (operator, mut rhs) = rest.next().unwrap();
+        while let Some((next, _)) = rest.peek() {
+            if !operator.yields_to(next)? {
+                break;
+            }
+            let min_precedence = if next.fixity.precedence > operator.fixity.precedence {
+                operator.fixity.precedence + 1
+            } else {
+                operator.fixity.precedence
+            };
+            rhs = climb(rhs, min_precedence, rest)?;
+        }
+        lhs = apply(operator, lhs, rhs);
+    }
+    Ok(lhs)
+}
+
+/// Apply the type constructor of an operator to its two operands
+fn apply(operator: ResolvedOperator, lhs: Operand, rhs: Operand) -> Operand {
+    let ResolvedOperator { operator, name, fixity } = operator;
+    let span = Span { start: lhs.span.start, end: rhs.span.end };
+    let exp = ast::TypCtor {
+        span: Some(span),
+        name,
+        args: ast::Args {
+            args: vec![
+                ast::Arg::UnnamedArg { arg: lhs.exp, erased: false },
+                ast::Arg::UnnamedArg { arg: rhs.exp, erased: false },
+            ],
+        },
+        is_bin_op: Some(ast::BinOp { operator: operator.id.clone(), fixity }),
+    }
+    .into();
+    Operand { span, exp: Box::new(exp) }
+}
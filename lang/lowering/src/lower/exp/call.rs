@@ -1,10 +1,12 @@
-use ast::{TypeUniv, VarBound, Variable};
+use ast::{IdBound, Level, LevelUniv, TypeUniv, Variable};
+use miette_util::codespan::Span;
 use miette_util::ToMiette;
 use parser::cst;
 
 use crate::{lower::Lower, Ctx, DeclMeta, LoweringError, LoweringResult};
 
 use super::args::lower_args;
+use super::level::lower_level;
 
 impl Lower for cst::exp::Call {
     type Target = ast::Exp;
@@ -15,16 +17,39 @@ impl Lower for cst::exp::Call {
         // The type universe "Type" is treated as an ordinary call in the lexer and parser.
         // For this reason we have to special case the logic for lowering the type universe here.
         if name.id == "Type" {
-            if !args.is_empty() {
-                return Err(LoweringError::TypeUnivArgs { span: span.to_miette() }.into());
+            if !ctx.predicative {
+                if !args.is_empty() {
+                    return Err(LoweringError::TypeUnivArgs { span: span.to_miette() }.into());
+                }
+                return Ok(TypeUniv { span: Some(*span), level: None }.into());
             }
-            return Ok(TypeUniv { span: Some(*span) }.into());
+            // In a predicative module, the universe "Type" without a level is the lowest universe "Type 0".
+            let level = match args.as_slice() {
+                [] => Level { span: Some(*span), constant: 0, summands: vec![] }.into(),
+                [cst::exp::Arg::UnnamedArg(level)] => lower_level(level, ctx)?,
+                _ => return Err(LoweringError::InvalidLevel { span: span.to_miette() }.into()),
+            };
+            return Ok(TypeUniv { span: Some(*span), level: Some(Box::new(level)) }.into());
+        }
+
+        // In a predicative module, "Level" is the sort of universe levels.
+        if ctx.predicative && name.id == "Level" && args.is_empty() {
+            return Ok(LevelUniv { span: Some(*span) }.into());
+        }
+
+        // If the identifier stands for an expression, such as the scrutinee of a catch-all clause,
+        // then we lower it to that expression.
+        if let Some(mut exp) = ctx.lookup_alias(name) {
+            if let ast::Exp::Call(call) = &mut exp {
+                call.span = Some(*span);
+            }
+            return Ok(exp);
         }
 
         // If we find the identifier in the local context then we have to lower
         // it to a variable.
         if let Some(idx) = ctx.lookup_local(name) {
-            let name = VarBound { span: Some(name.span), id: name.id.clone() };
+            let name = ctx.bound_name(idx, name);
             return Ok(ast::Exp::Variable(Variable {
                 span: Some(*span),
                 idx,
@@ -36,40 +61,50 @@ impl Lower for cst::exp::Call {
         // If we find the identifier in the global context then we have to lower
         // it to a call or a type constructor.
         let (meta, name) = ctx.symbol_table.lookup(name)?;
-        match meta {
-            DeclMeta::Data { params, .. } | DeclMeta::Codata { params, .. } => {
-                Ok(ast::Exp::TypCtor(ast::TypCtor {
-                    span: Some(*span),
-                    name,
-                    args: lower_args(*span, args, params.clone(), ctx)?,
-                    is_bin_op: None,
-                }))
-            }
-            DeclMeta::Def { .. } | DeclMeta::Dtor { .. } => {
-                Err(LoweringError::MustUseAsDotCall { name: name.clone(), span: span.to_miette() }
-                    .into())
-            }
-            DeclMeta::Ctor { params, .. } => Ok(ast::Exp::Call(ast::Call {
-                span: Some(*span),
-                kind: ast::CallKind::Constructor,
-                name,
-                args: lower_args(*span, args, params.clone(), ctx)?,
-                inferred_type: None,
-            })),
-            DeclMeta::Codef { params, .. } => Ok(ast::Exp::Call(ast::Call {
-                span: Some(*span),
-                kind: ast::CallKind::Codefinition,
-                name,
-                args: lower_args(*span, args, params.clone(), ctx)?,
-                inferred_type: None,
-            })),
-            DeclMeta::Let { params, .. } => Ok(ast::Exp::Call(ast::Call {
-                span: Some(*span),
-                kind: ast::CallKind::LetBound,
+        lower_global_call(*span, meta.clone(), name, args, ctx)
+    }
+}
+
+/// Lower a call of the top-level declaration `name` with metadata `meta`
+pub fn lower_global_call(
+    span: Span,
+    meta: DeclMeta,
+    name: IdBound,
+    args: &[cst::exp::Arg],
+    ctx: &mut Ctx,
+) -> LoweringResult<ast::Exp> {
+    match meta {
+        DeclMeta::Data { params, .. } | DeclMeta::Codata { params, .. } => {
+            Ok(ast::Exp::TypCtor(ast::TypCtor {
+                span: Some(span),
                 name,
-                args: lower_args(*span, args, params.clone(), ctx)?,
-                inferred_type: None,
-            })),
+                args: lower_args(span, args, params, ctx)?,
+                is_bin_op: None,
+            }))
+        }
+        DeclMeta::Def { .. } | DeclMeta::Dtor { .. } => {
+            Err(LoweringError::MustUseAsDotCall { name, span: span.to_miette() }.into())
         }
+        DeclMeta::Ctor { params, .. } => Ok(ast::Exp::Call(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::Constructor,
+            name,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
+        DeclMeta::Codef { params, .. } => Ok(ast::Exp::Call(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::Codefinition,
+            name,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
+        DeclMeta::Let { params, .. } => Ok(ast::Exp::Call(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::LetBound,
+            name,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
     }
 }
@@ -0,0 +1,250 @@
+//! Compilation of deep copatterns to nested copattern matches
+//!
+//! The clauses of a copattern match may contain deep copatterns such as `.tl.hd => e`, which define
+//! an observation on the result of a destructor. The AST only supports cases for a single
+//! destructor. Therefore, all clauses for the destructor `.tl` are compiled to a single case
+//! `.tl => comatch { .hd => e, ... }`, which is checked like any other copattern match by the
+//! typechecker. In particular, the typechecker reports missing observations in the nested
+//! copattern matches.
+//!
+//! A catch-all clause `_ => e` matches every destructor without a clause. It is kept in the AST
+//! and expanded by the typechecker, since only the typechecker knows the type of the comatch.
+
+use ast::ctx::values::Binder;
+use ast::ctx::BindContext;
+use ast::{HasSpan, Lvl};
+use miette_util::ToMiette;
+use parser::cst;
+use parser::cst::exp::{Copattern, OrWildcard};
+use parser::cst::ident::Ident;
+
+use crate::symbol_table::DeclMeta;
+use crate::{lower::Lower, Ctx, LoweringError, LoweringResult};
+
+use super::patterns::distinct_name;
+
+/// Lower the clauses of a `codef` or a local copattern match to cases and a catch-all clause
+pub fn lower_cocases(
+    clauses: &[cst::exp::Case<OrWildcard<Copattern>>],
+    ctx: &mut Ctx,
+) -> LoweringResult<(Vec<ast::Case>, Option<ast::CatchAll>)> {
+    let mut rows = vec![];
+    let mut catch_alls = vec![];
+    for (index, clause) in clauses.iter().enumerate() {
+        match &clause.pattern {
+            OrWildcard::Pattern(copattern) => rows.push(Row { index, names: vec![], copattern }),
+            OrWildcard::Wildcard { span } => catch_alls.push((clause, *span)),
+        }
+    }
+    let dtors: Vec<Ident> = rows.iter().map(|row| row.copattern.name.clone()).collect();
+
+    let mut compiler = Compiler { clauses, bound: vec![] };
+    let cases = compiler.compile_cases(ctx, rows)?;
+
+    let mut catch_alls = catch_alls.into_iter();
+    let Some((clause, pattern_span)) = catch_alls.next() else {
+        return Ok((cases, None));
+    };
+    if let Some((unreachable, _)) = catch_alls.next() {
+        return Err(LoweringError::UnreachableClause { span: unreachable.span.to_miette() }.into());
+    }
+    if names_all_dtors(ctx, &dtors)? {
+        return Err(LoweringError::UnreachableClause { span: clause.span.to_miette() }.into());
+    }
+
+    let name = ast::VarBind::Wildcard { span: Some(pattern_span) };
+    let body = ctx.bind_single(name.clone(), |ctx| clause.body.lower(ctx))?;
+    Ok((cases, Some(ast::CatchAll { span: Some(clause.span), name, body })))
+}
+
+/// A clause during the compilation of nested copattern matches
+#[derive(Clone)]
+struct Row<'a> {
+    /// The index of the clause
+    index: usize,
+    /// The names which the clause gives to the variables bound by the enclosing cases
+    names: Vec<(Lvl, ast::VarBind)>,
+    /// The copattern of the clause which remains to be matched
+    copattern: &'a Copattern,
+}
+
+impl Row<'_> {
+    fn name_for(&self, lvl: Lvl) -> ast::VarBind {
+        self.names
+            .iter()
+            .find(|(other, _)| *other == lvl)
+            .map(|(_, name)| name.clone())
+            .unwrap_or(ast::VarBind::Wildcard { span: None })
+    }
+
+    fn is_deep(&self) -> bool {
+        self.copattern.nested.is_some()
+    }
+}
+
+struct Compiler<'a> {
+    clauses: &'a [cst::exp::Case<OrWildcard<Copattern>>],
+    /// The variables bound by the enclosing cases
+    bound: Vec<(Lvl, ast::VarBind)>,
+}
+
+impl<'a> Compiler<'a> {
+    /// Compile the cases of a copattern match
+    fn compile_cases(
+        &mut self,
+        ctx: &mut Ctx,
+        rows: Vec<Row<'a>>,
+    ) -> LoweringResult<Vec<ast::Case>> {
+        // Clauses for the same destructor are grouped if one of them has a deep copattern.
+        // Other clauses for the same destructor are kept apart, such that the typechecker reports them as duplicates.
+        let mut groups: Vec<Vec<Row>> = vec![];
+        for row in rows {
+            let group = groups.iter_mut().find(|group| {
+                group[0].copattern.name.id == row.copattern.name.id
+                    && (row.is_deep() || group.iter().any(Row::is_deep))
+            });
+            match group {
+                Some(group) => group.push(row),
+                None => groups.push(vec![row]),
+            }
+        }
+
+        groups.into_iter().map(|group| self.compile_case(ctx, group)).collect()
+    }
+
+    /// Compile the case for a destructor whose clauses are given by `rows`
+    fn compile_case(&mut self, ctx: &mut Ctx, mut rows: Vec<Row<'a>>) -> LoweringResult<ast::Case> {
+        let first = rows[0].copattern;
+        let arity = first.params.len();
+        if let Some(row) = rows.iter().find(|row| row.copattern.params.len() != arity) {
+            return Err(LoweringError::PatternArity {
+                name: first.name.id.clone(),
+                expected: arity,
+                actual: row.copattern.params.len(),
+                span: row.copattern.span.to_miette(),
+            }
+            .into());
+        }
+
+        // Every variable is named after the first clause which gives it a name.
+        let fst = ctx.next_level();
+        let mut names: Vec<Option<ast::VarBind>> = vec![None; arity];
+        for row in rows.iter_mut() {
+            for (snd, param) in row.copattern.params.iter().enumerate() {
+                let var = param.lower(ctx)?;
+                if let ast::VarBind::Var { .. } = var {
+                    row.names.push((Lvl { fst, snd }, var.clone()));
+                }
+                if !matches!(names[snd], Some(ast::VarBind::Var { .. })) {
+                    names[snd] = Some(var);
+                }
+            }
+        }
+        let mut binders: Vec<ast::VarBind> = Vec::with_capacity(arity);
+        for name in names {
+            let binder = match name {
+                Some(ast::VarBind::Var { span, id }) => {
+                    ast::VarBind::Var { span, id: distinct_name(&binders, id) }
+                }
+                Some(name) => name,
+                None => ast::VarBind::Wildcard { span: None },
+            };
+            binders.push(binder);
+        }
+
+        let (_, name) = ctx.symbol_table.lookup(&first.name)?;
+        let span = self.clauses[rows[0].index].span;
+        let params = binders
+            .iter()
+            .map(|name| ast::ParamInst {
+                span: name.span(),
+                name: name.clone(),
+                typ: None,
+                erased: false,
+            })
+            .collect();
+
+        let bound_len = self.bound.len();
+        self.bound
+            .extend(binders.iter().enumerate().map(|(snd, name)| (Lvl { fst, snd }, name.clone())));
+        let binders = binders.into_iter().map(|name| Binder { name, content: () });
+        let body = ctx.bind_iter(binders, |ctx| self.compile_body(ctx, rows));
+        self.bound.truncate(bound_len);
+
+        Ok(ast::Case {
+            span: Some(span),
+            pattern: ast::Pattern {
+                span: Some(first.span),
+                is_copattern: true,
+                name,
+                params: ast::TelescopeInst { params },
+            },
+            body: body?,
+            expanded_from: None,
+        })
+    }
+
+    /// Compile the body of a case for the destructor matched by all given rows
+    fn compile_body(
+        &mut self,
+        ctx: &mut Ctx,
+        rows: Vec<Row<'a>>,
+    ) -> LoweringResult<Option<Box<ast::Exp>>> {
+        let Some(nested) = &rows[0].copattern.nested else {
+            // A clause without a deep copattern defines the destructor completely.
+            if let Some(row) = rows.get(1) {
+                let span = self.clauses[row.index].span;
+                return Err(LoweringError::UnreachableClause { span: span.to_miette() }.into());
+            }
+            let row = &rows[0];
+            let names = self.bound.iter().map(|(lvl, _)| (*lvl, row.name_for(*lvl))).collect();
+            let body = &self.clauses[row.index].body;
+            return ctx.with_renamed_binders(names, |ctx| body.lower(ctx));
+        };
+
+        let mut nested_rows = Vec::with_capacity(rows.len());
+        for row in rows {
+            let Some(copattern) = &row.copattern.nested else {
+                return Err(LoweringError::OverlappingCopatterns {
+                    name: row.copattern.name.id.clone(),
+                    span: self.clauses[row.index].span.to_miette(),
+                }
+                .into());
+            };
+            nested_rows.push(Row { copattern, ..row });
+        }
+        let cases = self.compile_cases(ctx, nested_rows)?;
+
+        Ok(Some(Box::new(
+            ast::LocalComatch {
+                span: Some(nested.span),
+                ctx: None,
+                name: ctx.unique_label(None, &nested.span)?,
+                is_lambda_sugar: false,
+                cases,
+                catch_all: None,
+                inferred_type: None,
+            }
+            .into(),
+        )))
+    }
+}
+
+/// Whether `dtors` contains all destructors of their codata type
+///
+/// Names which are not destructors are reported by the typechecker.
+fn names_all_dtors(ctx: &Ctx, dtors: &[Ident]) -> LoweringResult<bool> {
+    let Some(dtor) = dtors.first() else {
+        return Ok(false);
+    };
+    let (DeclMeta::Dtor { codata, .. }, _) = ctx.symbol_table.lookup(dtor)? else {
+        return Ok(false);
+    };
+    let codata = codata.clone();
+    match ctx.symbol_table.lookup(&codata)? {
+        (DeclMeta::Codata { dtors: declared, .. }, _) => {
+            Ok(declared.iter().all(|dtor| dtors.iter().any(|other| other.id == dtor.id)))
+        }
+        _ => Ok(false),
+    }
+}
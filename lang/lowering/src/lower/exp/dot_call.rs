@@ -1,9 +1,12 @@
+use ast::IdBound;
+use miette_util::codespan::Span;
 use miette_util::ToMiette;
 use parser::cst;
 
 use crate::{lower::Lower, Ctx, DeclMeta, LoweringError, LoweringResult};
 
 use super::args::lower_args;
+use super::call::lower_global_call;
 
 impl Lower for cst::exp::DotCall {
     type Target = ast::Exp;
@@ -11,26 +14,58 @@ impl Lower for cst::exp::DotCall {
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         let cst::exp::DotCall { span, exp, name, args } = self;
 
-        let (meta, name) = ctx.symbol_table.lookup(name)?;
-
-        match meta.clone() {
-            DeclMeta::Dtor { params, .. } => Ok(ast::Exp::DotCall(ast::DotCall {
-                span: Some(*span),
-                kind: ast::DotCallKind::Destructor,
-                exp: exp.lower(ctx)?,
-                name,
-                args: lower_args(*span, args, params, ctx)?,
-                inferred_type: None,
-            })),
-            DeclMeta::Def { params, .. } => Ok(ast::Exp::DotCall(ast::DotCall {
-                span: Some(*span),
-                kind: ast::DotCallKind::Definition,
-                exp: exp.lower(ctx)?,
-                name,
-                args: lower_args(*span, args, params, ctx)?,
-                inferred_type: None,
-            })),
-            _ => Err(LoweringError::CannotUseAsDotCall { name, span: span.to_miette() }.into()),
+        match &**exp {
+            // A top-level name qualified by a module alias, as in `L.Cons(x, xs)`
+            cst::exp::Exp::Call(cst::exp::Call { name: alias, args: alias_args, .. })
+                if alias_args.is_empty() && ctx.is_qualifier(alias) =>
+            {
+                let (meta, name) = ctx.symbol_table.lookup_qualified(alias, name)?;
+                lower_global_call(*span, meta.clone(), name, args, ctx)
+            }
+            // A destructor or definition qualified by a module alias, as in `xs.L.append(ys)`
+            cst::exp::Exp::DotCall(cst::exp::DotCall {
+                exp,
+                name: alias,
+                args: alias_args,
+                ..
+            }) if alias_args.is_empty() && ctx.is_qualifier(alias) => {
+                let (meta, name) = ctx.symbol_table.lookup_qualified(alias, name)?;
+                lower_dot_call(*span, meta.clone(), exp, name, args, ctx)
+            }
+            _ => {
+                let (meta, name) = ctx.symbol_table.lookup(name)?;
+                lower_dot_call(*span, meta.clone(), exp, name, args, ctx)
+            }
         }
     }
 }
+
+/// Lower a call of the destructor or definition `name` with metadata `meta` on `exp`
+fn lower_dot_call(
+    span: Span,
+    meta: DeclMeta,
+    exp: &cst::exp::Exp,
+    name: IdBound,
+    args: &[cst::exp::Arg],
+    ctx: &mut Ctx,
+) -> LoweringResult<ast::Exp> {
+    match meta {
+        DeclMeta::Dtor { params, .. } => Ok(ast::Exp::DotCall(ast::DotCall {
+            span: Some(span),
+            kind: ast::DotCallKind::Destructor,
+            exp: Box::new(exp.lower(ctx)?),
+            name,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
+        DeclMeta::Def { params, .. } => Ok(ast::Exp::DotCall(ast::DotCall {
+            span: Some(span),
+            kind: ast::DotCallKind::Definition,
+            exp: Box::new(exp.lower(ctx)?),
+            name,
+            args: lower_args(span, args, params, ctx)?,
+            inferred_type: None,
+        })),
+        _ => Err(LoweringError::CannotUseAsDotCall { name, span: span.to_miette() }.into()),
+    }
+}
@@ -1,4 +1,5 @@
 use ast::Hole;
+use miette_util::codespan::Span;
 use parser::cst;
 
 use crate::{lower::Lower, Ctx, LoweringResult};
@@ -34,3 +35,8 @@ impl Lower for cst::exp::Hole {
         .into())
     }
 }
+
+/// A case body which could not be parsed is lowered to a typed hole `?`, such that the remaining module can be checked.
+pub fn error_hole(span: Span, ctx: &mut Ctx) -> LoweringResult<ast::Exp> {
+    cst::exp::Hole { span, kind: cst::exp::HoleKind::CanSolve }.lower(ctx)
+}
@@ -11,7 +11,11 @@ impl Lower for cst::exp::Lam {
             span: *span,
             name: None,
             is_lambda_sugar: true,
-            cases: vec![case.clone()],
+            cases: vec![cst::exp::Case {
+                span: case.span,
+                pattern: cst::exp::OrWildcard::Pattern(case.pattern.clone()),
+                body: case.body.clone(),
+            }],
         });
         comatch.lower(ctx)
     }
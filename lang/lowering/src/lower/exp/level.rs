@@ -0,0 +1,53 @@
+use ast::Level;
+use miette_util::ToMiette;
+use num_bigint::BigUint;
+use parser::cst::{self, exp::NatLit};
+
+use crate::{lower::Lower, Ctx, LoweringError, LoweringResult};
+
+/// Lower an expression in a position where a universe level is expected
+///
+/// A universe level is either a number `n`, a level variable `l`, a level plus a number `l + n`,
+/// or the maximum `max(l, k)` of two or more levels.
+pub fn lower_level(exp: &cst::exp::Exp, ctx: &mut Ctx) -> LoweringResult<ast::Exp> {
+    match exp {
+        cst::exp::Exp::NatLit(NatLit { span, val }) => {
+            let constant = level_constant(exp, val)?;
+            Ok(Level { span: Some(*span), constant, summands: vec![] }.into())
+        }
+        cst::exp::Exp::BinOp(cst::exp::BinOp { span, lhs, rest })
+            if rest.iter().all(|(operator, _)| operator.id == "+") =>
+        {
+            let lhs = lower_level(lhs, ctx)?;
+            let mut level = Level::from_exp(&lhs);
+            for (_, rhs) in rest {
+                let cst::exp::Exp::NatLit(NatLit { val, .. }) = &**rhs else {
+                    return Err(LoweringError::InvalidLevel { span: rhs.span().to_miette() }.into());
+                };
+                level = level.add(level_constant(rhs, val)?);
+            }
+            Ok(Level { span: Some(*span), ..level }.into_exp())
+        }
+        cst::exp::Exp::Call(cst::exp::Call { span, name, args })
+            if name.id == "max" && !args.is_empty() =>
+        {
+            let mut level = Level { span: Some(*span), constant: 0, summands: vec![] };
+            for arg in args {
+                let cst::exp::Arg::UnnamedArg(arg) = arg else {
+                    return Err(LoweringError::InvalidLevel { span: arg.span().to_miette() }.into());
+                };
+                level = level.max(&Level::from_exp(&lower_level(arg, ctx)?));
+            }
+            Ok(level.into_exp())
+        }
+        // Level variables and holes are lowered as usual.
+        cst::exp::Exp::Call(cst::exp::Call { args, .. }) if args.is_empty() => exp.lower(ctx),
+        cst::exp::Exp::Hole(_) => exp.lower(ctx),
+        _ => Err(LoweringError::InvalidLevel { span: exp.span().to_miette() }.into()),
+    }
+}
+
+fn level_constant(exp: &cst::exp::Exp, val: &BigUint) -> LoweringResult<usize> {
+    usize::try_from(val)
+        .map_err(|_| LoweringError::InvalidLevel { span: exp.span().to_miette() }.into())
+}
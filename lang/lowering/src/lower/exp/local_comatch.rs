@@ -2,43 +2,24 @@ use parser::cst;
 
 use crate::{lower::Lower, Ctx, LoweringResult};
 
-use super::lower_telescope_inst;
+use super::lower_cocases;
 
 impl Lower for cst::exp::LocalComatch {
     type Target = ast::Exp;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         let cst::exp::LocalComatch { span, name, is_lambda_sugar, cases } = self;
+        let name = ctx.unique_label(name.to_owned(), span)?;
+        let (cases, catch_all) = lower_cocases(cases, ctx)?;
         Ok(ast::LocalComatch {
             span: Some(*span),
             ctx: None,
-            name: ctx.unique_label(name.to_owned(), span)?,
+            name,
             is_lambda_sugar: *is_lambda_sugar,
-            cases: cases.lower(ctx)?,
+            cases,
+            catch_all,
             inferred_type: None,
         }
         .into())
     }
 }
-
-impl Lower for cst::exp::Case<cst::exp::Copattern> {
-    type Target = ast::Case;
-
-    fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::exp::Case { span, pattern, body } = self;
-
-        lower_telescope_inst(&pattern.params, ctx, |ctx, params| {
-            let (_, name) = ctx.symbol_table.lookup(&pattern.name)?;
-            Ok(ast::Case {
-                span: Some(*span),
-                pattern: ast::Pattern {
-                    span: Some(pattern.span),
-                    is_copattern: true,
-                    name,
-                    params,
-                },
-                body: body.lower(ctx)?,
-            })
-        })
-    }
-}
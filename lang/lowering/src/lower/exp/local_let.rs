@@ -0,0 +1,27 @@
+use ast::ctx::BindContext;
+use parser::cst;
+
+use crate::{lower::Lower, Ctx, LoweringResult};
+
+impl Lower for cst::exp::LocalLet {
+    type Target = ast::Exp;
+
+    fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
+        let cst::exp::LocalLet { span, name, typ, bound, body } = self;
+
+        let typ = typ.lower(ctx)?;
+        let bound = bound.lower(ctx)?;
+        let name = name.lower(ctx)?;
+
+        Ok(ast::LocalLet {
+            span: Some(*span),
+            name: name.clone(),
+            typ,
+            bound,
+            body: ctx.bind_single(name, |ctx| body.lower(ctx))?,
+            bound_type: None,
+            inferred_type: None,
+        }
+        .into())
+    }
+}
@@ -2,45 +2,28 @@ use parser::cst;
 
 use crate::{lower::Lower, Ctx, LoweringResult};
 
-use super::lower_telescope_inst;
+use super::lower_cases;
 
 impl Lower for cst::exp::LocalMatch {
     type Target = ast::Exp;
 
     fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
         let cst::exp::LocalMatch { span, name, on_exp, motive, cases } = self;
+        let name = ctx.unique_label(name.to_owned(), span)?;
+        let on_exp = on_exp.lower(ctx)?;
+        let motive = motive.lower(ctx)?;
+        let (cases, catch_all) = lower_cases(cases, motive.as_ref(), ctx)?;
         Ok(ast::LocalMatch {
             span: Some(*span),
             ctx: None,
-            name: ctx.unique_label(name.to_owned(), span)?,
-            on_exp: on_exp.lower(ctx)?,
-            motive: motive.lower(ctx)?,
+            name,
+            on_exp,
+            motive,
             ret_typ: None,
-            cases: cases.lower(ctx)?,
+            cases,
+            catch_all,
             inferred_type: None,
         }
         .into())
     }
 }
-
-impl Lower for cst::exp::Case<cst::exp::Pattern> {
-    type Target = ast::Case;
-
-    fn lower(&self, ctx: &mut Ctx) -> LoweringResult<Self::Target> {
-        let cst::exp::Case { span, pattern, body } = self;
-
-        lower_telescope_inst(&pattern.params, ctx, |ctx, params| {
-            let (_, name) = ctx.symbol_table.lookup(&pattern.name)?;
-            Ok(ast::Case {
-                span: Some(*span),
-                pattern: ast::Pattern {
-                    span: Some(pattern.span),
-                    is_copattern: false,
-                    name,
-                    params,
-                },
-                body: body.lower(ctx)?,
-            })
-        })
-    }
-}
@@ -1,4 +1,3 @@
-use ast::ctx::values::Binder;
 use ast::ctx::BindContext;
 use ast::HasSpan;
 use miette_util::ToMiette;
@@ -13,12 +12,21 @@ mod anno;
 mod args;
 mod binop;
 mod call;
+mod copatterns;
 mod dot_call;
 mod hole;
 mod lam;
+mod level;
 mod local_comatch;
+mod local_let;
 mod local_match;
 mod nat_lit;
+mod patterns;
+
+use hole::error_hole;
+
+pub use copatterns::lower_cocases;
+pub use patterns::lower_cases;
 
 impl Lower for cst::exp::Exp {
     type Target = ast::Exp;
@@ -30,33 +38,16 @@ impl Lower for cst::exp::Exp {
             cst::exp::Exp::Anno(e) => e.lower(ctx),
             cst::exp::Exp::LocalMatch(e) => e.lower(ctx),
             cst::exp::Exp::LocalComatch(e) => e.lower(ctx),
+            cst::exp::Exp::LocalLet(e) => e.lower(ctx),
             cst::exp::Exp::Hole(e) => e.lower(ctx),
             cst::exp::Exp::NatLit(e) => e.lower(ctx),
             cst::exp::Exp::BinOp(e) => e.lower(ctx),
             cst::exp::Exp::Lam(e) => e.lower(ctx),
+            cst::exp::Exp::Error(span) => error_hole(*span, ctx),
         }
     }
 }
 
-fn lower_telescope_inst<T, F: FnOnce(&mut Ctx, ast::TelescopeInst) -> LoweringResult<T>>(
-    tel_inst: &[cst::exp::BindingSite],
-    ctx: &mut Ctx,
-    f: F,
-) -> LoweringResult<T> {
-    let tel_inst = tel_inst.iter().map(|bs| bs.lower(ctx)).collect::<Result<Vec<_>, _>>()?;
-    ctx.bind_fold_failable(
-        tel_inst.into_iter(),
-        vec![],
-        |_ctx, params_out, name| -> LoweringResult<Binder<()>> {
-            let param_out =
-                ast::ParamInst { span: name.span(), name: name.clone(), typ: None, erased: false };
-            params_out.push(param_out);
-            Ok(Binder { name, content: () })
-        },
-        |ctx, params| f(ctx, ast::TelescopeInst { params }),
-    )?
-}
-
 impl Lower for cst::exp::BindingSite {
     type Target = ast::VarBind;
 
@@ -41,7 +41,12 @@ impl Lower for cst::exp::NatLit {
             out = ast::Exp::Call(ast::Call {
                 span: Some(*span),
                 kind: call_kind,
-                name: ast::IdBound { span: Some(*span), id: "S".to_owned(), uri: name.uri.clone() },
+                name: ast::IdBound {
+                    span: Some(*span),
+                    id: "S".to_owned(),
+                    uri: name.uri.clone(),
+                    qualifier: None,
+                },
                 args: ast::Args {
                     args: vec![ast::Arg::UnnamedArg { arg: Box::new(out), erased: false }],
                 },
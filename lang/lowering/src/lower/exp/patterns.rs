@@ -0,0 +1,679 @@
+//! Compilation of nested patterns to case trees
+//!
+//! The clauses of a pattern match may contain nested patterns such as `S(S(x)) => e`, and several
+//! clauses may overlap. The AST only supports flat cases which match on a single constructor and
+//! bind a variable for each of its arguments. Therefore, a nested pattern `C(...)` on a variable `x`
+//! is compiled to a local pattern match `x.match { ... }`, which is checked like any other
+//! pattern match by the typechecker.
+//!
+//! Clauses are tried from top to bottom: every branch of the case tree uses the body of the first
+//! clause which matches it. Clauses which are not used in any branch are reported as unreachable.
+//! Branches which are not matched by any clause are left out, so that the typechecker reports
+//! them as missing cases.
+//!
+//! A catch-all clause `_ => e` or `x => e` matches all constructors. In a branch for the constructor
+//! `C`, the name `x` stands for the matched value `C(y₁, ..., yₙ)`. The constructors without a
+//! clause of their own are matched by the first catch-all clause, which is kept in the AST and
+//! expanded by the typechecker, since only the typechecker knows the type of the scrutinee.
+//!
+//! If the return type of the clauses depends on the scrutinee, then so does the return type of a
+//! local pattern match on a nested pattern. For example, the clause `S(S(y)) => e` of a definition
+//! with the return type `t` is compiled to `S(x) => x.match as x' => t[S(x')/self] { S(y) => e, ... }`.
+
+use std::collections::VecDeque;
+
+use ast::ctx::values::Binder;
+use ast::ctx::BindContext;
+use ast::rename::increment_name;
+use ast::{Assign, ContainsMetaVars, HasSpan, Idx, Lvl, Occurs, Shift, Substitutable, VarBound};
+use miette_util::codespan::Span;
+use miette_util::ToMiette;
+use parser::cst;
+use parser::cst::exp::{BindingSite, OrWildcard, Pattern};
+use parser::cst::ident::Ident;
+use url::Url;
+
+use crate::symbol_table::DeclMeta;
+use crate::{lower::Lower, Ctx, LoweringError, LoweringResult};
+
+use super::args::inserted_implicit_arg;
+
+/// Lower the clauses of a `def` or a local pattern match to a case tree and a catch-all clause
+///
+/// The motive `motive` of the clauses, if any, is used to compute the motives of the local pattern
+/// matches on nested patterns.
+pub fn lower_cases(
+    clauses: &[cst::exp::Case<OrWildcard<Pattern>>],
+    motive: Option<&ast::Motive>,
+    ctx: &mut Ctx,
+) -> LoweringResult<(Vec<ast::Case>, Option<ast::CatchAll>)> {
+    // A motive is only needed if the return type depends on the scrutinee.
+    let depth = ctx.next_level();
+    let motive = motive.filter(|motive| {
+        !motive.ret_typ.contains_metavars()
+            && ctx.bind_single(motive.param.name.clone(), |ctx| {
+                motive.ret_typ.occurs_var(ctx.ctx_mut(), Lvl { fst: depth, snd: 0 })
+            })
+    });
+    let mut compiler = Compiler {
+        clauses,
+        used: vec![false; clauses.len()],
+        bound: vec![],
+        motive: motive.map(|motive| (motive, depth)),
+        refined: vec![],
+    };
+
+    // The constructors are matched in the order in which they first occur.
+    let mut rows: Vec<(Row, Option<&Pattern>)> = vec![];
+    let mut ctors: Vec<Ctor> = vec![];
+    let mut catch_all: Option<(usize, ast::VarBind)> = None;
+    for (index, clause) in clauses.iter().enumerate() {
+        let mut row = Row::new(index);
+        match resolve(ctx, &clause.pattern, false)? {
+            Resolved::Ctor(pattern, ctor) => {
+                if !ctors.iter().any(|other| other.name.id == ctor.name.id) {
+                    ctors.push(ctor);
+                }
+                rows.push((row, Some(pattern)));
+            }
+            Resolved::Var(var) => {
+                catch_all.get_or_insert((index, var.clone()));
+                if let ast::VarBind::Var { id, .. } = var {
+                    row.alias = Some(Alias { name: id, ctor: None });
+                }
+                rows.push((row, None));
+            }
+            Resolved::Wildcard(span) => {
+                catch_all.get_or_insert((index, ast::VarBind::Wildcard { span: Some(span) }));
+                rows.push((row, None));
+            }
+        }
+    }
+
+    let mut cases = vec![];
+    for ctor in ctors.iter() {
+        let ctor_rows: Vec<_> = rows
+            .iter()
+            .filter(|(_, pattern)| pattern.map_or(true, |pattern| pattern.name.id == ctor.name.id))
+            .cloned()
+            .collect();
+        cases.push(compiler.compile_case(ctx, None, ctor, ctor_rows)?);
+    }
+
+    // The first catch-all clause is kept for the constructors without a clause, if there are any.
+    let catch_all = match catch_all {
+        Some((index, name)) if !names_all_ctors(ctx, &ctors)? => {
+            compiler.used[index] = true;
+            let clause = &clauses[index];
+            let body = ctx.bind_single(name.clone(), |ctx| clause.body.lower(ctx))?;
+            Some(ast::CatchAll { span: Some(clause.span), name, body })
+        }
+        _ => None,
+    };
+
+    compiler.check_reachable()?;
+    Ok((cases, catch_all))
+}
+
+/// A clause during the compilation of the case tree
+#[derive(Clone)]
+struct Row<'a> {
+    /// The index of the clause
+    index: usize,
+    /// The names which the clause gives to the variables bound by the case tree
+    names: Vec<(Lvl, ast::VarBind)>,
+    /// The nested patterns of the clause which remain to be matched, together with the variables they match on
+    pending: VecDeque<(Lvl, &'a Pattern)>,
+    /// The name which a catch-all clause `x => e` gives to the matched value
+    alias: Option<Alias>,
+}
+
+/// The name of the value matched by a catch-all clause
+#[derive(Clone)]
+struct Alias {
+    name: String,
+    /// The constructor of the value and the level of its arguments, once the constructor is known
+    ctor: Option<(Ctor, usize)>,
+}
+
+/// A constructor together with the module which defines it
+#[derive(Clone)]
+struct Ctor {
+    uri: Url,
+    /// The alias of the module if the constructor is referred to by a qualified name
+    qualifier: Option<String>,
+    name: Ident,
+}
+
+impl Ctor {
+    fn lookup<'b>(&self, ctx: &'b Ctx) -> LoweringResult<(&'b DeclMeta, ast::IdBound)> {
+        let (meta, name) = ctx.symbol_table.lookup_in(&self.uri, &self.name)?;
+        Ok((meta, ast::IdBound { qualifier: self.qualifier.clone(), ..name }))
+    }
+}
+
+impl Row<'_> {
+    fn new(index: usize) -> Self {
+        Row { index, names: vec![], pending: VecDeque::new(), alias: None }
+    }
+
+    fn name_for(&self, lvl: Lvl) -> ast::VarBind {
+        self.names
+            .iter()
+            .find(|(other, _)| *other == lvl)
+            .map(|(_, name)| name.clone())
+            .unwrap_or(ast::VarBind::Wildcard { span: None })
+    }
+}
+
+struct Compiler<'a> {
+    clauses: &'a [cst::exp::Case<OrWildcard<Pattern>>],
+    /// Whether the body of a clause has been used in the case tree
+    used: Vec<bool>,
+    /// The variables bound by the enclosing cases of the case tree
+    bound: Vec<(Lvl, ast::VarBind)>,
+    /// The motive of the clauses if the return type depends on the scrutinee, together with the
+    /// level of its parameter
+    motive: Option<(&'a ast::Motive, usize)>,
+    /// The constructors matched by the enclosing cases of the case tree, together with the variable
+    /// they match on and the level of their arguments. The scrutinee is represented by `None`.
+    refined: Vec<(Option<Lvl>, Ctor, usize)>,
+}
+
+/// A nested pattern after resolving whether it binds a variable or matches on a constructor
+enum Resolved<'a> {
+    Var(ast::VarBind),
+    Wildcard(Span),
+    Ctor(&'a Pattern, Ctor),
+}
+
+impl<'a> Compiler<'a> {
+    /// Compile the case for the constructor `ctor` of a match on the variable `on`, or on the scrutinee.
+    ///
+    /// The given rows either match on `ctor` with the given pattern, or match on any constructor.
+    fn compile_case(
+        &mut self,
+        ctx: &mut Ctx,
+        on: Option<Lvl>,
+        ctor: &Ctor,
+        rows: Vec<(Row<'a>, Option<&'a Pattern>)>,
+    ) -> LoweringResult<ast::Case> {
+        let (meta, name) = ctor.lookup(ctx)?;
+        let declared_params = match meta {
+            DeclMeta::Ctor { params, .. } => Some(params_info(params)),
+            _ => None,
+        };
+        let declared_arity = declared_params.as_ref().map(Vec::len);
+        // Catch-all clauses which name the matched value refer to all arguments of the constructor.
+        let has_alias = rows.iter().any(|(row, pattern)| {
+            pattern.is_none() && row.alias.as_ref().is_some_and(|alias| alias.ctor.is_none())
+        });
+        let patterns: Vec<&Pattern> = rows.iter().filter_map(|(_, pattern)| *pattern).collect();
+        let arity = match patterns.as_slice() {
+            // A single pattern is checked against the constructor by the typechecker.
+            [pattern] if !has_alias => pattern.params.len(),
+            _ => {
+                let Some(arity) = declared_arity else {
+                    return Err(LoweringError::NotAConstructor {
+                        name: ctor.name.id.clone(),
+                        span: ctor.name.span.to_miette(),
+                    }
+                    .into());
+                };
+                if let Some(pattern) = patterns.iter().find(|p| p.params.len() != arity) {
+                    return Err(LoweringError::PatternArity {
+                        name: name.id.clone(),
+                        expected: arity,
+                        actual: pattern.params.len(),
+                        span: pattern.span.to_miette(),
+                    }
+                    .into());
+                }
+                arity
+            }
+        };
+
+        let fst = ctx.next_level();
+        let mut names: Vec<Option<ast::VarBind>> = vec![None; arity];
+        let mut matched = vec![false; arity];
+        let mut rows_out = Vec::with_capacity(rows.len());
+        for (mut row, pattern) in rows {
+            let Some(pattern) = pattern else {
+                if let Some(alias @ Alias { ctor: None, .. }) = &mut row.alias {
+                    alias.ctor = Some((ctor.clone(), fst));
+                }
+                rows_out.push(row);
+                continue;
+            };
+            let mut nested = vec![];
+            for (snd, param) in pattern.params.iter().enumerate() {
+                let lvl = Lvl { fst, snd };
+                match resolve(ctx, param, true)? {
+                    Resolved::Var(var) => {
+                        if !matches!(names[snd], Some(ast::VarBind::Var { .. })) {
+                            names[snd] = Some(var.clone());
+                        }
+                        row.names.push((lvl, var));
+                    }
+                    Resolved::Wildcard(span) => {
+                        names[snd].get_or_insert(ast::VarBind::Wildcard { span: Some(span) });
+                    }
+                    Resolved::Ctor(pattern, _) => {
+                        matched[snd] = true;
+                        nested.push((lvl, pattern));
+                    }
+                }
+            }
+            // Nested patterns are matched before the remaining patterns of the enclosing constructors.
+            for item in nested.into_iter().rev() {
+                row.pending.push_front(item);
+            }
+            rows_out.push(row);
+        }
+
+        // Variables which are matched on need a name, even if no clause gives them one.
+        // The names of the binders must be distinct, since different clauses may use the same name
+        // for different arguments.
+        // If the matched value is named by a catch-all clause, then all variables need a name, for
+        // which we use the names of the parameters of the constructor.
+        let mut binders: Vec<ast::VarBind> = Vec::with_capacity(arity);
+        for (snd, (name, matched)) in names.into_iter().zip(matched).enumerate() {
+            let binder = match name {
+                Some(ast::VarBind::Var { span, id }) => {
+                    ast::VarBind::Var { span, id: distinct_name(&binders, id) }
+                }
+                _ if has_alias => {
+                    let (declared, _) = &declared_params.as_ref().unwrap()[snd];
+                    let declared = declared.as_deref().unwrap_or("x");
+                    ast::VarBind::Var { span: None, id: fresh_name(ctx, &binders, declared) }
+                }
+                _ if matched => {
+                    ast::VarBind::Var { span: None, id: fresh_name(ctx, &binders, "x") }
+                }
+                Some(name) => name,
+                None => ast::VarBind::Wildcard { span: None },
+            };
+            binders.push(binder);
+        }
+
+        let clause = &self.clauses[rows_out[0].index];
+        let pattern_span = patterns.first().map(|pattern| pattern.span).unwrap_or(clause.span);
+        let params = binders
+            .iter()
+            .map(|name| ast::ParamInst {
+                span: name.span(),
+                name: name.clone(),
+                typ: None,
+                erased: false,
+            })
+            .collect();
+
+        let bound_len = self.bound.len();
+        self.bound
+            .extend(binders.iter().enumerate().map(|(snd, name)| (Lvl { fst, snd }, name.clone())));
+        self.refined.push((on, ctor.clone(), fst));
+        let binders = binders.into_iter().map(|name| Binder { name, content: () });
+        let body = ctx.bind_iter(binders, |ctx| self.compile_body(ctx, rows_out));
+        self.refined.pop();
+        self.bound.truncate(bound_len);
+
+        Ok(ast::Case {
+            span: Some(clause.span),
+            pattern: ast::Pattern {
+                span: Some(pattern_span),
+                is_copattern: false,
+                name,
+                params: ast::TelescopeInst { params },
+            },
+            body: body?,
+            expanded_from: None,
+        })
+    }
+
+    /// Compile the body of a case whose enclosing patterns are matched by all given rows
+    fn compile_body(
+        &mut self,
+        ctx: &mut Ctx,
+        rows: Vec<Row<'a>>,
+    ) -> LoweringResult<Option<Box<ast::Exp>>> {
+        let Some(&(lvl, pattern)) = rows[0].pending.front() else {
+            // The first clause matches all values which reach this case.
+            let row = &rows[0];
+            self.used[row.index] = true;
+            let names = self.bound.iter().map(|(lvl, _)| (*lvl, row.name_for(*lvl))).collect();
+            let body = &self.clauses[row.index].body;
+            let Some(Alias { name, ctor: Some((ctor, fst)) }) = &row.alias else {
+                return ctx.with_renamed_binders(names, |ctx| body.lower(ctx));
+            };
+            let value = self.ctor_call(ctx, ctor, *fst, self.clauses[row.index].span)?;
+            return ctx.with_alias(name.clone(), value, |ctx| {
+                ctx.with_renamed_binders(names, |ctx| body.lower(ctx))
+            });
+        };
+
+        // Match on the variable of the first remaining nested pattern of the first clause.
+        let mut ctors = data_ctors(ctx, &resolve_ctor(ctx, pattern)?)?;
+        for row in rows.iter() {
+            // Constructors of other types are reported as undeclared by the typechecker.
+            if let Some((_, other)) = row.pending.iter().find(|(other, _)| *other == lvl) {
+                if !ctors.iter().any(|ctor| ctor.name.id == other.name.id) {
+                    ctors.push(resolve_ctor(ctx, other)?);
+                }
+            }
+        }
+
+        let mut cases = vec![];
+        for ctor in ctors {
+            let mut ctor_rows = vec![];
+            for row in rows.iter() {
+                match row.pending.iter().position(|(other, _)| *other == lvl) {
+                    Some(i) if row.pending[i].1.name.id == ctor.name.id => {
+                        let mut row = row.clone();
+                        let (_, pattern) = row.pending.remove(i).unwrap();
+                        ctor_rows.push((row, Some(pattern)));
+                    }
+                    Some(_) => {}
+                    None => ctor_rows.push((row.clone(), None)),
+                }
+            }
+            if ctor_rows.is_empty() {
+                continue;
+            }
+            let span =
+                ctor_rows.iter().find_map(|(_, p)| p.map(|p| p.span)).unwrap_or(pattern.span);
+            let ctor = Ctor { name: Ident { span, id: ctor.name.id.clone() }, ..ctor };
+            cases.push(self.compile_case(ctx, Some(lvl), &ctor, ctor_rows)?);
+        }
+
+        let name = self.var_name(lvl);
+        let motive = self.motive(ctx, lvl, &name, pattern.span)?;
+        let on_exp =
+            ast::Variable { span: None, idx: ctx.level_to_index(lvl), name, inferred_type: None };
+        Ok(Some(Box::new(
+            ast::LocalMatch {
+                span: Some(pattern.span),
+                ctx: None,
+                name: ctx.unique_label(None, &pattern.span)?,
+                on_exp: Box::new(on_exp.into()),
+                motive,
+                ret_typ: None,
+                cases,
+                catch_all: None,
+                inferred_type: None,
+            }
+            .into(),
+        )))
+    }
+
+    /// The name of the variable at level `lvl` bound by the case tree
+    fn var_name(&self, lvl: Lvl) -> VarBound {
+        match self.bound.iter().find(|(other, _)| *other == lvl) {
+            Some((_, ast::VarBind::Var { id, .. })) => VarBound::from_string(id),
+            _ => VarBound::from_string("x"),
+        }
+    }
+
+    /// The motive of a local pattern match on the variable at level `lvl` named `name`
+    ///
+    /// The motive is the motive of the clauses in which the scrutinee is replaced by the constructors
+    /// matched so far, and in which the variable at level `lvl` is replaced by the parameter of the motive.
+    fn motive(
+        &self,
+        ctx: &mut Ctx,
+        lvl: Lvl,
+        name: &VarBound,
+        span: Span,
+    ) -> LoweringResult<Option<ast::Motive>> {
+        let Some((motive, depth)) = self.motive else {
+            return Ok(None);
+        };
+        let param = ast::VarBind::from_string(&name.id);
+        let fst = ctx.next_level();
+        ctx.bind_single(param.clone(), |ctx| {
+            let scrutinee = self.refined_value(ctx, None, lvl, span)?;
+            // Move the return type from the context of the clauses into the current context.
+            let mut ret_typ = motive.ret_typ.clone();
+            ret_typ.shift_in_range(&(1..), ((fst - depth) as isize, 0));
+            let subst = Assign { lvl: Lvl { fst, snd: 0 }, exp: Box::new(scrutinee) };
+            // Unwrap is safe here because we are unwrapping an infallible result
+            let ret_typ = ret_typ.subst(ctx.ctx_mut(), &subst).unwrap();
+            Ok(Some(ast::Motive {
+                span: None,
+                param: ast::ParamInst { span: None, name: param, typ: None, erased: false },
+                ret_typ,
+            }))
+        })
+    }
+
+    /// The value of the variable `on`, or of the scrutinee, in terms of the variables bound by the case tree
+    ///
+    /// The variable at level `lvl` is replaced by the parameter of the motive, which is bound last.
+    fn refined_value(
+        &self,
+        ctx: &mut Ctx,
+        on: Option<Lvl>,
+        lvl: Lvl,
+        span: Span,
+    ) -> LoweringResult<ast::Exp> {
+        if on == Some(lvl) {
+            let name = self.var_name(lvl);
+            return Ok(ast::Variable {
+                span: None,
+                idx: Idx { fst: 0, snd: 0 },
+                name,
+                inferred_type: None,
+            }
+            .into());
+        }
+        let Some((_, ctor, fst)) = self.refined.iter().find(|(other, _, _)| *other == on) else {
+            // Unwrap is safe here because only the scrutinee is represented by `None`.
+            let lvl = on.unwrap();
+            let var = ast::Variable {
+                span: None,
+                idx: ctx.level_to_index(lvl),
+                name: self.var_name(lvl),
+                inferred_type: None,
+            };
+            return Ok(var.into());
+        };
+        let (DeclMeta::Ctor { params, .. }, name) = ctor.lookup(ctx)? else {
+            return Err(LoweringError::NotAConstructor {
+                name: ctor.name.id.clone(),
+                span: ctor.name.span.to_miette(),
+            }
+            .into());
+        };
+        let mut args = vec![];
+        for (snd, (declared, implicit)) in params_info(params).into_iter().enumerate() {
+            // Arguments for implicit parameters are given by name, unless the parameter has none.
+            let arg = match (declared, implicit) {
+                (None, true) => {
+                    args.push(inserted_implicit_arg(span, ctx));
+                    continue;
+                }
+                (Some(declared), true) => {
+                    let arg = self.refined_value(ctx, Some(Lvl { fst: *fst, snd }), lvl, span)?;
+                    let name = VarBound::from_string(&declared);
+                    ast::Arg::NamedArg { name, arg: Box::new(arg), erased: false }
+                }
+                (_, false) => {
+                    let arg = self.refined_value(ctx, Some(Lvl { fst: *fst, snd }), lvl, span)?;
+                    ast::Arg::UnnamedArg { arg: Box::new(arg), erased: false }
+                }
+            };
+            args.push(arg);
+        }
+        Ok(ast::Call {
+            span: None,
+            kind: ast::CallKind::Constructor,
+            name,
+            args: ast::Args { args },
+            inferred_type: None,
+        }
+        .into())
+    }
+
+    /// The value `C(y₁, ..., yₙ)` matched by a case for the constructor `C` whose arguments are bound at level `fst`
+    fn ctor_call(
+        &self,
+        ctx: &mut Ctx,
+        ctor: &Ctor,
+        fst: usize,
+        span: Span,
+    ) -> LoweringResult<ast::Exp> {
+        let (DeclMeta::Ctor { params, .. }, name) = ctor.lookup(ctx)? else {
+            return Err(LoweringError::Impossible {
+                message: format!("Expected {} to be a constructor", ctor.name.id),
+                span: Some(span.to_miette()),
+            }
+            .into());
+        };
+        let params = params_info(params);
+        let mut args = Vec::with_capacity(params.len());
+        for (snd, (_, implicit)) in params.into_iter().enumerate() {
+            if implicit {
+                args.push(inserted_implicit_arg(span, ctx));
+                continue;
+            }
+            let lvl = Lvl { fst, snd };
+            let var = ast::Variable {
+                span: None,
+                idx: ctx.level_to_index(lvl),
+                name: self.var_name(lvl),
+                inferred_type: None,
+            };
+            args.push(ast::Arg::UnnamedArg { arg: Box::new(var.into()), erased: false });
+        }
+        Ok(ast::Call {
+            span: Some(span),
+            kind: ast::CallKind::Constructor,
+            name,
+            args: ast::Args { args },
+            inferred_type: None,
+        }
+        .into())
+    }
+
+    fn check_reachable(&self) -> LoweringResult {
+        match self.used.iter().position(|used| !used) {
+            Some(index) => {
+                Err(LoweringError::UnreachableClause { span: self.clauses[index].span.to_miette() }
+                    .into())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Resolve whether a nested pattern binds a variable or matches on a constructor.
+///
+/// A nested pattern `x` without arguments binds a variable unless `x` is a constructor.
+/// If the pattern is not `nested` in another pattern, then undefined names starting with an uppercase
+/// letter are reported as undefined, since they are most likely misspelled constructors rather than
+/// catch-all clauses. Arguments of constructors are often types named by uppercase letters.
+fn resolve<'a>(
+    ctx: &mut Ctx,
+    pattern: &'a OrWildcard<Pattern>,
+    nested: bool,
+) -> LoweringResult<Resolved<'a>> {
+    let pattern = match pattern {
+        OrWildcard::Wildcard { span } => return Ok(Resolved::Wildcard(*span)),
+        OrWildcard::Pattern(pattern) => pattern,
+    };
+    let resolved = match &pattern.qualifier {
+        Some(alias) => Some(ctx.symbol_table.lookup_qualified(alias, &pattern.name)?),
+        None => ctx.symbol_table.lookup(&pattern.name).ok(),
+    };
+    let is_defined = resolved.is_some();
+    if let Some((DeclMeta::Ctor { .. }, name)) = resolved {
+        let ctor = Ctor { uri: name.uri, qualifier: name.qualifier, name: pattern.name.clone() };
+        return Ok(Resolved::Ctor(pattern, ctor));
+    }
+    let is_capitalized = pattern.name.id.starts_with(char::is_uppercase);
+    let is_var = is_defined || nested || !is_capitalized;
+    if pattern.params.is_empty() && pattern.qualifier.is_none() && is_var {
+        let var = BindingSite::Var { span: pattern.span, name: pattern.name.clone() }.lower(ctx)?;
+        return Ok(Resolved::Var(var));
+    }
+    if !is_defined {
+        ctx.symbol_table.lookup(&pattern.name)?;
+    }
+    Err(LoweringError::NotAConstructor {
+        name: pattern.name.id.clone(),
+        span: pattern.name.span.to_miette(),
+    }
+    .into())
+}
+
+/// The constructor named by the pattern `pattern`, which may be qualified by a module alias
+fn resolve_ctor(ctx: &Ctx, pattern: &Pattern) -> LoweringResult<Ctor> {
+    let (_, name) = match &pattern.qualifier {
+        Some(alias) => ctx.symbol_table.lookup_qualified(alias, &pattern.name)?,
+        None => ctx.symbol_table.lookup(&pattern.name)?,
+    };
+    Ok(Ctor { uri: name.uri, qualifier: name.qualifier, name: pattern.name.clone() })
+}
+
+/// Choose a name based on `name` which is distinct from the names in `taken`
+pub fn distinct_name(taken: &[ast::VarBind], mut name: String) -> String {
+    while taken.iter().any(|other| matches!(other, ast::VarBind::Var { id, .. } if *id == name)) {
+        name = increment_name(name);
+    }
+    name
+}
+
+/// Generate a name based on `name` which is distinct from the names in `taken` and does not shadow a variable in scope
+fn fresh_name(ctx: &Ctx, taken: &[ast::VarBind], name: &str) -> String {
+    let mut name = distinct_name(taken, name.to_owned());
+    while ctx.lookup_local(&Ident { span: Span::default(), id: name.clone() }).is_some() {
+        name = distinct_name(taken, increment_name(name));
+    }
+    name
+}
+
+/// The names of the parameters in a telescope, together with whether they are implicit
+fn params_info(params: &cst::decls::Telescope) -> Vec<(Option<String>, bool)> {
+    params
+        .0
+        .iter()
+        .flat_map(|param| {
+            std::iter::once(&param.name).chain(param.names.iter()).map(|name| match name {
+                BindingSite::Var { name, .. } => (Some(name.id.clone()), param.implicit),
+                BindingSite::Wildcard { .. } => (None, param.implicit),
+            })
+        })
+        .collect()
+}
+
+/// The constructors of the data type to which the constructor `ctor` belongs
+fn data_ctors(ctx: &Ctx, ctor: &Ctor) -> LoweringResult<Vec<Ctor>> {
+    let Ctor { uri, qualifier, name } = ctor;
+    let (DeclMeta::Ctor { data, .. }, _) = ctx.symbol_table.lookup_in(uri, name)? else {
+        return Err(LoweringError::NotAConstructor {
+            name: name.id.clone(),
+            span: name.span.to_miette(),
+        }
+        .into());
+    };
+    match ctx.symbol_table.lookup_in(uri, data)? {
+        (DeclMeta::Data { ctors, .. }, _) => Ok(ctors
+            .iter()
+            .map(|name| Ctor { uri: uri.clone(), qualifier: qualifier.clone(), name: name.clone() })
+            .collect()),
+        _ => Err(LoweringError::Impossible {
+            message: format!("Expected {} to be a data type", data.id),
+            span: Some(name.span.to_miette()),
+        }
+        .into()),
+    }
+}
+
+/// Whether `ctors` contains all constructors of their data type
+fn names_all_ctors(ctx: &Ctx, ctors: &[Ctor]) -> LoweringResult<bool> {
+    let Some(ctor) = ctors.first() else {
+        return Ok(false);
+    };
+    Ok(data_ctors(ctx, ctor)?
+        .iter()
+        .all(|ctor| ctors.iter().any(|other| other.name.id == ctor.name.id)))
+}
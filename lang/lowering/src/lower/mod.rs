@@ -3,6 +3,8 @@ use super::result::*;
 mod decls;
 mod exp;
 
+use exp::{lower_cases, lower_cocases};
+
 pub trait Lower {
     type Target;
 
@@ -101,6 +101,9 @@ pub enum LoweringError {
     },
     #[error("Type universe \"Type\" does not take arguments")]
     #[diagnostic(code("L-015"))]
+    #[diagnostic(help(
+        "Universe levels are only available in modules with the #![predicative] attribute."
+    ))]
     TypeUnivArgs {
         #[label]
         span: SourceSpan,
@@ -133,6 +136,62 @@ pub enum LoweringError {
         span: SourceSpan,
         message: String,
     },
+    #[error("Expected a universe level")]
+    #[diagnostic(code("L-020"))]
+    #[diagnostic(help(
+        "A universe level is a number, a level variable, a level plus a number, or a maximum max(l, k)."
+    ))]
+    InvalidLevel {
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Wrong number of arguments in pattern for {name}: got {actual}, expected {expected}")]
+    #[diagnostic(code("L-021"))]
+    PatternArity {
+        name: String,
+        expected: usize,
+        actual: usize,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("{name} is not a constructor")]
+    #[diagnostic(code("L-022"))]
+    #[diagnostic(help("Only constructors can be used in nested patterns."))]
+    NotAConstructor {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("This clause is unreachable")]
+    #[diagnostic(code("L-023"))]
+    #[diagnostic(help(
+        "All values matched by this clause are already matched by previous clauses."
+    ))]
+    UnreachableClause {
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("This clause for .{name} overlaps with a previous clause with a deep copattern")]
+    #[diagnostic(code("L-025"))]
+    #[diagnostic(help(
+        "A destructor is either defined by a single clause or by clauses with deep copatterns."
+    ))]
+    OverlappingCopatterns {
+        name: String,
+        #[label]
+        span: SourceSpan,
+    },
+    #[error("Cannot mix operators {lhs} and {rhs} without parentheses")]
+    #[diagnostic(code("L-026"))]
+    #[diagnostic(help(
+        "Operators of the same precedence can only be chained if they are both left-associative or both right-associative."
+    ))]
+    AmbiguousOperators {
+        lhs: String,
+        rhs: String,
+        #[label]
+        span: SourceSpan,
+    },
     #[error("An unexpected internal error occurred: {message}")]
     #[diagnostic(code("L-XXX"))]
     /// This error should not occur.
@@ -5,7 +5,7 @@ use parser::cst::*;
 
 use crate::{LoweringError, LoweringResult};
 
-use super::{DeclMeta, ModuleSymbolTable};
+use super::{DeclMeta, ModuleSymbolTable, OperatorMeta};
 
 pub fn build_symbol_table(module: &Module) -> LoweringResult<ModuleSymbolTable> {
     let mut symbol_table = ModuleSymbolTable::default();
@@ -19,6 +19,20 @@ pub fn build_symbol_table(module: &Module) -> LoweringResult<ModuleSymbolTable>
     Ok(symbol_table)
 }
 
+/// The attribute which hides a declaration from the modules which import it
+const PRIVATE: &str = "private";
+
+/// Records that the names are private if the attributes contain `#[private]`.
+fn mark_private<'a>(
+    symbol_table: &mut ModuleSymbolTable,
+    attr: &Attributes,
+    names: impl IntoIterator<Item = &'a Ident>,
+) {
+    if attr.attrs.iter().any(|attr| attr == PRIVATE) {
+        symbol_table.private.extend(names.into_iter().cloned());
+    }
+}
+
 /// Checks whether the identifier is reserved or already defined.
 fn check_name(symbol_table: &mut ModuleSymbolTable, name: &Ident, span: &Span) -> LoweringResult {
     if name.id == "Type" {
@@ -47,72 +61,70 @@ impl BuildSymbolTable for Decl {
             Decl::Codef(codef) => codef.build(symbol_table),
             Decl::Let(tl_let) => tl_let.build(symbol_table),
             Decl::Infix(infix) => infix.build(symbol_table),
+            Decl::Error(_) => Ok(()),
         }
     }
 }
 
 impl BuildSymbolTable for Data {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Data { span, name, params, ctors, .. } = self;
+        let Data { span, name, attr, params, ctors, .. } = self;
 
         check_name(symbol_table, name, span)?;
 
-        let meta = DeclMeta::Data { params: params.clone() };
+        let meta = DeclMeta::Data {
+            params: params.clone(),
+            ctors: ctors.iter().map(|ctor| ctor.name.clone()).collect(),
+        };
         symbol_table.idents.insert(name.clone(), meta);
 
         for ctor in ctors {
-            ctor.build(symbol_table)?;
+            check_name(symbol_table, &ctor.name, &ctor.span)?;
+            let meta = DeclMeta::Ctor { params: ctor.params.clone(), data: name.clone() };
+            symbol_table.idents.insert(ctor.name.clone(), meta);
         }
-        Ok(())
-    }
-}
-
-impl BuildSymbolTable for Ctor {
-    fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Ctor { span, name, params, .. } = self;
-        check_name(symbol_table, name, span)?;
-
-        let meta = DeclMeta::Ctor { params: params.clone() };
-        symbol_table.idents.insert(name.clone(), meta);
-
+        mark_private(
+            symbol_table,
+            attr,
+            std::iter::once(name).chain(ctors.iter().map(|c| &c.name)),
+        );
         Ok(())
     }
 }
 
 impl BuildSymbolTable for Codata {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Codata { span, name, params, dtors, .. } = self;
+        let Codata { span, name, attr, params, dtors, .. } = self;
         check_name(symbol_table, name, span)?;
 
-        let meta = DeclMeta::Codata { params: params.clone() };
+        let meta = DeclMeta::Codata {
+            params: params.clone(),
+            dtors: dtors.iter().map(|dtor| dtor.name.clone()).collect(),
+        };
         symbol_table.idents.insert(name.clone(), meta);
 
         for dtor in dtors {
-            dtor.build(symbol_table)?;
+            check_name(symbol_table, &dtor.name, &dtor.span)?;
+            let meta = DeclMeta::Dtor { params: dtor.params.clone(), codata: name.clone() };
+            symbol_table.idents.insert(dtor.name.clone(), meta);
         }
-        Ok(())
-    }
-}
-
-impl BuildSymbolTable for Dtor {
-    fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Dtor { span, name, params, .. } = self;
-        check_name(symbol_table, name, span)?;
-
-        let meta = DeclMeta::Dtor { params: params.clone() };
-        symbol_table.idents.insert(name.clone(), meta);
-
+        mark_private(
+            symbol_table,
+            attr,
+            std::iter::once(name).chain(dtors.iter().map(|d| &d.name)),
+        );
         Ok(())
     }
 }
 
 impl BuildSymbolTable for Def {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Def { span, name, params, .. } = self;
+        let Def { span, name, attr, params, .. } = self;
         check_name(symbol_table, name, span)?;
 
         let meta = DeclMeta::Def { params: params.clone() };
         symbol_table.idents.insert(name.clone(), meta);
+        mark_private(symbol_table, attr, [name]);
 
         Ok(())
     }
@@ -120,11 +132,12 @@ impl BuildSymbolTable for Def {
 
 impl BuildSymbolTable for Codef {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Codef { span, name, params, .. } = self;
+        let Codef { span, name, attr, params, .. } = self;
         check_name(symbol_table, name, span)?;
 
         let meta = DeclMeta::Codef { params: params.clone() };
         symbol_table.idents.insert(name.clone(), meta);
+        mark_private(symbol_table, attr, [name]);
 
         Ok(())
     }
@@ -132,11 +145,12 @@ impl BuildSymbolTable for Codef {
 
 impl BuildSymbolTable for Let {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Let { span, name, params, .. } = self;
+        let Let { span, name, attr, params, .. } = self;
         check_name(symbol_table, name, span)?;
 
         let meta = DeclMeta::Let { params: params.clone() };
         symbol_table.idents.insert(name.clone(), meta);
+        mark_private(symbol_table, attr, [name]);
 
         Ok(())
     }
@@ -144,17 +158,56 @@ impl BuildSymbolTable for Let {
 
 impl BuildSymbolTable for Infix {
     fn build(&self, symbol_table: &mut ModuleSymbolTable) -> LoweringResult {
-        let Infix { span, doc: _, lhs, rhs } = self;
+        let Infix { span, lhs, rhs, .. } = self;
 
-        if symbol_table.infix_ops.contains_key(&lhs.operator) {
+        // The parser ensures that the left hand side contains at least one operator.
+        let (operator, _) = &lhs.rest[0];
+
+        if symbol_table.infix_ops.contains_key(operator) {
             return Err(LoweringError::OperatorAlreadyDefined {
-                operator: lhs.operator.id.to_owned(),
+                operator: operator.id.to_owned(),
                 span: span.to_miette(),
             }
             .into());
         }
-        symbol_table.infix_ops.insert(lhs.operator.clone(), rhs.name.clone());
+        let meta = OperatorMeta { name: rhs.name.clone(), fixity: infix_fixity(self)? };
+        symbol_table.infix_ops.insert(operator.clone(), meta);
 
         Ok(())
     }
 }
+
+/// Determine the fixity of the operator declared by an infix declaration
+pub fn infix_fixity(infix: &Infix) -> LoweringResult<ast::Fixity> {
+    let Infix { associativity, precedence, .. } = infix;
+
+    // Declarations such as `infix _ -> _ := Fun(_,_)` keep the meaning they had before operators
+    // could be given a fixity: all of these operators are right-associative and equally binding.
+    if precedence.is_none() && *associativity == Associativity::None {
+        return Ok(ast::Fixity {
+            precedence: ast::Fixity::DEFAULT_PRECEDENCE,
+            associativity: ast::Associativity::Right,
+        });
+    }
+
+    let associativity = match associativity {
+        Associativity::Left => ast::Associativity::Left,
+        Associativity::Right => ast::Associativity::Right,
+        Associativity::None => ast::Associativity::None,
+    };
+    let precedence = match precedence {
+        Some(exp::NatLit { span, val }) => u32::try_from(val)
+            .ok()
+            .filter(|precedence| *precedence <= ast::Fixity::MAX_PRECEDENCE)
+            .ok_or_else(|| LoweringError::InvalidInfixDeclaration {
+                message: format!(
+                    "The precedence of an infix operator must be a number between 0 and {}.",
+                    ast::Fixity::MAX_PRECEDENCE
+                ),
+                span: span.to_miette(),
+            })?,
+        None => ast::Fixity::DEFAULT_PRECEDENCE,
+    };
+
+    Ok(ast::Fixity { precedence, associativity })
+}
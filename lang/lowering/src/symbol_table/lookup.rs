@@ -1,59 +1,201 @@
-use ast::IdBound;
+use ast::{HashMap, IdBound};
 use miette_util::ToMiette;
 use parser::cst::ident::{Ident, Operator};
 use url::Url;
 
 use crate::{LoweringError, LoweringResult};
 
-use super::{DeclMeta, SymbolTable};
+use super::{DeclMeta, Import, OperatorMeta, SymbolTable};
 
 impl SymbolTable {
-    /// Check whether the identifier already exists in any of the symbol tables.
-    pub fn lookup_exists(&self, name: &Ident) -> bool {
-        for symbol_table in self.map.values() {
-            if symbol_table.idents.contains_key(name) {
-                return true;
+    /// Lookup a name which the import exports, regardless of the names listed in the use declaration.
+    fn lookup_export<'a>(
+        &'a self,
+        import: &'a Import,
+        name: &Ident,
+    ) -> Option<(&'a DeclMeta, &'a Url)> {
+        import.exports.iter().find_map(|export| {
+            let symbol_table = &self.map[&export.uri];
+            if symbol_table.private.contains(name) || !export.admits(name) {
+                return None;
             }
+            symbol_table.idents.get(name).map(|meta| (meta, &export.uri))
+        })
+    }
+
+    /// Lookup a name which the import brings into scope.
+    fn lookup_import<'a>(
+        &'a self,
+        import: &'a Import,
+        name: &Ident,
+    ) -> Option<(&'a DeclMeta, &'a Url)> {
+        if !import.admits(name) {
+            return None;
         }
-        false
+        self.lookup_export(import, name)
+    }
+
+    /// The imports which bring names into scope without a qualifier
+    fn unqualified_imports(&self) -> impl Iterator<Item = &Import> {
+        self.imports.iter().filter(|import| import.alias.is_none())
+    }
+
+    /// Check whether the identifier is in scope without a qualifier.
+    pub fn lookup_exists(&self, name: &Ident) -> bool {
+        self.map[&self.uri].idents.contains_key(name)
+            || self.unqualified_imports().any(|import| self.lookup_import(import, name).is_some())
     }
 
     pub fn lookup(&self, name: &Ident) -> LoweringResult<(&DeclMeta, IdBound)> {
-        for (module_uri, symbol_table) in self.map.iter() {
-            match symbol_table.idents.get(name) {
-                Some(meta) => {
-                    let name = IdBound {
-                        span: Some(name.span),
-                        id: name.id.clone(),
-                        uri: module_uri.clone(),
-                    };
-                    return Ok((meta, name));
-                }
-                None => continue,
+        let own = self.map[&self.uri].idents.get(name).map(|meta| (meta, &self.uri));
+        let imported =
+            || self.unqualified_imports().find_map(|import| self.lookup_import(import, name));
+        match own.or_else(imported) {
+            Some((meta, uri)) => {
+                let name = IdBound {
+                    span: Some(name.span),
+                    id: name.id.clone(),
+                    uri: uri.clone(),
+                    qualifier: None,
+                };
+                Ok((meta, name))
+            }
+            None => Err(LoweringError::UndefinedIdent {
+                name: name.clone(),
+                span: name.span.to_miette(),
+            }
+            .into()),
+        }
+    }
+
+    /// Check whether the identifier is the alias of an imported module.
+    pub fn is_alias(&self, name: &Ident) -> bool {
+        self.imports.iter().any(|import| import.alias.as_ref() == Some(name))
+    }
+
+    /// Lookup the name `name` qualified by the module alias `alias`, as in `L.Cons`.
+    pub fn lookup_qualified(
+        &self,
+        alias: &Ident,
+        name: &Ident,
+    ) -> LoweringResult<(&DeclMeta, IdBound)> {
+        for import in self.imports.iter().filter(|import| import.alias.as_ref() == Some(alias)) {
+            if let Some((meta, uri)) = self.lookup_import(import, name) {
+                let name = IdBound {
+                    span: Some(name.span),
+                    id: name.id.clone(),
+                    uri: uri.clone(),
+                    qualifier: Some(alias.id.clone()),
+                };
+                return Ok((meta, name));
             }
         }
         Err(LoweringError::UndefinedIdent { name: name.clone(), span: name.span.to_miette() }
             .into())
     }
 
-    /// Check whether the operator already exists in any of the symbol tables.
-    pub fn lookup_operator_exists(&self, op: &Operator) -> bool {
-        for symbol_table in self.map.values() {
-            if symbol_table.infix_ops.contains_key(op) {
-                return true;
+    /// Lookup a name in the module with the given URI, regardless of whether it is in scope.
+    ///
+    /// This is used to resolve names which are recorded in the metadata of another declaration,
+    /// such as the type constructor of a constructor.
+    pub fn lookup_in(&self, uri: &Url, name: &Ident) -> LoweringResult<(&DeclMeta, IdBound)> {
+        match self.map.get(uri).and_then(|symbol_table| symbol_table.idents.get(name)) {
+            Some(meta) => {
+                let name = IdBound {
+                    span: Some(name.span),
+                    id: name.id.clone(),
+                    uri: uri.clone(),
+                    qualifier: None,
+                };
+                Ok((meta, name))
+            }
+            None => Err(LoweringError::UndefinedIdent {
+                name: name.clone(),
+                span: name.span.to_miette(),
             }
+            .into()),
         }
-        false
     }
 
-    pub fn lookup_operator(&self, op: &Operator) -> LoweringResult<(&Ident, &Url)> {
-        for (module_uri, symbol_table) in self.map.iter() {
-            match symbol_table.infix_ops.get(op) {
-                Some(id) => return Ok((id, module_uri)),
-                None => continue,
+    /// Check whether the operator is in scope.
+    pub fn lookup_operator_exists(&self, op: &Operator) -> bool {
+        self.lookup_operator(op).is_ok()
+    }
+
+    /// Lookup an operator.
+    ///
+    /// An imported operator is in scope if the name it stands for is in scope without a qualifier.
+    pub fn lookup_operator(&self, op: &Operator) -> LoweringResult<(&OperatorMeta, &Url)> {
+        if let Some(meta) = self.map[&self.uri].infix_ops.get(op) {
+            return Ok((meta, &self.uri));
+        }
+        for import in self.unqualified_imports() {
+            for export in &import.exports {
+                let symbol_table = &self.map[&export.uri];
+                match symbol_table.infix_ops.get(op) {
+                    Some(meta)
+                        if import.admits(&meta.name)
+                            && export.admits(&meta.name)
+                            && !symbol_table.private.contains(&meta.name) =>
+                    {
+                        return Ok((meta, &export.uri))
+                    }
+                    _ => continue,
+                }
             }
         }
         Err(LoweringError::UnknownOperator { span: op.span.to_miette(), operator: op.id.clone() }
             .into())
     }
+
+    /// Check that the names listed in the use declarations exist and that no two different
+    /// declarations are in scope under the same name.
+    pub fn check_imports(&self) -> LoweringResult {
+        // Maps each qualifier and name in scope to the module which defines it
+        let mut in_scope: HashMap<(Option<&Ident>, &Ident), &Url> = HashMap::default();
+        for name in self.map[&self.uri].idents.keys() {
+            in_scope.insert((None, name), &self.uri);
+        }
+
+        for import in &self.imports {
+            for item in import.filter.iter().flat_map(|filter| filter.items()) {
+                if self.lookup_export(import, item).is_none() {
+                    return Err(LoweringError::UndefinedIdent {
+                        name: item.clone(),
+                        span: item.span.to_miette(),
+                    }
+                    .into());
+                }
+            }
+            let mut names: Vec<(&Ident, &Url)> = import
+                .exports
+                .iter()
+                .flat_map(|export| self.map[&export.uri].idents.keys())
+                .filter_map(|name| self.lookup_import(import, name).map(|(_, uri)| (name, uri)))
+                .collect();
+            names.sort_by(|(x, _), (y, _)| x.id.cmp(&y.id));
+            for (name, uri) in names {
+                let defined_in = in_scope.entry((import.alias.as_ref(), name)).or_insert(uri);
+                if *defined_in != uri {
+                    return Err(LoweringError::AlreadyDefined {
+                        name: name.clone(),
+                        span: import.span.to_miette(),
+                    }
+                    .into());
+                }
+            }
+        }
+
+        // Qualified names must not be confused with dot calls on a top-level name.
+        for alias in self.imports.iter().filter_map(|import| import.alias.as_ref()) {
+            if in_scope.contains_key(&(None, alias)) {
+                return Err(LoweringError::AlreadyDefined {
+                    name: alias.clone(),
+                    span: alias.span.to_miette(),
+                }
+                .into());
+            }
+        }
+        Ok(())
+    }
 }
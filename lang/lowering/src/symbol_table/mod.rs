@@ -1,8 +1,9 @@
 use std::sync::Arc;
 
-use ast::HashMap;
+use ast::{HashMap, HashSet};
 use decls::*;
 use ident::Ident;
+use miette_util::codespan::Span;
 use parser::cst::{ident::Operator, *};
 use url::Url;
 
@@ -15,29 +16,104 @@ pub struct ModuleSymbolTable {
     /// The mapping of identifiers to their metadata
     pub idents: HashMap<Ident, DeclMeta>,
     /// The mapping of operators to their definition
-    pub infix_ops: HashMap<Operator, Ident>,
+    pub infix_ops: HashMap<Operator, OperatorMeta>,
+    /// The names which are declared `#[private]` and are not visible to importers
+    pub private: HashSet<Ident>,
 }
 
 /// The symbol table for a module and all of its imported modules.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Clone)]
 pub struct SymbolTable {
+    /// The module which is being lowered
+    uri: Url,
     // Maps modules to their respective symbol tables.
     map: HashMap<Url, Arc<ModuleSymbolTable>>,
+    /// The imports of the module in the order of its use declarations
+    imports: Vec<Import>,
 }
 
 impl SymbolTable {
-    pub fn insert(&mut self, url: Url, other: Arc<ModuleSymbolTable>) {
-        self.map.insert(url, other);
+    /// Create the symbol table of the module with the given `uri` without any imports.
+    pub fn new(uri: Url, module: Arc<ModuleSymbolTable>) -> Self {
+        let mut map = HashMap::default();
+        map.insert(uri.clone(), module);
+        Self { uri, map, imports: vec![] }
+    }
+
+    /// Add the symbol table of a module whose names are exported by an import.
+    pub fn insert(&mut self, uri: Url, module: Arc<ModuleSymbolTable>) {
+        self.map.insert(uri, module);
+    }
+
+    /// Bring the names exported by the imported module into scope.
+    ///
+    /// The symbol tables of all modules in `import.exports` must have been inserted.
+    pub fn import(&mut self, import: Import) {
+        self.imports.push(import);
+    }
+}
+
+/// A module brought into scope by a use declaration
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// The span of the use declaration
+    pub span: Span,
+    /// The module the path of the use declaration resolves to
+    pub uri: Url,
+    /// If present, the names of the module are only in scope qualified by the alias
+    pub alias: Option<Ident>,
+    /// Restricts the names of the module which are brought into scope
+    pub filter: Option<ImportFilter>,
+    /// The modules whose names are exported by the imported module
+    pub exports: Vec<Export>,
+}
+
+impl Import {
+    pub fn new(use_decl: &UseDecl, uri: Url, exports: Vec<Export>) -> Self {
+        let UseDecl { span, alias, filter, .. } = use_decl;
+        Self { span: *span, uri, alias: alias.clone(), filter: filter.clone(), exports }
+    }
+
+    /// Whether the import brings the given name into scope, qualified or unqualified
+    fn admits(&self, name: &Ident) -> bool {
+        self.filter.as_ref().map_or(true, |filter| filter.admits(name))
+    }
+}
+
+/// A module whose names are exported by another module
+///
+/// A module exports its own public names and, through `pub use` declarations, the names exported
+/// by other modules.
+#[derive(Debug, Clone)]
+pub struct Export {
+    /// The module which defines the exported names
+    pub uri: Url,
+    /// The filters of the `pub use` declarations through which the names are re-exported
+    pub filters: Vec<ImportFilter>,
+}
+
+impl Export {
+    /// Whether the re-exports admit the given name
+    fn admits(&self, name: &Ident) -> bool {
+        self.filters.iter().all(|filter| filter.admits(name))
     }
 }
 
 #[derive(Clone, Debug)]
 pub enum DeclMeta {
-    Data { params: Telescope },
-    Codata { params: Telescope },
+    Data { params: Telescope, ctors: Vec<Ident> },
+    Codata { params: Telescope, dtors: Vec<Ident> },
     Def { params: Telescope },
     Codef { params: Telescope },
-    Ctor { params: Telescope },
-    Dtor { params: Telescope },
+    Ctor { params: Telescope, data: Ident },
+    Dtor { params: Telescope, codata: Ident },
     Let { params: Telescope },
 }
+
+#[derive(Clone, Debug)]
+pub struct OperatorMeta {
+    /// The type constructor the operator stands for
+    pub name: Ident,
+    /// The precedence and associativity of the operator
+    pub fixity: ast::Fixity,
+}
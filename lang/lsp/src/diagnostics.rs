@@ -44,32 +44,35 @@ impl Diagnostics for Database {
     }
 
     fn error_diagnostics(&self, uri: &Url, error: Error) -> Vec<lsp_types::Diagnostic> {
-        // Compute the range where the error should be displayed.
-        // The range is computed from the first available label, otherwise
-        // the default range is used, which corresponds to the beginning of the
-        // file.
-        let span = get_span(&error);
-        let range =
-            span.and_then(|x| self.span_to_locations(uri, x.from_miette())).unwrap_or_default();
+        // An error may consist of several errors, e.g. all type errors of a module, which are published together.
+        error.into_errors().into_iter().map(|error| error_diagnostic(self, uri, error)).collect()
+    }
+}
+
+fn error_diagnostic(db: &Database, uri: &Url, error: Error) -> lsp_types::Diagnostic {
+    // Compute the range where the error should be displayed.
+    // The range is computed from the first available label, otherwise
+    // the default range is used, which corresponds to the beginning of the
+    // file.
+    let span = get_span(&error);
+    let range = span.and_then(|x| db.span_to_locations(uri, x.from_miette())).unwrap_or_default();
 
-        // Compute the message.
-        let message = error.to_string();
+    // Compute the message.
+    let message = error.to_string();
 
-        let diag = lsp_types::Diagnostic {
-            range,
-            message,
-            severity: match error.severity() {
-                Some(sev) => Some(sev.to_lsp()),
-                None => Some(lsp_types::DiagnosticSeverity::ERROR),
-            },
-            code: error.code().map(|x| NumberOrString::String(format!("{x}"))),
-            code_description: None,
-            source: None,
-            related_information: None,
-            tags: None,
-            data: None,
-        };
-        vec![diag]
+    lsp_types::Diagnostic {
+        range,
+        message,
+        severity: match error.severity() {
+            Some(sev) => Some(sev.to_lsp()),
+            None => Some(lsp_types::DiagnosticSeverity::ERROR),
+        },
+        code: error.code().map(|x| NumberOrString::String(format!("{x}"))),
+        code_description: None,
+        source: None,
+        related_information: None,
+        tags: None,
+        data: None,
     }
 }
 
@@ -6,5 +6,7 @@ mod format;
 mod gotodefinition;
 mod hover;
 mod server;
+mod stdsource;
 
 pub use server::*;
+pub use stdsource::STD_SOURCE_METHOD;
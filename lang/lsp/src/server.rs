@@ -1,35 +1,66 @@
 use async_lock::RwLock;
 use tower_lsp_server::jsonrpc::Result;
-use tower_lsp_server::{jsonrpc, lsp_types::*, LanguageServer};
+use tower_lsp_server::{jsonrpc, lsp_types::*, ClientSocket, LanguageServer, LspService};
 
-use driver::Database;
+use std::time::Duration;
+
+use driver::{Database, EvalLimits};
 #[cfg(not(target_arch = "wasm32"))]
-use driver::{FileSource, FileSystemSource, InMemorySource};
+use driver::{FileSource, FileSystemSource, InMemorySource, Manifest, StdSource, MANIFEST_FILE};
 
 use crate::conversion::{FromLsp, ToLsp};
 
 use super::capabilities::*;
 use super::diagnostics::*;
 
+/// The maximal number of evaluation steps of the language server unless the client configures it
+///
+/// Unlike `pol check` and `pol run`, the language server typechecks every edit, so a diverging
+/// evaluation must not block it. Typechecking the standard library and the examples takes far
+/// fewer steps, so that a diverging evaluation is reported within a few seconds even in debug builds.
+pub const DEFAULT_MAX_STEPS: u64 = 100_000;
+
 pub struct Server {
     pub client: tower_lsp_server::Client,
     pub database: RwLock<Database>,
+    /// The limits on evaluation which apply unless the client configures them
+    pub default_limits: EvalLimits,
 }
 
 impl Server {
     pub fn new(client: tower_lsp_server::Client) -> Self {
-        let database = Database::in_memory();
+        let mut database = Database::in_memory();
+        database.eval_limits.max_steps = Some(DEFAULT_MAX_STEPS);
         Self::with_database(client, database)
     }
 
     pub fn with_database(client: tower_lsp_server::Client, database: Database) -> Self {
-        Server { client, database: RwLock::new(database) }
+        let default_limits = database.eval_limits;
+        Server { client, database: RwLock::new(database), default_limits }
+    }
+
+    /// Create the service of the language server, including its custom requests
+    pub fn service<F>(init: F) -> (LspService<Self>, ClientSocket)
+    where
+        F: FnOnce(tower_lsp_server::Client) -> Self,
+    {
+        LspService::build(init)
+            .custom_method(super::stdsource::STD_SOURCE_METHOD, Self::std_source)
+            .finish()
+    }
+
+    /// Respond to the custom request for the source of a module of the standard library
+    pub async fn std_source(&self, params: TextDocumentIdentifier) -> jsonrpc::Result<String> {
+        super::stdsource::std_source(self, params).await
     }
 }
 
 impl LanguageServer for Server {
     async fn initialize(&self, params: InitializeParams) -> jsonrpc::Result<InitializeResult> {
         let capabilities = capabilities();
+        if let Some(options) = &params.initialization_options {
+            self.database.write().await.eval_limits = eval_limits(options, self.default_limits);
+        }
         #[cfg(not(target_arch = "wasm32"))]
         // FIXME: Use `workspace_folders` instead of `root_uri`.
         // `root_uri` is deprecated in in favor of `workspace_folders`, see:
@@ -38,10 +69,20 @@ impl LanguageServer for Server {
         if let Some(root_uri) = params.root_uri {
             let root_path =
                 root_uri.from_lsp().to_file_path().map_err(|_| jsonrpc::Error::internal_error())?;
-            let source = InMemorySource::new().fallback_to(FileSystemSource::new(root_path));
+            let manifest = match Manifest::discover(&root_path) {
+                Ok(manifest) => manifest,
+                Err(err) => {
+                    self.client.log_message(MessageType::ERROR, err.to_string()).await;
+                    None
+                }
+            };
+            let source = InMemorySource::new()
+                .fallback_to(StdSource::new())
+                .fallback_to(FileSystemSource::new(root_path));
             let mut database = self.database.write().await;
             let source_mut = database.file_source_mut();
             *source_mut = Box::new(source);
+            database.manifest = manifest;
         }
         // prevent unused variable warning when compiled for wasm
         let _ = params;
@@ -52,6 +93,42 @@ impl LanguageServer for Server {
         self.client.log_message(MessageType::INFO, "server initialized!").await;
     }
 
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        let mut db = self.database.write().await;
+        let limits = eval_limits(&params.settings, self.default_limits);
+        if db.eval_limits == limits {
+            return;
+        }
+        db.eval_limits = limits;
+        // Whether typechecking succeeds depends on the limits.
+        self.recheck_all(&mut db).await;
+    }
+
+    /// Reload the manifest if a manifest file of the project was created, changed or deleted
+    ///
+    /// The client is expected to watch the files named `polarity.toml`.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let mut db = self.database.write().await;
+        let dirs: Vec<_> = params
+            .changes
+            .iter()
+            .filter_map(|change| change.uri.from_lsp().to_file_path().ok())
+            .filter(|path| path.file_name().is_some_and(|name| name == MANIFEST_FILE))
+            .filter_map(|path| path.parent().map(ToOwned::to_owned))
+            .collect();
+        // The manifest applies to the modules in its directory, so it is discovered again for
+        // one of them, or for its own directory if it is the manifest currently in use.
+        let root = db.manifest.as_ref().and_then(|manifest| manifest.root.to_file_path().ok());
+        let modules: Vec<_> = db.ast.keys().filter_map(|uri| uri.to_file_path().ok()).collect();
+        for dir in dirs {
+            let module = modules.iter().find(|module| module.starts_with(&dir));
+            if let Some(path) = module.or_else(|| root.as_ref().filter(|root| **root == dir)) {
+                self.update_manifest(&mut db, path).await;
+            }
+        }
+    }
+
     async fn shutdown(&self) -> jsonrpc::Result<()> {
         self.client.log_message(MessageType::INFO, "server shutdown!").await;
         Ok(())
@@ -72,6 +149,11 @@ impl LanguageServer for Server {
         assert!(source_mut.manage(&text_document.uri.from_lsp()));
         source_mut.write_string(&text_document.uri.from_lsp(), &text_document.text).await.unwrap();
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(path) = text_document.uri.from_lsp().to_file_path() {
+            self.update_manifest(&mut db, &path).await;
+        }
+
         let res = db.ast(&text_document.uri.from_lsp()).await.map(|_| ());
         let diags = db.diagnostics(&text_document.uri.from_lsp(), res).await;
         self.send_diagnostics(diags).await;
@@ -112,6 +194,11 @@ impl LanguageServer for Server {
 
         db.invalidate(&text_document.uri.from_lsp()).await;
 
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Ok(path) = text_document.uri.from_lsp().to_file_path() {
+            self.update_manifest(&mut db, &path).await;
+        }
+
         let res = db.ast(&text_document.uri.from_lsp()).await.map(|_| ());
         let diags = db.diagnostics(&text_document.uri.from_lsp(), res).await;
 
@@ -147,4 +234,56 @@ impl Server {
             self.client.publish_diagnostics(uri.to_lsp(), diags, None).await;
         }
     }
+
+    /// Check all previously checked modules again and publish their diagnostics
+    async fn recheck_all(&self, db: &mut Database) {
+        let uris: Vec<_> = db.ast.keys().cloned().collect();
+        db.invalidate_all();
+        for uri in uris {
+            let res = db.ast(&uri).await.map(|_| ());
+            let diags = db.diagnostics(&uri, res).await;
+            self.send_diagnostics(diags).await;
+        }
+    }
+
+    /// Use the manifest of the project which contains the file or directory at `path`
+    ///
+    /// The manifest is searched in the directory of `path` and its ancestors. Since the manifest
+    /// determines how `use` declarations are resolved, all modules are checked again if it changed.
+    #[cfg(not(target_arch = "wasm32"))]
+    async fn update_manifest(&self, db: &mut Database, path: &std::path::Path) {
+        // The file itself may not have been saved yet.
+        let dir = if path.is_dir() { path } else { path.parent().unwrap_or(path) };
+        if !dir.is_dir() {
+            return;
+        }
+        let manifest = match Manifest::discover(dir) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                self.client.log_message(MessageType::ERROR, err.to_string()).await;
+                return;
+            }
+        };
+        if manifest != db.manifest {
+            db.manifest = manifest;
+            self.recheck_all(db).await;
+        }
+    }
+}
+
+/// Read the limits on evaluation from the configuration of the client
+///
+/// The configuration may contain the fields `maxSteps` and `timeoutMs`, either at the top level or
+/// in a `polarity` section. A `maxSteps` of 0 means that the number of steps is not limited.
+/// Fields which are missing are taken from `defaults`.
+fn eval_limits(options: &LSPAny, defaults: EvalLimits) -> EvalLimits {
+    let options = options.get("polarity").unwrap_or(options);
+    let mut limits = defaults;
+    if let Some(max_steps) = options.get("maxSteps").and_then(LSPAny::as_u64) {
+        limits.max_steps = Some(max_steps).filter(|steps| *steps > 0);
+    }
+    if let Some(timeout) = options.get("timeoutMs").and_then(LSPAny::as_u64) {
+        limits.timeout = Some(Duration::from_millis(timeout));
+    }
+    limits
 }
@@ -0,0 +1,33 @@
+//! Implementation of the custom request for the source of the standard library
+//!
+//! The standard library is bundled with the server and its modules have `std:` URIs, for example
+//! as the target of goto-definition. Clients can not open these URIs themselves, so they request
+//! the source of the module with the custom request `polarity/stdSource`.
+
+use tower_lsp_server::{jsonrpc, lsp_types::*};
+
+use driver::STD_SCHEME;
+
+use super::conversion::*;
+use super::server::*;
+
+/// The method of the custom request, whose parameters are a [`TextDocumentIdentifier`] and whose
+/// result is the source of the module
+pub const STD_SOURCE_METHOD: &str = "polarity/stdSource";
+
+pub async fn std_source(
+    server: &Server,
+    params: TextDocumentIdentifier,
+) -> jsonrpc::Result<String> {
+    let uri = params.uri.from_lsp();
+
+    server.client.log_message(MessageType::INFO, format!("StdSource request: {uri}")).await;
+
+    if uri.scheme() != STD_SCHEME {
+        return Err(jsonrpc::Error::invalid_params(format!(
+            "Not a module of the standard library: {uri}"
+        )));
+    }
+    let mut db = server.database.write().await;
+    db.source(&uri).await.map_err(|err| jsonrpc::Error::invalid_params(err.to_string()))
+}
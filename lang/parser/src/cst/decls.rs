@@ -2,7 +2,7 @@ use miette_util::codespan::Span;
 use url::Url;
 
 use super::exp::{self, Pattern};
-use super::exp::{BinOp, Call, Copattern};
+use super::exp::{BinOp, Call, Copattern, NatLit, OrWildcard};
 use super::ident::*;
 
 #[derive(Debug, Clone)]
@@ -21,6 +21,8 @@ pub struct Attributes {
 pub struct Module {
     /// The location of the module on disk
     pub uri: Url,
+    /// Attributes of the module, written as `#![attr1,attr2]` at the top of the module.
+    pub attr: Attributes,
     /// List of module imports at the top of a module.
     pub use_decls: Vec<UseDecl>,
     /// Declarations contained in the module other than imports.
@@ -31,11 +33,46 @@ pub struct Module {
 ///
 /// ```text
 /// use "Data/Bool.pol"
+/// use "Data/List.pol" as L
+/// use "Data/List.pol" (List, Cons, Nil)
+/// use "Data/Nat.pol" hiding (add)
+/// pub use "Data/Bool.pol"
 /// ```
 #[derive(Debug, Clone)]
 pub struct UseDecl {
     pub span: Span,
+    /// Whether the names of the module are re-exported to the importers of this module
+    pub public: bool,
     pub path: String,
+    /// If present, the names of the module are only in scope qualified by the alias, e.g. `L.Cons`
+    pub alias: Option<Ident>,
+    /// Restricts the names of the module which are brought into scope
+    pub filter: Option<ImportFilter>,
+}
+
+/// The list of names in a use declaration
+#[derive(Debug, Clone)]
+pub enum ImportFilter {
+    /// Only import the given names: `(A, B)`
+    Only(Vec<Ident>),
+    /// Import all names except the given ones: `hiding (A, B)`
+    Hiding(Vec<Ident>),
+}
+
+impl ImportFilter {
+    /// Whether the filter admits the given name
+    pub fn admits(&self, name: &Ident) -> bool {
+        match self {
+            ImportFilter::Only(items) => items.contains(name),
+            ImportFilter::Hiding(items) => !items.contains(name),
+        }
+    }
+
+    pub fn items(&self) -> &[Ident] {
+        match self {
+            ImportFilter::Only(items) | ImportFilter::Hiding(items) => items,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -46,6 +83,8 @@ pub enum Decl {
     Codef(Codef),
     Let(Let),
     Infix(Infix),
+    /// A declaration which could not be parsed
+    Error(Span),
 }
 
 /// Data type declaration
@@ -158,7 +197,7 @@ pub struct Def {
     pub params: Telescope,
     pub scrutinee: Scrutinee,
     pub ret_typ: Box<exp::Exp>,
-    pub cases: Vec<exp::Case<Pattern>>,
+    pub cases: Vec<exp::Case<OrWildcard<Pattern>>>,
 }
 
 /// Scrutinee within a toplevel definition
@@ -194,7 +233,7 @@ pub struct Codef {
     pub attr: Attributes,
     pub params: Telescope,
     pub typ: exp::Call,
-    pub cases: Vec<exp::Case<Copattern>>,
+    pub cases: Vec<exp::Case<OrWildcard<Copattern>>>,
 }
 
 /// Toplevel let-bound expression.
@@ -222,16 +261,36 @@ pub struct Let {
 pub struct Infix {
     pub span: Span,
     pub doc: Option<DocComment>,
+    /// The associativity given by the keyword `infix`, `infixl` or `infixr`
+    pub associativity: Associativity,
+    /// The optional precedence, e.g. `6` in `infixl 6 _ + _ := Add(_,_)`
+    pub precedence: Option<NatLit>,
     pub lhs: BinOp,
     pub rhs: Call,
 }
 
+/// The associativity of an infix operator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Associativity {
+    /// `infixl`: `a + b + c` is parsed as `(a + b) + c`
+    Left,
+    /// `infixr`: `a -> b -> c` is parsed as `a -> (b -> c)`
+    Right,
+    /// `infix`: `a == b == c` is rejected
+    ///
+    /// Without a precedence, as in `infix _ -> _ := Fun(_,_)`, the operator is right-associative.
+    None,
+}
+
 /// A `Param` can either be a single parameter, like `x : T`, or a list of parameters, like `x y z: T`.
 /// The parameter list can be optionally prefixed with the "implicit" keyword: `implicit x : T` or `implicit x y z: T`
+/// and with the "erased" keyword: `erased h: T` or `implicit erased x y: T`
 #[derive(Debug, Clone)]
 pub struct Param {
     /// Whether the "implicit" keyword was used.
     pub implicit: bool,
+    /// Whether the "erased" keyword was used.
+    pub erased: bool,
     /// The obligatory parameter name.
     pub name: exp::BindingSite,
     /// A possible list of additional parameter names.
@@ -18,18 +18,47 @@ impl BindingSite {
     }
 }
 
+/// A constructor pattern `C(p₁, ..., pₙ)` on the left-hand side of a clause
 #[derive(Debug, Clone)]
 pub struct Pattern {
     pub span: Span,
+    /// The alias of the module the constructor is imported from, as in `L.Cons(x, xs)`
+    pub qualifier: Option<Ident>,
     pub name: Ident,
-    pub params: Vec<BindingSite>,
+    pub params: Vec<OrWildcard<Pattern>>,
 }
 
+/// A (co)pattern or a wildcard `_`
+///
+/// Wildcards occur as arguments of constructor patterns and as catch-all clauses of
+/// (co)pattern matches. A pattern `x` without arguments is parsed as a constructor pattern.
+/// Whether it binds a variable or matches on a constructor is decided during lowering.
+#[derive(Debug, Clone)]
+pub enum OrWildcard<P> {
+    Pattern(P),
+    Wildcard { span: Span },
+}
+
+impl OrWildcard<Pattern> {
+    pub fn span(&self) -> Span {
+        match self {
+            OrWildcard::Pattern(pattern) => pattern.span,
+            OrWildcard::Wildcard { span } => *span,
+        }
+    }
+}
+
+/// A copattern `.d(x₁, ..., xₙ)` on the left-hand side of a clause
+///
+/// Copatterns can be nested, e.g. `.tl.hd`, in which case the clause defines an observation on the
+/// result of the destructor.
 #[derive(Debug, Clone)]
 pub struct Copattern {
     pub span: Span,
     pub name: Ident,
     pub params: Vec<BindingSite>,
+    /// The copattern on the result of the destructor, e.g. `.hd` in `.tl.hd`
+    pub nested: Option<Box<Copattern>>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,6 +105,9 @@ pub enum Exp {
     NatLit(NatLit),
     BinOp(BinOp),
     Lam(Lam),
+    LocalLet(LocalLet),
+    /// The body of a case which could not be parsed
+    Error(Span),
 }
 
 impl Exp {
@@ -90,6 +122,8 @@ impl Exp {
             Exp::NatLit(nat_lit) => nat_lit.span,
             Exp::BinOp(binop) => binop.span,
             Exp::Lam(lam) => lam.span,
+            Exp::LocalLet(local_let) => local_let.span,
+            Exp::Error(span) => *span,
         }
     }
 
@@ -134,7 +168,7 @@ pub struct LocalMatch {
     pub name: Option<Ident>,
     pub on_exp: Box<Exp>,
     pub motive: Option<Motive>,
-    pub cases: Vec<Case<Pattern>>,
+    pub cases: Vec<Case<OrWildcard<Pattern>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -143,7 +177,7 @@ pub struct LocalComatch {
     pub span: Span,
     pub name: Option<Ident>,
     pub is_lambda_sugar: bool,
-    pub cases: Vec<Case<Copattern>>,
+    pub cases: Vec<Case<OrWildcard<Copattern>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -168,12 +202,16 @@ pub struct NatLit {
 }
 
 #[derive(Debug, Clone)]
-/// Binary Operator, e.g. `e -> e` or `e + e`.
+/// A chain of binary operators, e.g. `e -> e` or `e * e + e`.
+///
+/// The parser does not know the precedence and associativity of the operators,
+/// so the chain is only associated during lowering.
 pub struct BinOp {
     pub span: Span,
-    pub operator: Operator,
+    /// The first operand of the chain
     pub lhs: Box<Exp>,
-    pub rhs: Box<Exp>,
+    /// The remaining operators together with their right operand
+    pub rest: Vec<(Operator, Box<Exp>)>,
 }
 
 #[derive(Debug, Clone)]
@@ -185,6 +223,16 @@ pub struct Lam {
     pub case: Case<Copattern>,
 }
 
+#[derive(Debug, Clone)]
+/// Local let binding `let x : t := e; body` with an optional type annotation `t`
+pub struct LocalLet {
+    pub span: Span,
+    pub name: BindingSite,
+    pub typ: Option<Box<Exp>>,
+    pub bound: Box<Exp>,
+    pub body: Box<Exp>,
+}
+
 #[derive(Debug, Clone)]
 pub struct Motive {
     pub span: Span,
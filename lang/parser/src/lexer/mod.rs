@@ -48,10 +48,24 @@ pub enum Token {
     Absurd,
     #[token("implicit")]
     Implicit,
+    #[token("erased")]
+    Erased,
     #[token("use")]
     Use,
+    #[token("pub")]
+    Pub,
+    #[token("hiding")]
+    Hiding,
     #[token("infix")]
     Infix,
+    #[token("infixl")]
+    Infixl,
+    #[token("infixr")]
+    Infixr,
+    // `Type` can also be used as an identifier, but is lexed separately
+    // such that universe levels can be written as in `Type 1`.
+    #[token("Type")]
+    Type,
 
     // Parens, Braces and Brackets
     //
@@ -90,6 +104,8 @@ pub enum Token {
     Backslash,
     #[token("#")]
     Hash,
+    #[token("#!")]
+    HashBang,
     #[token("_")]
     Underscore,
 
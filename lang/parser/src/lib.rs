@@ -12,12 +12,32 @@ pub use result::*;
 pub fn parse_exp(s: &str) -> Result<Box<cst::exp::Exp>, ParseError> {
     let lexer = Lexer::new(s);
     let parser = ExpParser::new();
-    parser.parse(lexer).map_err(From::from)
+    let mut errors = vec![];
+    let exp = parser.parse(&mut errors, lexer)?;
+    match errors.into_iter().next() {
+        Some(err) => Err(err.error.into()),
+        None => Ok(exp),
+    }
 }
 
-pub fn parse_module(uri: Url, s: &str) -> Result<cst::decls::Module, ParseError> {
+/// Parse a module
+///
+/// The parser recovers from syntax errors in declarations and cases, which are replaced by error nodes in the returned module.
+/// The returned list contains all syntax errors, i.e. the module is only complete if the list is empty.
+/// If the parser cannot recover from a syntax error, no module is returned.
+pub fn parse_module(uri: Url, s: &str) -> (Option<cst::decls::Module>, Vec<ParseError>) {
     let lexer = Lexer::new(s);
     let parser = ModuleContentsParser::new();
-    let (use_decls, decls) = parser.parse(lexer)?;
-    Ok(cst::decls::Module { uri, use_decls, decls })
+    let mut errors = vec![];
+    let res = parser.parse(&mut errors, lexer);
+    let mut errors: Vec<ParseError> = errors.into_iter().map(|err| err.error.into()).collect();
+    match res {
+        Ok((attr, use_decls, decls)) => {
+            (Some(cst::decls::Module { uri, attr, use_decls, decls }), errors)
+        }
+        Err(err) => {
+            errors.push(err.into());
+            (None, errors)
+        }
+    }
 }
@@ -28,7 +28,7 @@ where
     }
 
     fn fail_doc(&self) -> Self::Error {
-        io::Error::new(io::ErrorKind::Other, "Document failed to render")
+        io::Error::other("Document failed to render")
     }
 }
 
@@ -52,6 +52,7 @@ where
             Anno::BraceClose => r"\",
             Anno::Error => r"\textcolor{polRed}{",
             Anno::Reference { module_uri: _, name: _ } => "",
+            Anno::Highlight => r"\underline{",
         };
         self.upstream.write_all(out.as_bytes())
     }
@@ -38,7 +38,7 @@ where
     }
 
     fn fail_doc(&self) -> Self::Error {
-        io::Error::new(io::ErrorKind::Other, "Document failed to render")
+        io::Error::other("Document failed to render")
     }
 }
 
@@ -48,18 +48,30 @@ where
 {
     fn push_annotation(&mut self, anno: &Anno) -> Result<(), Self::Error> {
         self.anno_stack.push(anno.clone());
-        self.upstream.set_color(&anno.color_spec())
+        self.upstream.set_color(&self.current_spec())
     }
 
     fn pop_annotation(&mut self) -> Result<(), Self::Error> {
         self.anno_stack.pop();
-        match self.anno_stack.last() {
-            Some(previous) => self.upstream.set_color(&previous.color_spec()),
-            None => self.upstream.reset(),
+        if self.anno_stack.is_empty() {
+            self.upstream.reset()
+        } else {
+            self.upstream.set_color(&self.current_spec())
         }
     }
 }
 
+impl<W> RenderTermcolor<W> {
+    /// The color of the innermost annotation, underlined within a highlighted expression
+    fn current_spec(&self) -> ColorSpec {
+        let mut spec = self.anno_stack.last().map(Anno::color_spec).unwrap_or_default();
+        if self.anno_stack.iter().any(|anno| matches!(anno, Anno::Highlight)) {
+            spec.set_underline(true);
+        }
+        spec
+    }
+}
+
 impl Anno {
     fn color_spec(&self) -> ColorSpec {
         match self {
@@ -73,6 +85,7 @@ impl Anno {
             Anno::BraceClose => Default::default(),
             Anno::Error => ERROR.spec(),
             Anno::Reference { module_uri: _, name: _ } => Default::default(),
+            Anno::Highlight => Default::default(),
         }
     }
 }
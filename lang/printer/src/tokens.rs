@@ -26,18 +26,29 @@ pub const QUESTION_MARK: &str = "?";
 /// The symbol `#`
 pub const HASH: &str = "#";
 
+/// The symbol `#!`
+pub const HASH_BANG: &str = "#!";
+
+/// The symbol `+`
+pub const PLUS: &str = "+";
+
 /// The symbol `:=`
 pub const COLONEQ: &str = ":=";
 
 /// The symbol `_`
 pub const UNDERSCORE: &str = "_";
 
+/// The symbol `;`
+pub const SEMICOLON: &str = ";";
+
 // Keywords
 //
 //
 
 /// The keyword `infix`
 pub const INFIX: &str = "infix";
+pub const INFIXL: &str = "infixl";
+pub const INFIXR: &str = "infixr";
 
 /// The keyword `data`
 pub const DATA: &str = "data";
@@ -69,8 +80,23 @@ pub const ABSURD: &str = "absurd";
 /// The keyword `Type`
 pub const TYPE: &str = "Type";
 
+/// The keyword `Level`
+pub const LEVEL: &str = "Level";
+
+/// The keyword `max`
+pub const MAX: &str = "max";
+
 /// The keyword `implicit`
 pub const IMPLICIT: &str = "implicit";
 
+/// The keyword `erased`
+pub const ERASED: &str = "erased";
+
 /// The keyword `use`
 pub const USE: &str = "use";
+
+/// The keyword `pub`
+pub const PUB: &str = "pub";
+
+/// The keyword `hiding`
+pub const HIDING: &str = "hiding";
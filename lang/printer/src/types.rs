@@ -2,6 +2,8 @@ use std::rc::Rc;
 use std::{error::Error, io};
 use url::Url;
 
+use miette_util::codespan::Span;
+
 use pretty::{
     termcolor::{Ansi, WriteColor},
     DocAllocator,
@@ -20,7 +22,12 @@ pub enum Anno {
     BraceOpen,
     BraceClose,
     Error,
-    Reference { module_uri: Url, name: String },
+    Reference {
+        module_uri: Url,
+        name: String,
+    },
+    /// A highlighted subexpression, see [`PrintCfg::highlight`]
+    Highlight,
 }
 
 pub type Alloc<'a> = pretty::Arena<'a, Anno>;
@@ -29,6 +36,9 @@ pub type Builder<'a> = pretty::DocBuilder<'a, Alloc<'a>, Anno>;
 /// Operator precedences
 pub type Precedence = u32;
 
+/// The precedence of positions which require an atomic expression, such as the head of a dot call
+pub const PREC_ATOMIC: Precedence = Precedence::MAX;
+
 /// We implement the `Print` trait for all types that we want to prettyprint.
 /// It is sufficient to implement either the `print` or the `print_prec` function, depending
 /// on whether you need information about operator precedences or not.
@@ -95,6 +105,7 @@ pub trait Print {
             print_function_sugar: true,
             print_metavar_ids: true,
             print_metavar_args: true,
+            highlight: None,
         };
         self.print_to_colored_string(Some(&TRACE_CFG))
     }
@@ -227,6 +238,8 @@ pub struct PrintCfg {
     pub print_metavar_ids: bool,
     /// Whether to print the arguments of metavariables
     pub print_metavar_args: bool,
+    /// The span of the expression which is highlighted, such as the redex of an evaluation step
+    pub highlight: Option<Span>,
 }
 
 impl Default for PrintCfg {
@@ -241,6 +254,7 @@ impl Default for PrintCfg {
             print_function_sugar: true,
             print_metavar_ids: false,
             print_metavar_args: false,
+            highlight: None,
         }
     }
 }
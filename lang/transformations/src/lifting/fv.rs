@@ -94,12 +94,25 @@ impl FV for Exp {
             Exp::DotCall(dot_call) => dot_call.free_vars_closure(lvl_ctx, type_ctx),
             Exp::TypCtor(typ_ctor) => typ_ctor.free_vars_closure(lvl_ctx, type_ctx),
             Exp::Hole(hole) => hole.free_vars_closure(lvl_ctx, type_ctx),
-            Exp::TypeUniv(_) => HashSet::default(),
+            Exp::TypeUniv(type_univ) => type_univ.level.free_vars_closure(lvl_ctx, type_ctx),
+            Exp::LevelUniv(_) => HashSet::default(),
+            Exp::Level(level) => level.free_vars_closure(lvl_ctx, type_ctx),
             Exp::LocalMatch(local_match) => local_match.free_vars_closure(lvl_ctx, type_ctx),
+            Exp::LocalLet(local_let) => local_let.free_vars_closure(lvl_ctx, type_ctx),
         }
     }
 }
 
+impl FV for Level {
+    fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
+        let mut fvs = HashSet::default();
+        for (exp, _) in &self.summands {
+            fvs.extend(exp.free_vars_closure(lvl_ctx, type_ctx));
+        }
+        fvs
+    }
+}
+
 impl FV for Anno {
     fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
         let mut fvs = self.exp.free_vars_closure(lvl_ctx, type_ctx);
@@ -213,9 +226,19 @@ impl FV for LocalMatch {
     }
 }
 
+impl FV for LocalLet {
+    fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
+        let LocalLet { name, typ, bound, body, .. } = self;
+        let mut fvs = typ.free_vars_closure(lvl_ctx, type_ctx);
+        fvs.extend(bound.free_vars_closure(lvl_ctx, type_ctx));
+        fvs.extend(lvl_ctx.bind_single(name.clone(), |ctx| body.free_vars_closure(ctx, type_ctx)));
+        fvs
+    }
+}
+
 impl FV for Case {
     fn free_vars_closure(&self, lvl_ctx: &mut LevelCtx, type_ctx: &TypeCtx) -> HashSet<FreeVar> {
-        let Case { span: _, pattern, body } = self;
+        let Case { span: _, pattern, body, expanded_from: _ } = self;
         lvl_ctx.bind_iter(pattern.params.params.iter(), |ctx| body.free_vars_closure(ctx, type_ctx))
     }
 }
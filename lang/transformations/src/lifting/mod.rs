@@ -17,6 +17,8 @@ use fv::*;
 use signature::*;
 
 /// Lift local (co)matches for `name` in `module` to top-level (co)definitions
+///
+/// The module must be typechecked, such that all catch-all clauses are expanded to cases.
 pub fn lift(module: Arc<Module>, name: &str) -> LiftResult {
     let mut ctx = Ctx {
         name: name.to_owned(),
@@ -91,12 +93,13 @@ impl Lift for Module {
     type Target = Module;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Module { uri, use_decls, decls, meta_vars } = self;
+        let Module { uri, attr, use_decls, decls, meta_vars } = self;
 
         let decls = decls.iter().map(|decl| decl.lift(ctx)).collect();
 
         Module {
             uri: uri.clone(),
+            attr: attr.clone(),
             use_decls: use_decls.clone(),
             decls,
             meta_vars: meta_vars.clone(),
@@ -195,7 +198,7 @@ impl Lift for Def {
     type Target = Def;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Def { span, doc, name, attr, params, self_param, ret_typ, cases } = self;
+        let Def { span, doc, name, attr, params, self_param, ret_typ, cases, catch_all: _ } = self;
         ctx.set_curr_decl(name.clone());
 
         params.lift_telescope(ctx, |ctx, params| {
@@ -213,6 +216,7 @@ impl Lift for Def {
                 self_param,
                 ret_typ,
                 cases: cases.lift(ctx),
+                catch_all: None,
             }
         })
     }
@@ -222,7 +226,7 @@ impl Lift for Codef {
     type Target = Codef;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Codef { span, doc, name, attr, params, typ, cases } = self;
+        let Codef { span, doc, name, attr, params, typ, cases, catch_all: _ } = self;
         ctx.set_curr_decl(name.clone());
 
         params.lift_telescope(ctx, |ctx, params| Codef {
@@ -233,6 +237,7 @@ impl Lift for Codef {
             params,
             typ: typ.lift(ctx),
             cases: cases.lift(ctx),
+            catch_all: None,
         })
     }
 }
@@ -260,7 +265,7 @@ impl Lift for Case {
     type Target = Case;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let Case { span, pattern, body } = self;
+        let Case { span, pattern, body, expanded_from } = self;
 
         pattern.params.lift_telescope(ctx, |ctx, params| Case {
             span: *span,
@@ -271,6 +276,7 @@ impl Lift for Case {
                 params,
             },
             body: body.lift(ctx),
+            expanded_from: expanded_from.clone(),
         })
     }
 }
@@ -299,9 +305,12 @@ impl Lift for Exp {
             Exp::DotCall(e) => e.lift(ctx),
             Exp::Anno(e) => e.lift(ctx),
             Exp::TypeUniv(e) => e.lift(ctx),
+            Exp::LevelUniv(e) => e.lift(ctx),
+            Exp::Level(e) => e.lift(ctx),
             Exp::Hole(e) => e.lift(ctx).into(),
             Exp::LocalMatch(e) => e.lift(ctx),
             Exp::LocalComatch(e) => e.lift(ctx),
+            Exp::LocalLet(e) => e.lift(ctx),
         }
     }
 }
@@ -377,9 +386,27 @@ impl Lift for Anno {
 impl Lift for TypeUniv {
     type Target = Exp;
 
+    fn lift(&self, ctx: &mut Ctx) -> Self::Target {
+        let TypeUniv { span, level } = self;
+        Exp::TypeUniv(TypeUniv { span: *span, level: level.lift(ctx) })
+    }
+}
+
+impl Lift for LevelUniv {
+    type Target = Exp;
+
     fn lift(&self, _ctx: &mut Ctx) -> Self::Target {
-        let TypeUniv { span } = self;
-        Exp::TypeUniv(TypeUniv { span: *span })
+        Exp::LevelUniv(self.clone())
+    }
+}
+
+impl Lift for Level {
+    type Target = Exp;
+
+    fn lift(&self, ctx: &mut Ctx) -> Self::Target {
+        let Level { span, constant, summands } = self;
+        let summands = summands.iter().map(|(exp, k)| (exp.lift(ctx), *k)).collect();
+        Exp::Level(Level { span: *span, constant: *constant, summands })
     }
 }
 
@@ -404,8 +431,17 @@ impl Lift for LocalMatch {
     type Target = Exp;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let LocalMatch { span, ctx: type_ctx, name, on_exp, motive, ret_typ, cases, inferred_type } =
-            self;
+        let LocalMatch {
+            span,
+            ctx: type_ctx,
+            name,
+            on_exp,
+            motive,
+            ret_typ,
+            cases,
+            inferred_type,
+            ..
+        } = self;
         ctx.lift_match(
             span,
             &inferred_type.clone().unwrap(),
@@ -423,8 +459,9 @@ impl Lift for LocalComatch {
     type Target = Exp;
 
     fn lift(&self, ctx: &mut Ctx) -> Self::Target {
-        let LocalComatch { span, ctx: type_ctx, name, is_lambda_sugar, cases, inferred_type } =
-            self;
+        let LocalComatch {
+            span, ctx: type_ctx, name, is_lambda_sugar, cases, inferred_type, ..
+        } = self;
         ctx.lift_comatch(
             span,
             &inferred_type.clone().unwrap(),
@@ -435,6 +472,25 @@ impl Lift for LocalComatch {
         )
     }
 }
+impl Lift for LocalLet {
+    type Target = Exp;
+
+    fn lift(&self, ctx: &mut Ctx) -> Self::Target {
+        let LocalLet { span, name, typ, bound, body, .. } = self;
+
+        let binder = Binder { name: name.clone(), content: () };
+        Exp::LocalLet(LocalLet {
+            span: *span,
+            name: name.clone(),
+            typ: typ.lift(ctx),
+            bound: bound.lift(ctx),
+            body: ctx.bind_single(&binder, |ctx| body.lift(ctx)),
+            bound_type: None,
+            inferred_type: None,
+        })
+    }
+}
+
 impl Lift for Motive {
     type Target = Motive;
 
@@ -580,6 +636,7 @@ impl Ctx {
                 motive: motive.lift(self),
                 ret_typ: None,
                 cases: cases.lift(self),
+                catch_all: None,
             });
         }
 
@@ -633,6 +690,7 @@ impl Ctx {
             },
             ret_typ: def_ret_typ,
             cases,
+            catch_all: None,
         };
 
         self.new_decls.push(Decl::Def(def));
@@ -642,7 +700,12 @@ impl Ctx {
             span: None,
             kind: DotCallKind::Definition,
             exp: Box::new(on_exp.lift(self)),
-            name: IdBound { span: None, id: name.id.clone(), uri: self.uri.clone() },
+            name: IdBound {
+                span: None,
+                id: name.id.clone(),
+                uri: self.uri.clone(),
+                qualifier: None,
+            },
             args,
             inferred_type: None,
         })
@@ -666,6 +729,7 @@ impl Ctx {
                 name: name.clone(),
                 is_lambda_sugar,
                 cases: cases.lift(self),
+                catch_all: None,
                 inferred_type: None,
             });
         }
@@ -698,6 +762,7 @@ impl Ctx {
             params: telescope,
             typ,
             cases,
+            catch_all: None,
         };
 
         self.new_decls.push(Decl::Codef(codef));
@@ -706,7 +771,12 @@ impl Ctx {
         Exp::Call(Call {
             span: None,
             kind: CallKind::Codefinition,
-            name: IdBound { span: None, id: name.id.clone(), uri: self.uri.clone() },
+            name: IdBound {
+                span: None,
+                id: name.id.clone(),
+                uri: self.uri.clone(),
+                qualifier: None,
+            },
             args,
             inferred_type: None,
         })
@@ -2,7 +2,7 @@ use url::Url;
 
 use ast::ctx::{BindContext, LevelCtx};
 use ast::{self, HashMap, IdBound, SwapWithCtx};
-use ast::{Attributes, DocComment};
+use ast::{Attribute, Attributes, DocComment};
 use miette_util::codespan::Span;
 
 use crate::result::XfuncError;
@@ -19,10 +19,13 @@ pub struct XData {
     pub span: Option<Span>,
     pub doc: Option<DocComment>,
     pub name: ast::IdBind,
+    pub attr: Attributes,
     pub typ: Box<ast::Telescope>,
     pub ctors: HashMap<String, ast::Ctor>,
     pub dtors: HashMap<String, ast::Dtor>,
     pub exprs: HashMap<Key, Option<Box<ast::Exp>>>,
+    /// Whether one of the (co)definitions is exempt from the termination check
+    pub non_terminating: bool,
 }
 
 /// A key points to a matrix cell
@@ -82,17 +85,19 @@ impl BuildMatrix for ast::Module {
 
 impl BuildMatrix for ast::Data {
     fn build_matrix(&self, out: &mut Prg) -> Result<(), XfuncError> {
-        let ast::Data { span, doc, name, attr: _, typ, ctors } = self;
+        let ast::Data { span, doc, name, attr, typ, ctors } = self;
 
         let mut xdata = XData {
             repr: Repr::Data,
             span: *span,
             doc: doc.clone(),
             name: name.clone(),
+            attr: attr.clone(),
             typ: typ.clone(),
             ctors: HashMap::default(),
             dtors: HashMap::default(),
             exprs: HashMap::default(),
+            non_terminating: false,
         };
         for ctor in ctors {
             xdata.ctors.insert(ctor.name.id.clone(), ctor.clone());
@@ -104,17 +109,19 @@ impl BuildMatrix for ast::Data {
 }
 impl BuildMatrix for ast::Codata {
     fn build_matrix(&self, out: &mut Prg) -> Result<(), XfuncError> {
-        let ast::Codata { span, doc, name, attr: _, typ, dtors } = self;
+        let ast::Codata { span, doc, name, attr, typ, dtors } = self;
 
         let mut xdata = XData {
             repr: Repr::Codata,
             span: *span,
             doc: doc.clone(),
             name: name.clone(),
+            attr: attr.clone(),
             typ: typ.clone(),
             ctors: HashMap::default(),
             dtors: HashMap::default(),
             exprs: HashMap::default(),
+            non_terminating: false,
         };
 
         for dtor in dtors {
@@ -132,6 +139,7 @@ impl BuildMatrix for ast::Def {
         // Only add to the matrix if the type is declared in this module
         let Some(xdata) = out.map.get_mut(&type_name.id) else { return Ok(()) };
         xdata.dtors.insert(self.name.id.clone(), self.to_dtor());
+        xdata.non_terminating |= self.attr.attrs.contains(&Attribute::NonTerminating);
 
         let cases = &self.cases;
 
@@ -152,6 +160,7 @@ impl BuildMatrix for ast::Codef {
             return Ok(());
         };
         xdata.ctors.insert(self.name.id.clone(), self.to_ctor());
+        xdata.non_terminating |= self.attr.attrs.contains(&Attribute::NonTerminating);
 
         let cases = &self.cases;
 
@@ -176,13 +185,20 @@ impl BuildMatrix for ast::Codef {
 
 impl XData {
     pub fn as_data(&self, uri: &Url) -> (ast::Data, Vec<ast::Def>) {
-        let XData { name, doc, typ, ctors, dtors, exprs, .. } = self;
+        let XData { name, doc, attr, typ, ctors, dtors, exprs, .. } = self;
+
+        // Codata types are not checked for positivity in the parameters of their destructors,
+        // so the constructors of the resulting data type need not be strictly positive.
+        let mut attr = attr.clone();
+        if !attr.attrs.contains(&Attribute::NoPositivityCheck) {
+            attr.attrs.push(Attribute::NoPositivityCheck);
+        }
 
         let data = ast::Data {
             span: None,
             doc: doc.clone(),
             name: name.clone(),
-            attr: Attributes::default(),
+            attr,
             typ: typ.clone(),
             ctors: ctors.values().cloned().collect(),
         };
@@ -204,23 +220,29 @@ impl XData {
                                     span: None,
                                     id: ctor.name.id.clone(),
                                     uri: uri.clone(),
+                                    qualifier: None,
                                 },
                                 params: ctor.params.instantiate(),
                             },
                             body,
+                            expanded_from: None,
                         })
                     })
                     .collect();
 
+                // The bodies of the former comatches were not subject to the termination check.
+                // Defunctionalization turns them into cases of a recursive definition whose
+                // termination generally depends on higher-order reasoning, so we opt out of the check.
                 ast::Def {
                     span: None,
                     doc: dtor.doc.clone(),
                     name: dtor.name.clone(),
-                    attr: Attributes::default(),
+                    attr: non_terminating_attr(true),
                     params: dtor.params.clone(),
                     self_param: dtor.self_param.clone(),
                     ret_typ: dtor.ret_typ.clone(),
                     cases,
+                    catch_all: None,
                 }
             })
             .collect();
@@ -229,13 +251,13 @@ impl XData {
     }
 
     pub fn as_codata(&self, uri: &Url) -> (ast::Codata, Vec<ast::Codef>) {
-        let XData { name, doc, typ, ctors, dtors, exprs, .. } = self;
+        let XData { name, doc, attr, typ, ctors, dtors, exprs, non_terminating, .. } = self;
 
         let codata = ast::Codata {
             span: None,
             doc: doc.clone(),
             name: name.clone(),
-            attr: Attributes::default(),
+            attr: attr.clone(),
             typ: typ.clone(),
             dtors: dtors.values().cloned().collect(),
         };
@@ -266,10 +288,12 @@ impl XData {
                                     span: None,
                                     id: dtor.name.id.clone(),
                                     uri: uri.clone(),
+                                    qualifier: None,
                                 },
                                 params: dtor.params.instantiate(),
                             },
                             body,
+                            expanded_from: None,
                         })
                     })
                     .collect();
@@ -278,10 +302,11 @@ impl XData {
                     span: None,
                     doc: ctor.doc.clone(),
                     name: ctor.name.clone(),
-                    attr: Attributes::default(),
+                    attr: non_terminating_attr(*non_terminating),
                     params: ctor.params.clone(),
                     typ: ctor.typ.clone(),
                     cases,
+                    catch_all: None,
                 }
             })
             .collect();
@@ -289,3 +314,11 @@ impl XData {
         (codata, codefs)
     }
 }
+
+fn non_terminating_attr(non_terminating: bool) -> Attributes {
+    if non_terminating {
+        Attributes { attrs: vec![Attribute::NonTerminating] }
+    } else {
+        Attributes::default()
+    }
+}
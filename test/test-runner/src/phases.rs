@@ -1,10 +1,18 @@
 use std::error::Error;
 use std::fmt;
+use std::fs;
 use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::process::Command;
 use std::sync::Arc;
 
-use driver::{Database, FileSource, FileSystemSource, InMemorySource};
-use printer::Print as _;
+use backend::ast2ir::traits::ToIR;
+use backend::interpreter::Interpreter;
+use backend::ir;
+use backend::result::BackendError;
+use driver::{
+    Database, EvalLimits, FileSource, FileSystemSource, InMemorySource, Manifest, StdSource, Value,
+};
+use printer::{Print as _, PrintCfg};
 use url::Url;
 
 use parser::cst;
@@ -17,6 +25,10 @@ use crate::{
 /// Terminal width for pretty-printing error messages.
 const TERMINAL_WIDTH: usize = 200;
 
+/// The maximal number of evaluation steps, such that testcases which diverge fail or skip phases
+/// instead of blocking the test runner.
+const MAX_STEPS: u64 = 100_000;
+
 pub trait Phase {
     type Out: TestOutput;
 
@@ -36,6 +48,8 @@ pub struct PartialRun<O> {
     result: Result<O, PhasesError>,
     /// A textual report about all the previously run phases.
     report_phases: Vec<PhaseReport>,
+    /// The phases which were skipped for this testcase, together with the reason.
+    skipped: Vec<String>,
 }
 
 #[allow(dead_code)]
@@ -49,9 +63,13 @@ impl PartialRun<()> {
     pub fn start(case: Case) -> PartialRun<()> {
         let mut source = InMemorySource::new();
         source.insert(case.uri(), case.content().unwrap());
-        let source = source.fallback_to(FileSystemSource::new(&case.path));
-        let database = Database::from_source(source);
-        PartialRun { case, database, result: Ok(()), report_phases: vec![] }
+        let source =
+            source.fallback_to(StdSource::new()).fallback_to(FileSystemSource::new(&case.path));
+        let mut database = Database::from_source(source);
+        database.eval_limits = EvalLimits { max_steps: Some(MAX_STEPS), timeout: None };
+        database.manifest =
+            Manifest::discover(&case.path).expect("Failed to load project manifest");
+        PartialRun { case, database, result: Ok(()), report_phases: vec![], skipped: vec![] }
     }
 }
 
@@ -94,6 +112,9 @@ where
                         // There was no panic and `run` returned with a result.
                         self.report_phases
                             .push(PhaseReport { name: phase.name(), output: out2.test_output() });
+                        if let Some(reason) = out2.skipped() {
+                            self.skipped.push(format!("{}: {reason}", phase.name()));
+                        }
                         if !expect_success {
                             return Err(PhasesError::ExpectedFailure { got: out2.test_output() });
                         }
@@ -147,13 +168,14 @@ where
             case: self.case,
             result,
             report_phases: self.report_phases,
+            skipped: self.skipped,
         }
     }
 
     pub fn report(self) -> CaseResult {
         let result = match self.result {
             Ok(_) => Ok(()),
-            Err(PhasesError::AsExpected { .. }) => Ok(()),
+            Err(PhasesError::AsExpected) => Ok(()),
             Err(PhasesError::Mismatch { phase, expected, actual }) => {
                 Err(Failure::Mismatch { phase, expected, actual })
             }
@@ -162,7 +184,7 @@ where
             Err(PhasesError::Panic { msg }) => Err(Failure::Panic { msg }),
         };
 
-        CaseResult { result, case: self.case }
+        CaseResult { result, case: self.case, skipped: self.skipped }
     }
 }
 
@@ -408,10 +430,260 @@ impl Phase for IR {
     }
 }
 
+// Erased Phase
+//
+// This phase checks that the IR of the module contains neither zero-sized terms nor panics, i.e.
+// that all runtime-irrelevant terms have been erased.
+
+pub struct Erased {
+    name: &'static str,
+}
+
+impl Phase for Erased {
+    type Out = String;
+
+    fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(db: &mut Database, uri: &Url) -> Result<Self::Out, driver::Error> {
+        let ir = db.ir(uri).await?;
+        let ir::Module { def_decls, codef_decls, let_decls, .. } = &*ir;
+        let unerased = def_decls
+            .iter()
+            .find_map(|def| find_unerased_in_cases(&def.cases))
+            .or_else(|| codef_decls.iter().find_map(|codef| find_unerased_in_cases(&codef.cases)))
+            .or_else(|| let_decls.iter().find_map(|tl_let| find_unerased(&tl_let.body)));
+        if let Some(exp) = unerased {
+            return Err(driver::Error::Backend(BackendError::Impossible(format!(
+                "The IR contains the unerased term {}",
+                exp.print_to_string(None)
+            ))));
+        }
+        Ok(String::new())
+    }
+}
+
+/// A zero-sized term or panic in `exp`, if there is one
+fn find_unerased(exp: &ir::Exp) -> Option<&ir::Exp> {
+    match exp {
+        ir::Exp::Variable(_) => None,
+        ir::Exp::CtorCall(call) | ir::Exp::CodefCall(call) | ir::Exp::LetCall(call) => {
+            call.args.iter().find_map(find_unerased)
+        }
+        ir::Exp::DtorCall(dot_call) | ir::Exp::DefCall(dot_call) => {
+            find_unerased(&dot_call.exp).or_else(|| dot_call.args.iter().find_map(find_unerased))
+        }
+        ir::Exp::LocalMatch(local_match) => find_unerased(&local_match.on_exp)
+            .or_else(|| find_unerased_in_cases(&local_match.cases)),
+        ir::Exp::LocalComatch(local_comatch) => find_unerased_in_cases(&local_comatch.cases),
+        ir::Exp::LocalLet(local_let) => {
+            find_unerased(&local_let.bound).or_else(|| find_unerased(&local_let.body))
+        }
+        ir::Exp::Panic(_) | ir::Exp::ZST => Some(exp),
+    }
+}
+
+fn find_unerased_in_cases(cases: &[ir::Case]) -> Option<&ir::Exp> {
+    cases.iter().filter_map(|case| case.body.as_deref()).find_map(find_unerased)
+}
+
+// JS Phase
+//
+// This phase generates the JavaScript code of the module.
+
+pub struct JS {
+    name: &'static str,
+}
+
+impl Phase for JS {
+    type Out = String;
+
+    fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(db: &mut Database, uri: &Url) -> Result<Self::Out, driver::Error> {
+        db.js(uri).await
+    }
+}
+
+// Eval Phase
+//
+// This phase evaluates the main expression of the module, if there is one, with the
+// interpreter for the IR and compares the result with the normal form computed by the
+// normalizer.
+
+pub struct Eval {
+    name: &'static str,
+}
+
+impl Phase for Eval {
+    type Out = Skippable<String>;
+
+    fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(db: &mut Database, uri: &Url) -> Result<Self::Out, driver::Error> {
+        let val = eval_main(db, uri).await?;
+        Ok(val.map(|val| val.map(|val| val.print_to_string(None)).unwrap_or_default()))
+    }
+}
+
+/// The value of the main expression of the module, if there is one
+///
+/// Skipped if the main expression does not terminate under call-by-value evaluation.
+/// Fails if the interpreter for the IR and the normalizer compute different values.
+async fn eval_main(
+    db: &mut Database,
+    uri: &Url,
+) -> Result<Skippable<Option<Value>>, driver::Error> {
+    let val = match db.run_ir(uri).await {
+        Ok(Some(val)) => val,
+        Ok(None) => return Ok(Skippable::Ran(None)),
+        // The call-by-value interpreter may diverge when the call-by-need normalizer does
+        // not, so the results can only be compared if the interpreter terminates.
+        Err(driver::Error::Backend(BackendError::OutOfFuel(_))) => {
+            return Ok(Skippable::Skipped("the interpreter ran out of fuel"))
+        }
+        Err(err) => return Err(err),
+    };
+    let nf = db.run(uri).await?.expect("Failed to find main expression");
+
+    // The normal form is evaluated by the interpreter as well, so that both results are
+    // compared as values of the IR.
+    let modules = db.ir_with_deps(uri).await?;
+    let info_table = db.closed_type_info_table(uri).await?;
+    let nf = nf.to_ir(&info_table).map_err(driver::Error::Backend)?;
+    let nf_val = Interpreter::new(modules.iter().map(|module| &**module))
+        .eval(&nf)
+        .map_err(driver::Error::Backend)?;
+
+    if nf_val != val {
+        return Err(driver::Error::Backend(BackendError::Impossible(format!(
+            "The interpreter computed {}, but the normalizer computed {}",
+            val.print_to_string(None),
+            nf_val.print_to_string(None)
+        ))));
+    }
+    Ok(Skippable::Ran(Some(val)))
+}
+
+// Exec Phase
+//
+// This phase compiles the module to an executable with the C backend, if the module has a
+// main expression, and compares the output of the executable with the value computed by the
+// evaluators. The phase is skipped if no C compiler is installed.
+
+pub struct Exec {
+    name: &'static str,
+}
+
+impl Phase for Exec {
+    type Out = Skippable<String>;
+
+    fn new(name: &'static str) -> Self {
+        Self { name }
+    }
+
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    async fn run(db: &mut Database, uri: &Url) -> Result<Self::Out, driver::Error> {
+        if driver::c_compiler().arg("--version").output().is_err() {
+            return Ok(Skippable::Skipped("no C compiler is installed"));
+        }
+        let val = match eval_main(db, uri).await? {
+            Skippable::Ran(Some(val)) => val,
+            Skippable::Ran(None) => return Ok(Skippable::Ran(String::new())),
+            Skippable::Skipped(reason) => return Ok(Skippable::Skipped(reason)),
+        };
+        let c = db.c(uri).await?.expect("Failed to find main expression");
+
+        let path = uri.to_file_path().expect("Failed to convert URI to file path");
+        let name = path.file_stem().unwrap().to_string_lossy();
+        let dir = std::env::temp_dir().join(format!("polarity-test-runner-{}", std::process::id()));
+        fs::create_dir_all(&dir).map_err(io_error)?;
+        let c_path = dir.join(format!("{name}.c"));
+        let bin_path = dir.join(name.as_ref()).with_extension(std::env::consts::EXE_EXTENSION);
+        fs::write(&c_path, c).map_err(io_error)?;
+        driver::compile_c(&c_path, &bin_path)?;
+
+        let output = Command::new(&bin_path).output().map_err(io_error)?;
+        fs::remove_dir_all(&dir).map_err(io_error)?;
+        let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+        // The executable never breaks lines, unlike the pretty printer
+        let cfg = PrintCfg { width: usize::MAX, ..PrintCfg::default() };
+        let expected = format!("{}\n", val.print_to_string(Some(&cfg)));
+        if !output.status.success() || stdout != expected {
+            return Err(driver::Error::Backend(BackendError::Impossible(format!(
+                "The executable printed {stdout:?} with {}, but the evaluators computed {expected:?}",
+                output.status
+            ))));
+        }
+        Ok(Skippable::Ran(stdout))
+    }
+}
+
+fn io_error(err: std::io::Error) -> driver::Error {
+    driver::DriverError::Io(Arc::new(err)).into()
+}
+
 // TestOutput
 
 pub trait TestOutput {
     fn test_output(&self) -> String;
+
+    /// The reason why the phase was skipped, if it was.
+    fn skipped(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+/// The output of a phase which is not run for every testcase.
+pub enum Skippable<T> {
+    Ran(T),
+    /// The phase was skipped for the given reason.
+    Skipped(&'static str),
+}
+
+impl<T> Skippable<T> {
+    fn map<U>(self, f: impl FnOnce(T) -> U) -> Skippable<U> {
+        match self {
+            Skippable::Ran(out) => Skippable::Ran(f(out)),
+            Skippable::Skipped(reason) => Skippable::Skipped(reason),
+        }
+    }
+}
+
+impl<T: TestOutput> TestOutput for Skippable<T> {
+    fn test_output(&self) -> String {
+        match self {
+            Skippable::Ran(out) => out.test_output(),
+            Skippable::Skipped(_) => "".to_owned(),
+        }
+    }
+
+    fn skipped(&self) -> Option<&'static str> {
+        match self {
+            Skippable::Ran(out) => out.skipped(),
+            Skippable::Skipped(reason) => Some(reason),
+        }
+    }
 }
 
 impl TestOutput for () {
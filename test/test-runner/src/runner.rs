@@ -47,8 +47,13 @@ impl Runner {
     {
         let mut suites: HashMap<_, _> =
             suites::load(suites_path.as_ref()).map(|suite| (suite.name.clone(), suite)).collect();
-        suites.insert("examples".to_owned(), Suite::new(examples_path.as_ref().into()));
-        suites.insert("stdlib".to_owned(), Suite::new(stdlib_path.as_ref().into()));
+        // The examples and the standard library must be fully erased by the compiler.
+        for (name, path) in [("examples", examples_path.as_ref()), ("stdlib", stdlib_path.as_ref())]
+        {
+            let mut suite = Suite::new(path.into());
+            suite.config.erased = true;
+            suites.insert(name.to_owned(), suite);
+        }
 
         let index = create_index(&suites);
 
@@ -102,7 +107,7 @@ impl Runner {
 
     /// Run one individual testcase within a testsuite
     pub fn run_case(&self, config: &suites::Config, case: &Case) -> CaseResult {
-        PartialRun::start(case.clone())
+        let run = PartialRun::start(case.clone())
             .then(config, Parse::new("parse"))
             .then(config, Imports::new("imports"))
             .then(config, Lower::new("lower"))
@@ -113,7 +118,11 @@ impl Runner {
             .then(config, Lower::new("relower"))
             .then(config, Check::new("recheck"))
             .then(config, Xfunc::new("xfunc"))
-            .then(config, IR::new("ir"))
+            .then(config, IR::new("ir"));
+        let run = if config.erased { run.then(config, Erased::new("erased")) } else { run };
+        run.then(config, JS::new("js"))
+            .then(config, Eval::new("eval"))
+            .then(config, Exec::new("exec"))
             .report()
     }
 }
@@ -141,7 +150,7 @@ impl RunResult {
     /// Returns true when all failed tests were due to unexpected output.
     /// Tests can not be updated if they failed for a reason other than a mismatched output.
     pub fn update_expected(&self) -> bool {
-        self.case_results().all(|CaseResult { case, result }| match result {
+        self.case_results().all(|CaseResult { case, result, .. }| match result {
             Ok(_) => true,
             Err(Failure::Mismatch { phase, ref actual, .. }) => {
                 case.set_expected(phase, actual);
@@ -201,18 +210,25 @@ impl SuiteResult {
 pub struct CaseResult {
     pub case: Case,
     pub result: Result<(), Failure>,
+    /// The phases which were skipped for this testcase, together with the reason.
+    pub skipped: Vec<String>,
 }
 
 impl CaseResult {
     pub fn print(&self) {
-        let CaseResult { case, result } = self;
+        let CaseResult { case, result, skipped } = self;
         let mut stdout = StandardStream::stdout(ColorChoice::Auto);
         match result {
             Ok(_) => {
                 let str = format!("{} ({:?})", case.name, case.path);
                 write!(&mut stdout, "    - {:70} ", str).unwrap();
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::Green))).unwrap();
-                writeln!(&mut stdout, "✓").unwrap();
+                write!(&mut stdout, "✓").unwrap();
+                if !skipped.is_empty() {
+                    stdout.set_color(ColorSpec::new().set_fg(Some(Color::Yellow))).unwrap();
+                    write!(&mut stdout, " (skipped {})", skipped.join(", ")).unwrap();
+                }
+                writeln!(&mut stdout).unwrap();
                 stdout.set_color(ColorSpec::new().set_fg(Some(Color::White))).unwrap();
             }
             Err(err) => {
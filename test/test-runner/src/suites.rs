@@ -94,6 +94,10 @@ pub struct Config {
     pub fail: Option<String>,
     /// Human-readable description of what the tests in this suite are testing.
     pub description: String,
+    /// Whether the IR of the cases must be fully erased, i.e. contain neither zero-sized terms
+    /// nor panics.
+    #[serde(default)]
+    pub erased: bool,
 }
 
 /// A single testsuite such as "fail-lower", "fail-check" or "success".
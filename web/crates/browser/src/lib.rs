@@ -1,9 +1,9 @@
 #![deny(clippy::all)]
 #![deny(unsafe_code)]
 
-use driver::{FileSource, InMemorySource};
+use driver::{FileSource, InMemorySource, StdSource};
 use futures::stream::TryStreamExt;
-use tower_lsp_server::{LspService, Server};
+use tower_lsp_server::Server;
 use wasm_bindgen::{prelude::*, JsCast};
 use wasm_bindgen_futures::stream::JsStream;
 
@@ -64,12 +64,14 @@ pub async fn serve(config: ServerConfig) -> Result<(), JsValue> {
     let output = output.try_into_async_write().map_err(|err| err.0)?;
 
     let create_server = |client| {
-        let source = InMemorySource::new().fallback_to(FetchSource::default());
-        let database = driver::Database::from_source(source);
+        let source =
+            InMemorySource::new().fallback_to(StdSource::new()).fallback_to(FetchSource::default());
+        let mut database = driver::Database::from_source(source);
+        database.eval_limits.max_steps = Some(lsp_server::DEFAULT_MAX_STEPS);
         lsp_server::Server::with_database(client, database)
     };
 
-    let (service, messages) = LspService::new(create_server);
+    let (service, messages) = lsp_server::Server::service(create_server);
     Server::new(input, output, messages).serve(service).await;
 
     Ok(())
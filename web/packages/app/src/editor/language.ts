@@ -26,6 +26,31 @@ export default class Language {
       },
     });
 
+    vscode.languages.registerDefinitionProvider(this.id, {
+      async provideDefinition(document, position, token) {
+        void token;
+        const response = (await client.request(proto.DefinitionRequest.type.method, {
+          textDocument: code2Protocol.asTextDocumentIdentifier(document),
+          position: code2Protocol.asPosition(position),
+        })) as proto.Definition | null;
+
+        if (!response) {
+          return undefined;
+        }
+        return protocol2Code.asDefinitionResult(response);
+      },
+    });
+
+    // The standard library is bundled with the server, which provides the source of its modules.
+    vscode.workspace.registerTextDocumentContentProvider("std", {
+      async provideTextDocumentContent(uri, token) {
+        void token;
+        return (await client.request("polarity/stdSource", {
+          uri: uri.toString(),
+        } as proto.TextDocumentIdentifier)) as string;
+      },
+    });
+
     vscode.languages.registerCodeActionsProvider(this.id, {
       async provideCodeActions(document, range, context, token) {
         void token;